@@ -0,0 +1,46 @@
+//! Morton indexing of every tile of a large board, through `Coordinate`
+//! against the `morton` crate it replaced:
+//!
+//! ```sh
+//! cargo +nightly bench -p snake-core
+//! RUSTFLAGS="-C target-feature=+bmi2" cargo +nightly bench -p snake-core
+//! ```
+#![feature(test)]
+
+extern crate morton;
+extern crate snake_core;
+extern crate test;
+
+use morton::interleave_morton;
+use snake_core::data::Coordinate;
+use test::{black_box, Bencher};
+
+const SIDE: u16 = 256;
+
+#[bench]
+fn bench_encode_usize(b: &mut Bencher) {
+    b.iter(|| {
+        let mut sum = 0;
+        for y in 0..SIDE {
+            for x in 0..SIDE {
+                let at = black_box(Coordinate { x, y });
+                sum ^= at.encode_usize();
+            }
+        }
+        sum
+    });
+}
+
+#[bench]
+fn bench_interleave_morton(b: &mut Bencher) {
+    b.iter(|| {
+        let mut sum = 0;
+        for y in 0..SIDE {
+            for x in 0..SIDE {
+                let (x, y) = black_box((x, y));
+                sum ^= interleave_morton(x, y) as usize;
+            }
+        }
+        sum
+    });
+}
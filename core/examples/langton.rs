@@ -0,0 +1,233 @@
+//! Langton's ant on the engine of the snake game, to show none of
+//! `system` is about snakes: a `Stateful` model with updates of its own, an
+//! `IncrRender` drawing them on any `DrawGrid` and a `Game` running the
+//! two, here on an ascii board printed once the ant walks off of it.
+//!
+//! ```sh
+//! cargo +nightly run -p snake-core --example langton
+//! ```
+#![feature(generators, generator_trait)]
+
+extern crate snake_core;
+
+use std::cell::RefCell;
+use std::ops::{Generator, GeneratorState};
+use std::rc::Rc;
+
+use snake_core::data::{Coordinate, Direction, Grid, SmallNat};
+use snake_core::system::{
+    Color, DrawGrid, FrameCtx, GameOver, IncrRender, Stateful, Tick,
+    UnitInterval,
+};
+
+const WIDTH: SmallNat = 48;
+const HEIGHT: SmallNat = 24;
+
+#[derive(Debug, Copy, Clone)]
+enum AntUpdate {
+    Board { width: SmallNat, height: SmallNat },
+    // the ant flipped the cell it left, `black` or not, and stands on `ant`
+    Moved {
+        left: Coordinate,
+        black: bool,
+        ant: Coordinate,
+    },
+}
+
+struct Ant {
+    cells: Grid<bool>,
+    at: Coordinate,
+    dir: Direction,
+}
+
+impl Ant {
+    fn new(width: SmallNat, height: SmallNat) -> Self {
+        let cells = Grid::filled(width, height, false);
+        let at = cells.center();
+
+        Ant {
+            cells,
+            at,
+            dir: Direction::North,
+        }
+    }
+}
+
+impl<'m> Stateful<'m> for Ant {
+    type Cmd = ();
+    type Update = AntUpdate;
+    type Init = Option<AntUpdate>;
+    // the ant leaving the board quits, there's no next game
+    type Error = GameOver;
+
+    fn initialize(&'m mut self) -> Self::Init {
+        Some(AntUpdate::Board {
+            width: self.cells.width(),
+            height: self.cells.height(),
+        })
+    }
+
+    fn step(
+        &mut self,
+        _cmd: Option<()>,
+        _tick: Tick,
+    ) -> Result<Option<AntUpdate>, GameOver> {
+        let left = self.at;
+        let black = {
+            let cell = self.cells.get_mut(left).ok_or(GameOver::Quit)?;
+            *cell = !*cell;
+            *cell
+        };
+
+        // right on white, left on black
+        self.dir = if black {
+            self.dir.rotate_right()
+        } else {
+            self.dir.rotate_left()
+        };
+        self.at = self
+            .at
+            .move_towards(self.dir)
+            .bound_inside(self.cells.width(), self.cells.height())
+            .ok_or(GameOver::Quit)?;
+
+        Ok(Some(AntUpdate::Moved {
+            left,
+            black,
+            ant: self.at,
+        }))
+    }
+
+    fn tear_down(&mut self) {
+        *self = Ant::new(self.cells.width(), self.cells.height());
+    }
+}
+
+// draws an update in a single frame
+struct AntDraw(Option<AntUpdate>);
+
+impl<E: DrawGrid> IncrRender<E> for AntDraw {
+    type Patch = AntUpdate;
+
+    fn new_patch(update: AntUpdate, _ctx: FrameCtx) -> Self {
+        AntDraw(Some(update))
+    }
+
+    fn render(&mut self, env: &mut E) -> Option<()> {
+        let full = UnitInterval::max_value();
+
+        match self.0.take()? {
+            AntUpdate::Board { width, height } => {
+                env.setup(1, width, height);
+                env.clear();
+            }
+            AntUpdate::Moved { left, black, ant } => {
+                if black {
+                    env.fill_tile(left.x, left.y, Direction::East, full);
+                } else {
+                    env.clear_tile(left.x, left.y, Direction::East, full);
+                }
+                env.with_fill_color(Color::Red, |env| {
+                    env.circle(ant.x, ant.y, full);
+                });
+            }
+        }
+
+        // one frame per update, so the game steps again right away
+        None
+    }
+}
+
+// keeps the board as characters, `#` for black cells and `@` for the ant
+struct AsciiEnv {
+    width: SmallNat,
+    cells: Vec<char>,
+    color: Color,
+}
+
+impl AsciiEnv {
+    fn new() -> Self {
+        AsciiEnv {
+            width: 0,
+            cells: Vec::new(),
+            color: Color::Black,
+        }
+    }
+
+    fn put(&mut self, x: SmallNat, y: SmallNat, c: char) {
+        let index = y as usize * self.width as usize + x as usize;
+        if let Some(cell) = self.cells.get_mut(index) {
+            *cell = c;
+        }
+    }
+
+    fn print(&self) {
+        for row in self.cells.chunks(self.width as usize) {
+            println!("{}", row.iter().collect::<String>());
+        }
+    }
+}
+
+impl DrawGrid for AsciiEnv {
+    fn setup(
+        &mut self,
+        _tile_size: SmallNat,
+        width: SmallNat,
+        height: SmallNat,
+    ) {
+        self.width = width;
+        self.cells = vec![' '; width as usize * height as usize];
+    }
+
+    fn clear(&mut self) {
+        self.cells.iter_mut().for_each(|c| *c = ' ');
+    }
+
+    fn set_fill_color(&mut self, color: Color) -> Color {
+        std::mem::replace(&mut self.color, color)
+    }
+
+    // the ant, the cell it leaves is redrawn before it moves on
+    fn circle(&mut self, x: SmallNat, y: SmallNat, _radius: UnitInterval) {
+        self.put(x, y, '@');
+    }
+
+    fn fill_tile(
+        &mut self,
+        x: SmallNat,
+        y: SmallNat,
+        _dir: Direction,
+        _size: UnitInterval,
+    ) {
+        self.put(x, y, '#');
+    }
+
+    fn clear_tile(
+        &mut self,
+        x: SmallNat,
+        y: SmallNat,
+        _dir: Direction,
+        _size: UnitInterval,
+    ) {
+        self.put(x, y, ' ');
+    }
+
+    fn show_game_over(&mut self) {}
+}
+
+fn main() {
+    // the game owns its env, the board is read back through a shared one
+    let board = Rc::new(RefCell::new(AsciiEnv::new()));
+
+    let (_tx, mut generator) = Ant::new(WIDTH, HEIGHT)
+        .make_game(board.clone())
+        .new_game::<AntDraw, ()>();
+
+    let mut frames = 0;
+    while let GeneratorState::Yielded(()) = unsafe { generator.resume() } {
+        frames += 1;
+    }
+
+    board.borrow().print();
+    println!("the ant left the board after {} frames", frames);
+}
@@ -0,0 +1,322 @@
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use std::cell::RefCell;
+
+use void::Void;
+
+use constants::ANIMATION_FRAME_COUNT;
+use data::{Command, Coordinate, Direction};
+use draw::TickDraw;
+use system::{
+    DrawGrid, FrameCtx, Frames, Incr, IncrRender, Pacer, Stateful, Tick,
+};
+use world::{TickDelta, WorldUpdate};
+
+pub struct VariableFrame<T = TickDelta> {
+    pub update: T,
+    pub frame_count: u8,
+}
+impl Into<TickDelta> for VariableFrame<TickDelta> {
+    fn into(self) -> TickDelta {
+        self.update
+    }
+}
+impl From<WorldUpdate> for VariableFrame<TickDelta> {
+    fn from(u: WorldUpdate) -> Self {
+        VariableFrame {
+            update: TickDelta::from(u),
+            frame_count: ANIMATION_FRAME_COUNT,
+        }
+    }
+}
+impl<T> VariableFrame<T> {
+    pub fn pack(both: (T, u8)) -> Self {
+        VariableFrame {
+            update: both.0,
+            frame_count: both.1,
+        }
+    }
+}
+
+impl<E: DrawGrid> IncrRender<E> for TickDraw<VariableFrame<TickDelta>> {
+    type Patch = VariableFrame<TickDelta>;
+
+    fn new_patch(u: Self::Patch, _ctx: FrameCtx) -> Self {
+        let frame_count = u.frame_count;
+        TickDraw::new(u, frame_count)
+    }
+
+    #[inline]
+    fn render(&mut self, env: &mut E) -> Option<()> {
+        self.render(env)
+    }
+
+    fn tiles(&self) -> Option<Vec<Coordinate>> {
+        self.tiles()
+    }
+}
+
+/// The `RenderFactory` of `TickDraw::snapped` ticks, to switch to from the
+/// animated `boxed::<_, TickDraw<VariableFrame>>` in `Renderers`
+pub fn snapped<E>(u: VariableFrame<TickDelta>, _ctx: FrameCtx) -> Box<Frames<E>>
+where
+    E: DrawGrid + 'static,
+{
+    let frame_count = u.frame_count;
+    Box::new(Incr(TickDraw::new(u, frame_count).snapped()))
+}
+
+/// Tells a shared `Pacer` of the ticks `model` steps, each drawn in twice
+/// its `frame_count` frames as `TickDraw` draws it, and in a frame if it
+/// updates nothing
+pub struct Paced<M> {
+    model: M,
+    pacer: Rc<RefCell<Pacer>>,
+}
+
+impl<M> Paced<M> {
+    pub fn new(model: M, pacer: Rc<RefCell<Pacer>>) -> Self {
+        Paced { model, pacer }
+    }
+}
+
+impl<'m, M, T> Stateful<'m> for Paced<M>
+where
+    M: Stateful<'m, Update = VariableFrame<T>>,
+{
+    type Cmd = M::Cmd;
+    type Init = M::Init;
+    type Update = M::Update;
+    type Error = M::Error;
+
+    fn initialize(&'m mut self) -> Self::Init {
+        self.model.initialize()
+    }
+
+    #[inline]
+    fn step(
+        &mut self,
+        cmd: Option<Self::Cmd>,
+        tick: Tick,
+    ) -> Result<Option<Self::Update>, Self::Error> {
+        let update = self.model.step(cmd, tick);
+        match update {
+            Ok(Some(ref u)) => {
+                let frames = 2 * u.frame_count as u32;
+                self.pacer.borrow_mut().ran(frames);
+            }
+            Ok(None) => self.pacer.borrow_mut().ran(1),
+            Err(_) => {}
+        }
+        update
+    }
+
+    fn tear_down(&mut self) {
+        self.model.tear_down();
+    }
+}
+
+const FRAMES: f64 = ANIMATION_FRAME_COUNT as f64;
+const TIME_DELTA: f64 = 0.01;
+const MIN_VELOCITY: f64 = FRAMES;
+const MAX_VELOCITY: f64 = MIN_VELOCITY * FRAMES * 0.6;
+const FRICTION: f64 = 13.8629436112 / MIN_VELOCITY;
+const POWER: f64 = MAX_VELOCITY * MAX_VELOCITY * FRICTION;
+
+#[derive(Debug, Copy, Clone)]
+pub struct RenderSpeed {
+    direction: Direction,
+    initial_direction: Direction,
+    velocity: f64,
+}
+
+impl RenderSpeed {
+    pub fn new(direction: Direction) -> Self {
+        RenderSpeed {
+            direction,
+            initial_direction: direction,
+            velocity: MIN_VELOCITY,
+        }
+    }
+}
+
+impl<'m> Stateful<'m> for RenderSpeed {
+    type Cmd = Command;
+    type Init = Forever<u8>;
+    type Update = u8;
+    type Error = Void;
+
+    fn initialize(&'m mut self) -> Self::Init {
+        Forever(ANIMATION_FRAME_COUNT)
+    }
+
+    fn step(
+        &mut self,
+        cmd: Option<Self::Cmd>,
+        _tick: Tick,
+    ) -> Result<Option<Self::Update>, Self::Error> {
+        let mut v = self.velocity;
+        let acceleration: f64;
+
+        match cmd {
+            // only a held key speeds the snake up, taps just steer
+            Some(Command::Sprint(dir)) if dir == self.direction => {
+                acceleration = POWER / v - FRICTION * v;
+            }
+            Some(Command::Turn(dir)) | Some(Command::Sprint(dir))
+                if dir == self.direction.opposite() =>
+            {
+                acceleration = -POWER / v - FRICTION * v;
+            }
+            Some(Command::Turn(dir)) | Some(Command::Sprint(dir))
+                if dir != self.direction =>
+            {
+                self.direction = dir;
+                acceleration = 0.0;
+            }
+            _ => {
+                acceleration = -FRICTION * v * 0.5;
+            }
+        }
+
+        v = v + acceleration * TIME_DELTA;
+
+        v = if v > MIN_VELOCITY { v } else { MIN_VELOCITY };
+
+        self.velocity = v;
+
+        Ok(Some(self.derive_frame_count()))
+    }
+    fn tear_down(&mut self) {
+        self.direction = self.initial_direction;
+        self.velocity = MIN_VELOCITY;
+    }
+}
+
+impl RenderSpeed {
+    #[inline(always)]
+    fn derive_frame_count(&self) -> u8 {
+        let v = self.velocity;
+        let frames = MIN_VELOCITY / v * FRAMES;
+        // `f64::ceil` needs std, frames are always positive
+        let frame_count = frames as u8;
+
+        if (frame_count as f64) < frames {
+            frame_count + 1
+        } else {
+            frame_count
+        }
+    }
+}
+
+pub struct Forever<T>(T);
+
+impl<T: Copy> Iterator for Forever<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        Some(self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_acceleration_one_step() {
+        let mut render_speed = RenderSpeed::new(Direction::East);
+        let v0 = render_speed.velocity;
+
+        render_speed
+            .step(Some(Command::Sprint(Direction::East)), 0)
+            .expect("It never errors");
+
+        assert!(render_speed.velocity > v0);
+    }
+
+    #[test]
+    fn test_tap_does_not_accelerate() {
+        let mut render_speed = RenderSpeed::new(Direction::East);
+        let v0 = render_speed.velocity;
+
+        render_speed.step(Some(Command::Turn(Direction::East)), 0).unwrap();
+
+        assert!(render_speed.velocity <= v0);
+    }
+
+    #[test]
+    fn test_deacceleration_one_step() {
+        let mut render_speed = RenderSpeed::new(Direction::East);
+
+        render_speed.step(Some(Command::Sprint(Direction::East)), 0).unwrap();
+        render_speed.step(Some(Command::Sprint(Direction::East)), 0).unwrap();
+        render_speed.step(Some(Command::Sprint(Direction::East)), 0).unwrap();
+
+        let v1 = render_speed.velocity;
+
+        render_speed.step(None, 0).unwrap();
+
+        assert!(render_speed.velocity < v1);
+    }
+
+    #[test]
+    fn test_deacceleration_forced() {
+        let dir = Direction::North;
+
+        let mut render_speed = RenderSpeed::new(dir);
+
+        render_speed.step(Some(Command::Sprint(dir)), 0).unwrap();
+        render_speed.step(Some(Command::Sprint(dir)), 0).unwrap();
+        render_speed.step(Some(Command::Sprint(dir)), 0).unwrap();
+
+        let mut render_speed_a = render_speed;
+        let mut render_speed_b = render_speed;
+
+        render_speed_a.step(None, 0).unwrap();
+        render_speed_b
+            .step(Some(Command::Turn(dir.opposite())), 0)
+            .unwrap();
+
+        assert!(render_speed_a.velocity > render_speed_b.velocity);
+    }
+
+    #[test]
+    fn test_acceleration_limiting_behavior() {
+        fn final_frame_count_limit(n: usize) -> u8 {
+            let render_speed = RenderSpeed::new(Direction::East);
+
+            (0..n)
+                .scan(render_speed, |rs, _| {
+                    let frame_count = rs
+                        .step(Some(Command::Sprint(Direction::East)), 0)
+                        .unwrap()
+                        .unwrap();
+
+                    Some(frame_count)
+                })
+                .last()
+                .unwrap()
+        }
+
+        assert_eq!(final_frame_count_limit(100), final_frame_count_limit(500));
+
+        assert!(final_frame_count_limit(20) < ANIMATION_FRAME_COUNT);
+        assert!(final_frame_count_limit(20) > 0);
+    }
+
+    quickcheck! {
+        fn render_speed_produces_bounded_frame_counts(maybe_dirs: Vec<Option<Direction>>) -> bool {
+            let mut render_speed = RenderSpeed::new(Direction::East);
+
+            for cmd in maybe_dirs {
+                render_speed.step(cmd.map(Command::Sprint), 0).unwrap();
+            }
+
+            let frame_count = render_speed.derive_frame_count();
+
+            frame_count > 0 && frame_count <= ANIMATION_FRAME_COUNT
+        }
+    }
+}
@@ -0,0 +1,204 @@
+//! A headless game on a level drawn in ascii (see `World::from_ascii`),
+//! steered by a bot or a script of moves, printing the board every so many
+//! ticks and how the game went at the end. Handy for trying out a change
+//! of the rules without a browser.
+//!
+//! ```sh
+//! cargo +nightly run -p snake-core --features sim --bin snake-sim -- \
+//!     level.txt --rules rules.toml --bot astar --every 20
+//! ```
+//!
+//! The rules file is a `config::Config`, in toml or json, whose board size
+//! the level's takes over from. A script holds a move per tick, `^`, `>`, `v` or `<` to turn
+//! and `.` to go on, whitespace aside; the snake goes on once it runs out.
+extern crate rand;
+extern crate snake_core;
+
+use std::env;
+use std::fs;
+use std::process;
+
+use rand::SeedableRng;
+
+use snake_core::bot::{Policy, Strategy};
+use snake_core::config::Config;
+use snake_core::data::{Command, Direction, Wrapping};
+use snake_core::replay::rng_seed;
+use snake_core::system::{Stateful, Tick};
+use snake_core::world::{UpdateError, World, WorldUpdate, PLAYER};
+use snake_core::SmallRng;
+
+enum Input {
+    Bot(Box<Policy>),
+    // the move of each tick, from the first
+    Script(Vec<Option<Direction>>),
+}
+
+struct Options {
+    level: String,
+    rules: Option<String>,
+    input: Input,
+    // 0 prints the board at the end only
+    every: u32,
+    ticks: u32,
+}
+
+impl Options {
+    fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<Self, String> {
+        let mut level = None;
+        let mut rules = None;
+        let mut input = None;
+        let mut every = 10;
+        let mut ticks = 1000;
+
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next().ok_or_else(|| format!("{} takes a value", arg))
+            };
+            match arg.as_str() {
+                "--rules" => rules = Some(value()?),
+                "--bot" => {
+                    let name = value()?;
+                    let strategy = Strategy::from_name(&name)
+                        .ok_or_else(|| format!("no bot called {}", name))?;
+                    input = Some(Input::Bot(strategy.policy(0)));
+                }
+                "--script" => {
+                    let moves = script(&read(&value()?)?)?;
+                    input = Some(Input::Script(moves));
+                }
+                "--every" => every = number(&arg, &value()?)?,
+                "--ticks" => ticks = number(&arg, &value()?)?,
+                _ if level.is_none() => level = Some(arg.clone()),
+                _ => return Err(format!("unexpected {}", arg)),
+            }
+        }
+
+        Ok(Options {
+            level: level.ok_or("a level file is needed")?,
+            rules,
+            // a snake left alone goes straight on
+            input: input.unwrap_or_else(|| Input::Script(Vec::new())),
+            every,
+            ticks,
+        })
+    }
+}
+
+fn number(arg: &str, value: &str) -> Result<u32, String> {
+    value.parse().map_err(|_| format!("{} takes a number", arg))
+}
+
+fn read(path: &str) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|err| format!("{}: {}", path, err))
+}
+
+// the moves of a script file
+fn script(text: &str) -> Result<Vec<Option<Direction>>, String> {
+    text.chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| match c {
+            '^' => Ok(Some(Direction::North)),
+            '>' => Ok(Some(Direction::East)),
+            'v' => Ok(Some(Direction::South)),
+            '<' => Ok(Some(Direction::West)),
+            '.' => Ok(None),
+            c => Err(format!("{:?} is no move of a script", c)),
+        })
+        .collect()
+}
+
+fn print_board(tick: Tick, world: &World<SmallRng, Wrapping>) {
+    println!("tick {}, length {}", tick, world.snake().length());
+    println!("{}\n", world.view().grid());
+}
+
+fn run(mut options: Options) -> Result<(), String> {
+    let level = read(&options.level)?;
+    if !level.chars().any(|c| "^>v<".contains(c)) {
+        return Err(format!("{}: no snake on the level", options.level));
+    }
+    let config = match options.rules {
+        Some(ref path) => Config::from_file(path)
+            .map_err(|err| format!("{}: {}", path, err))?,
+        None => Config::default(),
+    };
+
+    let rng = SmallRng::from_seed(rng_seed(config.seed));
+    let mut world: World<SmallRng, Wrapping> =
+        World::from_ascii_with_rng(&level, rng).with_rules(config.rules);
+    config.apply(&mut world);
+    for _ in world.initialize() {}
+
+    let (mut food, mut lost, mut ended) = (0, 0, None);
+    let mut ticks = 0;
+    while ticks < options.ticks {
+        if options.every > 0 && ticks % options.every == 0 {
+            print_board(ticks, &world);
+        }
+
+        let dir = match options.input {
+            Input::Bot(ref mut policy) => Some(policy.decide(&world.view())),
+            Input::Script(ref moves) => {
+                moves.get(ticks as usize).cloned().unwrap_or(None)
+            }
+        };
+        let tick = ticks;
+        ticks += 1;
+
+        match Stateful::step(&mut world, dir.map(Command::Turn), tick) {
+            Ok(Some(delta)) => {
+                food += delta
+                    .changes()
+                    .filter(|u| match *u {
+                        WorldUpdate::FoodEaten { snake: PLAYER, .. } => true,
+                        _ => false,
+                    })
+                    .count();
+            }
+            Ok(None) => {}
+            // the snake is back at the start, the board as it was
+            Err(UpdateError::LifeLost) => {
+                lost += 1;
+                for _ in world.initialize() {}
+            }
+            Err(err) => {
+                ended = Some(err);
+                break;
+            }
+        }
+    }
+
+    print_board(ticks, &world);
+    println!(
+        "{} ticks, length {}, {} food eaten, {} lives lost",
+        ticks,
+        world.snake().length(),
+        food,
+        lost
+    );
+    match ended {
+        Some(err) => println!("game over: {:?}", err),
+        None => println!("still going"),
+    }
+    Ok(())
+}
+
+fn main() {
+    let options = match Options::parse(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("{}", err);
+            eprintln!(
+                "usage: snake-sim LEVEL [--rules FILE] \
+                 [--bot NAME | --script FILE] [--every N] [--ticks N]"
+            );
+            process::exit(2);
+        }
+    };
+
+    if let Err(err) = run(options) {
+        eprintln!("{}", err);
+        process::exit(1);
+    }
+}
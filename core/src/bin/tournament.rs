@@ -0,0 +1,173 @@
+//! Headless games between bot policies at full speed: every policy plays
+//! the same seeded board in each game, the longest snake at the end wins
+//! it. Prints the win rate and the average length of each policy and how
+//! many steps a second the world took, which is also a quick check that
+//! the step loop didn't get slower.
+//!
+//! ```sh
+//! cargo +nightly run --release -p snake-core --features tournament \
+//!     --bin tournament -- --games 200 greedy astar hamiltonian
+//! ```
+extern crate snake_core;
+
+use std::env;
+use std::process;
+use std::time::Instant;
+
+use snake_core::bot::{Policy, Strategy};
+use snake_core::data::SmallNat;
+use snake_core::replay::initial_world;
+use snake_core::system::Stateful;
+use snake_core::world::Rules;
+
+const STRATEGIES: [(&str, Strategy); 4] = [
+    ("greedy", Strategy::Greedy),
+    ("astar", Strategy::AStar),
+    ("hamiltonian", Strategy::Hamiltonian),
+    ("random", Strategy::Random),
+];
+
+struct Options {
+    games: u32,
+    width: SmallNat,
+    height: SmallNat,
+    // a game is over for a snake once it crashed or took as many steps
+    steps: u32,
+    seed: u32,
+    players: Vec<(&'static str, Strategy)>,
+}
+
+impl Options {
+    fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<Self, String> {
+        let mut options = Options {
+            games: 100,
+            width: 24,
+            height: 16,
+            steps: 5000,
+            seed: 0,
+            players: Vec::new(),
+        };
+
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .and_then(|value| value.parse::<u32>().ok())
+                    .ok_or_else(|| format!("{} takes a number", arg))
+            };
+            match arg.as_str() {
+                "--games" => options.games = value()?,
+                "--width" => options.width = value()? as SmallNat,
+                "--height" => options.height = value()? as SmallNat,
+                "--steps" => options.steps = value()?,
+                "--seed" => options.seed = value()?,
+                name => {
+                    let player = STRATEGIES
+                        .iter()
+                        .find(|&&(known, _)| known == name)
+                        .ok_or_else(|| format!("no bot called {}", name))?;
+                    options.players.push(*player);
+                }
+            }
+        }
+
+        // the initial snake spans x = 1..5 and needs room for its next move
+        if options.width < 6 || options.height < 2 {
+            return Err("the board is at least 6 by 2".into());
+        }
+        if options.players.is_empty() {
+            options.players = STRATEGIES.to_vec();
+        }
+        Ok(options)
+    }
+}
+
+#[derive(Default)]
+struct Standing {
+    wins: u32,
+    length: u64,
+}
+
+// the length `policy` got to on the board of `seed`, and the steps it took
+fn play(options: &Options, policy: &mut Policy, seed: u32) -> (u32, u32) {
+    let rules = Rules::default();
+    let mut world = initial_world(options.width, options.height, seed, rules);
+    for _ in world.initialize() {}
+
+    let mut steps = 0;
+    while steps < options.steps {
+        steps += 1;
+        let dir = policy.decide(&world.view());
+        if world.tick(Some(dir)).is_err() {
+            break;
+        }
+    }
+    (world.snake().length(), steps)
+}
+
+fn main() {
+    let options = match Options::parse(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("{}", err);
+            eprintln!(
+                "usage: tournament [--games N] [--width W] [--height H] \
+                 [--steps S] [--seed X] [greedy|astar|hamiltonian|random]..."
+            );
+            process::exit(2);
+        }
+    };
+
+    let mut standings: Vec<Standing> =
+        options.players.iter().map(|_| Standing::default()).collect();
+    let mut total_steps: u64 = 0;
+    let started = Instant::now();
+
+    for game in 0..options.games {
+        let seed = options.seed.wrapping_add(game);
+        let lengths: Vec<u32> = options
+            .players
+            .iter()
+            .map(|&(_, strategy)| {
+                let mut policy = strategy.policy(seed);
+                let (length, steps) = play(&options, &mut policy, seed);
+                total_steps += steps as u64;
+                length
+            })
+            .collect();
+
+        // no one wins a game two or more snakes got the longest in
+        let longest = lengths.iter().cloned().max().unwrap_or(0);
+        let winners = lengths.iter().filter(|&&l| l == longest).count();
+        for (standing, &length) in standings.iter_mut().zip(&lengths) {
+            standing.length += length as u64;
+            if length == longest && winners == 1 {
+                standing.wins += 1;
+            }
+        }
+    }
+
+    let elapsed = started.elapsed();
+    let seconds = elapsed.as_secs() as f64
+        + elapsed.subsec_nanos() as f64 / 1_000_000_000.0;
+    let games = options.games.max(1) as f64;
+
+    println!(
+        "{} games on {}x{}, up to {} steps each",
+        options.games, options.width, options.height, options.steps
+    );
+    println!("{:<12} {:>8} {:>10}", "bot", "wins", "length");
+    for (&(name, _), standing) in options.players.iter().zip(&standings) {
+        println!(
+            "{:<12} {:>7.1}% {:>10.1}",
+            name,
+            100.0 * standing.wins as f64 / games,
+            standing.length as f64 / games
+        );
+    }
+    println!(
+        "{} steps in {:.2}s, {:.0} steps/s",
+        total_steps,
+        seconds,
+        total_steps as f64 / seconds.max(1e-9)
+    );
+}
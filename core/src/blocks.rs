@@ -0,0 +1,468 @@
+//! Falling blocks, a third demo of `system`: pieces moved and rotated by
+//! key commands, with rows of cells changing in a single tick and games that
+//! end in a win as well as a loss.
+use alloc::vec::Vec;
+
+use rand::Rng;
+
+use constants::TILE_SIZE;
+use data::{Command, Coordinate, Direction, Grid, SmallNat};
+use system::{
+    DrawGrid, FrameCtx, GameOver, IncrRender, Stateful, Tick, UnitInterval,
+};
+
+// ticks for a piece to fall a row on its own
+const FALL_EVERY: Tick = 20;
+
+// the seven tetrominoes around the cell they turn on, the `O` doesn't turn
+const PIECES: [[(i16, i16); 4]; 7] = [
+    [(-1, 0), (0, 0), (1, 0), (2, 0)],
+    [(0, 0), (1, 0), (0, 1), (1, 1)],
+    [(-1, 0), (0, 0), (1, 0), (0, 1)],
+    [(0, 0), (1, 0), (-1, 1), (0, 1)],
+    [(-1, 0), (0, 0), (0, 1), (1, 1)],
+    [(-1, 0), (0, 0), (1, 0), (1, 1)],
+    [(-1, 0), (0, 0), (1, 0), (-1, 1)],
+];
+const O_PIECE: usize = 1;
+
+// shifts tried, in order, for a rotated piece to fit
+const KICKS: [(i16, i16); 4] = [(0, 0), (-1, 0), (1, 0), (0, 1)];
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum BlocksUpdate {
+    Loaded { width: SmallNat, height: SmallNat },
+    // the cells a tick emptied and filled, the falling piece included
+    Tick {
+        cleared: Vec<Coordinate>,
+        filled: Vec<Coordinate>,
+    },
+    Finished { won: bool },
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum BlocksError {
+    Over,
+    Restart,
+    Quit,
+}
+
+impl Into<GameOver> for BlocksError {
+    fn into(self) -> GameOver {
+        match self {
+            BlocksError::Over => GameOver::Over,
+            BlocksError::Restart => GameOver::Restart,
+            BlocksError::Quit => GameOver::Quit,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+struct Piece {
+    cells: [(i16, i16); 4],
+    x: i16,
+    y: i16,
+    turns: bool,
+}
+
+impl Piece {
+    fn coordinates(&self) -> impl Iterator<Item = (i16, i16)> {
+        let (x, y, cells) = (self.x, self.y, self.cells);
+        (0..cells.len()).map(move |i| (x + cells[i].0, y + cells[i].1))
+    }
+
+    fn shifted(mut self, dx: i16, dy: i16) -> Self {
+        self.x += dx;
+        self.y += dy;
+        self
+    }
+
+    // a quarter turn, clockwise on screen (y grows downwards) if `right`
+    fn rotated(mut self, right: bool) -> Self {
+        if self.turns {
+            for cell in self.cells.iter_mut() {
+                let (x, y) = *cell;
+                *cell = if right { (-y, x) } else { (y, -x) };
+            }
+        }
+        self
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Phase {
+    Falling,
+    // the last tick is drawn, the outcome is next
+    Ending { won: bool },
+    // waiting for a key to start over
+    Ended,
+}
+
+/// Pieces falling into a `width` x `height` well, moved sideways by
+/// `Command::Turn(West)` and `Command::Turn(East)`, dropped a row by
+/// `Turn(South)`, all the way by `Turn(North)`, and turned by
+/// `Command::RotateLeft` and `Command::RotateRight`. Full rows are cleared,
+/// clearing `goal` of them wins and a piece with no room to enter loses.
+pub struct FallingBlocks<R> {
+    settled: Grid<bool>,
+    piece: Option<Piece>,
+    rng: R,
+    goal: u32,
+    lines: u32,
+    paused: bool,
+    phase: Phase,
+}
+
+impl<R: Rng> FallingBlocks<R> {
+    pub fn new(width: SmallNat, height: SmallNat, goal: u32, rng: R) -> Self {
+        let mut blocks = FallingBlocks {
+            settled: Grid::filled(width, height, false),
+            piece: None,
+            rng,
+            goal,
+            lines: 0,
+            paused: false,
+            phase: Phase::Falling,
+        };
+        blocks.spawn();
+        blocks
+    }
+
+    /// Rows cleared so far in this game
+    pub fn lines(&self) -> u32 {
+        self.lines
+    }
+
+    fn fits(&self, piece: &Piece) -> bool {
+        let (width, height) = (self.settled.width(), self.settled.height());
+
+        piece.coordinates().all(|(x, y)| {
+            x >= 0
+                && y >= 0
+                && (x as SmallNat) < width
+                && (y as SmallNat) < height
+                && !self.is_settled(x as SmallNat, y as SmallNat)
+        })
+    }
+
+    fn is_settled(&self, x: SmallNat, y: SmallNat) -> bool {
+        self.settled.get(Coordinate { x, y }) == Some(&true)
+    }
+
+    // the well as drawn, settled cells and the falling piece
+    fn shown(&self) -> Grid<bool> {
+        let mut shown = self.settled.clone();
+        for (x, y) in self.piece.iter().flat_map(Piece::coordinates) {
+            let at = Coordinate {
+                x: x as SmallNat,
+                y: y as SmallNat,
+            };
+            if let Some(cell) = shown.get_mut(at) {
+                *cell = true;
+            }
+        }
+        shown
+    }
+
+    // a new random piece at the top, the game is lost if it doesn't fit
+    fn spawn(&mut self) {
+        let kind = self.rng.gen_range(0, PIECES.len());
+        let piece = Piece {
+            cells: PIECES[kind],
+            x: (self.settled.width() as i16 - 1) / 2,
+            y: 0,
+            turns: kind != O_PIECE,
+        };
+
+        if self.fits(&piece) {
+            self.piece = Some(piece);
+        } else {
+            self.piece = None;
+            self.phase = Phase::Ending { won: false };
+        }
+    }
+
+    // moves the piece to `next` if it fits there
+    fn try_move(&mut self, next: Piece) -> bool {
+        let fits = self.fits(&next);
+        if fits {
+            self.piece = Some(next);
+        }
+        fits
+    }
+
+    fn rotate(&mut self, piece: Piece, right: bool) {
+        let turned = piece.rotated(right);
+        for &(dx, dy) in KICKS.iter() {
+            if self.try_move(turned.shifted(dx, dy)) {
+                return;
+            }
+        }
+    }
+
+    // settles the piece where it is, clears full rows and brings the next
+    fn lock(&mut self, piece: Piece) {
+        for (x, y) in piece.coordinates() {
+            let at = Coordinate {
+                x: x as SmallNat,
+                y: y as SmallNat,
+            };
+            if let Some(cell) = self.settled.get_mut(at) {
+                *cell = true;
+            }
+        }
+        self.piece = None;
+        self.clear_rows();
+
+        if self.lines >= self.goal {
+            self.phase = Phase::Ending { won: true };
+        } else {
+            self.spawn();
+        }
+    }
+
+    fn clear_rows(&mut self) {
+        let (width, height) = (self.settled.width(), self.settled.height());
+        let mut y = height;
+        while y > 0 {
+            if !(0..width).all(|x| self.is_settled(x, y - 1)) {
+                y -= 1;
+                continue;
+            }
+
+            // everything above comes down a row, the same row is checked
+            // again afterwards
+            for (x, row) in iproduct!(0..width, (0..y).rev()) {
+                let above = row > 0 && self.is_settled(x, row - 1);
+                let at = Coordinate { x, y: row };
+                if let Some(cell) = self.settled.get_mut(at) {
+                    *cell = above;
+                }
+            }
+            self.lines += 1;
+        }
+    }
+
+    fn fall(&mut self, piece: Piece) {
+        if !self.try_move(piece.shifted(0, 1)) {
+            self.lock(piece);
+        }
+    }
+
+    fn command(&mut self, piece: Piece, cmd: Command) {
+        match cmd {
+            Command::Turn(Direction::West) => {
+                self.try_move(piece.shifted(-1, 0));
+            }
+            Command::Turn(Direction::East) => {
+                self.try_move(piece.shifted(1, 0));
+            }
+            Command::Turn(Direction::South)
+            | Command::Sprint(Direction::South) => self.fall(piece),
+            Command::Turn(Direction::North) => {
+                let mut landed = piece;
+                while self.fits(&landed.shifted(0, 1)) {
+                    landed = landed.shifted(0, 1);
+                }
+                self.lock(landed);
+            }
+            Command::RotateLeft => self.rotate(piece, false),
+            Command::RotateRight => self.rotate(piece, true),
+            _ => {}
+        }
+    }
+
+    // the cells that changed between `before` and what is shown now
+    fn diff(&self, before: &Grid<bool>) -> BlocksUpdate {
+        let (mut cleared, mut filled) = (Vec::new(), Vec::new());
+        for (at, full) in before.diff(&self.shown()) {
+            if full {
+                filled.push(at);
+            } else {
+                cleared.push(at);
+            }
+        }
+
+        BlocksUpdate::Tick { cleared, filled }
+    }
+}
+
+impl<'m, R: Rng + 'm> Stateful<'m> for FallingBlocks<R> {
+    type Cmd = Command;
+    type Update = BlocksUpdate;
+    type Init = Vec<BlocksUpdate>;
+    type Error = BlocksError;
+
+    fn initialize(&'m mut self) -> Self::Init {
+        let (width, height) = (self.settled.width(), self.settled.height());
+        let empty = Grid::filled(width, height, false);
+
+        vec![BlocksUpdate::Loaded { width, height }, self.diff(&empty)]
+    }
+
+    fn step(
+        &mut self,
+        cmd: Option<Command>,
+        tick: Tick,
+    ) -> Result<Option<BlocksUpdate>, BlocksError> {
+        match cmd {
+            Some(Command::Quit) => return Err(BlocksError::Quit),
+            Some(Command::Restart) => return Err(BlocksError::Restart),
+            _ => {}
+        }
+
+        match self.phase {
+            Phase::Falling => {}
+            Phase::Ending { won } => {
+                self.phase = Phase::Ended;
+                return Ok(Some(BlocksUpdate::Finished { won }));
+            }
+            Phase::Ended if cmd.is_some() => return Err(BlocksError::Over),
+            Phase::Ended => return Ok(None),
+        }
+
+        if cmd == Some(Command::Pause) {
+            self.paused = !self.paused;
+        }
+        if self.paused {
+            return Ok(None);
+        }
+
+        let before = self.shown();
+        if let (Some(piece), Some(cmd)) = (self.piece, cmd) {
+            self.command(piece, cmd);
+        }
+
+        if tick % FALL_EVERY == FALL_EVERY - 1 {
+            if let Some(piece) = self.piece {
+                self.fall(piece);
+            }
+        }
+
+        match self.diff(&before) {
+            BlocksUpdate::Tick {
+                ref cleared,
+                ref filled,
+            } if cleared.is_empty() && filled.is_empty() => Ok(None),
+            update => Ok(Some(update)),
+        }
+    }
+
+    fn tear_down(&mut self) {
+        let (width, height) = (self.settled.width(), self.settled.height());
+        self.settled = Grid::filled(width, height, false);
+        self.lines = 0;
+        self.paused = false;
+        self.phase = Phase::Falling;
+        self.spawn();
+    }
+}
+
+/// Draws an update in a single frame, blocks snap from row to row
+pub struct BlocksDraw(Option<BlocksUpdate>);
+
+impl<E: DrawGrid> IncrRender<E> for BlocksDraw {
+    type Patch = BlocksUpdate;
+
+    fn new_patch(update: BlocksUpdate, _ctx: FrameCtx) -> Self {
+        BlocksDraw(Some(update))
+    }
+
+    fn render(&mut self, env: &mut E) -> Option<()> {
+        let full = UnitInterval::max_value();
+        let dir = Direction::East;
+
+        match self.0.take()? {
+            BlocksUpdate::Loaded { width, height } => {
+                env.clear();
+                env.setup(TILE_SIZE as SmallNat, width, height);
+            }
+            BlocksUpdate::Tick { cleared, filled } => {
+                for at in cleared {
+                    env.clear_tile(at.x, at.y, dir, full);
+                }
+                for at in filled {
+                    env.fill_tile(at.x, at.y, dir, full);
+                }
+            }
+            // lost or won, the board tells which
+            BlocksUpdate::Finished { .. } => env.show_game_over(),
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use world::FakeRng;
+
+    // every piece an `I`
+    fn blocks(
+        width: SmallNat,
+        height: SmallNat,
+        goal: u32,
+    ) -> FallingBlocks<FakeRng> {
+        let rng = FakeRng::new(vec![0; 16]);
+        FallingBlocks::new(width, height, goal, rng)
+    }
+
+    fn at(x: SmallNat, y: SmallNat) -> Coordinate {
+        Coordinate { x, y }
+    }
+
+    #[test]
+    fn test_rotation_kicks_off_the_top() {
+        let mut blocks = blocks(6, 6, 10);
+
+        let update = blocks.step(Some(Command::RotateRight), 0).unwrap();
+        assert_eq!(
+            update,
+            Some(BlocksUpdate::Tick {
+                cleared: vec![at(1, 0), at(3, 0), at(4, 0)],
+                filled: vec![at(2, 1), at(2, 2), at(2, 3)],
+            })
+        );
+
+        // turned back it is level with the row it was kicked down to
+        blocks.step(Some(Command::RotateLeft), 0).unwrap();
+        let shown = blocks.shown();
+        assert!((1..5).all(|x| shown.get(at(x, 1)) == Some(&true)));
+    }
+
+    #[test]
+    fn test_clearing_the_goal_wins() {
+        let mut blocks = blocks(4, 4, 1);
+
+        blocks.step(Some(Command::Turn(Direction::North)), 0).unwrap();
+        assert_eq!(blocks.lines(), 1);
+        assert_eq!(
+            blocks.step(None, 0),
+            Ok(Some(BlocksUpdate::Finished { won: true }))
+        );
+        assert_eq!(blocks.step(None, 0), Ok(None));
+        assert_eq!(
+            blocks.step(Some(Command::Turn(Direction::East)), 0),
+            Err(BlocksError::Over)
+        );
+    }
+
+    #[test]
+    fn test_no_room_at_the_top_loses() {
+        let mut blocks = blocks(5, 2, 10);
+        let drop = Some(Command::Turn(Direction::North));
+
+        blocks.step(drop, 0).unwrap();
+        blocks.step(drop, 0).unwrap();
+        assert_eq!(blocks.lines(), 0);
+        assert_eq!(
+            blocks.step(None, 0),
+            Ok(Some(BlocksUpdate::Finished { won: false }))
+        );
+
+        blocks.tear_down();
+        assert!(blocks.step(None, 0).is_ok());
+        assert_eq!(blocks.phase, Phase::Falling);
+    }
+}
@@ -0,0 +1,171 @@
+use alloc::collections::{BinaryHeap, VecDeque};
+use alloc::vec::Vec;
+use std::cmp::Reverse;
+
+use data::{Coordinate, Direction, Grid};
+use world::WorldView;
+
+use super::{nearest_open, Policy};
+
+/// Takes the shortest way to the food, found with A*, as long as the snake
+/// could still reach its tail from there once it ate. Otherwise it follows
+/// its tail, which always leaves it a way out, until the way to the food is
+/// safe.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct AStar;
+
+impl Policy for AStar {
+    fn decide(&mut self, view: &WorldView) -> Direction {
+        let body: VecDeque<Coordinate> =
+            view.snake().body().iter().cloned().collect();
+
+        let to_food = view.food().and_then(|food| shortest_way(view, food));
+        if let Some(way) = to_food {
+            if tail_reachable_after(view, &body, &way) {
+                return way[0];
+            }
+        }
+
+        // stepping straight onto the tail crashes, it only moves after the
+        // head
+        let tail = view.snake().tail();
+        match shortest_way(view, tail) {
+            Some(ref way) if way.len() > 1 => way[0],
+            _ => nearest_open(view, None).unwrap_or_else(|| view.heading()),
+        }
+    }
+}
+
+// the moves of the shortest way from the head to `goal` over open tiles,
+// `goal` itself open or not
+fn shortest_way(view: &WorldView, goal: Coordinate) -> Option<Vec<Direction>> {
+    let (width, height) = (view.width(), view.height());
+    let (head, back) = (view.head(), view.heading().opposite());
+
+    let mut cost = Grid::filled(width, height, u32::max_value());
+    let mut came = Grid::filled(width, height, None);
+    let mut open = BinaryHeap::new();
+
+    if let Some(at) = cost.get_mut(head) {
+        *at = 0;
+    }
+    open.push(Reverse((view.distance(head, goal), 0, head.x, head.y)));
+
+    while let Some(Reverse((_, steps, x, y))) = open.pop() {
+        let at = Coordinate { x, y };
+        if at == goal {
+            return Some(way_back(view, &came, head, goal));
+        }
+        if cost.get(at).map_or(true, |&best| steps > best) {
+            continue;
+        }
+
+        let moves = view.neighbors(at).filter(|&(dir, _)| {
+            // the snake can't turn back on its first move
+            at != head || dir != back
+        });
+        for (dir, next) in moves {
+            if next != goal && !view.is_open(next) {
+                continue;
+            }
+
+            let better = cost.get(next).map_or(false, |&best| steps + 1 < best);
+            if !better {
+                continue;
+            }
+            if let Some(best) = cost.get_mut(next) {
+                *best = steps + 1;
+            }
+            if let Some(from) = came.get_mut(next) {
+                *from = Some(dir);
+            }
+            let estimate = steps + 1 + view.distance(next, goal);
+            open.push(Reverse((estimate, steps + 1, next.x, next.y)));
+        }
+    }
+    None
+}
+
+// the moves from `from` to `to` that `came` took, following it back from
+// `to`
+fn way_back(
+    view: &WorldView,
+    came: &Grid<Option<Direction>>,
+    from: Coordinate,
+    to: Coordinate,
+) -> Vec<Direction> {
+    let mut way = Vec::new();
+    let mut at = to;
+    while at != from {
+        let dir = match came.get(at) {
+            Some(&Some(dir)) => dir,
+            _ => break,
+        };
+        way.push(dir);
+        at = match view.ahead(at, dir.opposite()) {
+            Some(previous) => previous,
+            None => break,
+        };
+    }
+    way.reverse();
+    way
+}
+
+// whether the snake, `body` head first, could still reach its tail once it
+// went `way` and ate the food at the end of it
+fn tail_reachable_after(
+    view: &WorldView,
+    body: &VecDeque<Coordinate>,
+    way: &[Direction],
+) -> bool {
+    let (width, height) = (view.width(), view.height());
+    let mut body = body.clone();
+
+    for (i, &dir) in way.iter().enumerate() {
+        let head = match body.front().and_then(|&at| view.ahead(at, dir)) {
+            Some(head) => head,
+            None => return false,
+        };
+        body.push_front(head);
+        // the food is at the end, the snake grows there
+        if i + 1 < way.len() {
+            body.pop_back();
+        }
+    }
+
+    let mut taken = Grid::filled(width, height, false);
+    for &at in &body {
+        if let Some(tile) = taken.get_mut(at) {
+            *tile = true;
+        }
+    }
+    let (head, tail) = match (body.front(), body.back()) {
+        (Some(&head), Some(&tail)) => (head, tail),
+        _ => return false,
+    };
+
+    // the tiles the snake left are open again, whatever is on the board
+    let open = |at: Coordinate| {
+        let left = view.block(at).snake().is_some();
+        !taken.get(at).cloned().unwrap_or(true) && (left || view.is_open(at))
+    };
+
+    let mut seen = Grid::filled(width, height, false);
+    let mut frontier = VecDeque::new();
+    frontier.push_back(head);
+    while let Some(at) = frontier.pop_front() {
+        for (_, next) in view.neighbors(at) {
+            if next == tail && at != head {
+                return true;
+            }
+            let fresh = seen.get(next).map_or(false, |&visited| !visited);
+            if fresh && open(next) {
+                if let Some(tile) = seen.get_mut(next) {
+                    *tile = true;
+                }
+                frontier.push_back(next);
+            }
+        }
+    }
+    false
+}
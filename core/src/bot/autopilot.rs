@@ -0,0 +1,134 @@
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use std::cell::{Cell, RefCell};
+
+use data::Direction;
+use world::WorldView;
+
+use super::Policy;
+
+/// A bot at the wheel of a snake while it is engaged, shared between the
+/// world it steers (see `World::set_autopilot` and `World::add_snake`) and
+/// whoever engages it
+#[derive(Clone)]
+pub struct Autopilot {
+    policy: Rc<RefCell<Box<Policy>>>,
+    engaged: Rc<Cell<bool>>,
+}
+
+impl Autopilot {
+    pub fn new(policy: Box<Policy>) -> Self {
+        Autopilot {
+            policy: Rc::new(RefCell::new(policy)),
+            engaged: Rc::new(Cell::new(false)),
+        }
+    }
+
+    pub fn engage(&self, engaged: bool) {
+        self.engaged.set(engaged);
+    }
+
+    pub fn is_engaged(&self) -> bool {
+        self.engaged.get()
+    }
+
+    /// The move of the snake seen in `view`, none when disengaged
+    pub fn steer(&self, view: &WorldView) -> Option<Direction> {
+        if self.is_engaged() {
+            Some(self.policy.borrow_mut().decide(view))
+        } else {
+            None
+        }
+    }
+}
+
+/// What the host of an `AttractMode` does on a frame
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Attract {
+    /// Starts a demo game, with word that any key plays instead
+    Launch,
+    /// Starts another demo game, the last one is over
+    Again,
+    /// Starts a game for the player, who just pressed a key
+    Stop,
+}
+
+/// Plays demo games with an `Autopilot` once nobody has pressed a key for
+/// `after` frames while the game is over (or yet to start), one after the
+/// other until somebody does
+pub struct AttractMode {
+    autopilot: Autopilot,
+    after: u32,
+    // frames the game over screen of a demo game stays up
+    between: u32,
+    // frames in a row the game was over
+    over_for: u32,
+}
+
+impl AttractMode {
+    pub fn new(autopilot: Autopilot, after: u32, between: u32) -> Self {
+        AttractMode {
+            autopilot,
+            after,
+            between,
+            over_for: 0,
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.autopilot.is_engaged()
+    }
+
+    /// Counts a frame `idle` frames after the last key, as
+    /// `Schedule::idle_frames` counts them, on which the game is `over` or
+    /// not, engaging or disengaging the autopilot for what it returns
+    pub fn frame(&mut self, idle: u32, over: bool) -> Option<Attract> {
+        self.over_for = if over { self.over_for.saturating_add(1) } else { 0 };
+
+        let attract = if self.is_running() {
+            if idle == 0 {
+                Attract::Stop
+            } else if self.over_for >= self.between {
+                Attract::Again
+            } else {
+                return None;
+            }
+        } else if over && idle >= self.after {
+            Attract::Launch
+        } else {
+            return None;
+        };
+
+        self.autopilot.engage(attract != Attract::Stop);
+        self.over_for = 0;
+        Some(attract)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bot::Greedy;
+
+    #[test]
+    fn test_demo_runs_until_a_key_is_pressed() {
+        let autopilot = Autopilot::new(Box::new(Greedy));
+        let mut attract = AttractMode::new(autopilot.clone(), 3, 2);
+
+        // a game being played, or a key pressed on the game over screen
+        assert_eq!(attract.frame(5, false), None);
+        assert_eq!(attract.frame(0, true), None);
+        assert_eq!(attract.frame(2, true), None);
+        assert_eq!(attract.frame(3, true), Some(Attract::Launch));
+        assert!(autopilot.is_engaged());
+
+        // the demo game plays, and is over
+        assert_eq!(attract.frame(4, false), None);
+        assert_eq!(attract.frame(5, true), None);
+        assert_eq!(attract.frame(6, true), Some(Attract::Again));
+
+        assert_eq!(attract.frame(0, false), Some(Attract::Stop));
+        assert!(!autopilot.is_engaged());
+        assert_eq!(attract.frame(1, false), None);
+    }
+}
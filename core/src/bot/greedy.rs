@@ -0,0 +1,16 @@
+use data::Direction;
+use world::WorldView;
+
+use super::{nearest_open, Policy};
+
+/// Heads for the food by the shortest way, ignoring whatever is in between,
+/// and only dodges what is right ahead: it is easily trapped, as a bot for
+/// beginners should be
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Greedy;
+
+impl Policy for Greedy {
+    fn decide(&mut self, view: &WorldView) -> Direction {
+        nearest_open(view, view.food()).unwrap_or_else(|| view.heading())
+    }
+}
@@ -0,0 +1,81 @@
+use data::{Coordinate, Direction, SmallNat};
+use world::WorldView;
+
+use super::{nearest_open, Policy};
+
+/// Goes round a cycle through every tile of the board, which never crashes
+/// into the snake however long it gets, at the cost of taking the long way
+/// to each food. A board with odd sides has no such cycle, the bot plays
+/// like `Greedy` there, as it does off the cycle or with obstacles in its
+/// way.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Hamiltonian;
+
+impl Policy for Hamiltonian {
+    fn decide(&mut self, view: &WorldView) -> Direction {
+        let head = view.head();
+        let along = cycle(view.width(), view.height(), head)
+            .filter(|&dir| dir != view.heading().opposite())
+            .filter(|&dir| {
+                view.ahead(head, dir).map_or(false, |at| view.is_open(at))
+            });
+
+        along
+            .or_else(|| nearest_open(view, view.food()))
+            .unwrap_or_else(|| view.heading())
+    }
+}
+
+/// The move from `at` along a cycle through every tile of a `width` x
+/// `height` board that doesn't cross its edges, none if the board has no
+/// even side to make one with
+pub(super) fn cycle(
+    width: SmallNat,
+    height: SmallNat,
+    at: Coordinate,
+) -> Option<Direction> {
+    if height % 2 == 0 && width >= 2 {
+        Some(along_rows(at.x, at.y, width, height))
+    } else if width % 2 == 0 && height >= 2 {
+        // the same cycle on the board turned on its side
+        Some(match along_rows(at.y, at.x, height, width) {
+            Direction::East => Direction::South,
+            Direction::South => Direction::East,
+            Direction::West => Direction::North,
+            Direction::North => Direction::West,
+        })
+    } else {
+        None
+    }
+}
+
+// east along the top row, back and forth along the rows under it but for
+// the first column, and up the first column to the start, on a board of an
+// even `height`
+fn along_rows(
+    x: SmallNat,
+    y: SmallNat,
+    width: SmallNat,
+    height: SmallNat,
+) -> Direction {
+    if y == 0 {
+        if x + 1 < width {
+            Direction::East
+        } else {
+            Direction::South
+        }
+    } else if x == 0 {
+        Direction::North
+    } else if y % 2 == 1 {
+        // the last row is an odd one, it leads on to the first column
+        if x > 1 || y + 1 == height {
+            Direction::West
+        } else {
+            Direction::South
+        }
+    } else if x + 1 < width {
+        Direction::East
+    } else {
+        Direction::South
+    }
+}
@@ -0,0 +1,92 @@
+//! Bots that steer a snake: the autopilot of the player's snake and the
+//! opponents of versus games. Each `Policy` picks a move from what it sees
+//! of the world, the same way whatever plays it.
+use alloc::boxed::Box;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use data::{Coordinate, Direction};
+use world::WorldView;
+
+pub use self::astar::AStar;
+pub use self::autopilot::{Attract, AttractMode, Autopilot};
+pub use self::greedy::Greedy;
+pub use self::hamiltonian::Hamiltonian;
+pub use self::random::Random;
+
+mod astar;
+mod autopilot;
+mod greedy;
+mod hamiltonian;
+mod random;
+#[cfg(test)]
+mod tests;
+
+/// Picks the moves of a bot's snake
+pub trait Policy {
+    /// Where the snake heads on its next move, given the world as it is
+    /// before the move
+    fn decide(&mut self, view: &WorldView) -> Direction;
+}
+
+impl<P: Policy + ?Sized> Policy for Box<P> {
+    fn decide(&mut self, view: &WorldView) -> Direction {
+        (**self).decide(view)
+    }
+}
+
+/// The built-in policies, for picking one by name or number
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Strategy {
+    Greedy = 0,
+    AStar = 1,
+    Hamiltonian = 2,
+    Random = 3,
+}
+
+impl Strategy {
+    pub fn from_u8(n: u8) -> Option<Self> {
+        match n {
+            0 => Some(Strategy::Greedy),
+            1 => Some(Strategy::AStar),
+            2 => Some(Strategy::Hamiltonian),
+            3 => Some(Strategy::Random),
+            _ => None,
+        }
+    }
+
+    /// The strategy called `name` in lower case, as the binaries take them
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "greedy" => Some(Strategy::Greedy),
+            "astar" => Some(Strategy::AStar),
+            "hamiltonian" => Some(Strategy::Hamiltonian),
+            "random" => Some(Strategy::Random),
+            _ => None,
+        }
+    }
+
+    /// A policy playing the strategy, `seed` seeding the ones that roll
+    /// dice
+    pub fn policy(self, seed: u32) -> Box<Policy> {
+        match self {
+            Strategy::Greedy => Box::new(Greedy),
+            Strategy::AStar => Box::new(AStar),
+            Strategy::Hamiltonian => Box::new(Hamiltonian),
+            Strategy::Random => Box::new(Random::new(seed)),
+        }
+    }
+}
+
+// the move onto an open tile nearest to `target`, if there is one
+fn nearest_open(
+    view: &WorldView,
+    target: Option<Coordinate>,
+) -> Option<Direction> {
+    view.moves()
+        .filter(|&(_, at)| view.is_open(at))
+        .min_by_key(|&(_, at)| target.map_or(0, |to| view.distance(at, to)))
+        .map(|(dir, _)| dir)
+}
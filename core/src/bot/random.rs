@@ -0,0 +1,39 @@
+use alloc::vec::Vec;
+
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+
+use data::Direction;
+use replay::rng_seed;
+use world::WorldView;
+
+use super::Policy;
+
+/// Wanders onto any open tile next to the head, with equal odds, and goes
+/// on ahead when there is none
+pub struct Random {
+    rng: SmallRng,
+}
+
+impl Random {
+    pub fn new(seed: u32) -> Self {
+        Random {
+            rng: SmallRng::from_seed(rng_seed(seed)),
+        }
+    }
+}
+
+impl Policy for Random {
+    fn decide(&mut self, view: &WorldView) -> Direction {
+        let open: Vec<_> = view
+            .moves()
+            .filter(|&(_, at)| view.is_open(at))
+            .map(|(dir, _)| dir)
+            .collect();
+
+        match self.rng.choose(&open) {
+            Some(&dir) => dir,
+            None => view.heading(),
+        }
+    }
+}
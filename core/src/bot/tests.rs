@@ -0,0 +1,154 @@
+use rand::rngs::SmallRng;
+
+use super::hamiltonian::cycle;
+use super::*;
+use data::{Bounding, BoundingBehavior, Coordinate, Wrapping};
+use world::{UpdateError, World, WorldUpdate};
+
+// plays `policy` on `world` until it ate `food` or `ticks` went by, the
+// food it ate and the crash that ended its game, if one did
+fn play<P: Policy, BB: BoundingBehavior>(
+    policy: &mut P,
+    world: &mut World<SmallRng, BB>,
+    food: usize,
+    ticks: u32,
+) -> (usize, Option<UpdateError>) {
+    let mut eaten = 0;
+    for _ in 0..ticks {
+        if eaten >= food {
+            break;
+        }
+
+        let dir = policy.decide(&world.view());
+        match world.tick(Some(dir)) {
+            Ok(delta) => {
+                eaten += delta
+                    .changes()
+                    .filter(|u| matches!(*u, WorldUpdate::FoodEaten { .. }))
+                    .count();
+            }
+            Err(err) => return (eaten, Some(err)),
+        }
+    }
+    (eaten, None)
+}
+
+#[test]
+fn test_greedy_heads_for_the_food() {
+    let mut world: World<SmallRng, Wrapping> = World::from_ascii(indoc!(
+        "
+        ........
+        .>>.....
+        ........
+        ....*...
+        ........"
+    ));
+    assert_eq!(play(&mut Greedy, &mut world, 1, 4), (1, None));
+
+    // a wall right ahead is dodged
+    let world: World<SmallRng, Bounding> = World::from_ascii(indoc!(
+        "
+        ......
+        .>>#.*
+        ......"
+    ));
+    assert_ne!(Greedy.decide(&world.view()), Direction::East);
+}
+
+#[test]
+fn test_astar_goes_round_walls() {
+    let mut world: World<SmallRng, Bounding> = World::from_ascii(indoc!(
+        "
+        .........
+        .>>..#*..
+        .....#...
+        ........."
+    ));
+    assert_eq!(play(&mut AStar, &mut world, 1, 6), (1, None));
+}
+
+#[test]
+fn test_astar_keeps_a_way_out() {
+    // the food is at the end of a dead end too narrow to turn round in,
+    // the snake follows its tail round the top instead
+    let world: World<SmallRng, Bounding> = World::from_ascii(indoc!(
+        "
+        ..........
+        .>>>>>>>..
+        #########.
+        *........."
+    ));
+    assert_eq!(AStar.decide(&world.view()), Direction::North);
+
+    // and it never goes for food in a pocket it couldn't get out of
+    let mut world: World<SmallRng, Bounding> = World::from_ascii(indoc!(
+        "
+        ..........
+        .......#..
+        .>>>>>>#*#
+        .......###"
+    ));
+    assert_eq!(play(&mut AStar, &mut world, 1, 40), (0, None));
+}
+
+#[test]
+fn test_astar_and_hamiltonian_survive() {
+    let board = indoc!(
+        "
+        >>>.....
+        ........
+        ........
+        .....*..
+        ........
+        ........"
+    );
+
+    let mut world: World<SmallRng, Bounding> = World::from_ascii(board);
+    assert_eq!(play(&mut AStar, &mut world, 5, 2000), (5, None));
+
+    let mut world: World<SmallRng, Bounding> = World::from_ascii(board);
+    assert_eq!(play(&mut Hamiltonian, &mut world, 8, 2000), (8, None));
+}
+
+#[test]
+fn test_hamiltonian_cycles_cover_the_board() {
+    for &(width, height) in &[(4, 4), (5, 4), (4, 5), (2, 3)] {
+        let mut at = Coordinate { x: 0, y: 0 };
+        let mut seen = vec![];
+        loop {
+            seen.push(at);
+            let dir = cycle(width, height, at).unwrap();
+            let next = at.move_towards(dir).bound_inside(width, height);
+            at = next.unwrap();
+            if at == (Coordinate { x: 0, y: 0 }) {
+                break;
+            }
+            assert!(!seen.contains(&at), "{:?} again", at);
+        }
+        assert_eq!(seen.len(), width as usize * height as usize);
+    }
+    assert_eq!(cycle(3, 5, Coordinate { x: 1, y: 1 }), None);
+}
+
+#[test]
+fn test_random_only_takes_open_tiles() {
+    let world: World<SmallRng, Bounding> = World::from_ascii(indoc!(
+        "
+        ..#..
+        .>>#.
+        ....."
+    ));
+
+    for seed in 0..20 {
+        let mut random = Random::new(seed);
+        assert_eq!(random.decide(&world.view()), Direction::South);
+    }
+
+    let world: World<SmallRng, Wrapping> =
+        World::from_ascii(".....\n.>>..\n.....");
+    let decisions = |seed| {
+        let mut random = Strategy::Random.policy(seed);
+        (0..8).map(|_| random.decide(&world.view())).collect::<Vec<_>>()
+    };
+    assert_eq!(decisions(7), decisions(7));
+}
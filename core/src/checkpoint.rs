@@ -0,0 +1,151 @@
+use data::Command;
+use history::Rewind;
+use system::{Stateful, Tick};
+use world::{TickDelta, UpdateError, WorldUpdate};
+
+/// Wraps the world of a campaign level with checkpoints: crossing one
+/// takes a snapshot, and a crash after that ends the game as a restart
+/// would, the game that follows carrying on from the last checkpoint
+/// rather than from the start of the level.
+pub struct Checkpoints<M: Rewind> {
+    model: M,
+    last: Option<M::Snapshot>,
+    // the snake crashed past a checkpoint, `tear_down` puts it back
+    restoring: bool,
+}
+
+impl<M: Rewind> Checkpoints<M> {
+    pub fn new(model: M) -> Self {
+        Checkpoints {
+            model,
+            last: None,
+            restoring: false,
+        }
+    }
+
+    /// Whether a crash goes back to a checkpoint
+    pub fn has_checkpoint(&self) -> bool {
+        self.last.is_some()
+    }
+}
+
+impl<'m, M> Stateful<'m> for Checkpoints<M>
+where
+    M: Rewind
+        + Stateful<'m, Cmd = Command, Update = TickDelta, Error = UpdateError>,
+{
+    type Cmd = Command;
+    type Init = M::Init;
+    type Update = TickDelta;
+    type Error = UpdateError;
+
+    fn initialize(&'m mut self) -> Self::Init {
+        self.model.initialize()
+    }
+
+    fn step(
+        &mut self,
+        cmd: Option<Command>,
+        tick: Tick,
+    ) -> Result<Option<TickDelta>, UpdateError> {
+        let update = match self.model.step(cmd, tick) {
+            Err(err) if err.is_crash() && self.last.is_some() => {
+                info!("back to the last checkpoint after {:?}", err);
+                self.restoring = true;
+                return Err(UpdateError::Restart);
+            }
+            update => update?,
+        };
+
+        if let Some(ref delta) = update {
+            let reached = |u: WorldUpdate| match u {
+                WorldUpdate::CheckpointReached { .. } => true,
+                _ => false,
+            };
+            let died = |u: WorldUpdate| match u {
+                WorldUpdate::Died { .. } | WorldUpdate::Won => true,
+                _ => false,
+            };
+
+            // a snapshot of a dying snake would only die again
+            if delta.changes().any(reached) && !delta.changes().any(died) {
+                self.last = Some(self.model.snapshot());
+            }
+        }
+
+        Ok(update)
+    }
+
+    fn tear_down(&mut self) {
+        if ::std::mem::replace(&mut self.restoring, false) {
+            if let Some(ref snapshot) = self.last {
+                return self.model.restore(snapshot);
+            }
+        }
+
+        self.model.tear_down();
+        self.last = None;
+    }
+}
+
+impl<M: Rewind> Rewind for Checkpoints<M> {
+    type Snapshot = M::Snapshot;
+
+    fn snapshot(&self) -> M::Snapshot {
+        self.model.snapshot()
+    }
+
+    fn restore(&mut self, snapshot: &M::Snapshot) {
+        self.restoring = false;
+        self.model.restore(snapshot);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    use rand::rngs::SmallRng;
+
+    use data::{Block, Direction, Wrapping};
+    use world::World;
+
+    #[test]
+    fn test_crash_goes_back_to_the_checkpoint() {
+        let world: World<SmallRng, Wrapping> = World::from_ascii(indoc!(
+            "
+            .....
+            .>>c#
+            ....."
+        ));
+        let mut level = Checkpoints::new(world);
+        for _ in level.initialize() {}
+
+        // onto the checkpoint, then into the wall
+        let delta = Stateful::step(&mut level, None, 0).unwrap().unwrap();
+        assert!(delta.changes().any(|u| match u {
+            WorldUpdate::CheckpointReached { .. } => true,
+            _ => false,
+        }));
+        assert!(level.has_checkpoint());
+
+        Stateful::step(&mut level, None, 0).unwrap();
+        assert_matches!(
+            Stateful::step(&mut level, None, 0),
+            Err(UpdateError::Restart)
+        );
+        Stateful::tear_down(&mut level);
+
+        // the level carries on with the head on the checkpoint
+        let loaded: Vec<TickDelta> = level.initialize().collect();
+        assert_matches!(
+            loaded.last().unwrap().effects[0],
+            Some(WorldUpdate::SetBlock {
+                block: Block::from(Direction::East),
+                at,
+            }) if at.x == 3 && at.y == 1
+        );
+        assert!(level.has_checkpoint());
+    }
+}
@@ -0,0 +1,265 @@
+//! The settings of a game, which the browser's `GameConfig` fills in from
+//! a js object and, with the `config` feature, the native frontends and
+//! `snake-sim` read from a toml or json file.
+//!
+//! ```toml
+//! width = 32
+//! height = 16
+//! lives = 3
+//!
+//! [rules]
+//! wrap = false
+//! growth = 2
+//!
+//! [theme]
+//! palette = "ColorBlind"
+//! ```
+#[cfg(feature = "config")]
+use std::fmt;
+#[cfg(feature = "config")]
+use std::fs;
+#[cfg(feature = "config")]
+use std::io;
+#[cfg(feature = "config")]
+use std::path::Path;
+
+use rand::Rng;
+#[cfg(feature = "config")]
+use serde_json;
+#[cfg(feature = "config")]
+use toml;
+
+use constants::{HAZARD_WARNING, LASER_WARNING, MAGNET_TICKS};
+use data::{BoundingBehavior, ConfigError, Grid, SmallNat, Wrapping};
+use replay::{initial_world, lives};
+use theme::Theme;
+use world::{Arena, Countdown, Hazards, Lasers, PowerUps, Rules, World};
+use SmallRng;
+
+/// A game as the browser or a config file sets it up, any field left out
+/// as in a game of the browser without a config. Unknown fields are
+/// refused, so that a misspelt one isn't silently ignored.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "config", derive(Deserialize))]
+#[cfg_attr(feature = "config", serde(default, deny_unknown_fields))]
+pub struct Config {
+    // tiles, neither side longer than `MAX_BOARD_SIDE`
+    pub width: SmallNat,
+    pub height: SmallNat,
+    pub seed: u32,
+    // lives before the game is over, a single one with 0 or 1
+    pub lives: u32,
+    // the snake stops short of its first crash, see `World::set_grace`
+    pub grace: bool,
+    // see `Arena`, 0 for a board that doesn't shrink
+    pub shrink_every: u32,
+    // see `Countdown`, 0 for an untimed game
+    pub time_budget: u32,
+    pub time_bonus: u32,
+    // ticks between obstacles, lasers and magnets showing up, see
+    // `Hazards`, `Lasers` and `PowerUps`, 0 for none
+    pub hazard_every: u32,
+    pub laser_every: u32,
+    pub magnet_every: u32,
+    pub rules: Rules,
+    pub theme: Theme,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            width: 64,
+            height: 32,
+            // same rng state as the original hard coded [123; 16] seed
+            seed: 0x7b7b_7b7b,
+            lives: 0,
+            grace: false,
+            shrink_every: 0,
+            time_budget: 0,
+            time_bonus: 0,
+            hazard_every: 0,
+            laser_every: 0,
+            magnet_every: 0,
+            rules: Rules::default(),
+            theme: Theme::default(),
+        }
+    }
+}
+
+/// Why a config file couldn't be loaded
+#[cfg(feature = "config")]
+#[derive(Debug)]
+pub enum FileError {
+    Io(io::Error),
+    // not toml, or not the fields of a `Config`
+    Toml(toml::de::Error),
+    Json(serde_json::Error),
+    Invalid(ConfigError),
+}
+
+#[cfg(feature = "config")]
+impl fmt::Display for FileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            FileError::Io(ref err) => write!(f, "{}", err),
+            FileError::Toml(ref err) => write!(f, "invalid config: {}", err),
+            FileError::Json(ref err) => write!(f, "invalid config: {}", err),
+            FileError::Invalid(err) => write!(f, "invalid config: {}", err),
+        }
+    }
+}
+
+#[cfg(feature = "config")]
+impl Config {
+    pub fn from_toml(text: &str) -> Result<Self, FileError> {
+        toml::from_str::<Config>(text)
+            .map_err(FileError::Toml)?
+            .validate()
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, FileError> {
+        serde_json::from_str::<Config>(text)
+            .map_err(FileError::Json)?
+            .validate()
+    }
+
+    /// The config of the file at `path`, json if it is named so and toml
+    /// otherwise
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, FileError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path).map_err(FileError::Io)?;
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Config::from_json(&text),
+            _ => Config::from_toml(&text),
+        }
+    }
+
+    fn validate(self) -> Result<Self, FileError> {
+        self.check()
+            .map(|()| self)
+            .map_err(FileError::Invalid)
+    }
+}
+
+impl Config {
+    /// Whether a game of the config can be made at all
+    pub fn check(&self) -> Result<(), ConfigError> {
+        Grid::check_size(self.width, self.height)
+    }
+
+    /// A world of the config's board and rules, set up like `apply` does
+    pub fn world(&self) -> World<SmallRng, Wrapping> {
+        let mut world =
+            initial_world(self.width, self.height, self.seed, self.rules);
+        self.apply(&mut world);
+        world
+    }
+
+    /// Sets up `world` for the game of the config but for its board and
+    /// rules, which it was built with
+    pub fn apply<R: Rng, BB: BoundingBehavior>(
+        &self,
+        world: &mut World<R, BB>,
+    ) {
+        if self.shrink_every > 0 {
+            world.set_arena(Some(Arena::new(self.shrink_every)));
+        }
+        if self.time_budget > 0 {
+            let countdown = Countdown::new(self.time_budget, self.time_bonus);
+            world.set_countdown(Some(countdown));
+        }
+        if self.hazard_every > 0 {
+            let hazards = Hazards::new(self.hazard_every, HAZARD_WARNING);
+            world.set_hazards(Some(hazards));
+        }
+        if self.laser_every > 0 {
+            let lasers = Lasers::new(self.laser_every, LASER_WARNING);
+            world.set_lasers(Some(lasers));
+        }
+        if self.magnet_every > 0 {
+            let power_ups = PowerUps::new(self.magnet_every, MAGNET_TICKS);
+            world.set_power_ups(Some(power_ups));
+        }
+        world.set_grace(self.grace);
+        world.set_lives(lives(self.lives));
+    }
+}
+
+#[cfg(all(test, feature = "config"))]
+mod tests {
+    use super::*;
+    use constants::MAX_BOARD_SIDE;
+    use theme::Palette;
+
+    #[test]
+    fn test_config_from_toml_and_json() {
+        let config = Config::from_toml(indoc!(
+            "
+            width = 32
+            lives = 3
+
+            [rules]
+            wrap = false
+            growth = 2"
+        ))
+        .unwrap();
+        let rules = Rules {
+            wrap: false,
+            growth: 2,
+            ..Rules::default()
+        };
+        assert_eq!(
+            config,
+            Config {
+                width: 32,
+                lives: 3,
+                rules,
+                ..Config::default()
+            }
+        );
+
+        let json = r#"{"width": 32, "lives": 3, "rules": {"wrap": false,
+            "growth": 2}}"#;
+        assert_eq!(Config::from_json(json).unwrap(), config);
+    }
+
+    #[test]
+    fn test_config_themes_the_board() {
+        let config = Config::from_toml(indoc!(
+            "
+            [theme]
+            palette = \"ColorBlind\"
+            grid = false
+            team_colors = [\"red\"]"
+        ))
+        .unwrap();
+        assert_eq!(config.theme.palette, Palette::ColorBlind);
+        assert!(!config.theme.grid);
+        assert_eq!(config.theme.team_colors, vec!["red".to_string()]);
+        assert_eq!(config.theme.board, Theme::default().board);
+    }
+
+    #[test]
+    fn test_config_errors_name_what_is_wrong() {
+        let err = Config::from_toml("widht = 32").unwrap_err();
+        assert!(err.to_string().contains("unknown field `widht`"), "{}", err);
+
+        let err = Config::from_json(r#"{"rules": {"wrap": 1}}"#).unwrap_err();
+        assert!(err.to_string().contains("expected a boolean"), "{}", err);
+
+        let err = Config::from_toml("[theme]\npalette = 2").unwrap_err();
+        assert!(err.to_string().contains("palette"), "{}", err);
+
+        match Config::from_toml("width = 5000") {
+            Err(FileError::Invalid(ConfigError::BoardTooLarge { max })) => {
+                assert_eq!(max, MAX_BOARD_SIDE)
+            }
+            other => panic!("{:?}", other),
+        }
+        match Config::from_json(r#"{"height": 0}"#) {
+            Err(FileError::Invalid(ConfigError::InvalidSide)) => {}
+            other => panic!("{:?}", other),
+        }
+    }
+}
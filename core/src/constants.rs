@@ -0,0 +1,22 @@
+use data::SmallNat;
+
+pub const TILE_SIZE: u32 = 16;
+pub const ANIMATION_FRAME_COUNT: u8 = 8;
+// frames food and pickups take to shrink a little and grow back, see
+// `draw::entities`
+pub const PULSE_FRAMES: u8 = 48;
+pub const TURN_BUFFER_SIZE: usize = 4;
+pub const HOLD_FRAME_COUNT: u8 = 12;
+pub const RESPAWN_SHIELD: u32 = 12;
+// moves from the head within which a magnet pulls food
+pub const MAGNET_RADIUS: u32 = 5;
+// tiles along the longest side of a board, a grid of its square holds a
+// million blocks in Morton order
+pub const MAX_BOARD_SIDE: SmallNat = 1024;
+// snakes a world holds, the player's and its rivals and allies
+pub const MAX_SNAKES: usize = 4;
+// ticks a hazard blinks and a laser is shown before either strikes, and
+// that a magnet pulls food once taken, see `config::Config`
+pub const HAZARD_WARNING: u32 = 6;
+pub const LASER_WARNING: u32 = 8;
+pub const MAGNET_TICKS: u32 = 60;
@@ -0,0 +1,1234 @@
+use alloc::vec::Vec;
+use std::cmp::{max, min, Ordering};
+use std::convert::{From, Into};
+use std::fmt;
+use std::iter::FromIterator;
+use std::ops::{Index, IndexMut};
+
+use morton::deinterleave_morton;
+use rand::Rng;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use constants::{HOLD_FRAME_COUNT, MAX_BOARD_SIDE};
+use interleave::interleave;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Key {
+    code: u8,
+    // consecutive frames this key has been down before the current one
+    frames: u8,
+}
+
+impl Key {
+    pub fn none() -> Self {
+        Key { code: 0, frames: 0 }
+    }
+
+    pub fn code(self) -> u8 {
+        self.code
+    }
+
+    pub fn is_direction_key(self) -> bool {
+        let dir: Option<Direction> = self.into();
+        dir.is_some()
+    }
+
+    pub fn is_repeat(self) -> bool {
+        self.frames > 0
+    }
+
+    pub fn is_held(self) -> bool {
+        self.frames >= HOLD_FRAME_COUNT
+    }
+}
+
+/// Tells taps from holds, for inputs that report the currently pressed key
+/// once per frame
+#[derive(Debug, Copy, Clone)]
+pub struct KeyRepeat {
+    last: Key,
+}
+
+impl KeyRepeat {
+    pub fn new() -> Self {
+        KeyRepeat { last: Key::none() }
+    }
+
+    pub fn feed(&mut self, key: Key) -> Key {
+        let frames = if key.code != 0 && key.code == self.last.code {
+            self.last.frames.saturating_add(1)
+        } else {
+            0
+        };
+
+        self.last = Key {
+            code: key.code,
+            frames,
+        };
+        self.last
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Command {
+    Turn(Direction),
+    // direction key held down
+    Sprint(Direction),
+    Pause,
+    Restart,
+    DebugToggle,
+    Quit,
+    // quarter turns of a piece, for models with something to rotate
+    RotateLeft,
+    RotateRight,
+    // a diagonal key, which only steers snakes whose rules allow it
+    Diagonal(Direction8),
+}
+
+impl Command {
+    pub fn direction(self) -> Option<Direction> {
+        match self {
+            Command::Turn(dir) | Command::Sprint(dir) => Some(dir),
+            _ => None,
+        }
+    }
+}
+
+// exported to js as integers 0..=3 in declaration order
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Direction {
+    North = 0,
+    South = 1,
+    East = 2,
+    West = 3,
+}
+
+impl Direction {
+    pub fn from_u8(n: u8) -> Option<Self> {
+        match n {
+            0 => Some(Direction::North),
+            1 => Some(Direction::South),
+            2 => Some(Direction::East),
+            3 => Some(Direction::West),
+            _ => None,
+        }
+    }
+
+    // KeyboardEvent.keyCode of the matching arrow key
+    pub fn key_code(self) -> u8 {
+        match self {
+            Direction::West => 37,
+            Direction::North => 38,
+            Direction::East => 39,
+            Direction::South => 40,
+        }
+    }
+
+    pub fn opposite(self) -> Self {
+        match self {
+            Direction::North => Direction::South,
+            Direction::South => Direction::North,
+            Direction::East => Direction::West,
+            Direction::West => Direction::East,
+        }
+    }
+
+    /// A quarter turn counterclockwise
+    pub fn rotate_left(self) -> Self {
+        match self {
+            Direction::North => Direction::West,
+            Direction::South => Direction::East,
+            Direction::East => Direction::North,
+            Direction::West => Direction::South,
+        }
+    }
+
+    /// A quarter turn clockwise
+    pub fn rotate_right(self) -> Self {
+        self.opposite().rotate_left()
+    }
+
+    pub fn axis(self) -> Axis {
+        match self {
+            Direction::North | Direction::South => Axis::Vertical,
+            Direction::East | Direction::West => Axis::Horizontal,
+        }
+    }
+
+    /// The move of one tile this way, `y` growing towards the bottom
+    pub fn as_unit_vector(self) -> (i8, i8) {
+        match self {
+            Direction::North => (0, -1),
+            Direction::South => (0, 1),
+            Direction::East => (1, 0),
+            Direction::West => (-1, 0),
+        }
+    }
+
+    /// The way a move of `(dx, dy)` goes, by sign alone; none for a move
+    /// that goes nowhere or along both axes
+    pub fn from_delta(dx: i32, dy: i32) -> Option<Self> {
+        match (dx.signum(), dy.signum()) {
+            (0, -1) => Some(Direction::North),
+            (0, 1) => Some(Direction::South),
+            (1, 0) => Some(Direction::East),
+            (-1, 0) => Some(Direction::West),
+            _ => None,
+        }
+    }
+}
+
+/// The line a `Direction` goes along
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Axis {
+    Horizontal,
+    Vertical,
+}
+
+impl Axis {
+    /// The axis across this one
+    pub fn cross(self) -> Self {
+        match self {
+            Axis::Horizontal => Axis::Vertical,
+            Axis::Vertical => Axis::Horizontal,
+        }
+    }
+
+    /// The directions along this axis, the way coordinates grow last
+    pub fn directions(self) -> [Direction; 2] {
+        match self {
+            Axis::Horizontal => [Direction::West, Direction::East],
+            Axis::Vertical => [Direction::North, Direction::South],
+        }
+    }
+}
+
+/// One of the eight ways of a board with diagonal moves, clockwise from
+/// the top. A diagonal is a move along both axes at once.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Direction8 {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction8 {
+    /// The way `n` in declaration order, as replays encode it
+    pub fn from_u8(n: u8) -> Option<Self> {
+        match n {
+            0 => Some(Direction8::North),
+            1 => Some(Direction8::NorthEast),
+            2 => Some(Direction8::East),
+            3 => Some(Direction8::SouthEast),
+            4 => Some(Direction8::South),
+            5 => Some(Direction8::SouthWest),
+            6 => Some(Direction8::West),
+            7 => Some(Direction8::NorthWest),
+            _ => None,
+        }
+    }
+
+    /// The way of two arrows held together: the diagonal between them, the
+    /// arrow itself for one held twice, none for opposite arrows
+    pub fn from_chord(a: Direction, b: Direction) -> Option<Self> {
+        if a == b {
+            return Some(a.into());
+        }
+        let (vertical, horizontal) = match (a.axis(), b.axis()) {
+            (Axis::Vertical, Axis::Horizontal) => (a, b),
+            (Axis::Horizontal, Axis::Vertical) => (b, a),
+            _ => return None,
+        };
+
+        match (vertical, horizontal) {
+            (Direction::North, Direction::East) => Some(Direction8::NorthEast),
+            (Direction::South, Direction::East) => Some(Direction8::SouthEast),
+            (Direction::South, Direction::West) => Some(Direction8::SouthWest),
+            _ => Some(Direction8::NorthWest),
+        }
+    }
+
+    /// The move along each axis, vertical first; a straight move only has
+    /// the one
+    pub fn parts(self) -> (Direction, Option<Direction>) {
+        match self {
+            Direction8::North => (Direction::North, None),
+            Direction8::NorthEast => (Direction::North, Some(Direction::East)),
+            Direction8::East => (Direction::East, None),
+            Direction8::SouthEast => (Direction::South, Some(Direction::East)),
+            Direction8::South => (Direction::South, None),
+            Direction8::SouthWest => (Direction::South, Some(Direction::West)),
+            Direction8::West => (Direction::West, None),
+            Direction8::NorthWest => (Direction::North, Some(Direction::West)),
+        }
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self.parts().1.is_some()
+    }
+
+    pub fn opposite(self) -> Self {
+        match self.parts() {
+            (a, Some(b)) => {
+                // both halves are turned, they stay a diagonal
+                Direction8::from_chord(a.opposite(), b.opposite())
+                    .unwrap_or(self)
+            }
+            (a, None) => a.opposite().into(),
+        }
+    }
+
+    /// The move of one tile this way, `y` growing towards the bottom
+    pub fn as_unit_vector(self) -> (i8, i8) {
+        let (a, b) = self.parts();
+        let (ax, ay) = a.as_unit_vector();
+        let (bx, by) = b.map_or((0, 0), Direction::as_unit_vector);
+        (ax + bx, ay + by)
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(dir: Direction) -> Direction8 {
+        match dir {
+            Direction::North => Direction8::North,
+            Direction::South => Direction8::South,
+            Direction::East => Direction8::East,
+            Direction::West => Direction8::West,
+        }
+    }
+}
+
+/// Tells the snakes of a world apart
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct SnakeId(pub u8);
+
+/// The snake of the player, the one a world steps with its commands
+pub const PLAYER: SnakeId = SnakeId(0);
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Block<T = Direction> {
+    Empty,
+    // a tile of the snake of the id, heading the way it leaves it
+    Snake(T, SnakeId),
+    Food,
+    // a wall inside the board, e.g. where a shrinking arena closed in
+    Obstacle,
+    // a checkpoint of a campaign level, the snake crosses it
+    Checkpoint,
+    // an obstacle still blinking before it turns solid, see `Hazards`
+    Looming,
+    // a pickup the snake crosses to turn it on, see `PowerUps`
+    PowerUp(PowerUp),
+    OutOfBound,
+}
+
+impl<T> Block<T> {
+    pub fn is_empty(self) -> bool {
+        match self {
+            Block::Empty => true,
+            _ => false,
+        }
+    }
+
+    pub fn snake(self) -> Option<T> {
+        match self {
+            Block::Snake(s, _) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// The snake on the tile, if any
+    pub fn owner(self) -> Option<SnakeId> {
+        match self {
+            Block::Snake(_, id) => Some(id),
+            _ => None,
+        }
+    }
+
+    pub fn snake_or_err<E>(self, err: E) -> Result<T, E> {
+        match self {
+            Block::Snake(s, _) => Ok(s),
+            _ => Err(err),
+        }
+    }
+}
+
+pub type SmallNat = u16;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub struct Coordinate {
+    pub x: SmallNat,
+    pub y: SmallNat,
+}
+
+impl Coordinate {
+    pub fn move_towards(self, dir: Direction) -> UncheckedCoordinate {
+        let Coordinate { x, y } = self;
+        match dir {
+            Direction::North => UncheckedCoordinate::new(x, y.wrapping_sub(1)),
+            Direction::South => UncheckedCoordinate::new(x, y.wrapping_add(1)),
+            Direction::East => UncheckedCoordinate::new(x.wrapping_add(1), y),
+            Direction::West => UncheckedCoordinate::new(x.wrapping_sub(1), y),
+        }
+    }
+
+    /// The tile a move `dir` takes this one to, diagonals included
+    pub fn move_towards8(self, dir: Direction8) -> UncheckedCoordinate {
+        let (a, b) = dir.parts();
+        let moved = self.move_towards(a);
+        match b {
+            Some(b) => moved.inner.move_towards(b),
+            None => moved,
+        }
+    }
+
+    /// Whether a diagonal move `dir` from here squeezes between two tiles
+    /// for which `blocked` holds, crossing the line of tiles they are on
+    pub fn squeezes(
+        self,
+        dir: Direction8,
+        mut blocked: impl FnMut(UncheckedCoordinate) -> bool,
+    ) -> bool {
+        match dir.parts() {
+            (a, Some(b)) => {
+                blocked(self.move_towards(a)) && blocked(self.move_towards(b))
+            }
+            (_, None) => false,
+        }
+    }
+
+    #[inline(always)]
+    pub fn encode_usize(self) -> usize {
+        interleave(self.x, self.y) as usize
+    }
+    pub fn decode_usize(n: usize) -> Self {
+        let (x, y) = deinterleave_morton(n as u32);
+        Coordinate { x, y }
+    }
+
+    pub fn from_usizes(x: usize, y: usize) -> Self {
+        Coordinate {
+            x: x as SmallNat,
+            y: y as SmallNat,
+        }
+    }
+}
+
+/// The product order: a coordinate is below another if neither of its axes
+/// is above the other's, and the two are unordered if each is above on one
+/// axis. It is a partial order, and Morton indices (`encode_usize`) order
+/// coordinates the same way wherever it does order them.
+impl PartialOrd for Coordinate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self.x.cmp(&other.x), self.y.cmp(&other.y)) {
+            (Ordering::Equal, o) => Some(o),
+            (o, Ordering::Equal) => Some(o),
+            (ox, oy) if ox == oy => Some(ox),
+            _ => None,
+        }
+    }
+}
+
+/// What a power-up does while it is on
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum PowerUp {
+    /// Food near the head moves towards it
+    Magnet,
+}
+
+/// A whole row or column of the board
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Line {
+    Row(SmallNat),
+    Column(SmallNat),
+}
+
+impl Line {
+    pub fn contains(self, at: Coordinate) -> bool {
+        match self {
+            Line::Row(y) => at.y == y,
+            Line::Column(x) => at.x == x,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct UncheckedCoordinate {
+    inner: Coordinate,
+}
+impl UncheckedCoordinate {
+    #[inline(always)]
+    fn new(x: SmallNat, y: SmallNat) -> Self {
+        UncheckedCoordinate {
+            inner: Coordinate { x, y },
+        }
+    }
+
+    pub fn bound_inside(
+        self,
+        bound_width: SmallNat,
+        bound_height: SmallNat,
+    ) -> Option<Coordinate> {
+        if self.inner.x < bound_width && self.inner.y < bound_height {
+            Some(self.inner)
+        } else {
+            None
+        }
+    }
+
+    pub fn wrap_inside(
+        self,
+        bound_width: SmallNat,
+        bound_height: SmallNat,
+    ) -> Coordinate {
+        debug_assert!(bound_width > 0 && bound_height > 0);
+
+        let Coordinate { x, y } = self.inner;
+
+        Coordinate {
+            x: x.wrapping_add(bound_width) % bound_width,
+            y: y.wrapping_add(bound_height) % bound_height,
+        }
+    }
+
+    #[inline(always)]
+    pub fn inside<B: BoundingBehavior>(
+        self,
+        grid: &Grid,
+    ) -> Option<Coordinate> {
+        B::BOUND_FN(self, grid.width(), grid.height()).into()
+    }
+}
+
+type BoundFn<T> = fn(UncheckedCoordinate, SmallNat, SmallNat) -> T;
+
+/// Marker trait to decide how to unwrap an UncheckedCoordinate
+pub trait BoundingBehavior: Copy {
+    type Return: Into<Option<Coordinate>>;
+
+    const BOUND_FN: BoundFn<Self::Return>;
+    /// Whether coordinates past an edge come back on the other side
+    const WRAPS: bool;
+}
+
+#[derive(Copy, Clone)]
+pub struct Wrapping;
+#[derive(Copy, Clone)]
+pub struct Bounding;
+
+impl BoundingBehavior for Wrapping {
+    type Return = Coordinate;
+
+    const BOUND_FN: BoundFn<Coordinate> = UncheckedCoordinate::wrap_inside;
+    const WRAPS: bool = true;
+}
+impl BoundingBehavior for Bounding {
+    type Return = Option<Coordinate>;
+    const BOUND_FN: BoundFn<Option<Coordinate>> =
+        UncheckedCoordinate::bound_inside;
+    const WRAPS: bool = false;
+}
+
+/// Why a board can't be made as configured
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ConfigError {
+    // a side is longer than `max` tiles
+    BoardTooLarge { max: SmallNat },
+    // a side isn't a whole number of tiles, or is none at all
+    InvalidSide,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ConfigError::BoardTooLarge { max } => {
+                write!(f, "board is larger than {}x{} tiles", max, max)
+            }
+            ConfigError::InvalidSide => {
+                write!(f, "board sides must be a whole number of tiles")
+            }
+        }
+    }
+}
+
+/// Morton ordered storage of one value per tile, `Block`s by default. Its
+/// sides are clamped to `1..=MAX_BOARD_SIDE`, as such a grid takes up to
+/// the square of its longest side in blocks: gigabytes for the largest
+/// `SmallNat`s. `check_size` and the `try_` constructors reject longer
+/// sides instead.
+#[derive(Clone)]
+pub struct Grid<T = Block> {
+    blocks: Vec<T>,
+    width: SmallNat,
+    height: SmallNat,
+}
+
+impl Grid {
+    /// Whether a `width` x `height` grid has a tile at all and fits within
+    /// `MAX_BOARD_SIDE`
+    pub fn check_size(
+        width: SmallNat,
+        height: SmallNat,
+    ) -> Result<(), ConfigError> {
+        if width == 0 || height == 0 {
+            Err(ConfigError::InvalidSide)
+        } else if width > MAX_BOARD_SIDE || height > MAX_BOARD_SIDE {
+            Err(ConfigError::BoardTooLarge {
+                max: MAX_BOARD_SIDE,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn try_empty(
+        width: SmallNat,
+        height: SmallNat,
+    ) -> Result<Self, ConfigError> {
+        Grid::check_size(width, height).map(|()| Grid::empty(width, height))
+    }
+
+    pub fn empty(width: SmallNat, height: SmallNat) -> Self {
+        let mut grid = Grid {
+            width: 1,
+            height: 1,
+            blocks: Vec::new(),
+        };
+        grid.reset(width, height);
+        grid
+    }
+
+    /// Turns the grid into an `empty` one of `width` x `height`, in the
+    /// blocks it has already unless it needs more, so that a restart on a
+    /// board of the same size doesn't allocate
+    pub fn reset(&mut self, width: SmallNat, height: SmallNat) {
+        self.refill(width, height, Block::OutOfBound);
+        debug!(
+            "grid {}x{} uses {} blocks",
+            self.width,
+            self.height,
+            self.blocks.len()
+        );
+
+        for (x, y) in iproduct!(0..self.width, 0..self.height) {
+            let index = Coordinate { x, y }.encode_usize();
+            self.blocks[index] = Block::Empty;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.iter_coordinates().for_each(|coord| {
+            self[coord] = Block::Empty;
+        });
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn try_filled(
+        width: SmallNat,
+        height: SmallNat,
+        value: T,
+    ) -> Result<Self, ConfigError> {
+        Grid::check_size(width, height)
+            .map(|()| Grid::filled(width, height, value))
+    }
+
+    pub fn filled(width: SmallNat, height: SmallNat, value: T) -> Self {
+        let mut grid = Grid {
+            width: 1,
+            height: 1,
+            blocks: Vec::new(),
+        };
+        grid.refill(width, height, value);
+        grid
+    }
+
+    /// Turns the grid into a `filled` one, reusing its blocks like `reset`
+    pub fn refill(&mut self, width: SmallNat, height: SmallNat, value: T) {
+        self.width = min(max(1, width), MAX_BOARD_SIDE);
+        self.height = min(max(1, height), MAX_BOARD_SIDE);
+
+        let max_coord = Coordinate {
+            x: self.width - 1,
+            y: self.height - 1,
+        };
+        self.blocks.clear();
+        self.blocks.resize(max_coord.encode_usize() + 1, value);
+    }
+}
+
+impl<T> Grid<T> {
+    #[inline(always)]
+    pub fn width(&self) -> SmallNat {
+        self.width
+    }
+    #[inline(always)]
+    pub fn height(&self) -> SmallNat {
+        self.height
+    }
+
+    pub fn random_coordinate<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+    ) -> Coordinate {
+        let x = rng.gen_range(0, self.width);
+        let y = rng.gen_range(0, self.height);
+
+        Coordinate { x, y }
+    }
+
+    /// Whether `at` is on the board
+    #[inline(always)]
+    pub fn contains(&self, at: Coordinate) -> bool {
+        at.x < self.width && at.y < self.height
+    }
+
+    /// The tile on the board nearest to `at`
+    pub fn clamp(&self, at: Coordinate) -> Coordinate {
+        Coordinate {
+            x: ::std::cmp::min(at.x, self.width - 1),
+            y: ::std::cmp::min(at.y, self.height - 1),
+        }
+    }
+
+    /// The middle tile, the one right and below of it on boards of an
+    /// even size
+    pub fn center(&self) -> Coordinate {
+        Coordinate {
+            x: self.width / 2,
+            y: self.height / 2,
+        }
+    }
+
+    pub fn get(&self, at: Coordinate) -> Option<&T> {
+        if self.contains(at) {
+            Some(&self.blocks[at.encode_usize()])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, at: Coordinate) -> Option<&mut T> {
+        if self.contains(at) {
+            Some(&mut self.blocks[at.encode_usize()])
+        } else {
+            None
+        }
+    }
+
+    /// Every tile with its value, row by row from the top left
+    pub fn iter<'a>(
+        &'a self,
+    ) -> impl Iterator<Item = (Coordinate, &'a T)> + 'a {
+        (0..self.height).flat_map(move |y| self.iter_row(y))
+    }
+
+    /// The tiles of row `y` with their values, left to right; none for a
+    /// row below the board
+    pub fn iter_row<'a>(
+        &'a self,
+        y: SmallNat,
+    ) -> impl Iterator<Item = (Coordinate, &'a T)> + 'a {
+        let width = if y < self.height { self.width } else { 0 };
+        (0..width).map(move |x| self.tile(Coordinate { x, y }))
+    }
+
+    /// The tiles of column `x` with their values, top to bottom; none for
+    /// a column right of the board
+    pub fn iter_col<'a>(
+        &'a self,
+        x: SmallNat,
+    ) -> impl Iterator<Item = (Coordinate, &'a T)> + 'a {
+        let height = if x < self.width { self.height } else { 0 };
+        (0..height).map(move |y| self.tile(Coordinate { x, y }))
+    }
+
+    // `at` on the board with its value
+    fn tile(&self, at: Coordinate) -> (Coordinate, &T) {
+        (at, &self.blocks[at.encode_usize()])
+    }
+
+    /// Every tile of the board, column by column from the top left
+    pub fn iter_coordinates(&self) -> impl Iterator<Item = Coordinate> {
+        iproduct!(0..self.width, 0..self.height)
+            .map(|(x, y)| Coordinate { x, y })
+    }
+}
+
+impl<T: Clone + PartialEq> Grid<T> {
+    /// The tiles where `other`, a grid of the same size, differs from this
+    /// one, with their values in `other`: what it takes to turn this board
+    /// into that one, in storage order. Only the changes are allocated for.
+    pub fn diff(&self, other: &Grid<T>) -> Vec<(Coordinate, T)> {
+        assert_eq!(
+            (self.width, self.height),
+            (other.width, other.height),
+            "diff of grids of different sizes"
+        );
+
+        self.blocks
+            .iter()
+            .zip(other.blocks.iter())
+            .enumerate()
+            .filter(|(_, (a, b))| a != b)
+            .map(|(i, (_, b))| (Coordinate::decode_usize(i), b.clone()))
+            // the padding of the morton order is never set
+            .filter(|&(at, _)| self.contains(at))
+            .collect()
+    }
+}
+
+// traits implements
+
+impl From<u8> for Key {
+    fn from(code: u8) -> Key {
+        Key { code, frames: 0 }
+    }
+}
+
+impl From<Direction> for Key {
+    fn from(dir: Direction) -> Key {
+        Key::from(dir.key_code())
+    }
+}
+
+impl From<Key> for Option<Direction> {
+    fn from(k: Key) -> Option<Direction> {
+        match k.code {
+            37 => Some(Direction::West),
+            38 => Some(Direction::North),
+            39 => Some(Direction::East),
+            40 => Some(Direction::South),
+            _ => None,
+        }
+    }
+}
+
+// a tile of the player's snake
+impl From<Direction> for Block {
+    fn from(dir: Direction) -> Block {
+        Block::Snake(dir, PLAYER)
+    }
+}
+
+impl Index<Coordinate> for Grid {
+    type Output = Block;
+
+    fn index<'a>(&'a self, index: Coordinate) -> &'a Block {
+        if self.contains(index) {
+            &self.blocks[index.encode_usize()]
+        } else {
+            &Block::OutOfBound
+        }
+    }
+}
+impl IndexMut<Coordinate> for Grid {
+    fn index_mut<'a>(&'a mut self, index: Coordinate) -> &'a mut Block {
+        if self.contains(index) {
+            &mut self.blocks[index.encode_usize()]
+        } else {
+            panic!("Accessing out of bound block")
+        }
+    }
+}
+
+impl FromIterator<(Coordinate, Block)> for Grid {
+    fn from_iter<T>(iter: T) -> Self
+    where
+        T: IntoIterator<Item = (Coordinate, Block)>,
+    {
+        let mut blocks = vec![Block::Empty];
+        let mut x_max: SmallNat = 0;
+        let mut y_max: SmallNat = 0;
+
+        for (coord, block) in iter {
+            x_max = max(x_max, coord.x);
+            y_max = max(y_max, coord.y);
+
+            let index = Coordinate { x: x_max, y: y_max }.encode_usize();
+
+            for _ in blocks.len()..=index {
+                blocks.push(Block::Empty);
+            }
+
+            blocks[coord.encode_usize()] = block;
+        }
+
+        let width = x_max + 1;
+        let height = y_max + 1;
+
+        blocks.iter_mut().enumerate().for_each(|(index, block)| {
+            let Coordinate { x, y } = Coordinate::decode_usize(index);
+            if x > x_max || y > y_max {
+                *block = Block::OutOfBound;
+            }
+        });
+
+        Grid {
+            width,
+            height,
+            blocks,
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test_utils {
+    use super::*;
+    use quickcheck::{Arbitrary, Gen};
+
+    const SIZE_LIMIT: SmallNat = 255;
+
+    #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+    pub struct Bound {
+        pub width: SmallNat,
+        pub height: SmallNat,
+    }
+
+    impl Arbitrary for Bound {
+        fn arbitrary<G: Gen>(g: &mut G) -> Self {
+            let w = SmallNat::arbitrary(g) % SIZE_LIMIT;
+            let h = SmallNat::arbitrary(g) % SIZE_LIMIT;
+
+            Bound {
+                width: max(w, 1),
+                height: max(h, 1),
+            }
+        }
+    }
+
+    impl Arbitrary for Direction {
+        fn arbitrary<G: Gen>(g: &mut G) -> Self {
+            match g.size() % 4 {
+                0 => Direction::East,
+                1 => Direction::West,
+                2 => Direction::South,
+                3 => Direction::North,
+                _ => unreachable!(),
+            }
+        }
+    }
+    impl Arbitrary for Coordinate {
+        fn arbitrary<G: Gen>(g: &mut G) -> Self {
+            Coordinate {
+                x: SmallNat::arbitrary(g) % SIZE_LIMIT,
+                y: SmallNat::arbitrary(g) % SIZE_LIMIT,
+            }
+        }
+    }
+    impl Arbitrary for UncheckedCoordinate {
+        fn arbitrary<G: Gen>(g: &mut G) -> Self {
+            UncheckedCoordinate {
+                inner: Coordinate::arbitrary(g),
+            }
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_utils::*;
+    use super::*;
+
+    // `a` moved next to the origin, so that random ones are often equal or
+    // ordered when paired up
+    fn near(a: Coordinate) -> Coordinate {
+        Coordinate {
+            x: a.x % 4,
+            y: a.y % 4,
+        }
+    }
+
+    #[test]
+    fn test_block_size() {
+        // nested enum optimization kicking in
+        assert_eq!(::std::mem::size_of::<Block>(), 1,)
+    }
+    #[test]
+    fn test_key_repeat_detects_hold() {
+        let mut repeat = KeyRepeat::new();
+        let up = Key::from(38);
+
+        assert!(!repeat.feed(up).is_repeat());
+        assert!(repeat.feed(up).is_repeat());
+
+        for _ in 2..HOLD_FRAME_COUNT {
+            repeat.feed(up);
+        }
+        assert!(repeat.feed(up).is_held());
+
+        assert!(!repeat.feed(Key::from(37)).is_held());
+        assert!(!repeat.feed(Key::none()).is_held());
+    }
+    #[test]
+    fn test_grid_diff() {
+        let mut a = Grid::empty(5, 3);
+        let mut b = a.clone();
+        assert!(a.diff(&b).is_empty());
+
+        let food = Coordinate { x: 4, y: 2 };
+        let snake = Coordinate { x: 0, y: 1 };
+        b[food] = Block::Food;
+        b[snake] = Block::Snake(Direction::East, PLAYER);
+        a[Coordinate { x: 2, y: 2 }] = Block::Obstacle;
+
+        let mut diff = a.diff(&b);
+        diff.sort_by_key(|(at, _)| (at.x, at.y));
+        assert_eq!(
+            diff,
+            vec![
+                (snake, Block::Snake(Direction::East, PLAYER)),
+                (Coordinate { x: 2, y: 2 }, Block::Empty),
+                (food, Block::Food),
+            ]
+        );
+
+        for (at, block) in diff {
+            a[at] = block;
+        }
+        assert!(a.diff(&b).is_empty());
+    }
+    #[test]
+    fn test_board_size_is_capped() {
+        let too_large = Err(ConfigError::BoardTooLarge {
+            max: MAX_BOARD_SIDE,
+        });
+        assert_eq!(Grid::check_size(MAX_BOARD_SIDE + 1, 2), too_large);
+        assert_eq!(Grid::check_size(2, SmallNat::max_value()), too_large);
+        assert!(Grid::try_empty(MAX_BOARD_SIDE, MAX_BOARD_SIDE).is_ok());
+        assert!(Grid::try_filled(1, MAX_BOARD_SIDE + 1, 0u32).is_err());
+        let invalid = Err(ConfigError::InvalidSide);
+        assert_eq!(Grid::check_size(0, 2), invalid);
+        assert_eq!(Grid::check_size(2, 0), invalid);
+
+        // a thin board is as long as it may be, not a gigabyte
+        let grid = Grid::empty(SmallNat::max_value(), 1);
+        assert_eq!((grid.width(), grid.height()), (MAX_BOARD_SIDE, 1));
+        assert!(grid.blocks.len() < 1 << 20);
+    }
+    #[test]
+    fn test_grid_iterators() {
+        let mut grid = Grid::empty(3, 2);
+        let food = Coordinate { x: 1, y: 1 };
+        grid[food] = Block::Food;
+
+        let tiles: Vec<_> = grid.iter().map(|(at, _)| (at.x, at.y)).collect();
+        assert_eq!(tiles, vec![(0, 0), (1, 0), (2, 0), (0, 1), (1, 1), (2, 1)]);
+        assert_eq!(grid.iter().filter(|&(_, &b)| b == Block::Food).count(), 1);
+
+        let row: Vec<_> = grid.iter_row(1).map(|(_, &b)| b).collect();
+        assert_eq!(row, vec![Block::Empty, Block::Food, Block::Empty]);
+        let col: Vec<_> = grid.iter_col(1).map(|(at, _)| at.y).collect();
+        assert_eq!(col, vec![0, 1]);
+
+        assert_eq!(grid.iter_row(2).count(), 0);
+        assert_eq!(grid.iter_col(3).count(), 0);
+    }
+    #[test]
+    fn test_grid_bounds_helpers() {
+        let grid = Grid::empty(4, 3);
+        let corner = Coordinate { x: 3, y: 2 };
+
+        assert!(grid.contains(corner));
+        assert!(!grid.contains(Coordinate { x: 4, y: 0 }));
+        let outside = Coordinate { x: 9, y: 1 };
+        assert_eq!(grid.clamp(outside), Coordinate { x: 3, y: 1 });
+        assert_eq!(grid.clamp(corner), corner);
+        assert_eq!(grid.center(), Coordinate { x: 2, y: 1 });
+        assert_eq!(grid.iter_coordinates().count(), 12);
+    }
+    #[test]
+    fn test_diagonal_moves() {
+        let at = Coordinate { x: 2, y: 2 };
+        let moved = at.move_towards8(Direction8::NorthWest).bound_inside(5, 5);
+        assert_eq!(moved, Some(Coordinate { x: 1, y: 1 }));
+
+        // a body running from the north to the west of `at` can't be crossed
+        let body = [Coordinate { x: 2, y: 1 }, Coordinate { x: 1, y: 2 }];
+        let blocked = |tile: UncheckedCoordinate| {
+            tile.bound_inside(5, 5).map_or(false, |t| body.contains(&t))
+        };
+        assert!(at.squeezes(Direction8::NorthWest, blocked));
+        assert!(!at.squeezes(Direction8::NorthEast, blocked));
+        assert!(!at.squeezes(Direction8::North, blocked));
+    }
+    #[test]
+    fn test_unchecked_coordinate_generic_inside() {
+        let orig = Coordinate { x: 0, y: 0 };
+        let unchecked = orig.move_towards(Direction::West);
+
+        let grid = Grid::empty(10, 2);
+
+        assert_eq!(
+            unchecked.inside::<Wrapping>(&grid),
+            Some(Coordinate { x: 9, y: 0 })
+        );
+        assert_eq!(unchecked.inside::<Bounding>(&grid), None);
+    }
+
+    quickcheck! {
+        fn double_opposite_is_identity(dir: Direction) -> bool {
+            dir.opposite().opposite() == dir
+        }
+
+        fn four_rotations_are_identity(dir: Direction) -> bool {
+            let left = dir.rotate_left();
+
+            left.rotate_right() == dir
+                && left.axis() == dir.axis().cross()
+                && left.rotate_left().rotate_left().rotate_left() == dir
+        }
+
+        fn unit_vectors_roundtrip(dir: Direction) -> bool {
+            let (dx, dy) = dir.as_unit_vector();
+            let (dx, dy) = (i32::from(dx), i32::from(dy));
+
+            Direction::from_delta(dx, dy) == Some(dir)
+                && Direction::from_delta(dx * 7, dy * 7) == Some(dir)
+                && dir.axis().directions().contains(&dir)
+        }
+
+        fn chords_make_diagonals(dir: Direction) -> bool {
+            let chord = Direction8::from_chord(dir, dir.rotate_right());
+            let (dx, dy) = chord.map_or((0, 0), Direction8::as_unit_vector);
+
+            let back = dir.opposite();
+            let back = Direction8::from_chord(back, back.rotate_right());
+
+            chord.map_or(false, Direction8::is_diagonal)
+                && dx.abs() == 1
+                && dy.abs() == 1
+                && chord.map(Direction8::opposite) == back
+                && Direction8::from_chord(dir, dir.opposite()).is_none()
+        }
+
+        fn direction_conversions_roundtrip(dir: Direction) -> bool {
+            let key_dir: Option<Direction> = Key::from(dir).into();
+
+            Direction::from_u8(dir as u8) == Some(dir) && key_dir == Some(dir)
+        }
+
+        fn coordinate_usize_preserves_partial_order(inputs: (Coordinate, Coordinate)) -> bool {
+            let (a, b) = inputs;
+
+            if let Some(ordering) = a.partial_cmp(&b) {
+                ordering == a.encode_usize().cmp(&b.encode_usize())
+            } else {
+                true
+            }
+        }
+
+        fn coordinate_order_is_consistent_with_eq(inputs: (Coordinate, Coordinate)) -> bool {
+            let (a, b) = (near(inputs.0), near(inputs.1));
+
+            (a.partial_cmp(&b) == Some(Ordering::Equal)) == (a == b)
+                && (a <= b && b <= a) == (a == b)
+        }
+
+        fn coordinate_order_is_antisymmetric(inputs: (Coordinate, Coordinate)) -> bool {
+            let (a, b) = (near(inputs.0), near(inputs.1));
+
+            a.partial_cmp(&b) == b.partial_cmp(&a).map(Ordering::reverse)
+                && (a < b) == (b > a)
+        }
+
+        fn coordinate_order_is_transitive(inputs: (Coordinate, Coordinate, Coordinate)) -> bool {
+            let (a, b, c) = (near(inputs.0), near(inputs.1), near(inputs.2));
+
+            if a <= b && b <= c {
+                a <= c && (a < b || b < c) == (a < c)
+            } else {
+                true
+            }
+        }
+
+        fn coordinate_order_is_dominance(inputs: (Coordinate, Coordinate)) -> bool {
+            let (a, b) = (near(inputs.0), near(inputs.1));
+
+            (a <= b) == (a.x <= b.x && a.y <= b.y)
+                && (a.partial_cmp(&b).is_none())
+                    == (a.x < b.x && a.y > b.y || a.x > b.x && a.y < b.y)
+        }
+
+        fn opposite_direction_moves_cancel(inputs: (UncheckedCoordinate, Direction, Bound)) -> bool {
+            let (coord, dir, Bound { width, height }) = inputs;
+
+            // make sure starting coord is inside bound
+            let coord = coord.wrap_inside(width, height);
+            let orig_coord = coord;
+
+            let coord = coord.move_towards(dir).wrap_inside(width, height);
+            let coord = coord
+                .move_towards(dir.opposite())
+                .wrap_inside(width, height);
+
+            coord == orig_coord
+        }
+
+        fn coord_returns_after_ENWS_moves(inputs: (UncheckedCoordinate, Bound)) -> bool {
+            let (coord, Bound { width, height }) = inputs;
+
+            // make sure starting coord is inside bound
+            let mut coord = coord.wrap_inside(width, height);
+            let orig_coord = coord;
+
+            coord = coord
+                .move_towards(Direction::East)
+                .wrap_inside(width, height);
+            coord = coord
+                .move_towards(Direction::North)
+                .wrap_inside(width, height);
+            coord = coord
+                .move_towards(Direction::West)
+                .wrap_inside(width, height);
+            coord = coord
+                .move_towards(Direction::South)
+                .wrap_inside(width, height);
+
+            coord == orig_coord
+        }
+
+        fn coord_wrap_is_identity_if_already_inside(inputs: (UncheckedCoordinate, Bound)) -> bool {
+            let (coord, bound) = inputs;
+            let Bound { width, height } = bound;
+
+            if let Some(bounded) = coord.bound_inside(width, height) {
+                coord.wrap_inside(width, height) == bounded
+            } else {
+                true
+            }
+        }
+
+        fn build_grid_with_correct_oob_markers(coords: Vec<Coordinate>) -> bool {
+            let grid: Grid = coords
+                .iter()
+                .map(|c| (*c, Block::Empty))
+                .collect();
+
+            let valid_count =
+                grid.blocks
+                .iter()
+                .filter(|&b| *b != Block::OutOfBound)
+                .count();
+
+            valid_count == (grid.width * grid.height) as usize
+        }
+
+        fn build_grid_with_correct_indexing(coords: Vec<Coordinate>) -> bool {
+            let grid: Grid = coords
+                .iter()
+                .map(|c| (*c, Block::Food))
+                .collect();
+
+            coords.into_iter().all(|coord| grid[coord] == Block::Food)
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use std::marker::PhantomData;
 
-use data::Key;
-use system::{GameOver, Stateful};
+use data::{Command, Key};
+use system::{GameOver, Stateful, Tick};
 use world::WorldUpdate;
 
 pub enum CtrlEvent {
@@ -19,6 +19,22 @@ impl Into<Option<CtrlEvent>> for Key {
     }
 }
 
+impl Into<Option<CtrlEvent>> for Command {
+    fn into(self) -> Option<CtrlEvent> {
+        match self {
+            Command::Quit => Some(CtrlEvent::QuitGame),
+            // pausing or debugging the game over screen does nothing
+            Command::Pause | Command::DebugToggle => None,
+            Command::Restart
+            | Command::Turn(_)
+            | Command::Sprint(_)
+            | Command::RotateLeft
+            | Command::RotateRight
+            | Command::Diagonal(_) => Some(CtrlEvent::StartGame),
+        }
+    }
+}
+
 pub struct Dead<U> {
     _update_type: PhantomData<U>,
 }
@@ -46,6 +62,7 @@ where
     fn step(
         &mut self,
         cmd: Option<Self::Cmd>,
+        _tick: Tick,
     ) -> Result<Option<Self::Update>, Self::Error> {
         match cmd {
             Some(CtrlEvent::StartGame) => Err(GameOver::Over),
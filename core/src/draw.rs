@@ -0,0 +1,766 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use std::marker::PhantomData;
+
+use constants::{ANIMATION_FRAME_COUNT, PULSE_FRAMES, TILE_SIZE};
+use data::{Block, Coordinate, Direction, PowerUp, SmallNat};
+use system::{
+    Color, DrawGrid, FrameCtx, IncrRender, Layer, Lifecycle,
+    PersistentRender, RenderPool, UnitInterval,
+};
+use world::{Arena, SnakeId, TickDelta, WorldUpdate, WorldView};
+
+pub struct WorldUpdateDraw<U: Into<WorldUpdate> = WorldUpdate> {
+    update: WorldUpdate,
+    current_frame: u8,
+    total_frame: u8,
+    _update_type: PhantomData<U>,
+}
+
+impl<E: DrawGrid> IncrRender<E> for WorldUpdateDraw<WorldUpdate> {
+    type Patch = WorldUpdate;
+
+    fn new_patch(u: WorldUpdate, _ctx: FrameCtx) -> Self {
+        WorldUpdateDraw::new(u, ANIMATION_FRAME_COUNT)
+    }
+    #[inline]
+    fn render(&mut self, env: &mut E) -> Option<()> {
+        self.render(env)
+    }
+
+    fn tiles(&self) -> Option<Vec<Coordinate>> {
+        let mut tiles = Vec::new();
+        if self.current_frame < self.total_frame {
+            tiles_of(&self.update, &mut tiles)?;
+        }
+        Some(tiles)
+    }
+}
+
+/// Draws the changes of a tick together, each frame in the order
+/// `TickDelta::changes` has them
+pub struct TickDraw<U: Into<TickDelta> = TickDelta> {
+    delta: TickDelta,
+    current_frame: u8,
+    total_frame: u8,
+    // bit `i` is set once the `i`th change is drawn completely
+    finished: u32,
+    snapped: bool,
+    _update_type: PhantomData<U>,
+}
+
+impl<E: DrawGrid> IncrRender<E> for TickDraw<TickDelta> {
+    type Patch = TickDelta;
+
+    fn new_patch(u: TickDelta, _ctx: FrameCtx) -> Self {
+        TickDraw::new(u, ANIMATION_FRAME_COUNT)
+    }
+    #[inline]
+    fn render(&mut self, env: &mut E) -> Option<()> {
+        self.render(env)
+    }
+
+    fn tiles(&self) -> Option<Vec<Coordinate>> {
+        self.tiles()
+    }
+}
+
+// pushes the tiles `update` changes onto `tiles`, none if it changes the
+// whole board
+fn tiles_of(
+    update: &WorldUpdate,
+    tiles: &mut Vec<Coordinate>,
+) -> Option<()> {
+    match *update {
+        WorldUpdate::LevelLoaded { .. }
+        | WorldUpdate::Respawned { .. }
+        | WorldUpdate::ArenaShrunk { .. }
+        | WorldUpdate::LaserWarning { .. }
+        | WorldUpdate::LaserFired { .. }
+        | WorldUpdate::LaserCleared { .. }
+        | WorldUpdate::Dead => return None,
+        WorldUpdate::HeadMoved { at, neck, .. } => {
+            tiles.extend_from_slice(&[at, neck]);
+        }
+        WorldUpdate::Bend { at, corner, .. } => {
+            tiles.extend_from_slice(&[at, corner]);
+        }
+        WorldUpdate::FoodMoved { from, to } => {
+            tiles.extend_from_slice(&[from, to]);
+        }
+        WorldUpdate::SetBlock { at, .. }
+        | WorldUpdate::TailRetracted { at, .. }
+        | WorldUpdate::FoodEaten { at, .. }
+        | WorldUpdate::FoodSpawned { at }
+        | WorldUpdate::PowerUpSpawned { at, .. }
+        | WorldUpdate::PowerUpTaken { at, .. }
+        | WorldUpdate::HazardWarning { at, .. }
+        | WorldUpdate::HazardRaised { at }
+        | WorldUpdate::Teetering { at, .. }
+        | WorldUpdate::CheckpointReached { at }
+        | WorldUpdate::NextTurn { at, .. } => tiles.push(at),
+        // drawn off the board, if at all
+        _ => {}
+    }
+    Some(())
+}
+
+impl<U> TickDraw<U>
+where
+    U: Into<TickDelta>,
+{
+    /// `frame_count` is per end of the snake, which used to move on
+    /// separate updates, so the snake keeps its pace
+    pub fn new(u: U, frame_count: u8) -> Self {
+        TickDraw {
+            delta: u.into(),
+            current_frame: 0,
+            total_frame: frame_count.saturating_mul(2),
+            finished: 0,
+            snapped: false,
+            _update_type: PhantomData,
+        }
+    }
+
+    /// The tiles of the changes not drawn completely yet, none if one of
+    /// them changes the whole board, see `Frames::tiles`
+    pub fn tiles(&self) -> Option<Vec<Coordinate>> {
+        let mut tiles = Vec::new();
+        for (i, update) in self.delta.changes().enumerate() {
+            if self.finished & (1 << i) == 0 {
+                tiles_of(&update, &mut tiles)?;
+            }
+        }
+        Some(tiles)
+    }
+
+    /// Draws the tick whole on its first frame, as with reduced motion
+    /// whatever the env's motion policy, the frames after keeping the pace
+    pub fn snapped(mut self) -> Self {
+        self.snapped = true;
+        self
+    }
+
+    #[inline]
+    pub fn render<E: DrawGrid>(&mut self, env: &mut E) -> Option<()> {
+        let mut pending = 0;
+        // with reduced motion the whole tick is drawn as it ends, on its
+        // first frame, and the frames after only keep the pace
+        let reduced = self.snapped || env.motion().is_reduced();
+        let frame = if reduced {
+            self.total_frame.saturating_sub(1)
+        } else {
+            self.current_frame
+        };
+
+        for (i, update) in self.delta.changes().enumerate() {
+            let bit = 1 << i;
+            if self.finished & bit != 0 {
+                continue;
+            }
+
+            let part = WorldUpdateDraw::<WorldUpdate> {
+                update,
+                current_frame: frame,
+                total_frame: self.total_frame,
+                _update_type: PhantomData,
+            };
+
+            if part.draw_frame(env) >= self.total_frame {
+                self.finished |= bit;
+            } else {
+                pending += 1;
+            }
+        }
+
+        self.current_frame += 1;
+        env.end_frame();
+
+        let paced = !reduced || self.current_frame >= self.total_frame;
+        if pending == 0 && paced {
+            None
+        } else {
+            Some(())
+        }
+    }
+}
+
+impl<U> WorldUpdateDraw<U>
+where
+    U: Into<WorldUpdate>,
+{
+    pub fn new(u: U, total_frame: u8) -> Self {
+        WorldUpdateDraw {
+            update: u.into(),
+            current_frame: 0,
+            total_frame,
+            _update_type: PhantomData,
+        }
+    }
+
+    #[inline]
+    pub fn render<E: DrawGrid>(&mut self, env: &mut E) -> Option<()> {
+        if env.motion().is_reduced() {
+            self.current_frame = self.total_frame.saturating_sub(1);
+        }
+        let next_frame = self.draw_frame(env);
+
+        if next_frame >= self.total_frame {
+            self.current_frame = self.total_frame;
+            None
+        } else {
+            self.current_frame = next_frame;
+            Some(())
+        }
+    }
+
+    // checkpoints are gray discs, squares where the red food discs take a
+    // shape of their own
+    fn checkpoint<E: DrawGrid>(
+        env: &mut E,
+        x: SmallNat,
+        y: SmallNat,
+        t: UnitInterval,
+    ) {
+        if env.distinct_shapes() {
+            env.square(x, y, t);
+        } else {
+            env.circle(x, y, t);
+        }
+    }
+
+    // pickups are red tiles at `size`, triangles where they'd be told
+    // apart from looming hazards by color alone
+    fn pickup<E: DrawGrid>(
+        env: &mut E,
+        x: SmallNat,
+        y: SmallNat,
+        size: UnitInterval,
+    ) {
+        if env.distinct_shapes() {
+            env.triangle(x, y, size);
+        } else {
+            env.fill_tile(x, y, Direction::East, size);
+        }
+    }
+
+    // the frame after this one, `total_frame` once the update is drawn
+    fn draw_frame<E: DrawGrid>(&self, env: &mut E) -> u8 {
+        let t = UnitInterval::from_u8_and_range(
+            self.current_frame,
+            0..self.total_frame,
+        );
+        // looming hazards and pickups are drawn at half size
+        let half = UnitInterval::from_u8_and_range(0, 0..2);
+
+        match self.update {
+            WorldUpdate::LevelLoaded { width, height }
+            | WorldUpdate::Respawned { width, height } => {
+                env.clear();
+                env.setup(TILE_SIZE as SmallNat, width, height);
+                self.total_frame
+            }
+            WorldUpdate::TailRetracted { at, dir, .. } => {
+                env.clear_tail(at.x, at.y, dir, t);
+                self.current_frame + 1
+            }
+            WorldUpdate::SetBlock { block, at } => {
+                match block {
+                    Block::Food => env.with_fill_color(Color::Red, |env| {
+                        env.circle(at.x, at.y, t);
+                    }),
+                    Block::Snake(dir, snake) => {
+                        env.set_snake(snake);
+                        env.fill_tile(at.x, at.y, dir, t);
+                    }
+                    Block::Obstacle => env.with_fill_color(Color::Gray, |env| {
+                        env.fill_tile(at.x, at.y, Direction::East, t);
+                    }),
+                    Block::Looming => env.with_fill_color(Color::Gray, |env| {
+                        env.fill_tile(at.x, at.y, Direction::East, half);
+                    }),
+                    // a disc, where obstacles fill the whole tile
+                    Block::Checkpoint => {
+                        env.with_fill_color(Color::Gray, |env| {
+                            Self::checkpoint(env, at.x, at.y, t);
+                        })
+                    }
+                    Block::PowerUp(_) => {
+                        env.with_fill_color(Color::Red, |env| {
+                            Self::pickup(env, at.x, at.y, half);
+                        })
+                    }
+                    _ => {}
+                }
+                self.current_frame + 1
+            }
+            WorldUpdate::HeadMoved {
+                snake,
+                at,
+                neck,
+                dir,
+            } => {
+                env.set_snake(snake);
+                // square off the previous head
+                env.fill_tile(neck.x, neck.y, dir, UnitInterval::max_value());
+                env.fill_head(at.x, at.y, dir, t);
+                self.current_frame + 1
+            }
+            WorldUpdate::Bend {
+                snake,
+                at,
+                corner,
+                from,
+                to,
+            } => {
+                env.set_snake(snake);
+                env.fill_corner(corner.x, corner.y, from, to);
+                env.fill_head(at.x, at.y, to, t);
+                self.current_frame + 1
+            }
+            WorldUpdate::FoodSpawned { at } => {
+                env.with_fill_color(Color::Red, |env| {
+                    env.circle(at.x, at.y, t);
+                });
+                self.current_frame + 1
+            }
+            WorldUpdate::FoodMoved { from, to } => {
+                let full = UnitInterval::max_value();
+                env.clear_tile(from.x, from.y, Direction::East, full);
+                env.with_fill_color(Color::Red, |env| {
+                    env.circle(to.x, to.y, t);
+                });
+                self.current_frame + 1
+            }
+            WorldUpdate::PowerUpSpawned { at, .. } => {
+                env.with_fill_color(Color::Red, |env| {
+                    Self::pickup(env, at.x, at.y, half);
+                });
+                self.total_frame
+            }
+            // the head drew over the pickup already
+            WorldUpdate::PowerUpTaken { power_up, .. } => {
+                env.show_power_up(power_up, true);
+                self.total_frame
+            }
+            WorldUpdate::PowerUpEnded { power_up } => {
+                env.show_power_up(power_up, false);
+                self.total_frame
+            }
+            WorldUpdate::ArenaShrunk {
+                inset,
+                width,
+                height,
+            } => {
+                // walls close in from the outside, over any food they took
+                let dir = Direction::East;
+                env.with_fill_color(Color::Gray, |env| {
+                    for at in Arena::ring(width, height, inset) {
+                        env.fill_tile(at.x, at.y, dir, t);
+                    }
+                });
+                self.current_frame + 1
+            }
+            WorldUpdate::FoodEaten { at, .. } => {
+                env.food_eaten(at.x, at.y);
+                self.total_frame
+            }
+            // the board already shows those
+            WorldUpdate::Grew { .. }
+            | WorldUpdate::CheckpointReached { .. }
+            | WorldUpdate::SnakeDied { .. } => self.total_frame,
+            WorldUpdate::Died { reason } => {
+                debug!("died: {:?}", reason);
+                self.total_frame
+            }
+            WorldUpdate::Won => {
+                debug!("won");
+                self.total_frame
+            }
+            WorldUpdate::InvalidInput(dir) => {
+                trace!("rejected turn {:?}", dir);
+                env.invalid_input(dir);
+                self.total_frame
+            }
+            // on the last frame, once the head is drawn
+            WorldUpdate::NextTurn { at, dir } => {
+                if self.current_frame + 1 >= self.total_frame {
+                    env.show_next_turn(at.x, at.y, dir);
+                }
+                self.current_frame + 1
+            }
+            // blinks at half size, one tick on and one off
+            WorldUpdate::HazardWarning { at, left } => {
+                let full = UnitInterval::max_value();
+                env.clear_tile(at.x, at.y, Direction::East, full);
+                if left % 2 == 0 || env.motion().is_reduced() {
+                    env.with_fill_color(Color::Gray, |env| {
+                        env.fill_tile(at.x, at.y, Direction::East, half);
+                    });
+                }
+                self.total_frame
+            }
+            WorldUpdate::HazardRaised { at } => {
+                env.with_fill_color(Color::Gray, |env| {
+                    env.fill_tile(at.x, at.y, Direction::East, t);
+                });
+                self.current_frame + 1
+            }
+            // flickers between two shades until it fires
+            WorldUpdate::LaserWarning { line, left } => {
+                let shade = if env.motion().is_reduced() {
+                    1
+                } else {
+                    (left % 2) as u8
+                };
+                let alpha = UnitInterval::from_u8_and_range(shade, 0..4);
+                env.show_band(line, Color::Red, alpha);
+                self.total_frame
+            }
+            WorldUpdate::LaserFired { line } => {
+                let alpha = UnitInterval::from_u8_and_range(2, 0..4);
+                env.show_band(line, Color::Red, alpha);
+                self.total_frame
+            }
+            WorldUpdate::LaserCleared { line } => {
+                env.hide_band(line);
+                self.total_frame
+            }
+            WorldUpdate::Teetering { snake, at, dir } => {
+                env.set_snake(snake);
+                // blinks the head in red, back to its own color at the end
+                let full = UnitInterval::max_value();
+                let last = self.current_frame + 1 >= self.total_frame;
+                // or stays red throughout with reduced motion
+                let red = env.motion().is_reduced()
+                    || (!last && self.current_frame / 2 % 2 == 0);
+                env.clear_tile(at.x, at.y, dir, full);
+                if red {
+                    env.with_fill_color(Color::Red, |env| {
+                        env.fill_head(at.x, at.y, dir, full);
+                    });
+                } else {
+                    env.fill_head(at.x, at.y, dir, full);
+                }
+                self.current_frame + 1
+            }
+            // nothing on the board changes, hosts learn about it from events
+            WorldUpdate::Paused(_) => self.total_frame,
+            WorldUpdate::Debug(on) => {
+                env.show_debug(on);
+                self.total_frame
+            }
+            WorldUpdate::Clock { left } => {
+                env.show_clock(left);
+                self.total_frame
+            }
+            WorldUpdate::Lives { left } => {
+                env.show_lives(left);
+                self.total_frame
+            }
+            WorldUpdate::Prompt(Some(text)) => {
+                env.show_text(text);
+                self.total_frame
+            }
+            WorldUpdate::Prompt(None) => {
+                env.hide_text();
+                self.total_frame
+            }
+            WorldUpdate::Dead => {
+                env.show_game_over();
+                self.total_frame
+            }
+        }
+    }
+}
+
+/// Draws every tile of `view` anew on the board layer, e.g. once the env
+/// draws in another theme. The tiles are whole, the head aside, so the
+/// corners the snake turned on stay square until its tail passes them; an
+/// env with layers leaves the food and the pickups to `entities`.
+pub fn redraw_view<E: DrawGrid>(env: &mut E, view: &WorldView) {
+    let full = UnitInterval::max_value();
+    let layered = env.has_layers();
+    let head_of = |snake: SnakeId| {
+        view.snakes().find(|s| s.id() == snake).map(|s| s.head())
+    };
+
+    env.with_layer(Layer::Board, |env| {
+        for (x, y) in iproduct!(0..view.width(), 0..view.height()) {
+            let at = Coordinate { x, y };
+            let block = view.block(at);
+
+            env.clear_tile(x, y, Direction::East, full);
+            match block {
+                Block::Food | Block::PowerUp(_) if layered => {}
+                Block::Snake(dir, snake) if Some(at) == head_of(snake) => {
+                    env.set_snake(snake);
+                    env.fill_head(x, y, dir, full);
+                }
+                _ => {
+                    let update = WorldUpdate::SetBlock { block, at };
+                    WorldUpdateDraw::new(update, 1).render(env);
+                }
+            }
+        }
+    });
+}
+
+/// What lives on the board across ticks, see `entities`
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Entity {
+    Food,
+    Pickup(PowerUp),
+}
+
+pub type Entities<E> = RenderPool<E, Entity, WorldUpdate>;
+
+/// The food and the pickups on the board, each gently pulsing while it
+/// waits to be taken: its size eases down to four fifths and back up
+/// every `PULSE_FRAMES` frames. With reduced motion they keep still. An
+/// env with layers has them pulse on `Layer::Entities`, over the board.
+pub fn entities<E: DrawGrid>() -> Entities<E> {
+    RenderPool::new(lifecycle, spawn::<E>)
+}
+
+// what `update` does to the entities of the board
+fn lifecycle(update: &WorldUpdate) -> Option<Lifecycle<Entity>> {
+    match *update {
+        WorldUpdate::SetBlock {
+            block: Block::Food,
+            ..
+        }
+        | WorldUpdate::FoodSpawned { .. } => {
+            Some(Lifecycle::Spawn(Entity::Food))
+        }
+        WorldUpdate::SetBlock {
+            block: Block::PowerUp(power_up),
+            ..
+        }
+        | WorldUpdate::PowerUpSpawned { power_up, .. } => {
+            Some(Lifecycle::Spawn(Entity::Pickup(power_up)))
+        }
+        WorldUpdate::FoodMoved { .. } => {
+            Some(Lifecycle::Update(Entity::Food))
+        }
+        // the head goes over them
+        WorldUpdate::FoodEaten { .. } => {
+            Some(Lifecycle::Despawn(Entity::Food))
+        }
+        WorldUpdate::PowerUpTaken { power_up, .. } => {
+            Some(Lifecycle::Despawn(Entity::Pickup(power_up)))
+        }
+        // walls close in over any of them
+        WorldUpdate::ArenaShrunk { .. } => Some(Lifecycle::UpdateAll),
+        WorldUpdate::LevelLoaded { .. }
+        | WorldUpdate::Respawned { .. }
+        | WorldUpdate::Died { .. }
+        | WorldUpdate::Won => Some(Lifecycle::DespawnAll),
+        _ => None,
+    }
+}
+
+fn spawn<E: DrawGrid>(
+    entity: Entity,
+    update: &WorldUpdate,
+) -> Option<Box<PersistentRender<E, Patch = WorldUpdate>>> {
+    match *update {
+        WorldUpdate::SetBlock { at, .. }
+        | WorldUpdate::FoodSpawned { at }
+        | WorldUpdate::PowerUpSpawned { at, .. } => {
+            Some(Box::new(Pulse::new(entity, at)))
+        }
+        _ => None,
+    }
+}
+
+/// An entity of `entities` pulsing on its tile
+pub struct Pulse {
+    entity: Entity,
+    at: Coordinate,
+    // frames left to the update drawing the entity in
+    wait: u8,
+    // into the current pulse
+    frame: u8,
+    // drawn on the entities layer since it was placed
+    shown: bool,
+    // the tile it moved off, still showing it there
+    left: Option<Coordinate>,
+}
+
+impl Pulse {
+    pub fn new(entity: Entity, at: Coordinate) -> Self {
+        // as long as a `TickDraw` takes to grow food in, pickups show up
+        // at once and are drawn over from the next frame
+        let wait = match entity {
+            Entity::Food => ANIMATION_FRAME_COUNT.saturating_mul(2),
+            Entity::Pickup(_) => 1,
+        };
+        Pulse {
+            entity,
+            at,
+            wait,
+            frame: 0,
+            shown: false,
+            left: None,
+        }
+    }
+
+    /// The size of an entity `frame` frames into a pulse, of its full size
+    pub fn size(frame: u8) -> UnitInterval {
+        let half = PULSE_FRAMES / 2;
+        let frame = frame % PULSE_FRAMES;
+        let x = if frame < half {
+            frame
+        } else {
+            PULSE_FRAMES - 1 - frame
+        };
+        let t = UnitInterval::from_u8_and_range(x, 0..half).ease_in_out();
+        let low = UnitInterval::from_u8_and_range(3, 0..5);
+        t.lerp(UnitInterval::max_value(), low)
+    }
+
+    // moves along with the food, false once the entity is gone
+    fn on_update(&mut self, update: &WorldUpdate) -> bool {
+        match *update {
+            WorldUpdate::FoodMoved { to, .. } => {
+                let left = if self.shown { Some(self.at) } else { self.left };
+                *self = Pulse::new(self.entity, to);
+                self.left = left;
+                true
+            }
+            WorldUpdate::ArenaShrunk {
+                inset,
+                width,
+                height,
+            } => {
+                let at = self.at;
+                !Arena::ring(width, height, inset).any(|c| c == at)
+            }
+            _ => true,
+        }
+    }
+
+    // takes it off the entities layer, where nothing else draws over it;
+    // on an env without layers it pulsed where the snake may be by now
+    fn erase<E: DrawGrid>(env: &mut E, at: Coordinate) {
+        if env.has_layers() {
+            let full = UnitInterval::max_value();
+            env.with_layer(Layer::Entities, |env| {
+                env.clear_tile(at.x, at.y, Direction::East, full)
+            });
+        }
+    }
+}
+
+impl<E: DrawGrid> PersistentRender<E> for Pulse {
+    type Patch = WorldUpdate;
+
+    fn render(&mut self, env: &mut E) {
+        if let Some(left) = self.left.take() {
+            Self::erase(env, left);
+        }
+        if self.wait > 0 {
+            self.wait -= 1;
+            return;
+        }
+
+        let size = if env.motion().is_reduced() {
+            UnitInterval::max_value()
+        } else {
+            Self::size(self.frame)
+        };
+        let (x, y) = (self.at.x, self.at.y);
+        let (full, entity) = (UnitInterval::max_value(), self.entity);
+        if !self.shown && env.has_layers() {
+            // the one the update drew on the board, pulsing over it instead
+            env.clear_tile(x, y, Direction::East, full);
+        }
+        env.with_layer(Layer::Entities, |env| {
+            env.clear_tile(x, y, Direction::East, full);
+            env.with_fill_color(Color::Red, |env| match entity {
+                Entity::Food => env.circle(x, y, size),
+                // at half size, like `WorldUpdateDraw` draws them
+                Entity::Pickup(_) => {
+                    let half = UnitInterval::from_u8_and_range(0, 0..2);
+                    let size = size.lerp(UnitInterval::min_value(), half);
+                    WorldUpdateDraw::<WorldUpdate>::pickup(env, x, y, size);
+                }
+            })
+        });
+        self.shown = true;
+        self.frame = (self.frame + 1) % PULSE_FRAMES;
+    }
+
+    fn update(&mut self, update: &WorldUpdate) -> bool {
+        self.on_update(update)
+    }
+
+    fn despawn(&mut self, env: &mut E) {
+        if self.shown {
+            Self::erase(env, self.at);
+        }
+        if let Some(left) = self.left {
+            Self::erase(env, left);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::*;
+    use world::PLAYER;
+
+    #[test]
+    fn test_entities_pulse_down_and_back() {
+        let full = UnitInterval::max_value();
+        let sizes: Vec<UnitInterval> =
+            (0..PULSE_FRAMES).map(Pulse::size).collect();
+
+        let half = PULSE_FRAMES as usize / 2;
+        assert!(sizes[0] < full);
+        assert!(sizes[..half].windows(2).all(|s| s[1] <= s[0]));
+        assert!(sizes[half..].windows(2).all(|s| s[1] >= s[0]));
+        // the same on the way back up, and again on the next pulse
+        assert!(sizes[half - 1] == sizes[half]);
+        assert!(sizes[0] == sizes[PULSE_FRAMES as usize - 1]);
+        assert!(Pulse::size(PULSE_FRAMES) == sizes[0]);
+        assert!(sizes[half] == UnitInterval::from_u8_and_range(3, 0..5));
+    }
+
+    #[test]
+    fn test_updates_spawn_and_despawn_entities() {
+        let at = Coordinate { x: 1, y: 2 };
+        let power_up = PowerUp::Magnet;
+
+        let spawned = WorldUpdate::FoodSpawned { at };
+        assert_eq!(lifecycle(&spawned), Some(Lifecycle::Spawn(Entity::Food)));
+        let taken = WorldUpdate::PowerUpTaken { at, power_up };
+        assert_eq!(
+            lifecycle(&taken),
+            Some(Lifecycle::Despawn(Entity::Pickup(power_up)))
+        );
+        let loaded = WorldUpdate::LevelLoaded {
+            width: 4,
+            height: 4,
+        };
+        assert_eq!(lifecycle(&loaded), Some(Lifecycle::DespawnAll));
+        let grew = WorldUpdate::Grew {
+            snake: PLAYER,
+            length: 4,
+        };
+        assert_eq!(lifecycle(&grew), None);
+
+        // (1, 2) is on the ring a tile in from the edge of a 4 x 5 board
+        let mut pulse = Pulse::new(Entity::Pickup(power_up), at);
+        let shrunk = |inset| WorldUpdate::ArenaShrunk {
+            inset,
+            width: 4,
+            height: 5,
+        };
+        assert!(pulse.on_update(&shrunk(0)));
+        assert!(!pulse.on_update(&shrunk(1)));
+    }
+}
@@ -0,0 +1,258 @@
+//! `DrawGrid` over an embedded-graphics `DrawTarget`, e.g. a 32x32 LED
+//! matrix or an SSD1306 OLED driven from a microcontroller build of this
+//! crate
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{Circle, Rectangle};
+use embedded_graphics::style::PrimitiveStyle;
+
+use data::{Direction, SmallNat};
+use system::{partial_tile, Color, DrawGrid, UnitInterval};
+
+/// Display colors for the game colors, `background` is what cleared tiles
+/// are filled with
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Palette<C> {
+    pub background: C,
+    pub snake: C,
+    pub food: C,
+}
+
+impl<C: PixelColor> Palette<C> {
+    fn color(&self, color: Color) -> C {
+        match color {
+            Color::Black => self.snake,
+            Color::Red => self.food,
+            // few displays have a color to spare for the walls
+            Color::Gray => self.snake,
+        }
+    }
+}
+
+pub struct MatrixEnv<D, C>
+where
+    D: DrawTarget<C>,
+    C: PixelColor,
+{
+    target: D,
+    palette: Palette<C>,
+    color: Color,
+    // in pixels, the board is scaled to fit the display
+    tile_size: SmallNat,
+    width: SmallNat,
+    height: SmallNat,
+    error: Option<D::Error>,
+}
+
+impl<D, C> MatrixEnv<D, C>
+where
+    D: DrawTarget<C>,
+    C: PixelColor,
+{
+    pub fn new(target: D, palette: Palette<C>) -> Self {
+        MatrixEnv {
+            target,
+            palette,
+            color: Color::Black,
+            tile_size: 1,
+            width: 0,
+            height: 0,
+            error: None,
+        }
+    }
+
+    pub fn target(&self) -> &D {
+        &self.target
+    }
+
+    /// Gives the display back, e.g. to flush the frame buffer of an OLED
+    pub fn target_mut(&mut self) -> &mut D {
+        &mut self.target
+    }
+
+    pub fn into_inner(self) -> D {
+        self.target
+    }
+
+    /// The first error of the display since the last call, drawing carries
+    /// on after an error since `DrawGrid` can't report it
+    pub fn take_error(&mut self) -> Option<D::Error> {
+        self.error.take()
+    }
+
+    fn fill_rect(&mut self, x: f64, y: f64, w: f64, h: f64, color: C) {
+        let (x, y, w, h) = (x as i32, y as i32, w as i32, h as i32);
+        if w < 1 || h < 1 {
+            return;
+        }
+
+        // corners are inclusive
+        let rect = Rectangle::new(
+            Point::new(x, y),
+            Point::new(x + w - 1, y + h - 1),
+        );
+        let result = rect
+            .into_styled(PrimitiveStyle::with_fill(color))
+            .draw(&mut self.target);
+
+        self.record(result);
+    }
+
+    fn record(&mut self, result: Result<(), D::Error>) {
+        if let Err(e) = result {
+            if self.error.is_none() {
+                self.error = Some(e);
+            }
+        }
+    }
+}
+
+impl<D, C> DrawGrid for MatrixEnv<D, C>
+where
+    D: DrawTarget<C>,
+    C: PixelColor,
+{
+    fn setup(
+        &mut self,
+        _tile_size: SmallNat,
+        width: SmallNat,
+        height: SmallNat,
+    ) {
+        let size = self.target.size();
+        let fit_x = size.width / width.max(1) as u32;
+        let fit_y = size.height / height.max(1) as u32;
+
+        self.tile_size = fit_x.min(fit_y).max(1) as SmallNat;
+        self.width = width;
+        self.height = height;
+    }
+
+    fn clear(&mut self) {
+        let background = self.palette.background;
+        let result = self.target.clear(background);
+
+        self.record(result);
+    }
+
+    fn set_fill_color(&mut self, color: Color) -> Color {
+        let prev_color = self.color;
+        self.color = color;
+
+        prev_color
+    }
+
+    fn circle(&mut self, x: SmallNat, y: SmallNat, radius: UnitInterval) {
+        let color = self.palette.color(self.color);
+        let tile_size = self.tile_size as i32;
+
+        // a single pixel can't be round
+        if tile_size < 3 {
+            let (x, y) = (x as f64, y as f64);
+            let size = tile_size as f64;
+            self.fill_rect(x * size, y * size, size, size, color);
+            return;
+        }
+
+        let r_full = tile_size / 2;
+        let center = Point::new(
+            x as i32 * tile_size + r_full,
+            y as i32 * tile_size + r_full,
+        );
+        let r = radius.scale(r_full as f64) as u32;
+
+        let result = Circle::new(center, r)
+            .into_styled(PrimitiveStyle::with_fill(color))
+            .draw(&mut self.target);
+
+        self.record(result);
+    }
+
+    fn fill_tile(
+        &mut self,
+        x: SmallNat,
+        y: SmallNat,
+        dir: Direction,
+        size: UnitInterval,
+    ) {
+        let color = self.palette.color(self.color);
+        let (x, y, w, h) =
+            partial_tile(self.tile_size as f64, x, y, dir, size);
+
+        self.fill_rect(x, y, w, h, color);
+    }
+
+    fn clear_tile(
+        &mut self,
+        x: SmallNat,
+        y: SmallNat,
+        dir: Direction,
+        size: UnitInterval,
+    ) {
+        let background = self.palette.background;
+        let (x, y, w, h) =
+            partial_tile(self.tile_size as f64, x, y, dir, size);
+
+        self.fill_rect(x, y, w, h, background);
+    }
+
+    fn show_game_over(&mut self) {
+        // no room for text, frame the board in the food color instead
+        let food = self.palette.food;
+        let tile_size = self.tile_size as f64;
+        let w = self.width as f64 * tile_size;
+        let h = self.height as f64 * tile_size;
+
+        self.fill_rect(0.0, 0.0, w, 1.0, food);
+        self.fill_rect(0.0, h - 1.0, w, 1.0, food);
+        self.fill_rect(0.0, 0.0, 1.0, h, food);
+        self.fill_rect(w - 1.0, 0.0, 1.0, h, food);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::pixelcolor::BinaryColor;
+    use void::Void;
+
+    struct Matrix([[bool; 8]; 8]);
+
+    impl DrawTarget<BinaryColor> for Matrix {
+        type Error = Void;
+
+        fn draw_pixel(
+            &mut self,
+            Pixel(at, color): Pixel<BinaryColor>,
+        ) -> Result<(), Void> {
+            if at.x >= 0 && at.x < 8 && at.y >= 0 && at.y < 8 {
+                self.0[at.y as usize][at.x as usize] = color.is_on();
+            }
+            Ok(())
+        }
+
+        fn size(&self) -> Size {
+            Size::new(8, 8)
+        }
+    }
+
+    #[test]
+    fn test_tiles_are_scaled_to_the_display() {
+        let palette = Palette {
+            background: BinaryColor::Off,
+            snake: BinaryColor::On,
+            food: BinaryColor::On,
+        };
+        let mut env = MatrixEnv::new(Matrix([[false; 8]; 8]), palette);
+
+        env.setup(16, 4, 4);
+        env.clear();
+        env.fill_tile(1, 0, Direction::East, UnitInterval::max_value());
+
+        let lit: Vec<_> = iproduct!(0..8, 0..8)
+            .filter(|&(y, x)| env.target().0[y][x])
+            .collect();
+        assert_eq!(lit, vec![(0, 2), (0, 3), (1, 2), (1, 3)]);
+
+        env.clear_tile(1, 0, Direction::East, UnitInterval::max_value());
+        assert!(env.target().0.iter().all(|row| row.iter().all(|on| !on)));
+    }
+}
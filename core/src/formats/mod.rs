@@ -0,0 +1,126 @@
+//! The formats the game stores data in, the version each is at and what
+//! changed in it. Data of an older version is migrated up to the current
+//! one where it still means the same, and rejected where it doesn't,
+//! rather than read as if it were current.
+//!
+//! `Snapshot`s have no format: a `Timeline` only keeps them in memory.
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+
+use replay::VerifyError;
+use world::Rules;
+
+/// Encoded `Replay`s:
+///
+/// 2. a world step is a whole tick
+/// 3. food is drawn from the index of empty tiles
+/// 4. the lives of the games, which respawn the snake
+/// 5. the `Rules` of the games
+/// 6. diagonal moves, ghosts and the speed curve in the `Rules`
+pub const REPLAY: u8 = 6;
+
+/// Settings of the frontend, which only ever gain fields; a blob missing
+/// one leaves it unset:
+///
+/// 1. palette, pixel art, shapes, crt, reduced motion and locale
+pub const SETTINGS: u32 = 1;
+
+/// Ratings of the player and the bots they played, likewise:
+///
+/// 1. the player's rating and one for each bot difficulty
+pub const RATINGS: u32 = 1;
+
+// magic, version, width, height, seed, lives
+const REPLAY_V4_HEADER_LEN: usize = 4 + 1 + 2 + 2 + 4 + 4;
+// and the wrap and reverse flags and the growth of the rules
+const REPLAY_V5_HEADER_LEN: usize = REPLAY_V4_HEADER_LEN + 2;
+
+/// An encoded replay of any version that plays back as it was played, as
+/// the current version encodes it, borrowed if it already is. Replays
+/// older than 4 stepped the world differently, those are unsupported.
+pub fn migrate_replay(bytes: &[u8]) -> Result<Cow<[u8]>, VerifyError> {
+    match bytes.get(4).cloned() {
+        Some(REPLAY) => Ok(Cow::Borrowed(bytes)),
+        // the rules had no speed curve, the snake moved on every tick
+        Some(5) if bytes.len() >= REPLAY_V5_HEADER_LEN => {
+            let header = &bytes[..REPLAY_V5_HEADER_LEN];
+            let commands = &bytes[REPLAY_V5_HEADER_LEN..];
+            let classic = Rules::default().to_bytes();
+
+            let mut migrated = Vec::with_capacity(bytes.len() + 2);
+            migrated.extend_from_slice(header);
+            migrated[4] = REPLAY;
+            migrated.extend_from_slice(&classic[2..]);
+            migrated.extend_from_slice(commands);
+            Ok(Cow::Owned(migrated))
+        }
+        // games had the classic rules before they were recorded
+        Some(4) if bytes.len() >= REPLAY_V4_HEADER_LEN => {
+            let header = &bytes[..REPLAY_V4_HEADER_LEN];
+            let commands = &bytes[REPLAY_V4_HEADER_LEN..];
+
+            let mut migrated = Vec::with_capacity(bytes.len() + Rules::LEN);
+            migrated.extend_from_slice(header);
+            migrated[4] = REPLAY;
+            migrated.extend_from_slice(&Rules::default().to_bytes());
+            migrated.extend_from_slice(commands);
+            Ok(Cow::Owned(migrated))
+        }
+        Some(4) | Some(5) | None => Err(VerifyError::Truncated),
+        Some(version) => Err(VerifyError::UnsupportedVersion(version)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data::{Command, Direction};
+    use replay::Replay;
+
+    const REPLAY_V3: &[u8] = include_bytes!("fixtures/replay-v3.bin");
+    const REPLAY_V4: &[u8] = include_bytes!("fixtures/replay-v4.bin");
+
+    #[test]
+    fn test_replays_of_version_4_load_with_the_classic_rules() {
+        let replay = Replay::from_bytes(REPLAY_V4).unwrap();
+
+        assert_eq!((replay.width, replay.height), (16, 8));
+        assert_eq!((replay.seed, replay.lives), (0xdead_beef, 3));
+        assert_eq!(replay.rules, Rules::default());
+        assert_eq!(replay.commands().len(), 13);
+        assert_eq!(
+            replay.commands()[2],
+            Some(Command::Sprint(Direction::South))
+        );
+
+        let current = replay.to_bytes();
+        assert_eq!(current[4], REPLAY);
+        assert_eq!(migrate_replay(&current), Ok(Cow::Borrowed(&current[..])));
+    }
+
+    #[test]
+    fn test_replays_of_version_5_keep_their_pace() {
+        let mut replay = Replay::new(16, 8, 7);
+        replay.rules.growth = 2;
+        replay.push(Some(Command::Turn(Direction::North)));
+        let mut v5 = replay.to_bytes();
+        v5[4] = 5;
+        v5.drain(REPLAY_V5_HEADER_LEN..REPLAY_V5_HEADER_LEN + 2);
+
+        let migrated = Replay::from_bytes(&v5).unwrap();
+        assert_eq!(migrated.rules, replay.rules);
+        assert_eq!(migrated.commands(), replay.commands());
+    }
+
+    #[test]
+    fn test_replays_older_than_version_4_are_unsupported() {
+        assert_eq!(
+            Replay::from_bytes(REPLAY_V3),
+            Err(VerifyError::UnsupportedVersion(3))
+        );
+        assert_eq!(
+            migrate_replay(&REPLAY_V4[..10]),
+            Err(VerifyError::Truncated)
+        );
+    }
+}
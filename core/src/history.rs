@@ -0,0 +1,254 @@
+use alloc::collections::VecDeque;
+use alloc::rc::Rc;
+use std::cell::RefCell;
+
+use data::Command;
+use system::{Stateful, Tick};
+use world::{TickDelta, UpdateError};
+
+/// A model whose state can be taken and put back
+pub trait Rewind {
+    type Snapshot: Clone;
+
+    fn snapshot(&self) -> Self::Snapshot;
+
+    /// Puts `snapshot` back, the next `initialize` carries on from it
+    /// instead of starting a new game
+    fn restore(&mut self, snapshot: &Self::Snapshot);
+}
+
+/// A tick of the game: the state it started from and what it changed
+#[derive(Clone)]
+pub struct Entry<S> {
+    pub tick: u32,
+    pub snapshot: S,
+    pub delta: TickDelta,
+}
+
+/// The last `capacity` ticks of a game, recorded by a `History` and shared
+/// with whoever looks back on them
+pub struct Timeline<S> {
+    entries: VecDeque<Entry<S>>,
+    capacity: usize,
+    jump: Option<u32>,
+    // the ticks are of a game that is over
+    ended: bool,
+}
+
+impl<S> Timeline<S> {
+    pub fn new(capacity: usize) -> Self {
+        Timeline {
+            entries: VecDeque::with_capacity(capacity),
+            capacity,
+            jump: None,
+            ended: false,
+        }
+    }
+
+    /// Oldest first
+    pub fn entries<'a>(&'a self) -> impl Iterator<Item = &'a Entry<S>> + 'a {
+        self.entries.iter()
+    }
+
+    pub fn get(&self, tick: u32) -> Option<&Entry<S>> {
+        self.entries.iter().find(|entry| entry.tick == tick)
+    }
+
+    /// The tick recorded last, whose snapshot and delta are where the game
+    /// got to
+    pub fn latest(&self) -> Option<&Entry<S>> {
+        self.entries.back()
+    }
+
+    /// Whether the ticks are of a game that is over, kept until the next
+    /// one steps: a board drawn since then may show another game
+    pub fn ended(&self) -> bool {
+        self.ended
+    }
+
+    /// Has the game go back to the start of `tick` on its next step, false
+    /// if the tick isn't (or is no longer) in the timeline
+    pub fn jump_to(&mut self, tick: u32) -> bool {
+        let found = self.get(tick).is_some();
+        if found {
+            self.jump = Some(tick);
+        }
+        found
+    }
+
+    fn push(&mut self, entry: Entry<S>) {
+        if self.entries.len() >= self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+
+    // a jump rewrites the ticks after it
+    fn truncate_from(&mut self, tick: u32) {
+        self.entries.retain(|entry| entry.tick < tick);
+    }
+}
+
+/// Wraps the world and records each of its ticks into a `Timeline`. A jump
+/// ends the game as a restart would, and the game that follows starts from
+/// the snapshot, which is how the board gets redrawn.
+pub struct History<M: Rewind> {
+    model: M,
+    timeline: Rc<RefCell<Timeline<M::Snapshot>>>,
+    tick: u32,
+    // the previous game's ticks stay around until the next one steps
+    fresh: bool,
+    rewind: Option<(u32, M::Snapshot)>,
+}
+
+impl<M: Rewind> History<M> {
+    pub fn new(
+        model: M,
+        timeline: Rc<RefCell<Timeline<M::Snapshot>>>,
+    ) -> Self {
+        History {
+            model,
+            timeline,
+            tick: 0,
+            fresh: true,
+            rewind: None,
+        }
+    }
+}
+
+impl<'m, M> Stateful<'m> for History<M>
+where
+    M: Rewind
+        + Stateful<'m, Cmd = Command, Update = TickDelta, Error = UpdateError>,
+{
+    type Cmd = Command;
+    type Init = M::Init;
+    type Update = TickDelta;
+    type Error = UpdateError;
+
+    fn initialize(&'m mut self) -> Self::Init {
+        self.model.initialize()
+    }
+
+    fn step(
+        &mut self,
+        cmd: Option<Command>,
+        tick: Tick,
+    ) -> Result<Option<TickDelta>, UpdateError> {
+        let mut timeline = self.timeline.borrow_mut();
+
+        if let Some(tick) = timeline.jump.take() {
+            if let Some(entry) = timeline.get(tick) {
+                self.rewind = Some((tick, entry.snapshot.clone()));
+                return Err(UpdateError::Restart);
+            }
+        }
+
+        let snapshot = self.model.snapshot();
+        let update = self.model.step(cmd, tick)?;
+
+        if let Some(delta) = update {
+            if self.fresh {
+                self.fresh = false;
+                timeline.entries.clear();
+                timeline.ended = false;
+            }
+
+            timeline.push(Entry {
+                tick: self.tick,
+                snapshot,
+                delta,
+            });
+            self.tick += 1;
+        }
+
+        Ok(update)
+    }
+
+    fn tear_down(&mut self) {
+        match self.rewind.take() {
+            Some((tick, snapshot)) => {
+                self.model.restore(&snapshot);
+                self.timeline.borrow_mut().truncate_from(tick);
+                self.tick = tick;
+                self.fresh = false;
+            }
+            None => {
+                self.model.tear_down();
+                self.timeline.borrow_mut().ended = true;
+                self.tick = 0;
+                self.fresh = true;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    use replay::initial_world;
+    use world::{Rules, WorldUpdate};
+
+    #[test]
+    fn test_jump_restores_the_tick() {
+        let timeline = Rc::new(RefCell::new(Timeline::new(4)));
+        let world = initial_world(16, 8, 1, Rules::default());
+        let mut history = History::new(world, timeline.clone());
+
+        for _ in history.initialize() {}
+        for _ in 0..6 {
+            Stateful::step(&mut history, None, 0).unwrap();
+        }
+
+        // only the last four ticks are kept
+        assert!(!timeline.borrow_mut().jump_to(1));
+        assert!(timeline.borrow_mut().jump_to(3));
+        let head = timeline.borrow().get(3).unwrap().snapshot.head();
+
+        assert_matches!(
+            Stateful::step(&mut history, None, 0),
+            Err(UpdateError::Restart)
+        );
+        Stateful::tear_down(&mut history);
+        assert_eq!(timeline.borrow().entries().count(), 1);
+
+        // the board is loaded as it was, the head of the snake last
+        let loaded: Vec<TickDelta> = history.initialize().collect();
+        assert_matches!(
+            loaded.last().unwrap().effects[0],
+            Some(WorldUpdate::SetBlock { at, .. }) if at == head
+        );
+
+        Stateful::step(&mut history, None, 0).unwrap();
+        assert!(timeline.borrow().get(3).is_some());
+    }
+
+    #[test]
+    fn test_latest_tick_outlasts_its_game() {
+        let timeline = Rc::new(RefCell::new(Timeline::new(4)));
+        let world = initial_world(16, 8, 1, Rules::default());
+        let mut history = History::new(world, timeline.clone());
+        let latest = |timeline: &Rc<RefCell<Timeline<_>>>| {
+            timeline.borrow().latest().map(|entry| entry.tick)
+        };
+
+        for _ in history.initialize() {}
+        for _ in 0..2 {
+            Stateful::step(&mut history, None, 0).unwrap();
+        }
+        assert_eq!(latest(&timeline), Some(1));
+        assert!(!timeline.borrow().ended());
+
+        // the next game doesn't clear it before it steps
+        Stateful::tear_down(&mut history);
+        for _ in history.initialize() {}
+        assert_eq!(latest(&timeline), Some(1));
+        assert!(timeline.borrow().ended());
+
+        Stateful::step(&mut history, None, 0).unwrap();
+        assert_eq!(latest(&timeline), Some(0));
+        assert!(!timeline.borrow().ended());
+    }
+}
@@ -0,0 +1,170 @@
+//! Every string a player sees, in each language the game comes in. Envs
+//! and models look their strings up in a `Locale` rather than spell them
+//! out, so that embedders can pick the language (or add one here) without
+//! touching the renderer.
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+/// A language of the game
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Locale {
+    En = 0,
+    Fr = 1,
+}
+
+/// A string a player sees, see `Locale::text`
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Text {
+    GameOver,
+    // labels of the heads up display
+    Time,
+    Lives,
+    Magnet,
+    Team,
+    // prompts of `tutorial::BASICS`
+    PressDown,
+    PressRight,
+    HoldToSprint,
+    WellDone,
+    // entries of the demo menu
+    Snake,
+    Life,
+    Blocks,
+    Replays,
+    // the game over summary, see `telemetry::GameSummary`, the labels
+    // followed by a number and the causes of the crash on their own
+    Length,
+    FoodEaten,
+    Coverage,
+    OutOfBound,
+    CollideBody,
+    CollideObstacle,
+    TimeUp,
+    // what a screen reader announces, the first two followed by a number
+    FoodEatenLength,
+    GameOverScore,
+    Started,
+    Paused,
+    Resumed,
+    Won,
+    // over the demo games of the attract mode
+    PressAnyKey,
+}
+
+impl Locale {
+    pub fn from_u8(n: u8) -> Option<Self> {
+        match n {
+            0 => Some(Locale::En),
+            1 => Some(Locale::Fr),
+            _ => None,
+        }
+    }
+
+    /// The locale of a language tag such as `navigator.language`'s
+    /// `"fr-CA"`, going by the language alone
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let language = tag.split(|c| c == '-' || c == '_').next()?;
+
+        if language.eq_ignore_ascii_case("en") {
+            Some(Locale::En)
+        } else if language.eq_ignore_ascii_case("fr") {
+            Some(Locale::Fr)
+        } else {
+            None
+        }
+    }
+
+    pub fn text(self, text: Text) -> &'static str {
+        match self {
+            Locale::En => en(text),
+            Locale::Fr => fr(text),
+        }
+    }
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Locale::En
+    }
+}
+
+fn en(text: Text) -> &'static str {
+    match text {
+        Text::GameOver => "Game Over",
+        Text::Time => "time",
+        Text::Lives => "lives",
+        Text::Magnet => "magnet",
+        Text::Team => "team",
+        Text::PressDown => "press \u{2193} to turn",
+        Text::PressRight => "press \u{2192} to turn back",
+        Text::HoldToSprint => "hold an arrow key to speed up",
+        Text::WellDone => "well done! eat to grow, turn to go on",
+        Text::Snake => "Snake",
+        Text::Life => "Game of Life",
+        Text::Blocks => "Falling Blocks",
+        Text::Replays => "Replays",
+        Text::Length => "length",
+        Text::FoodEaten => "food eaten",
+        Text::Coverage => "board covered",
+        Text::OutOfBound => "ran off the board",
+        Text::CollideBody => "ran into its own tail",
+        Text::CollideObstacle => "hit an obstacle",
+        Text::TimeUp => "ran out of time",
+        Text::FoodEatenLength => "food eaten, length",
+        Text::GameOverScore => "game over, score",
+        Text::Started => "game started",
+        Text::Paused => "paused",
+        Text::Resumed => "resumed",
+        Text::Won => "the board is yours, you won",
+        Text::PressAnyKey => "press any key to play",
+    }
+}
+
+fn fr(text: Text) -> &'static str {
+    match text {
+        Text::GameOver => "Partie termin\u{e9}e",
+        Text::Time => "temps",
+        Text::Lives => "vies",
+        Text::Magnet => "aimant",
+        Text::Team => "\u{e9}quipe",
+        Text::PressDown => "appuyez sur \u{2193} pour tourner",
+        Text::PressRight => "appuyez sur \u{2192} pour revenir",
+        Text::HoldToSprint => {
+            "maintenez une fl\u{e8}che pour acc\u{e9}l\u{e9}rer"
+        }
+        Text::WellDone => "bravo ! mangez pour grandir, tournez pour repartir",
+        Text::Snake => "Snake",
+        Text::Life => "Jeu de la vie",
+        Text::Blocks => "Blocs qui tombent",
+        Text::Replays => "Rediffusions",
+        Text::Length => "longueur",
+        Text::FoodEaten => "nourriture mang\u{e9}e",
+        Text::Coverage => "plateau parcouru",
+        Text::OutOfBound => "sorti du plateau",
+        Text::CollideBody => "mordu sa propre queue",
+        Text::CollideObstacle => "heurt\u{e9} un obstacle",
+        Text::TimeUp => "temps \u{e9}coul\u{e9}",
+        Text::FoodEatenLength => "nourriture mang\u{e9}e, longueur",
+        Text::GameOverScore => "partie termin\u{e9}e, score",
+        Text::Started => "partie lanc\u{e9}e",
+        Text::Paused => "en pause",
+        Text::Resumed => "reprise",
+        Text::Won => "le plateau est \u{e0} vous, gagn\u{e9}",
+        Text::PressAnyKey => "appuyez sur une touche pour jouer",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locale_from_language_tag() {
+        assert_eq!(Locale::from_tag("fr-CA"), Some(Locale::Fr));
+        assert_eq!(Locale::from_tag("EN_gb"), Some(Locale::En));
+        assert_eq!(Locale::from_tag("de"), None);
+        assert_eq!(Locale::Fr.text(Text::Lives), "vies");
+    }
+}
@@ -0,0 +1,160 @@
+use alloc::vec::Vec;
+
+use data::{Command, Direction, Direction8, Key};
+
+/// What a key is bound to, `Direction` bindings become `Command::Turn` or
+/// `Command::Sprint` depending on whether the key is held, the others only
+/// fire once per key press
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Binding {
+    Direction(Direction),
+    // a way between two arrows, for boards with diagonal moves; a snake
+    // keeps heading that way, so it fires once per key press
+    Diagonal(Direction8),
+    Pause,
+    Restart,
+    DebugToggle,
+    Quit,
+    RotateLeft,
+    RotateRight,
+    // debug stepping, which goes to the `Schedule` of the game rather than
+    // the model and so has no command
+    StepOnce,
+    FastForward,
+}
+
+pub struct KeyMap {
+    bindings: Vec<(u8, Binding)>,
+}
+
+impl KeyMap {
+    pub fn new() -> Self {
+        KeyMap {
+            bindings: Vec::new(),
+        }
+    }
+
+    /// Binds a key code, replacing any existing binding of that code
+    pub fn bind(&mut self, code: u8, binding: Binding) -> &mut Self {
+        self.unbind(code);
+        self.bindings.push((code, binding));
+        self
+    }
+
+    pub fn unbind(&mut self, code: u8) -> &mut Self {
+        self.bindings.retain(|(c, _)| *c != code);
+        self
+    }
+
+    pub fn binding(&self, key: Key) -> Option<Binding> {
+        self.bindings
+            .iter()
+            .find(|(code, _)| *code == key.code())
+            .map(|(_, binding)| *binding)
+    }
+
+    pub fn command(&self, key: Key) -> Option<Command> {
+        let command = match self.binding(key)? {
+            Binding::Direction(dir) if key.is_held() => Command::Sprint(dir),
+            Binding::Direction(dir) => Command::Turn(dir),
+            _ if key.is_repeat() => return None,
+            Binding::Pause => Command::Pause,
+            Binding::Restart => Command::Restart,
+            Binding::DebugToggle => Command::DebugToggle,
+            Binding::Quit => Command::Quit,
+            Binding::RotateLeft => Command::RotateLeft,
+            Binding::RotateRight => Command::RotateRight,
+            Binding::Diagonal(dir) => Command::Diagonal(dir),
+            Binding::StepOnce | Binding::FastForward => return None,
+        };
+
+        Some(command)
+    }
+}
+
+impl Default for KeyMap {
+    // key codes as reported by KeyboardEvent.keyCode
+    fn default() -> Self {
+        let mut keymap = KeyMap::new();
+
+        keymap
+            .bind(37, Binding::Direction(Direction::West))
+            .bind(38, Binding::Direction(Direction::North))
+            .bind(39, Binding::Direction(Direction::East))
+            .bind(40, Binding::Direction(Direction::South))
+            // home, page up, end and page down, the diagonals of the
+            // number pad without num lock
+            .bind(36, Binding::Diagonal(Direction8::NorthWest))
+            .bind(33, Binding::Diagonal(Direction8::NorthEast))
+            .bind(35, Binding::Diagonal(Direction8::SouthWest))
+            .bind(34, Binding::Diagonal(Direction8::SouthEast))
+            .bind(13, Binding::Restart)
+            .bind(80, Binding::Pause)
+            .bind(90, Binding::RotateLeft)
+            .bind(88, Binding::RotateRight)
+            .bind(192, Binding::DebugToggle)
+            .bind(190, Binding::StepOnce)
+            .bind(191, Binding::FastForward);
+
+        keymap
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data::KeyRepeat;
+
+    #[test]
+    fn test_default_keymap() {
+        let keymap = KeyMap::default();
+
+        assert_eq!(
+            keymap.command(Key::from(38)),
+            Some(Command::Turn(Direction::North))
+        );
+        assert_eq!(keymap.command(Key::from(80)), Some(Command::Pause));
+        assert_eq!(keymap.command(Key::from(88)), Some(Command::RotateRight));
+        assert_eq!(
+            keymap.command(Key::from(33)),
+            Some(Command::Diagonal(Direction8::NorthEast))
+        );
+        assert_eq!(keymap.command(Key::none()), None);
+        assert_eq!(keymap.binding(Key::from(190)), Some(Binding::StepOnce));
+        assert_eq!(keymap.command(Key::from(190)), None);
+    }
+
+    #[test]
+    fn test_held_direction_key_sprints() {
+        let keymap = KeyMap::default();
+        let mut repeat = KeyRepeat::new();
+
+        let command = (0..100)
+            .map(|_| keymap.command(repeat.feed(Key::from(39))))
+            .last()
+            .unwrap();
+
+        assert_eq!(command, Some(Command::Sprint(Direction::East)));
+    }
+
+    #[test]
+    fn test_pause_fires_once_per_press() {
+        let keymap = KeyMap::default();
+        let mut repeat = KeyRepeat::new();
+
+        let pauses = (0..10)
+            .filter_map(|_| keymap.command(repeat.feed(Key::from(80))))
+            .count();
+
+        assert_eq!(pauses, 1);
+    }
+
+    #[test]
+    fn test_rebind_replaces_binding() {
+        let mut keymap = KeyMap::default();
+
+        keymap.bind(80, Binding::Quit);
+
+        assert_eq!(keymap.command(Key::from(80)), Some(Command::Quit));
+    }
+}
@@ -0,0 +1,110 @@
+//! Engine of the game: the board, the world model, the state machine
+//! combinators and the incremental renderer, free of any browser api so that
+//! servers, terminals and tests can run the same simulation as the browser.
+//!
+//! The crate is `#![no_std]` and only needs an allocator unless the `std`
+//! feature is on, so the engine also runs on embedded targets:
+//!
+//! ```sh
+//! cargo +nightly build -p snake-core --target thumbv7em-none-eabihf
+//! ```
+//!
+//! With the `embedded` feature, `embedded::MatrixEnv` draws the game on any
+//! embedded-graphics `DrawTarget`. `config::Config` holds the settings of a
+//! game in any frontend, and with `config` native ones read it from a file.
+//!
+//! Models, renderers and envs meet in `system`, which isn't tied to the
+//! snake: `life` plays Conway's Game of Life on it, `blocks` falling blocks
+//! and `examples/langton.rs` Langton's ant.
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+#![feature(alloc, arbitrary_self_types, generators, generator_trait)]
+
+#[cfg(not(any(feature = "std", test, debug)))]
+extern crate core as std;
+
+#[macro_use]
+extern crate alloc;
+
+extern crate void;
+#[cfg(feature = "wasm")]
+extern crate wasm_bindgen;
+#[cfg(feature = "embedded")]
+extern crate embedded_graphics;
+#[cfg(feature = "config")]
+extern crate serde;
+#[cfg(feature = "config")]
+#[macro_use]
+extern crate serde_derive;
+#[cfg(feature = "config")]
+extern crate serde_json;
+#[cfg(feature = "config")]
+extern crate toml;
+
+#[macro_use]
+extern crate itertools;
+extern crate arraydeque;
+extern crate morton;
+extern crate rand;
+
+#[macro_use]
+#[cfg(test)]
+extern crate indoc;
+#[macro_use]
+#[cfg(test)]
+extern crate matches;
+#[macro_use]
+#[cfg(test)]
+extern crate quickcheck;
+
+pub use rand::rngs::SmallRng;
+
+#[macro_use]
+mod macros;
+#[cfg(test)]
+mod counting_alloc;
+mod interleave;
+
+pub mod data;
+pub mod draw;
+pub mod system;
+pub mod world;
+
+pub mod acceleration;
+pub mod blocks;
+pub mod bot;
+pub mod checkpoint;
+pub mod config;
+pub mod constants;
+pub mod dead;
+#[cfg(feature = "embedded")]
+pub mod embedded;
+pub mod formats;
+pub mod history;
+pub mod i18n;
+pub mod keymap;
+pub mod life;
+pub mod log;
+pub mod replay;
+pub mod theme;
+pub mod tutorial;
+
+pub use acceleration::{Paced, RenderSpeed, VariableFrame};
+pub use blocks::{BlocksDraw, BlocksUpdate, FallingBlocks};
+pub use checkpoint::Checkpoints;
+pub use data::{Bounding, Command, Direction, Key, KeyRepeat, Wrapping};
+pub use dead::{CtrlEvent, Dead};
+pub use draw::{TickDraw, WorldUpdateDraw};
+pub use history::{History, Rewind, Timeline};
+pub use i18n::{Locale, Text};
+pub use keymap::{Binding, KeyMap};
+pub use life::{LifeDraw, LifeModel, LifeUpdate};
+pub use log::LogLevel;
+#[cfg(not(target_arch = "wasm32"))]
+pub use replay::verify_replay;
+pub use replay::{Playback, Record, Replay, Score, VerifyError};
+pub use system::*;
+pub use tutorial::Tutorial;
+pub use world::{
+    PerChange, Rules, Snake, SnakeBody, SnakeId, Snapshot, TeamId, TickDelta,
+    Versus, World, WorldBuilder, WorldUpdate, PLAYER,
+};
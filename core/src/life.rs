@@ -0,0 +1,284 @@
+//! Conway's Game of Life, the second demo of `system`: a board of cells that
+//! all change at once, drawn a generation at a time.
+use alloc::vec::Vec;
+
+use rand::Rng;
+
+use constants::{ANIMATION_FRAME_COUNT, TILE_SIZE};
+use data::{Command, Coordinate, Direction, Grid, SmallNat};
+use system::{
+    DrawGrid, FrameCtx, GameOver, IncrRender, Stateful, Tick, UnitInterval,
+};
+
+// chance of a cell to start out alive
+const DENSITY: f64 = 0.3;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum LifeUpdate {
+    Loaded { width: SmallNat, height: SmallNat },
+    // every cell that changed in a generation, drawn together
+    Generation {
+        born: Vec<Coordinate>,
+        died: Vec<Coordinate>,
+    },
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum LifeError {
+    // no cell is left alive
+    Extinct,
+    Quit,
+}
+
+impl Into<GameOver> for LifeError {
+    fn into(self) -> GameOver {
+        match self {
+            LifeError::Extinct => GameOver::Over,
+            LifeError::Quit => GameOver::Quit,
+        }
+    }
+}
+
+/// Cells alive or dead on a board wrapping around at the edges, a generation
+/// per step. `Command::Pause` holds the board, `Command::Restart` seeds a new
+/// one.
+pub struct LifeModel<R> {
+    cells: Grid<bool>,
+    rng: R,
+    paused: bool,
+}
+
+impl<R: Rng> LifeModel<R> {
+    /// A `width` x `height` board seeded by `rng`
+    pub fn new(width: SmallNat, height: SmallNat, rng: R) -> Self {
+        let cells = Grid::filled(width, height, false);
+        let mut life = Self::from_cells(cells, rng);
+        life.cells = life.seeded();
+        life
+    }
+
+    /// Starts from `cells` rather than a random board
+    pub fn from_cells(cells: Grid<bool>, rng: R) -> Self {
+        LifeModel {
+            cells,
+            rng,
+            paused: false,
+        }
+    }
+
+    pub fn is_alive(&self, at: Coordinate) -> bool {
+        self.cells.get(at).cloned().unwrap_or(false)
+    }
+
+    // a new random board of the same size
+    fn seeded(&mut self) -> Grid<bool> {
+        let (width, height) = (self.cells.width(), self.cells.height());
+        let mut cells = Grid::filled(width, height, false);
+        for (x, y) in iproduct!(0..width, 0..height) {
+            if let Some(cell) = cells.get_mut(Coordinate { x, y }) {
+                *cell = self.rng.gen_bool(DENSITY);
+            }
+        }
+        cells
+    }
+
+    fn neighbours(&self, at: Coordinate) -> usize {
+        let (width, height) = (self.cells.width(), self.cells.height());
+        let around = |n: SmallNat, size: SmallNat| {
+            [(n + size - 1) % size, n, (n + 1) % size]
+        };
+
+        iproduct!(around(at.x, width).iter(), around(at.y, height).iter())
+            .map(|(&x, &y)| Coordinate { x, y })
+            .filter(|&c| c != at && self.is_alive(c))
+            .count()
+    }
+
+    // moves the board to `next`, the cells that changed on the way
+    fn replace(&mut self, next: Grid<bool>) -> LifeUpdate {
+        let (mut born, mut died) = (Vec::new(), Vec::new());
+        for (at, alive) in self.cells.diff(&next) {
+            if alive {
+                born.push(at);
+            } else {
+                died.push(at);
+            }
+        }
+
+        self.cells = next;
+        LifeUpdate::Generation { born, died }
+    }
+
+    fn next_generation(&self) -> Grid<bool> {
+        let mut next = self.cells.clone();
+        for at in self.cells.iter_coordinates() {
+            let alive = match (self.is_alive(at), self.neighbours(at)) {
+                (true, 2) | (_, 3) => true,
+                _ => false,
+            };
+            if let Some(cell) = next.get_mut(at) {
+                *cell = alive;
+            }
+        }
+        next
+    }
+}
+
+impl<'m, R: Rng + 'm> Stateful<'m> for LifeModel<R> {
+    type Cmd = Command;
+    type Update = LifeUpdate;
+    type Init = Vec<LifeUpdate>;
+    type Error = LifeError;
+
+    fn initialize(&'m mut self) -> Self::Init {
+        let born = self
+            .cells
+            .iter_coordinates()
+            .filter(|&c| self.is_alive(c))
+            .collect();
+
+        vec![
+            LifeUpdate::Loaded {
+                width: self.cells.width(),
+                height: self.cells.height(),
+            },
+            LifeUpdate::Generation {
+                born,
+                died: Vec::new(),
+            },
+        ]
+    }
+
+    fn step(
+        &mut self,
+        cmd: Option<Command>,
+        _tick: Tick,
+    ) -> Result<Option<LifeUpdate>, LifeError> {
+        match cmd {
+            Some(Command::Quit) => return Err(LifeError::Quit),
+            Some(Command::Pause) => self.paused = !self.paused,
+            Some(Command::Restart) => {
+                let next = self.seeded();
+                return Ok(Some(self.replace(next)));
+            }
+            _ => {}
+        }
+
+        if self.paused {
+            return Ok(None);
+        }
+
+        let next = self.next_generation();
+        if self.cells.iter_coordinates().all(|at| next.get(at) != Some(&true)) {
+            return Err(LifeError::Extinct);
+        }
+        Ok(Some(self.replace(next)))
+    }
+
+    fn tear_down(&mut self) {
+        self.paused = false;
+        self.cells = self.seeded();
+    }
+}
+
+/// Draws a generation in `ANIMATION_FRAME_COUNT` frames, the cells born
+/// growing in while the ones that died fade out, all in the same frames
+pub struct LifeDraw {
+    update: LifeUpdate,
+    current_frame: u8,
+}
+
+impl<E: DrawGrid> IncrRender<E> for LifeDraw {
+    type Patch = LifeUpdate;
+
+    fn new_patch(update: LifeUpdate, _ctx: FrameCtx) -> Self {
+        LifeDraw {
+            update,
+            current_frame: 0,
+        }
+    }
+
+    fn render(&mut self, env: &mut E) -> Option<()> {
+        let t = UnitInterval::from_u8_and_range(
+            self.current_frame,
+            0..ANIMATION_FRAME_COUNT,
+        );
+        let dir = Direction::East;
+
+        match self.update {
+            LifeUpdate::Loaded { width, height } => {
+                env.clear();
+                env.setup(TILE_SIZE as SmallNat, width, height);
+                return None;
+            }
+            LifeUpdate::Generation {
+                ref born,
+                ref died,
+            } => {
+                for at in born {
+                    env.fill_tile(at.x, at.y, dir, t);
+                }
+                for at in died {
+                    env.clear_tile(at.x, at.y, dir, t);
+                }
+            }
+        }
+
+        self.current_frame += 1;
+        if self.current_frame >= ANIMATION_FRAME_COUNT {
+            None
+        } else {
+            Some(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use world::FakeRng;
+
+    fn blinker() -> LifeModel<FakeRng> {
+        let mut cells = Grid::filled(5, 5, false);
+        for y in 1..4 {
+            *cells.get_mut(Coordinate { x: 2, y }).unwrap() = true;
+        }
+        LifeModel::from_cells(cells, FakeRng::new(vec![]))
+    }
+
+    #[test]
+    fn test_blinker_turns_over() {
+        let mut life = blinker();
+
+        let update = life.step(None, 0).unwrap().unwrap();
+        let at = |x, y| Coordinate { x, y };
+        assert_eq!(
+            update,
+            LifeUpdate::Generation {
+                born: vec![at(1, 2), at(3, 2)],
+                died: vec![at(2, 1), at(2, 3)],
+            }
+        );
+
+        life.step(None, 0).unwrap();
+        assert!((1..4).all(|y| life.is_alive(at(2, y))));
+        assert!(!life.is_alive(at(1, 2)));
+    }
+
+    #[test]
+    fn test_paused_board_holds() {
+        let mut life = blinker();
+        assert_eq!(life.step(Some(Command::Pause), 0), Ok(None));
+        assert_eq!(life.step(None, 0), Ok(None));
+        assert!(life.step(Some(Command::Pause), 0).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_lonely_cell_dies_out() {
+        let mut cells = Grid::filled(4, 4, false);
+        *cells.get_mut(Coordinate { x: 1, y: 1 }).unwrap() = true;
+        let mut life = LifeModel::from_cells(cells, FakeRng::new(vec![]));
+
+        assert_eq!(life.step(None, 0), Err(LifeError::Extinct));
+    }
+}
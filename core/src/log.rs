@@ -0,0 +1,80 @@
+use std::fmt;
+use std::mem;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+/// Verbosity of the engine log, see the `trace!`, `debug!`, `info!` and
+/// `warn!` macros
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum LogLevel {
+    Trace = 0,
+    Debug = 1,
+    Info = 2,
+    Warn = 3,
+    Off = 4,
+}
+
+impl LogLevel {
+    fn from_usize(n: usize) -> Self {
+        match n {
+            0 => LogLevel::Trace,
+            1 => LogLevel::Debug,
+            2 => LogLevel::Info,
+            3 => LogLevel::Warn,
+            _ => LogLevel::Off,
+        }
+    }
+}
+
+static LEVEL: AtomicUsize = AtomicUsize::new(LogLevel::Warn as usize);
+
+pub fn set_level(level: LogLevel) {
+    LEVEL.store(level as usize, Ordering::Relaxed);
+}
+
+pub fn level() -> LogLevel {
+    LogLevel::from_usize(LEVEL.load(Ordering::Relaxed))
+}
+
+#[inline]
+pub fn enabled(level: LogLevel) -> bool {
+    level != LogLevel::Off && level >= self::level()
+}
+
+/// Writes a log line, installed with `set_logger` by frontends that have a
+/// better place for logs than stdout
+pub type Logger = fn(LogLevel, fmt::Arguments);
+
+static LOGGER: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+pub fn set_logger(logger: Logger) {
+    LOGGER.store(logger as *mut (), Ordering::Relaxed);
+}
+
+#[doc(hidden)]
+pub fn log(level: LogLevel, args: fmt::Arguments) {
+    if !enabled(level) {
+        return;
+    }
+
+    let logger = LOGGER.load(Ordering::Relaxed);
+    if logger.is_null() {
+        write(level, args);
+    } else {
+        // only ever stored from a `Logger` in `set_logger`
+        let logger: Logger = unsafe { mem::transmute(logger) };
+        logger(level, args);
+    }
+}
+
+#[cfg(any(feature = "std", test))]
+fn write(level: LogLevel, args: fmt::Arguments) {
+    println!("[{:?}] {}", level, args);
+}
+
+#[cfg(not(any(feature = "std", test)))]
+fn write(_level: LogLevel, _args: fmt::Arguments) {}
@@ -0,0 +1,42 @@
+#[macro_export]
+macro_rules! yield_from {
+    ($e:expr) => {{
+        use std::ops::{Generator, GeneratorState};
+
+        let mut gen = $e;
+        loop {
+            match unsafe { gen.resume() } {
+                GeneratorState::Complete(_) => break,
+                GeneratorState::Yielded(x) => yield x,
+            }
+        }
+    }};
+}
+
+#[macro_export]
+macro_rules! trace {
+    ($($t:tt)*) => {
+        $crate::log::log($crate::log::LogLevel::Trace, format_args!($($t)*))
+    };
+}
+
+#[macro_export]
+macro_rules! debug {
+    ($($t:tt)*) => {
+        $crate::log::log($crate::log::LogLevel::Debug, format_args!($($t)*))
+    };
+}
+
+#[macro_export]
+macro_rules! info {
+    ($($t:tt)*) => {
+        $crate::log::log($crate::log::LogLevel::Info, format_args!($($t)*))
+    };
+}
+
+#[macro_export]
+macro_rules! warn {
+    ($($t:tt)*) => {
+        $crate::log::log($crate::log::LogLevel::Warn, format_args!($($t)*))
+    };
+}
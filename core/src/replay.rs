@@ -0,0 +1,519 @@
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use std::cell::RefCell;
+use std::fmt;
+
+use rand::rngs::SmallRng;
+
+use constants::RESPAWN_SHIELD;
+use data::{Block, Command, Direction, Direction8, SmallNat, Wrapping};
+use formats;
+use system::{Stateful, Tick};
+use world::{
+    Lives, Rules, TickDelta, UpdateError, World, WorldBuilder, WorldUpdate,
+    PLAYER,
+};
+
+const MAGIC: &[u8; 4] = b"SNKR";
+// magic, version, width, height, seed, lives, rules
+const HEADER_LEN: usize = 4 + 1 + 2 + 2 + 4 + 4 + Rules::LEN;
+
+/// The world every game starts from: a snake of four blocks heading east
+/// from the top left corner, on a board that wraps unless `rules` say
+/// otherwise
+pub fn initial_world(
+    width: SmallNat,
+    height: SmallNat,
+    seed: u32,
+    rules: Rules,
+) -> World<SmallRng, Wrapping> {
+    let facing = Direction::East;
+
+    WorldBuilder::new()
+        .width(width)
+        .height(height)
+        .rules(rules)
+        .set_snake(1, 1)
+        .extend(facing)
+        .extend(facing)
+        .extend(facing)
+        .extend(facing)
+        .build_with_seed(rng_seed(seed))
+}
+
+/// The `Lives` of games with `total` of them, `None` for a single one
+pub fn lives(total: u32) -> Option<Lives> {
+    if total > 1 {
+        Some(Lives::new(total, RESPAWN_SHIELD))
+    } else {
+        None
+    }
+}
+
+pub fn rng_seed(seed: u32) -> [u8; 16] {
+    let mut bytes = [0; 16];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = (seed >> (8 * (i % 4))) as u8;
+    }
+    bytes
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum VerifyError {
+    BadMagic,
+    UnsupportedVersion(u8),
+    Truncated,
+    UnknownCommand(u8),
+    // rules of a later version
+    UnknownRules,
+    // too small to place the initial snake on, or larger than a board gets
+    InvalidBoard,
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            VerifyError::BadMagic => write!(f, "not a replay"),
+            VerifyError::UnsupportedVersion(v) => {
+                write!(f, "unsupported replay version {}", v)
+            }
+            VerifyError::Truncated => write!(f, "replay is truncated"),
+            VerifyError::UnknownCommand(c) => {
+                write!(f, "unknown command {} in replay", c)
+            }
+            VerifyError::UnknownRules => write!(f, "unknown rules in replay"),
+            VerifyError::InvalidBoard => write!(f, "invalid board size"),
+        }
+    }
+}
+
+/// Every command a `World` stepped with since it was created, enough to
+/// re-simulate its games deterministically
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Replay {
+    pub width: SmallNat,
+    pub height: SmallNat,
+    pub seed: u32,
+    // see `lives`, the snake respawns on the same ticks when played back
+    pub lives: u32,
+    pub rules: Rules,
+    commands: Vec<Option<Command>>,
+}
+
+impl Replay {
+    pub fn new(width: SmallNat, height: SmallNat, seed: u32) -> Self {
+        Replay {
+            width,
+            height,
+            seed,
+            lives: 0,
+            rules: Rules::default(),
+            commands: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, cmd: Option<Command>) {
+        self.commands.push(cmd);
+    }
+
+    pub fn commands(&self) -> &[Option<Command>] {
+        &self.commands
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_LEN + self.commands.len());
+
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(formats::REPLAY);
+        bytes.extend_from_slice(&le_u16(self.width));
+        bytes.extend_from_slice(&le_u16(self.height));
+        bytes.extend_from_slice(&le_u32(self.seed));
+        bytes.extend_from_slice(&le_u32(self.lives));
+        bytes.extend_from_slice(&self.rules.to_bytes());
+        bytes.extend(self.commands.iter().map(|&cmd| encode_command(cmd)));
+
+        bytes
+    }
+
+    /// Decodes a replay of `to_bytes`, of this version or one that
+    /// `formats::migrate_replay` brings up to it
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, VerifyError> {
+        if !bytes.starts_with(MAGIC) {
+            return Err(if bytes.len() < 4 {
+                VerifyError::Truncated
+            } else {
+                VerifyError::BadMagic
+            });
+        }
+        let bytes = formats::migrate_replay(bytes)?;
+        let bytes = &bytes[..];
+        if bytes.len() < HEADER_LEN {
+            return Err(VerifyError::Truncated);
+        }
+
+        let u32_at = |at: usize| {
+            (0..4).fold(0u32, |n, i| n | u32::from(bytes[at + i]) << (8 * i))
+        };
+        let mut replay = Replay::new(
+            u16::from(bytes[5]) | u16::from(bytes[6]) << 8,
+            u16::from(bytes[7]) | u16::from(bytes[8]) << 8,
+            u32_at(9),
+        );
+        replay.lives = u32_at(13);
+        let mut rules = [0; Rules::LEN];
+        rules.copy_from_slice(&bytes[17..17 + Rules::LEN]);
+        replay.rules =
+            Rules::from_bytes(rules).ok_or(VerifyError::UnknownRules)?;
+
+        for &byte in &bytes[HEADER_LEN..] {
+            replay.push(decode_command(byte)?);
+        }
+
+        Ok(replay)
+    }
+
+    /// FNV-1a of the encoded replay, submitted along with scores
+    pub fn hash(&self) -> u32 {
+        self.to_bytes().iter().fold(0x811c_9dc5u32, |h, &b| {
+            (h ^ u32::from(b)).wrapping_mul(0x0100_0193)
+        })
+    }
+}
+
+/// Outcome of the last game in a replay
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct Score {
+    pub food: u32,
+    pub length: u32,
+    pub steps: u32,
+}
+
+impl Score {
+    fn record(&mut self, delta: &TickDelta) {
+        for update in delta.changes() {
+            match update {
+                WorldUpdate::SetBlock {
+                    block: Block::Snake(_, PLAYER),
+                    ..
+                }
+                | WorldUpdate::HeadMoved { snake: PLAYER, .. }
+                | WorldUpdate::Bend { snake: PLAYER, .. } => self.length += 1,
+                WorldUpdate::FoodEaten { snake: PLAYER, .. } => self.food += 1,
+                WorldUpdate::TailRetracted { snake: PLAYER, .. } => {
+                    self.length = self.length.saturating_sub(1)
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Re-simulates an encoded `Replay` and returns the score of the last game
+/// played in it, for servers validating leaderboard submissions
+#[cfg(not(target_arch = "wasm32"))]
+pub fn verify_replay(bytes: &[u8]) -> Result<Score, VerifyError> {
+    let replay = Replay::from_bytes(bytes)?;
+
+    // the initial snake spans x = 1..5 and needs room for its next move,
+    // and a forged replay mustn't make the server allocate gigabytes
+    let fits = ::data::Grid::check_size(replay.width, replay.height).is_ok();
+    if replay.width < 6 || replay.height < 2 || !fits {
+        return Err(VerifyError::InvalidBoard);
+    }
+
+    let mut world = initial_world(
+        replay.width,
+        replay.height,
+        replay.seed,
+        replay.rules,
+    );
+    world.set_lives(lives(replay.lives));
+    let mut commands = replay.commands().iter().peekable();
+    // counted across games, as by the `Game` that recorded it
+    let mut tick: Tick = 0;
+
+    loop {
+        let mut score = Score::default();
+        for delta in world.initialize() {
+            score.record(&delta);
+        }
+        // the first food is part of the board, not eaten
+        score.food = 0;
+
+        loop {
+            let cmd = match commands.next() {
+                Some(&cmd) => cmd,
+                None => return Ok(score),
+            };
+
+            score.steps += 1;
+
+            // not the inherent `World::step`, which takes a direction
+            let stepped = Stateful::step(&mut world, cmd, tick);
+            tick = tick.wrapping_add(1);
+
+            match stepped {
+                Ok(Some(delta)) => score.record(&delta),
+                Ok(None) => {}
+                Err(UpdateError::Quit) => return Ok(score),
+                // the snake starts over, the game and its score go on
+                Err(UpdateError::LifeLost) => {
+                    world.tear_down();
+                    score.length = 0;
+                    for delta in world.initialize() {
+                        score.record(&delta);
+                    }
+                }
+                Err(_) if commands.peek().is_none() => return Ok(score),
+                Err(_) => break,
+            }
+        }
+
+        world.tear_down();
+    }
+}
+
+/// Records the commands `model` is stepped with into a shared `Replay`
+pub struct Record<M> {
+    model: M,
+    replay: Rc<RefCell<Replay>>,
+}
+
+impl<M> Record<M> {
+    pub fn new(model: M, replay: Rc<RefCell<Replay>>) -> Self {
+        Record { model, replay }
+    }
+}
+
+impl<'m, M> Stateful<'m> for Record<M>
+where
+    M: Stateful<'m, Cmd = Command>,
+{
+    type Cmd = Command;
+    type Init = M::Init;
+    type Update = M::Update;
+    type Error = M::Error;
+
+    fn initialize(&'m mut self) -> Self::Init {
+        self.model.initialize()
+    }
+
+    #[inline]
+    fn step(
+        &mut self,
+        cmd: Option<Self::Cmd>,
+        tick: Tick,
+    ) -> Result<Option<Self::Update>, Self::Error> {
+        self.replay.borrow_mut().push(cmd);
+        self.model.step(cmd, tick)
+    }
+
+    fn tear_down(&mut self) {
+        self.model.tear_down();
+    }
+}
+
+/// Steps `model` with the commands of a `Replay` in place of its input, and
+/// quits once they run out, to play a stored game back through a renderer
+pub struct Playback<M> {
+    model: M,
+    commands: Vec<Option<Command>>,
+    next: usize,
+}
+
+impl<M> Playback<M> {
+    pub fn new(model: M, commands: Vec<Option<Command>>) -> Self {
+        Playback {
+            model,
+            commands,
+            next: 0,
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.next >= self.commands.len()
+    }
+}
+
+impl<'m, M> Stateful<'m> for Playback<M>
+where
+    M: Stateful<'m, Cmd = Command, Error = UpdateError>,
+{
+    type Cmd = Command;
+    type Init = M::Init;
+    type Update = M::Update;
+    type Error = UpdateError;
+
+    fn initialize(&'m mut self) -> Self::Init {
+        self.model.initialize()
+    }
+
+    fn step(
+        &mut self,
+        _cmd: Option<Command>,
+        tick: Tick,
+    ) -> Result<Option<Self::Update>, UpdateError> {
+        let cmd = *self.commands.get(self.next).ok_or(UpdateError::Quit)?;
+        self.next += 1;
+
+        self.model.step(cmd, tick)
+    }
+
+    // keeps its place, a game that ended in the replay goes on with the
+    // commands of the next one
+    fn tear_down(&mut self) {
+        self.model.tear_down();
+    }
+}
+
+fn encode_command(cmd: Option<Command>) -> u8 {
+    match cmd {
+        None => 0,
+        Some(Command::Turn(dir)) => 1 + dir as u8,
+        Some(Command::Sprint(dir)) => 5 + dir as u8,
+        Some(Command::Pause) => 9,
+        Some(Command::Restart) => 10,
+        Some(Command::DebugToggle) => 11,
+        Some(Command::Quit) => 12,
+        Some(Command::RotateLeft) => 13,
+        Some(Command::RotateRight) => 14,
+        Some(Command::Diagonal(dir)) => 15 + dir as u8,
+    }
+}
+
+fn decode_command(byte: u8) -> Result<Option<Command>, VerifyError> {
+    let dir = |n: u8| {
+        Direction::from_u8(n).ok_or(VerifyError::UnknownCommand(byte))
+    };
+
+    Ok(match byte {
+        0 => None,
+        1...4 => Some(Command::Turn(dir(byte - 1)?)),
+        5...8 => Some(Command::Sprint(dir(byte - 5)?)),
+        9 => Some(Command::Pause),
+        10 => Some(Command::Restart),
+        11 => Some(Command::DebugToggle),
+        12 => Some(Command::Quit),
+        13 => Some(Command::RotateLeft),
+        14 => Some(Command::RotateRight),
+        15...22 => Some(Command::Diagonal(
+            Direction8::from_u8(byte - 15)
+                .ok_or(VerifyError::UnknownCommand(byte))?,
+        )),
+        _ => return Err(VerifyError::UnknownCommand(byte)),
+    })
+}
+
+fn le_u16(n: u16) -> [u8; 2] {
+    [n as u8, (n >> 8) as u8]
+}
+
+fn le_u32(n: u32) -> [u8; 4] {
+    [n as u8, (n >> 8) as u8, (n >> 16) as u8, (n >> 24) as u8]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use history::Rewind;
+
+    fn sample() -> Replay {
+        let mut replay = Replay::new(16, 8, 0xdead_beef);
+        replay.push(None);
+        replay.push(Some(Command::Turn(Direction::South)));
+        replay.push(Some(Command::Sprint(Direction::South)));
+        for _ in 0..10 {
+            replay.push(None);
+        }
+        replay
+    }
+
+    #[test]
+    fn test_replay_roundtrip() {
+        let mut replay = sample();
+        replay.lives = 3;
+        replay.rules.growth = 2;
+        let bytes = replay.to_bytes();
+
+        assert_eq!(Replay::from_bytes(&bytes), Ok(replay.clone()));
+        assert_eq!(Replay::from_bytes(b"SNKR"), Err(VerifyError::Truncated));
+        assert_eq!(Replay::from_bytes(b"nope"), Err(VerifyError::BadMagic));
+        assert_eq!(
+            Replay::from_bytes(&[0; HEADER_LEN]),
+            Err(VerifyError::BadMagic)
+        );
+
+        let mut corrupt = bytes.clone();
+        corrupt.push(200);
+        assert_eq!(
+            Replay::from_bytes(&corrupt),
+            Err(VerifyError::UnknownCommand(200))
+        );
+
+        let mut later = bytes.clone();
+        later[17] |= 1 << 7;
+        assert_eq!(Replay::from_bytes(&later), Err(VerifyError::UnknownRules));
+    }
+
+    #[test]
+    fn test_playback_quits_when_the_replay_runs_out() {
+        let replay = sample();
+        let (width, height) = (replay.width, replay.height);
+        let world = initial_world(width, height, replay.seed, replay.rules);
+        let mut playback = Playback::new(world, replay.commands().to_vec());
+
+        // the commands of the replay win over the ones passed in
+        let quit = Some(Command::Quit);
+        for _ in 0..replay.commands().len() {
+            let result = Stateful::step(&mut playback, quit, 0);
+            assert_ne!(result.err(), Some(UpdateError::Quit));
+        }
+
+        assert!(playback.is_done());
+        assert_matches!(
+            Stateful::step(&mut playback, None, 0),
+            Err(UpdateError::Quit)
+        );
+    }
+
+    #[test]
+    fn test_playback_ends_on_the_recorded_board() {
+        let sample = sample();
+        let replay = Rc::new(RefCell::new(Replay::new(16, 8, sample.seed)));
+        let world = initial_world(16, 8, sample.seed, sample.rules);
+        let mut played = Record::new(world, replay.clone());
+        for _ in played.initialize() {}
+        for &cmd in sample.commands() {
+            let _ = Stateful::step(&mut played, cmd, 0);
+        }
+
+        let commands = replay.borrow().commands().to_vec();
+        let world = initial_world(16, 8, sample.seed, sample.rules);
+        let mut playback = Playback::new(world, commands);
+        for _ in playback.initialize() {}
+        while !playback.is_done() {
+            let _ = Stateful::step(&mut playback, None, 0);
+        }
+
+        let recorded = played.model.snapshot();
+        let replayed = playback.model.snapshot();
+        assert!(recorded.grid().diff(replayed.grid()).is_empty());
+    }
+
+    #[test]
+    fn test_verify_is_deterministic() {
+        let bytes = sample().to_bytes();
+
+        let score = verify_replay(&bytes).unwrap();
+        assert_eq!(verify_replay(&bytes), Ok(score));
+        assert_eq!(score.steps, 13);
+        assert!(score.length >= 4);
+
+        let mut tiny = Replay::new(4, 4, 0);
+        tiny.push(None);
+        assert_eq!(
+            verify_replay(&tiny.to_bytes()),
+            Err(VerifyError::InvalidBoard)
+        );
+    }
+}
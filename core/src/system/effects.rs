@@ -0,0 +1,81 @@
+use alloc::vec::Vec;
+
+use super::UnitInterval;
+
+/// Effects an env keeps drawing for a number of frames after the update
+/// that started them, such as a popup floating off, on top of the board
+/// that an `IncrRender` draws
+#[derive(Debug, Clone)]
+pub struct Effects<T> {
+    // each effect with the frames it lasts and the frames it is into them
+    running: Vec<(T, u8, u8)>,
+}
+
+impl<T> Effects<T> {
+    pub fn new() -> Self {
+        Effects {
+            running: Vec::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.running.is_empty()
+    }
+
+    /// Starts `effect`, to last `frames` frames
+    pub fn push(&mut self, effect: T, frames: u8) {
+        self.running.push((effect, ::std::cmp::max(1, frames), 0));
+    }
+
+    pub fn clear(&mut self) {
+        self.running.clear();
+    }
+
+    /// Moves every effect on a frame, dropping the effects over, and calls
+    /// `draw` with each of the others and how far along it is
+    pub fn advance<F>(&mut self, mut draw: F)
+    where
+        F: FnMut(&T, UnitInterval),
+    {
+        for effect in &mut self.running {
+            effect.2 += 1;
+        }
+        self.running.retain(|&(_, frames, frame)| frame <= frames);
+
+        for &(ref effect, frames, frame) in &self.running {
+            let t = UnitInterval::from_u8_and_range(frame - 1, 0..frames);
+            draw(effect, t);
+        }
+    }
+}
+
+impl<T> Default for Effects<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_effect_runs_for_its_frames() {
+        let mut effects = Effects::new();
+        effects.push('a', 2);
+
+        let mut drawn = 0;
+        effects.advance(|_, t| {
+            assert!(t == UnitInterval::from_u8_and_range(0, 0..2));
+            drawn += 1;
+        });
+        effects.advance(|_, t| {
+            assert!(t == UnitInterval::max_value());
+            drawn += 1;
+        });
+        assert_eq!(drawn, 2);
+
+        effects.advance(|_, _| panic!("the effect is over"));
+        assert!(effects.is_empty());
+    }
+}
@@ -0,0 +1,39 @@
+//! The framework the game is built on, which knows nothing about snakes:
+//!
+//! - A model is `Stateful`. `initialize` yields the updates that set up a
+//!   game, `step` turns an optional command into an optional update or an
+//!   error, whose `GameOver` tells whether to start over or to quit, and
+//!   `tear_down` gets the model ready for the next game. The `Game` counts
+//!   the ticks, each step gets the current one.
+//! - The combinators of `Stateful` stack models: `observe` lets an
+//!   `Observer` watch one, `zip_with` steps two in lockstep and
+//!   `alternating` hands over from one to the other once a game is over.
+//! - An `IncrRender` draws each update over a few frames on a `DrawGrid`, a
+//!   board of square tiles, knowing the tick of the update from its
+//!   `FrameCtx`. `Effects` outlast the update that started them, an env
+//!   moves them along once a frame is drawn. An env may stack `Layer`s,
+//!   each kept as drawn while the others change. `Renderers` boxes renderers
+//!   as `Frames` to switch between them while a game runs, and a
+//!   `RendererRegistry` picks one by the kind of update. A `Persistent`
+//!   renderer lives for the whole game instead and draws on every frame;
+//!   a `RenderPool` is one, keeping a `PersistentRender` per entity on the
+//!   board that updates spawn, change and despawn.
+//! - `Stateful::make_game` ties a model to its env in a `Game`, whose
+//!   `new_game` yields once per frame and steps the model as its `Schedule`
+//!   says.
+//!
+//! `data::Grid` keeps a value per tile for models of any kind, and
+//! `examples/langton.rs` runs a whole other game on the same traits.
+mod effects;
+mod input_buffer;
+mod phase;
+mod render;
+mod schedule;
+mod state;
+
+pub use self::effects::Effects;
+pub use self::input_buffer::InputDblBuffer;
+pub use self::phase::{current_phase, enter_phase, ErrorPhase};
+pub use self::render::*;
+pub use self::schedule::{Frame, Lag, Pacer, Schedule};
+pub use self::state::*;
@@ -0,0 +1,29 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+/// What the engine was doing when an error happened
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ErrorPhase {
+    Setup = 0,
+    Model = 1,
+    Render = 2,
+}
+
+// wasm is single threaded, the atomic only saves us a `static mut`
+static PHASE: AtomicUsize = AtomicUsize::new(ErrorPhase::Setup as usize);
+
+#[inline(always)]
+pub fn enter_phase(phase: ErrorPhase) {
+    PHASE.store(phase as usize, Ordering::Relaxed);
+}
+
+pub fn current_phase() -> ErrorPhase {
+    match PHASE.load(Ordering::Relaxed) {
+        1 => ErrorPhase::Model,
+        2 => ErrorPhase::Render,
+        _ => ErrorPhase::Setup,
+    }
+}
@@ -0,0 +1,1291 @@
+use data::SmallNat;
+
+use alloc::boxed::Box;
+use alloc::rc::{Rc, Weak};
+use alloc::string::String;
+use alloc::vec::Vec;
+use std::cell::RefCell;
+use std::ops::Range;
+use std::ops::{DerefMut, Generator, GeneratorState};
+
+use data::{Coordinate, Direction, Line, PowerUp, SnakeId};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use super::state::{Observer, Tick};
+
+// https://english.stackexchange.com/questions/275734/a-word-for-a-value-between-0-and-1-inclusive
+#[derive(Copy, Clone, PartialEq, PartialOrd)]
+pub struct UnitInterval(f64);
+
+impl UnitInterval {
+    pub fn min_value() -> Self {
+        UnitInterval(0.0)
+    }
+    pub fn max_value() -> Self {
+        UnitInterval(1.0)
+    }
+
+    pub fn from_u8_and_range(x: u8, range: Range<u8>) -> Self {
+        assert!(range.start != range.end);
+
+        let start;
+        let end;
+
+        if range.start > range.end {
+            start = range.end;
+            end = range.start;
+        } else {
+            start = range.start;
+            end = range.end;
+        }
+
+        if x < start {
+            Self::min_value()
+        } else if x >= end {
+            Self::max_value()
+        } else {
+            let a = x - start + 1;
+            let b = end - start;
+
+            let v = (a as f64) / (b as f64);
+
+            UnitInterval(v)
+        }
+    }
+
+    #[inline(always)]
+    pub fn scale(self, v: f64) -> f64 {
+        self.0 * v
+    }
+    #[inline(always)]
+    #[allow(dead_code)]
+    pub fn complement(self) -> Self {
+        UnitInterval(1.0 - self.0)
+    }
+
+    /// Slow at both ends and fastest half way, for motion that doesn't
+    /// start or stop with a jolt
+    pub fn ease_in_out(self) -> Self {
+        let t = self.0;
+        UnitInterval(t * t * (3.0 - 2.0 * t))
+    }
+
+    /// The point `self` of the way from `from` to `to`
+    pub fn lerp(self, from: Self, to: Self) -> Self {
+        UnitInterval(from.0 + (to.0 - from.0) * self.0)
+    }
+}
+
+impl Eq for UnitInterval {}
+
+impl Default for UnitInterval {
+    fn default() -> Self {
+        Self::min_value()
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Color {
+    Red = 0,
+    Black = 1,
+    // obstacles
+    Gray = 2,
+}
+
+impl Color {
+    pub fn from_u8(n: u8) -> Option<Self> {
+        match n {
+            0 => Some(Color::Red),
+            1 => Some(Color::Black),
+            2 => Some(Color::Gray),
+            _ => None,
+        }
+    }
+
+    pub fn to_rgb(self) -> &'static str {
+        match self {
+            Color::Black => "rgb(34, 34, 34)",
+            Color::Red => "rgb(240, 10, 10)",
+            Color::Gray => "rgb(136, 136, 136)",
+        }
+    }
+
+    /// The color as a css `rgba()`, as opaque as `alpha`
+    pub fn to_rgba(self, alpha: UnitInterval) -> String {
+        // "rgb(r, g, b)" with the alpha before the closing paren
+        let rgb = self.to_rgb();
+        let channels = &rgb[3..rgb.len() - 1];
+        format!("rgba{}, {})", channels, alpha.scale(1.0))
+    }
+}
+
+/// How much an env moves things around: with `Reduced`, for players who
+/// asked for less motion (`prefers-reduced-motion`), tiles snap into place
+/// rather than grow in, nothing blinks and popups stand still. Renderers and
+/// effects consult it through `DrawGrid::motion`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum MotionPolicy {
+    Full = 0,
+    Reduced = 1,
+}
+
+impl MotionPolicy {
+    pub fn is_reduced(self) -> bool {
+        self == MotionPolicy::Reduced
+    }
+}
+
+impl Default for MotionPolicy {
+    fn default() -> Self {
+        MotionPolicy::Full
+    }
+}
+
+/// The layers of an env that keeps what is drawn apart, from the bottom up:
+/// what goes on one stays as it was when another is drawn on or cleared,
+/// e.g. an effect going away leaves the snake under it. See
+/// `DrawGrid::set_layer`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Layer {
+    // the board itself and its grid lines
+    Background = 0,
+    // the snake, walls and whatever a patch draws
+    Board = 1,
+    // what a `Persistent` renderer keeps drawing, e.g. the food
+    Entities = 2,
+    // bands and popups over the board
+    Effects = 3,
+    // the game over screen
+    Hud = 4,
+    // e.g. the heatmap of a game
+    Debug = 5,
+}
+
+impl Layer {
+    /// Every layer in the order they are stacked, the bottom one first
+    pub const ALL: [Layer; 6] = [
+        Layer::Background,
+        Layer::Board,
+        Layer::Entities,
+        Layer::Effects,
+        Layer::Hud,
+        Layer::Debug,
+    ];
+}
+
+/// A board of square tiles to draw on, in the current fill color. The
+/// methods with a default are refinements an env may draw better.
+pub trait DrawGrid {
+    fn setup(&mut self, tile_size: SmallNat, width: SmallNat, height: SmallNat);
+
+    fn clear(&mut self);
+
+    // returns current fill color
+    fn set_fill_color(&mut self, color: Color) -> Color;
+
+    fn circle(&mut self, x: SmallNat, y: SmallNat, radius: UnitInterval);
+
+    fn fill_tile(
+        &mut self,
+        x: SmallNat,
+        y: SmallNat,
+        dir: Direction,
+        size: UnitInterval,
+    );
+
+    fn clear_tile(
+        &mut self,
+        x: SmallNat,
+        y: SmallNat,
+        dir: Direction,
+        size: UnitInterval,
+    );
+
+    // front of the snake, `size` as in `fill_tile`
+    fn fill_head(
+        &mut self,
+        x: SmallNat,
+        y: SmallNat,
+        dir: Direction,
+        size: UnitInterval,
+    ) {
+        self.fill_tile(x, y, dir, size);
+    }
+
+    // end of the snake moving on, `size` as in `clear_tile`
+    fn clear_tail(
+        &mut self,
+        x: SmallNat,
+        y: SmallNat,
+        dir: Direction,
+        size: UnitInterval,
+    ) {
+        self.clear_tile(x, y, dir, size);
+    }
+
+    // a body tile the snake turns on, entered heading `from` and left
+    // heading `to`; plain full tile unless the env can do better
+    fn fill_corner(
+        &mut self,
+        x: SmallNat,
+        y: SmallNat,
+        _from: Direction,
+        to: Direction,
+    ) {
+        self.fill_tile(x, y, to, UnitInterval::max_value());
+    }
+
+    // a triangle pointing up in the middle of the tile, `size` of it high;
+    // a circle unless the env can do better
+    fn triangle(&mut self, x: SmallNat, y: SmallNat, size: UnitInterval) {
+        self.circle(x, y, size);
+    }
+
+    // a square in the middle of the tile, `size` of it wide
+    fn square(&mut self, x: SmallNat, y: SmallNat, size: UnitInterval) {
+        self.fill_tile(x, y, Direction::East, size);
+    }
+
+    // whether pieces of the same shape in different colors take shapes of
+    // their own, for players who can't tell the colors apart
+    fn distinct_shapes(&self) -> bool {
+        false
+    }
+
+    fn motion(&self) -> MotionPolicy {
+        MotionPolicy::Full
+    }
+
+    // whether `set_layer` draws on layers of their own rather than all on
+    // the board
+    fn has_layers(&self) -> bool {
+        false
+    }
+
+    // draws and clears on `layer` from now on, returns the one it did
+    fn set_layer(&mut self, _layer: Layer) -> Layer {
+        Layer::Board
+    }
+
+    fn show_game_over(&mut self);
+
+    // feedback for a rejected turn, e.g. a short flash or buzz
+    fn invalid_input(&mut self, _dir: Direction) {}
+
+    // the turn the head on (x, y) takes next, e.g. an arrow on it; the head
+    // moving on draws over it
+    fn show_next_turn(
+        &mut self,
+        _x: SmallNat,
+        _y: SmallNat,
+        _dir: Direction,
+    ) {
+    }
+
+    // caption over the board, e.g. the instructions of the tutorial
+    fn show_text(&mut self, _text: &str) {}
+
+    fn hide_text(&mut self) {}
+
+    // the debug overlay turned on, or off again, e.g. the heatmap so far
+    fn show_debug(&mut self, _on: bool) {}
+
+    // ticks left on the clock of a timed game, e.g. for a heads up display
+    fn show_clock(&mut self, _left: u32) {}
+
+    // lives left in a game with lives, likewise
+    fn show_lives(&mut self, _left: u32) {}
+
+    // a band of `color` as opaque as `alpha` over a whole row or column,
+    // e.g. the laser about to sweep it; it lies over the board rather than
+    // on it, what is drawn under it stays until `hide_band`
+    fn show_band(
+        &mut self,
+        _line: Line,
+        _color: Color,
+        _alpha: UnitInterval,
+    ) {
+    }
+
+    fn hide_band(&mut self, _line: Line) {}
+
+    // the power-up turned on, or off again, likewise for a heads up display
+    fn show_power_up(&mut self, _power_up: PowerUp, _active: bool) {}
+
+    // food eaten on (x, y), e.g. to pop up the points it was worth
+    fn food_eaten(&mut self, _x: SmallNat, _y: SmallNat) {}
+
+    // the snake the tiles filled next are of, e.g. to fill them in the
+    // color of its team, until the fill color is set again
+    fn set_snake(&mut self, _snake: SnakeId) {}
+
+    // every frame once it is drawn, e.g. to move `Effects` along
+    fn end_frame(&mut self) {}
+
+    fn with_fill_color<F>(&mut self, color: Color, mut f: F)
+    where
+        Self: Sized,
+        F: FnMut(&mut Self),
+    {
+        let prev_color = self.set_fill_color(color);
+
+        f(self);
+
+        self.set_fill_color(prev_color);
+    }
+
+    fn with_layer<F>(&mut self, layer: Layer, mut f: F)
+    where
+        Self: Sized,
+        F: FnMut(&mut Self),
+    {
+        let prev_layer = self.set_layer(layer);
+
+        f(self);
+
+        self.set_layer(prev_layer);
+    }
+}
+
+/// Shares an env with whoever made it, e.g. to read back what a game drew
+impl<E: DrawGrid> DrawGrid for Rc<RefCell<E>> {
+    fn setup(
+        &mut self,
+        tile_size: SmallNat,
+        width: SmallNat,
+        height: SmallNat,
+    ) {
+        self.borrow_mut().setup(tile_size, width, height);
+    }
+
+    fn clear(&mut self) {
+        self.borrow_mut().clear();
+    }
+
+    fn set_fill_color(&mut self, color: Color) -> Color {
+        self.borrow_mut().set_fill_color(color)
+    }
+
+    fn circle(&mut self, x: SmallNat, y: SmallNat, radius: UnitInterval) {
+        self.borrow_mut().circle(x, y, radius);
+    }
+
+    fn fill_tile(
+        &mut self,
+        x: SmallNat,
+        y: SmallNat,
+        dir: Direction,
+        size: UnitInterval,
+    ) {
+        self.borrow_mut().fill_tile(x, y, dir, size);
+    }
+
+    fn clear_tile(
+        &mut self,
+        x: SmallNat,
+        y: SmallNat,
+        dir: Direction,
+        size: UnitInterval,
+    ) {
+        self.borrow_mut().clear_tile(x, y, dir, size);
+    }
+
+    fn fill_head(
+        &mut self,
+        x: SmallNat,
+        y: SmallNat,
+        dir: Direction,
+        size: UnitInterval,
+    ) {
+        self.borrow_mut().fill_head(x, y, dir, size);
+    }
+
+    fn clear_tail(
+        &mut self,
+        x: SmallNat,
+        y: SmallNat,
+        dir: Direction,
+        size: UnitInterval,
+    ) {
+        self.borrow_mut().clear_tail(x, y, dir, size);
+    }
+
+    fn fill_corner(
+        &mut self,
+        x: SmallNat,
+        y: SmallNat,
+        from: Direction,
+        to: Direction,
+    ) {
+        self.borrow_mut().fill_corner(x, y, from, to);
+    }
+
+    fn triangle(&mut self, x: SmallNat, y: SmallNat, size: UnitInterval) {
+        self.borrow_mut().triangle(x, y, size);
+    }
+
+    fn square(&mut self, x: SmallNat, y: SmallNat, size: UnitInterval) {
+        self.borrow_mut().square(x, y, size);
+    }
+
+    fn distinct_shapes(&self) -> bool {
+        self.borrow().distinct_shapes()
+    }
+
+    fn motion(&self) -> MotionPolicy {
+        self.borrow().motion()
+    }
+
+    fn has_layers(&self) -> bool {
+        self.borrow().has_layers()
+    }
+
+    fn set_layer(&mut self, layer: Layer) -> Layer {
+        self.borrow_mut().set_layer(layer)
+    }
+
+    fn show_game_over(&mut self) {
+        self.borrow_mut().show_game_over();
+    }
+
+    fn invalid_input(&mut self, dir: Direction) {
+        self.borrow_mut().invalid_input(dir);
+    }
+
+    fn show_next_turn(&mut self, x: SmallNat, y: SmallNat, dir: Direction) {
+        self.borrow_mut().show_next_turn(x, y, dir);
+    }
+
+    fn show_text(&mut self, text: &str) {
+        self.borrow_mut().show_text(text);
+    }
+
+    fn hide_text(&mut self) {
+        self.borrow_mut().hide_text();
+    }
+
+    fn show_debug(&mut self, on: bool) {
+        self.borrow_mut().show_debug(on);
+    }
+
+    fn show_clock(&mut self, left: u32) {
+        self.borrow_mut().show_clock(left);
+    }
+
+    fn show_lives(&mut self, left: u32) {
+        self.borrow_mut().show_lives(left);
+    }
+
+    fn show_band(&mut self, line: Line, color: Color, alpha: UnitInterval) {
+        self.borrow_mut().show_band(line, color, alpha);
+    }
+
+    fn hide_band(&mut self, line: Line) {
+        self.borrow_mut().hide_band(line);
+    }
+
+    fn show_power_up(&mut self, power_up: PowerUp, active: bool) {
+        self.borrow_mut().show_power_up(power_up, active);
+    }
+
+    fn food_eaten(&mut self, x: SmallNat, y: SmallNat) {
+        self.borrow_mut().food_eaten(x, y);
+    }
+
+    fn set_snake(&mut self, snake: SnakeId) {
+        self.borrow_mut().set_snake(snake);
+    }
+
+    fn end_frame(&mut self) {
+        self.borrow_mut().end_frame();
+    }
+}
+
+/// Draws on several envs at once, e.g. the board and a small copy of it
+/// elsewhere on the page. The first env is the main one: its fill color and
+/// layer are the ones `set_fill_color` and `set_layer` give back, and its
+/// shapes, motion policy and layers are the game's.
+pub struct Fanout {
+    envs: Vec<Box<DrawGrid>>,
+}
+
+impl Fanout {
+    pub fn new(main: Box<DrawGrid>) -> Self {
+        Fanout { envs: vec![main] }
+    }
+
+    pub fn with(mut self, env: Box<DrawGrid>) -> Self {
+        self.envs.push(env);
+        self
+    }
+
+    fn each<F: FnMut(&mut DrawGrid)>(&mut self, mut f: F) {
+        for env in self.envs.iter_mut() {
+            f(&mut **env);
+        }
+    }
+}
+
+impl DrawGrid for Fanout {
+    fn setup(
+        &mut self,
+        tile_size: SmallNat,
+        width: SmallNat,
+        height: SmallNat,
+    ) {
+        self.each(|env| env.setup(tile_size, width, height));
+    }
+
+    fn clear(&mut self) {
+        self.each(|env| env.clear());
+    }
+
+    fn set_fill_color(&mut self, color: Color) -> Color {
+        let mut main = None;
+        self.each(|env| {
+            let prev = env.set_fill_color(color);
+            main = main.or(Some(prev));
+        });
+        main.unwrap_or(color)
+    }
+
+    fn circle(&mut self, x: SmallNat, y: SmallNat, radius: UnitInterval) {
+        self.each(|env| env.circle(x, y, radius));
+    }
+
+    fn fill_tile(
+        &mut self,
+        x: SmallNat,
+        y: SmallNat,
+        dir: Direction,
+        size: UnitInterval,
+    ) {
+        self.each(|env| env.fill_tile(x, y, dir, size));
+    }
+
+    fn clear_tile(
+        &mut self,
+        x: SmallNat,
+        y: SmallNat,
+        dir: Direction,
+        size: UnitInterval,
+    ) {
+        self.each(|env| env.clear_tile(x, y, dir, size));
+    }
+
+    fn fill_head(
+        &mut self,
+        x: SmallNat,
+        y: SmallNat,
+        dir: Direction,
+        size: UnitInterval,
+    ) {
+        self.each(|env| env.fill_head(x, y, dir, size));
+    }
+
+    fn clear_tail(
+        &mut self,
+        x: SmallNat,
+        y: SmallNat,
+        dir: Direction,
+        size: UnitInterval,
+    ) {
+        self.each(|env| env.clear_tail(x, y, dir, size));
+    }
+
+    fn fill_corner(
+        &mut self,
+        x: SmallNat,
+        y: SmallNat,
+        from: Direction,
+        to: Direction,
+    ) {
+        self.each(|env| env.fill_corner(x, y, from, to));
+    }
+
+    fn triangle(&mut self, x: SmallNat, y: SmallNat, size: UnitInterval) {
+        self.each(|env| env.triangle(x, y, size));
+    }
+
+    fn square(&mut self, x: SmallNat, y: SmallNat, size: UnitInterval) {
+        self.each(|env| env.square(x, y, size));
+    }
+
+    fn distinct_shapes(&self) -> bool {
+        self.envs[0].distinct_shapes()
+    }
+
+    fn motion(&self) -> MotionPolicy {
+        self.envs[0].motion()
+    }
+
+    fn has_layers(&self) -> bool {
+        self.envs[0].has_layers()
+    }
+
+    fn set_layer(&mut self, layer: Layer) -> Layer {
+        let mut main = None;
+        self.each(|env| {
+            let prev = env.set_layer(layer);
+            main = main.or(Some(prev));
+        });
+        main.unwrap_or(layer)
+    }
+
+    fn show_game_over(&mut self) {
+        self.each(|env| env.show_game_over());
+    }
+
+    fn invalid_input(&mut self, dir: Direction) {
+        self.each(|env| env.invalid_input(dir));
+    }
+
+    fn show_next_turn(&mut self, x: SmallNat, y: SmallNat, dir: Direction) {
+        self.each(|env| env.show_next_turn(x, y, dir));
+    }
+
+    fn show_text(&mut self, text: &str) {
+        self.each(|env| env.show_text(text));
+    }
+
+    fn hide_text(&mut self) {
+        self.each(|env| env.hide_text());
+    }
+
+    fn show_debug(&mut self, on: bool) {
+        self.each(|env| env.show_debug(on));
+    }
+
+    fn show_clock(&mut self, left: u32) {
+        self.each(|env| env.show_clock(left));
+    }
+
+    fn show_lives(&mut self, left: u32) {
+        self.each(|env| env.show_lives(left));
+    }
+
+    fn show_band(&mut self, line: Line, color: Color, alpha: UnitInterval) {
+        self.each(|env| env.show_band(line, color, alpha));
+    }
+
+    fn hide_band(&mut self, line: Line) {
+        self.each(|env| env.hide_band(line));
+    }
+
+    fn show_power_up(&mut self, power_up: PowerUp, active: bool) {
+        self.each(|env| env.show_power_up(power_up, active));
+    }
+
+    fn food_eaten(&mut self, x: SmallNat, y: SmallNat) {
+        self.each(|env| env.food_eaten(x, y));
+    }
+
+    fn set_snake(&mut self, snake: SnakeId) {
+        self.each(|env| env.set_snake(snake));
+    }
+
+    fn end_frame(&mut self) {
+        self.each(|env| env.end_frame());
+    }
+}
+
+/// What a renderer gets to know about the game besides its patch
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct FrameCtx {
+    /// The tick of the step the patch came from, `Stateful::step`'s
+    pub tick: Tick,
+}
+
+/// Draws a `Patch` (an update of the model) on an `Env` a frame at a time
+pub trait IncrRender<Env> {
+    type Patch;
+
+    fn new_patch(u: Self::Patch, ctx: FrameCtx) -> Self;
+
+    /// Draws the next frame, `None` once the patch is drawn completely
+    fn render(&mut self, env: &mut Env) -> Option<()>;
+
+    /// See `Frames::tiles`
+    fn tiles(&self) -> Option<Vec<Coordinate>> {
+        None
+    }
+
+    fn to_generator(self, env: &Rc<RefCell<Env>>) -> IncrRenderGen<Self, Env>
+    where
+        Self: Sized,
+    {
+        IncrRenderGen::Created(self, Rc::downgrade(env))
+    }
+}
+
+/// The drawing half of an `IncrRender`, which makes a trait object: a
+/// renderer picked while the game runs is a `Box<Frames<Env>>`, see
+/// `Renderers`
+pub trait Frames<Env> {
+    /// Draws the next frame, `None` once the patch is drawn completely
+    fn next_frame(&mut self, env: &mut Env) -> Option<()>;
+
+    /// The tiles the frames left to draw change, `None` for the whole
+    /// board as with renderers that don't tell. With `Lag::Skip`, a newer
+    /// patch due before these frames are drawn cuts them short only if it
+    /// changes one of their tiles, and they go on alongside it otherwise.
+    fn tiles(&self) -> Option<Vec<Coordinate>> {
+        None
+    }
+}
+
+/// A renderer kept for the whole game rather than made for a patch, which
+/// draws an entity that moves on its own between updates, e.g. food that
+/// pulses until it is eaten. It learns where the entity is some other way,
+/// typically as an `Observer` of the model, and a `Game` it is added to
+/// draws a frame of it before every frame of the patches, and on the
+/// frames without one too. See `Game::with_persistent`.
+pub trait Persistent<Env> {
+    fn next_frame(&mut self, env: &mut Env);
+}
+
+impl<Env, P: Persistent<Env>> Persistent<Env> for Rc<RefCell<P>> {
+    #[inline]
+    fn next_frame(&mut self, env: &mut Env) {
+        self.borrow_mut().next_frame(env);
+    }
+}
+
+/// Persistent renderers drawn together, in the order they were added
+pub struct PersistentSet<Env> {
+    renderers: Vec<Box<Persistent<Env>>>,
+}
+
+impl<Env> PersistentSet<Env> {
+    pub fn new() -> Self {
+        PersistentSet {
+            renderers: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, renderer: Box<Persistent<Env>>) {
+        self.renderers.push(renderer);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.renderers.is_empty()
+    }
+
+    pub fn next_frame(&mut self, env: &mut Env) {
+        for renderer in &mut self.renderers {
+            renderer.next_frame(env);
+        }
+    }
+}
+
+impl<Env> Default for PersistentSet<Env> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The renderer of an entity that lives on the board across many ticks,
+/// e.g. the food or a pickup, in a `RenderPool`: made when the patch that
+/// places it spawns it, told of the patches that change it and drawing a
+/// frame on every frame until one despawns it
+pub trait PersistentRender<Env> {
+    type Patch;
+
+    fn render(&mut self, env: &mut Env);
+
+    /// The entity changed as `patch` says, false if it is gone with it
+    fn update(&mut self, _patch: &Self::Patch) -> bool {
+        true
+    }
+
+    /// Drawn once on the frame after the entity is gone, e.g. to clear a
+    /// layer of its own that nothing else draws over
+    fn despawn(&mut self, _env: &mut Env) {}
+}
+
+/// What a patch does to the entities of a `RenderPool`, keyed by `K`
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Lifecycle<K> {
+    // in place of any entity of the same key
+    Spawn(K),
+    Update(K),
+    Despawn(K),
+    // every entity is told of the patch, e.g. one taking a whole row
+    UpdateAll,
+    // e.g. the board being drawn anew
+    DespawnAll,
+}
+
+/// The entity renderer `spawn` makes on `Lifecycle::Spawn`, if the patch
+/// tells enough to draw the entity
+pub type SpawnFactory<Env, K, P> =
+    fn(K, &P) -> Option<Box<PersistentRender<Env, Patch = P>>>;
+
+/// Renderers of the entities on the board by their key, spawned, updated
+/// and despawned as `lifecycle` says of each patch. The pool is an
+/// `Observer` of the model for its patches and a `Persistent` renderer of
+/// the `Game` drawing every entity on every frame; a model error ends the
+/// game or the life it drew, and every entity with it, without the
+/// `PersistentRender::despawn` of the ones despawned one at a time.
+pub struct RenderPool<Env, K, P> {
+    lifecycle: fn(&P) -> Option<Lifecycle<K>>,
+    spawn: SpawnFactory<Env, K, P>,
+    // in the order they were spawned, drawn in that order
+    entities: Vec<(K, Box<PersistentRender<Env, Patch = P>>)>,
+    // gone since the last frame, despawned on the next one
+    despawned: Vec<Box<PersistentRender<Env, Patch = P>>>,
+}
+
+impl<Env, K: Copy + Eq, P> RenderPool<Env, K, P> {
+    pub fn new(
+        lifecycle: fn(&P) -> Option<Lifecycle<K>>,
+        spawn: SpawnFactory<Env, K, P>,
+    ) -> Self {
+        RenderPool {
+            lifecycle,
+            spawn,
+            entities: Vec::new(),
+            despawned: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn contains(&self, key: K) -> bool {
+        self.entities.iter().any(|&(k, _)| k == key)
+    }
+
+    /// The entities the pool draws, in the order they are drawn
+    pub fn keys<'a>(&'a self) -> impl Iterator<Item = K> + 'a {
+        self.entities.iter().map(|&(k, _)| k)
+    }
+
+    pub fn clear(&mut self) {
+        self.entities.clear();
+        self.despawned.clear();
+    }
+
+    /// Spawns, updates or despawns the entities `patch` is about
+    pub fn on_patch(&mut self, patch: &P) {
+        match (self.lifecycle)(patch) {
+            Some(Lifecycle::Spawn(key)) => {
+                self.despawn(key);
+                if let Some(renderer) = (self.spawn)(key, patch) {
+                    self.entities.push((key, renderer));
+                }
+            }
+            Some(Lifecycle::Update(key)) => self.update(patch, |k| k == key),
+            Some(Lifecycle::Despawn(key)) => self.despawn(key),
+            Some(Lifecycle::UpdateAll) => self.update(patch, |_| true),
+            Some(Lifecycle::DespawnAll) => self.clear(),
+            None => {}
+        }
+    }
+
+    fn despawn(&mut self, key: K) {
+        if let Some(i) = self.entities.iter().position(|&(k, _)| k == key) {
+            let (_, renderer) = self.entities.remove(i);
+            self.despawned.push(renderer);
+        }
+    }
+
+    // tells the entities `which` is true of about `patch`, despawning the
+    // ones gone with it
+    fn update<F: Fn(K) -> bool>(&mut self, patch: &P, which: F) {
+        let mut i = 0;
+        while i < self.entities.len() {
+            let alive = {
+                let (key, ref mut renderer) = self.entities[i];
+                !which(key) || renderer.update(patch)
+            };
+            if alive {
+                i += 1;
+            } else {
+                let (_, renderer) = self.entities.remove(i);
+                self.despawned.push(renderer);
+            }
+        }
+    }
+}
+
+impl<Env, K: Copy + Eq, P> Persistent<Env> for RenderPool<Env, K, P> {
+    fn next_frame(&mut self, env: &mut Env) {
+        for mut renderer in self.despawned.drain(..) {
+            renderer.despawn(env);
+        }
+        for &mut (_, ref mut renderer) in &mut self.entities {
+            renderer.render(env);
+        }
+    }
+}
+
+impl<Env, K: Copy + Eq, P, E> Observer<P, E> for RenderPool<Env, K, P> {
+    fn on_update(&mut self, patch: &P) {
+        self.on_patch(patch);
+    }
+
+    fn on_error(&mut self, _err: &E) {
+        self.clear();
+    }
+}
+
+/// A frame of the persistent renderers before each frame of `frames`, so
+/// that the env ending the frame shows both, see `Persistent`
+pub struct WithPersistent<F, Env> {
+    pub frames: F,
+    pub persistent: Rc<RefCell<PersistentSet<Env>>>,
+}
+
+impl<Env, F: Frames<Env>> Frames<Env> for WithPersistent<F, Env> {
+    #[inline]
+    fn next_frame(&mut self, env: &mut Env) -> Option<()> {
+        self.persistent.borrow_mut().next_frame(env);
+        self.frames.next_frame(env)
+    }
+
+    fn tiles(&self) -> Option<Vec<Coordinate>> {
+        self.frames.tiles()
+    }
+}
+
+/// An `IncrRender` as `Frames`
+pub struct Incr<R>(pub R);
+
+impl<Env, R: IncrRender<Env>> Frames<Env> for Incr<R> {
+    #[inline]
+    fn next_frame(&mut self, env: &mut Env) -> Option<()> {
+        self.0.render(env)
+    }
+
+    fn tiles(&self) -> Option<Vec<Coordinate>> {
+        self.0.tiles()
+    }
+}
+
+impl<Env> Frames<Env> for Box<Frames<Env>> {
+    #[inline]
+    fn next_frame(&mut self, env: &mut Env) -> Option<()> {
+        (**self).next_frame(env)
+    }
+
+    fn tiles(&self) -> Option<Vec<Coordinate>> {
+        (**self).tiles()
+    }
+}
+
+/// Draws `renderer` on `env` a frame at a time like the generator of
+/// `IncrRender::to_generator`, yielding after each frame but the last
+/// one, which it yields after too if it is the first
+pub fn draw_frames<Env, F>(
+    mut renderer: F,
+    env: Rc<RefCell<Env>>,
+) -> impl Generator<Yield = (), Return = ()>
+where
+    F: Frames<Env>,
+{
+    move || {
+        let mut more = renderer.next_frame(env.borrow_mut().deref_mut());
+        yield ();
+        while more.is_some() {
+            more = renderer.next_frame(env.borrow_mut().deref_mut());
+            if more.is_some() {
+                yield ();
+            }
+        }
+    }
+}
+
+/// Makes the renderer of a patch, see `Renderers`
+pub type RenderFactory<Env, P> = fn(P, FrameCtx) -> Box<Frames<Env>>;
+
+/// The `RenderFactory` of an `IncrRender`, e.g. `boxed::<_, TickDraw>`
+pub fn boxed<Env, R>(patch: R::Patch, ctx: FrameCtx) -> Box<Frames<Env>>
+where
+    Env: 'static,
+    R: IncrRender<Env> + 'static,
+{
+    Box::new(Incr(R::new_patch(patch, ctx)))
+}
+
+/// Renderers to switch between while a game runs, e.g. from a menu of
+/// looks, see `Game::new_game_with`. The selected one draws every patch
+/// from the next one on. A factory can look at the patch too, to draw
+/// some updates its own way and hand the others to another renderer.
+pub struct Renderers<Env, P> {
+    factories: Vec<RenderFactory<Env, P>>,
+    selected: usize,
+}
+
+impl<Env, P> Renderers<Env, P> {
+    pub fn new(first: RenderFactory<Env, P>) -> Self {
+        Renderers {
+            factories: vec![first],
+            selected: 0,
+        }
+    }
+
+    pub fn with(mut self, factory: RenderFactory<Env, P>) -> Self {
+        self.factories.push(factory);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.factories.len()
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Draws the next patches with the `index`th renderer, false if there
+    /// isn't one
+    pub fn select(&mut self, index: usize) -> bool {
+        let found = index < self.factories.len();
+        if found {
+            self.selected = index;
+        }
+        found
+    }
+
+    pub fn new_patch(&self, patch: P, ctx: FrameCtx) -> Box<Frames<Env>> {
+        (self.factories[self.selected])(patch, ctx)
+    }
+}
+
+/// Renderers by the kind of patch they draw, e.g. a `WorldUpdate` variant,
+/// so that a feature registers how it is drawn on its own instead of
+/// adding arms to the match of one renderer. Patches of a kind nothing is
+/// registered for go to the fallback. With `Game::new_game_by`:
+///
+/// ```ignore
+/// let registry = RendererRegistry::new(boxed::<_, WorldUpdateDraw>)
+///     .register(|u| matches!(*u, WorldUpdate::LaserFired { .. }), laser);
+/// game.new_game_by::<Command, _, _>(move |delta: TickDelta, ctx| {
+///     registry.new_patches(delta.changes(), ctx)
+/// })
+/// ```
+pub struct RendererRegistry<Env, P> {
+    kinds: Vec<(fn(&P) -> bool, RenderFactory<Env, P>)>,
+    fallback: RenderFactory<Env, P>,
+}
+
+impl<Env, P> RendererRegistry<Env, P> {
+    pub fn new(fallback: RenderFactory<Env, P>) -> Self {
+        RendererRegistry {
+            kinds: Vec::new(),
+            fallback,
+        }
+    }
+
+    /// Draws the patches `kind` is true of with `factory`, rather than
+    /// with what was registered for them before
+    pub fn register(
+        mut self,
+        kind: fn(&P) -> bool,
+        factory: RenderFactory<Env, P>,
+    ) -> Self {
+        self.kinds.push((kind, factory));
+        self
+    }
+
+    /// The factory `patch` is drawn with
+    pub fn factory(&self, patch: &P) -> RenderFactory<Env, P> {
+        self.kinds
+            .iter()
+            .rev()
+            .find(|&&(kind, _)| kind(patch))
+            .map_or(self.fallback, |&(_, factory)| factory)
+    }
+
+    pub fn new_patch(&self, patch: P, ctx: FrameCtx) -> Box<Frames<Env>> {
+        (self.factory(&patch))(patch, ctx)
+    }
+
+    /// Draws `patches` together, the way `TickDraw` draws the changes of a
+    /// tick, each with its own renderer
+    pub fn new_patches<I>(&self, patches: I, ctx: FrameCtx) -> Together<Env>
+    where
+        I: IntoIterator<Item = P>,
+    {
+        Together {
+            parts: patches
+                .into_iter()
+                .map(|patch| Some(self.new_patch(patch, ctx)))
+                .collect(),
+        }
+    }
+}
+
+/// Renderers drawing a frame each before the frame ends, until all of them
+/// are done, see `RendererRegistry::new_patches`
+pub struct Together<Env> {
+    // none once drawn completely
+    parts: Vec<Option<Box<Frames<Env>>>>,
+}
+
+impl<Env: DrawGrid> Frames<Env> for Together<Env> {
+    fn next_frame(&mut self, env: &mut Env) -> Option<()> {
+        let mut pending = false;
+        for part in &mut self.parts {
+            let done = match *part {
+                Some(ref mut frames) => frames.next_frame(env).is_none(),
+                None => continue,
+            };
+            if done {
+                *part = None;
+            } else {
+                pending = true;
+            }
+        }
+        env.end_frame();
+
+        if pending {
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    fn tiles(&self) -> Option<Vec<Coordinate>> {
+        let mut tiles = Vec::new();
+        for frames in self.parts.iter().filter_map(|part| part.as_ref()) {
+            tiles.extend(frames.tiles()?);
+        }
+        Some(tiles)
+    }
+}
+
+pub enum IncrRenderGen<R, E> {
+    Created(R, Weak<RefCell<E>>),
+    InProgress(R, Weak<RefCell<E>>),
+    Done,
+}
+impl<R, E> Generator for IncrRenderGen<R, E>
+where
+    R: IncrRender<E>,
+    E: DrawGrid,
+{
+    type Yield = ();
+    type Return = ();
+
+    unsafe fn resume(&mut self) -> GeneratorState<Self::Yield, Self::Return> {
+        let this = ::std::mem::replace(self, IncrRenderGen::Done);
+
+        match this {
+            // yield once
+            IncrRenderGen::Created(mut renderer, env) => {
+                let env = match env.upgrade() {
+                    Some(env) => env,
+                    _ => {
+                        ::std::mem::replace(self, IncrRenderGen::Done);
+                        return GeneratorState::Yielded(());
+                    }
+                };
+
+                let mut env_ref = env.borrow_mut();
+
+                match renderer.render(env_ref.deref_mut()) {
+                    Some(_) => {
+                        ::std::mem::replace(
+                            self,
+                            IncrRenderGen::InProgress(
+                                renderer,
+                                Rc::downgrade(&env),
+                            ),
+                        );
+                        GeneratorState::Yielded(())
+                    }
+                    _ => {
+                        ::std::mem::replace(self, IncrRenderGen::Done);
+                        GeneratorState::Yielded(())
+                    }
+                }
+            }
+            // yield zero or more times
+            IncrRenderGen::InProgress(mut renderer, env) => {
+                let env = match env.upgrade() {
+                    Some(env) => env,
+                    _ => {
+                        ::std::mem::replace(self, IncrRenderGen::Done);
+                        return GeneratorState::Complete(());
+                    }
+                };
+
+                let mut env_ref = env.borrow_mut();
+
+                match renderer.render(env_ref.deref_mut()) {
+                    Some(_) => {
+                        ::std::mem::replace(
+                            self,
+                            IncrRenderGen::InProgress(
+                                renderer,
+                                Rc::downgrade(&env),
+                            ),
+                        );
+                        GeneratorState::Yielded(())
+                    }
+                    _ => {
+                        ::std::mem::replace(self, IncrRenderGen::Done);
+                        GeneratorState::Complete(())
+                    }
+                }
+            }
+            // return
+            IncrRenderGen::Done => GeneratorState::Complete(()),
+        }
+    }
+}
+
+/// Rectangle `(x, y, w, h)` in pixels covering `size` of the tile at `(x, y)`,
+/// growing from the edge opposite to `dir`
+pub fn partial_tile(
+    tile_size: f64,
+    x: SmallNat,
+    y: SmallNat,
+    dir: Direction,
+    size: UnitInterval,
+) -> (f64, f64, f64, f64) {
+    let x0 = x as f64 * tile_size;
+    let y0 = y as f64 * tile_size;
+
+    let long = tile_size;
+    let short = size.scale(tile_size);
+
+    let x;
+    let y;
+    let w;
+    let h;
+
+    match dir {
+        Direction::East => {
+            x = x0;
+            y = y0;
+            w = short;
+            h = long;
+        }
+        Direction::West => {
+            x = x0 + long - short;
+            y = y0;
+            w = short;
+            h = long;
+        }
+        Direction::South => {
+            x = x0;
+            y = y0;
+            w = long;
+            h = short;
+        }
+        Direction::North => {
+            x = x0;
+            y = y0 + long - short;
+            w = long;
+            h = short;
+        }
+    }
+    (x, y, w, h)
+}
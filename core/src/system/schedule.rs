@@ -0,0 +1,375 @@
+use alloc::rc::Rc;
+use std::cell::Cell;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Mode {
+    Continuous,
+    // the model steps only `ticks` more times, drawn as usual
+    Manual { ticks: u32 },
+    // `ticks` steps within the next frame, then manual
+    Headless { ticks: u32 },
+    // `ticks` steps within the next frame, then continuous again
+    CatchUp { ticks: u32 },
+}
+
+/// What becomes of the animation of a tick once the next tick is due,
+/// e.g. `step` was called faster than the ticks are drawn, or the host fell
+/// behind the clock while the game runs (see `Schedule::catch_up`)
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Lag {
+    /// Drawn to the end, the next tick waits for it
+    Animate,
+    /// The next tick starts on the next frame, the tiles it changes drawn
+    /// to their last frame at once and the others animating on alongside
+    /// it, see `Frames::tiles`
+    Skip,
+}
+
+/// What the game does on its next frame
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Frame {
+    /// Step the model once the previous tick is drawn
+    Step,
+    /// Stand still
+    Hold,
+    /// Step the model this many times and draw the outcome at once
+    Headless(u32),
+}
+
+/// How a game advances, shared between the running game and whoever steps it
+/// by hand. Games run continuously, a tick whenever the previous one has been
+/// drawn, until a call to `step` or `fast_forward` switches them to manual
+/// stepping. It also counts the frames since the player last pressed a key,
+/// as the host reports them, to tell when nobody is playing.
+#[derive(Debug, Clone)]
+pub struct Schedule {
+    mode: Rc<Cell<Mode>>,
+    idle: Rc<Cell<u32>>,
+    lag: Rc<Cell<Lag>>,
+    // ticks the host is behind by while running continuously with
+    // `Lag::Skip`, each stepped animated without waiting for the one
+    // before
+    due: Rc<Cell<u32>>,
+}
+
+impl Schedule {
+    pub fn new() -> Self {
+        Schedule {
+            mode: Rc::new(Cell::new(Mode::Continuous)),
+            idle: Rc::new(Cell::new(0)),
+            lag: Rc::new(Cell::new(Lag::Animate)),
+            due: Rc::new(Cell::new(0)),
+        }
+    }
+
+    /// Counts a frame of the host, on which the player `pressed` a key or
+    /// not
+    pub fn input(&self, pressed: bool) {
+        let idle = self.idle.get();
+        self.idle.set(if pressed { 0 } else { idle.saturating_add(1) });
+    }
+
+    /// Frames since the player last pressed a key
+    pub fn idle_frames(&self) -> u32 {
+        self.idle.get()
+    }
+
+    /// Back to continuous ticks
+    pub fn run(&self) {
+        self.mode.set(Mode::Continuous);
+        self.due.set(0);
+    }
+
+    pub fn is_manual(&self) -> bool {
+        match self.mode.get() {
+            Mode::Continuous | Mode::CatchUp { .. } => false,
+            _ => true,
+        }
+    }
+
+    pub fn lag(&self) -> Lag {
+        self.lag.get()
+    }
+
+    pub fn set_lag(&self, lag: Lag) {
+        self.lag.set(lag);
+    }
+
+    /// Whether the tick being drawn is to be cut short, because another
+    /// one is due and `Lag::Skip` says not to wait for the animation
+    pub fn is_superseded(&self) -> bool {
+        let due = match self.mode.get() {
+            Mode::Continuous => self.due.get() > 0,
+            Mode::Manual { ticks: 0 } => false,
+            _ => true,
+        };
+        due && self.lag.get() == Lag::Skip
+    }
+
+    /// Steps the model `ticks` more times, animated as usual, and holds
+    pub fn step(&self, ticks: u32) {
+        self.due.set(0);
+        let ticks = match self.mode.get() {
+            Mode::Manual { ticks: pending } => pending + ticks,
+            _ => ticks,
+        };
+
+        self.mode.set(Mode::Manual { ticks });
+    }
+
+    /// Steps the model `ticks` times within a single frame, drawing each tick
+    /// without its animation, and holds
+    pub fn fast_forward(&self, ticks: u32) {
+        self.due.set(0);
+        self.mode.set(Mode::Headless { ticks });
+    }
+
+    /// Steps the model `ticks` times within the next frame like
+    /// `fast_forward`, then goes on running continuously, for a host that
+    /// fell behind (see `Pacer`). With `Lag::Skip` the ticks are stepped a
+    /// frame apart instead, animated as far as the ones after let them,
+    /// `ticks` the ones still due rather than more. A game stepped by hand
+    /// isn't behind anything and stays as it is.
+    pub fn catch_up(&self, ticks: u32) {
+        match self.mode.get() {
+            Mode::Continuous if self.lag.get() == Lag::Skip => {
+                self.due.set(ticks)
+            }
+            Mode::Continuous => self.mode.set(Mode::CatchUp { ticks }),
+            Mode::CatchUp { ticks: pending } => {
+                self.mode.set(Mode::CatchUp {
+                    ticks: pending + ticks,
+                })
+            }
+            _ => {}
+        }
+    }
+
+    pub fn next_frame(&self) -> Frame {
+        match self.mode.get() {
+            Mode::Continuous => {
+                self.due.set(self.due.get().saturating_sub(1));
+                Frame::Step
+            }
+            Mode::Manual { ticks: 0 } => Frame::Hold,
+            Mode::Manual { ticks } => {
+                self.mode.set(Mode::Manual { ticks: ticks - 1 });
+                Frame::Step
+            }
+            Mode::Headless { ticks } => {
+                self.mode.set(Mode::Manual { ticks: 0 });
+                Frame::Headless(ticks)
+            }
+            Mode::CatchUp { ticks } => {
+                self.mode.set(Mode::Continuous);
+                Frame::Headless(ticks)
+            }
+        }
+    }
+}
+
+/// Tells a host by how many ticks the game fell behind the clock. A tick
+/// lasts as many frames as its animation, so a host drawing fewer frames
+/// a second than it should would slow the game down, against replays and
+/// other players, unless it makes up for them with `Schedule::catch_up`.
+/// The clock adds up: the time the game ran for against the time of the
+/// ticks run, each as long as its own frames, see `Pacer::ran`.
+#[derive(Debug, Clone)]
+pub struct Pacer {
+    frame_ms: f64,
+    most: u32,
+    // the time of the last frame
+    last: Option<f64>,
+    // milliseconds the game ran for, by the host's clock and by the ticks
+    // run before the current one
+    elapsed: f64,
+    ran: f64,
+    // milliseconds the current tick lasts, none before the first one
+    tick_ms: f64,
+}
+
+impl Pacer {
+    /// A host meant to draw `fps` frames a second. Being more than `most`
+    /// ticks behind is taken for the host having been away, e.g. a hidden
+    /// tab, nothing is made up for those.
+    pub fn new(fps: u32, most: u32) -> Self {
+        Pacer {
+            frame_ms: 1000.0 / fps.max(1) as f64,
+            most,
+            last: None,
+            elapsed: 0.0,
+            ran: 0.0,
+            tick_ms: 0.0,
+        }
+    }
+
+    /// Draws `fps` frames a second from the next tick on, e.g. half as
+    /// many while a power saver skips every other one
+    pub fn set_fps(&mut self, fps: u32) {
+        self.frame_ms = 1000.0 / fps.max(1) as f64;
+    }
+
+    /// Counts a tick stepped, to be drawn in `frames` frames, which ends
+    /// the one before
+    pub fn ran(&mut self, frames: u32) {
+        self.ran += self.tick_ms;
+        self.tick_ms = self.frame_ms * frames.max(1) as f64;
+    }
+
+    /// Counts a frame at `now` milliseconds, the ticks to make up for:
+    /// how many of the current one the time gone by since it should have
+    /// started makes, none while it lasts
+    pub fn frame(&mut self, now: f64) -> u32 {
+        let last = self.last.unwrap_or(now);
+        self.last = Some(now);
+        // ticks drawn faster than the clock bank no time for later
+        self.elapsed = (self.elapsed + now - last).max(self.ran);
+
+        let tick_ms = self.tick_ms.max(self.frame_ms);
+        let due = ((self.elapsed - self.ran) / tick_ms) as u32;
+        if due > self.most {
+            // the current tick starts now
+            self.elapsed = self.ran;
+            0
+        } else {
+            due
+        }
+    }
+
+    /// Counts a frame at `now` milliseconds on which the game stood still,
+    /// e.g. stepped by hand, which the clock doesn't run on
+    pub fn hold(&mut self, now: f64) {
+        self.last = Some(now);
+    }
+}
+
+impl Default for Schedule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manual_stepping() {
+        let schedule = Schedule::new();
+        assert_eq!(schedule.next_frame(), Frame::Step);
+        assert!(!schedule.is_manual());
+
+        schedule.step(1);
+        assert_eq!(schedule.next_frame(), Frame::Step);
+        assert_eq!(schedule.next_frame(), Frame::Hold);
+        assert!(schedule.is_manual());
+
+        schedule.fast_forward(20);
+        assert_eq!(schedule.next_frame(), Frame::Headless(20));
+        assert_eq!(schedule.next_frame(), Frame::Hold);
+
+        schedule.run();
+        assert_eq!(schedule.next_frame(), Frame::Step);
+    }
+
+    #[test]
+    fn test_catching_up_runs_on() {
+        let schedule = Schedule::new();
+        schedule.catch_up(3);
+        schedule.catch_up(2);
+        assert!(!schedule.is_manual());
+        assert_eq!(schedule.next_frame(), Frame::Headless(5));
+        assert_eq!(schedule.next_frame(), Frame::Step);
+
+        // but for games stepped by hand
+        schedule.step(1);
+        schedule.catch_up(3);
+        assert_eq!(schedule.next_frame(), Frame::Step);
+        assert_eq!(schedule.next_frame(), Frame::Hold);
+    }
+
+    #[test]
+    fn test_pacer_counts_the_ticks_a_late_frame_missed() {
+        // 10 frames a second, 100ms a frame
+        let mut pacer = Pacer::new(10, 5);
+        pacer.ran(2);
+        assert_eq!(pacer.frame(1000.0), 0);
+        assert_eq!(pacer.frame(1100.0), 0);
+        // the 200ms tick should be over
+        assert_eq!(pacer.frame(1200.0), 1);
+        assert_eq!(pacer.frame(1400.0), 2);
+        // the next one, of 4 frames, half way through
+        pacer.ran(4);
+        assert_eq!(pacer.frame(1400.0), 0);
+        // a tick and a half of that length
+        assert_eq!(pacer.frame(1800.0), 1);
+        // away for a minute
+        assert_eq!(pacer.frame(62_000.0), 0);
+        assert_eq!(pacer.frame(62_100.0), 0);
+    }
+
+    #[test]
+    fn test_pacer_banks_no_time_for_fast_or_held_frames() {
+        let mut pacer = Pacer::new(10, 5);
+        pacer.ran(2);
+        pacer.frame(1000.0);
+        // ticks stepped ahead of the clock
+        pacer.ran(2);
+        pacer.ran(2);
+        pacer.frame(1100.0);
+        assert_eq!(pacer.frame(1300.0), 1);
+
+        pacer.ran(2);
+        pacer.frame(1300.0);
+        pacer.hold(5000.0);
+        assert_eq!(pacer.frame(5100.0), 0);
+    }
+
+    #[test]
+    fn test_due_ticks_supersede_only_when_skipping() {
+        let schedule = Schedule::new();
+        schedule.step(2);
+        assert!(!schedule.is_superseded());
+
+        schedule.set_lag(Lag::Skip);
+        assert!(schedule.is_superseded());
+        assert_eq!(schedule.next_frame(), Frame::Step);
+        assert!(schedule.is_superseded());
+        // the last tick has nothing after it
+        assert_eq!(schedule.next_frame(), Frame::Step);
+        assert!(!schedule.is_superseded());
+
+        schedule.run();
+        assert!(!schedule.is_superseded());
+    }
+
+    #[test]
+    fn test_ticks_fallen_behind_supersede_when_skipping() {
+        let schedule = Schedule::new();
+        schedule.set_lag(Lag::Skip);
+        schedule.catch_up(2);
+        assert!(!schedule.is_manual());
+        assert!(schedule.is_superseded());
+
+        // stepped animated, one a frame
+        assert_eq!(schedule.next_frame(), Frame::Step);
+        assert!(schedule.is_superseded());
+        assert_eq!(schedule.next_frame(), Frame::Step);
+        assert!(!schedule.is_superseded());
+        assert_eq!(schedule.next_frame(), Frame::Step);
+        assert!(!schedule.is_superseded());
+    }
+
+    #[test]
+    fn test_idle_frames_count_from_last_key() {
+        let schedule = Schedule::new();
+        schedule.input(false);
+        schedule.input(false);
+        assert_eq!(schedule.idle_frames(), 2);
+
+        schedule.input(true);
+        assert_eq!(schedule.idle_frames(), 0);
+        schedule.input(false);
+        assert_eq!(schedule.idle_frames(), 1);
+    }
+}
@@ -0,0 +1,1209 @@
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+
+use std::cell::{RefCell, RefMut};
+use std::iter::{IntoIterator, Map, Zip};
+use std::marker::PhantomData;
+
+use std::ops::{DerefMut, Generator};
+
+use void::Void;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use super::input_buffer::InputDblBuffer;
+use super::phase::{enter_phase, ErrorPhase};
+use super::render::{
+    draw_frames, DrawGrid, FrameCtx, Frames, Incr, IncrRender, Persistent,
+    PersistentSet, Renderers, WithPersistent,
+};
+use super::schedule::{Frame, Schedule};
+
+/// Steps of a `Game` so far, see `Stateful::step`
+pub type Tick = u32;
+
+/// How a game ended, what model errors turn into
+pub enum GameOver {
+    Over,
+    // the player beat the game, which ends like any other
+    Won,
+    // start over without leaving the current stage
+    Restart,
+    Quit,
+}
+
+/// Coarse state of a running game, as reported to js
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum GameState {
+    Playing = 0,
+    Paused = 1,
+    Over = 2,
+}
+
+impl GameState {
+    pub fn from_u8(n: u8) -> Option<Self> {
+        match n {
+            0 => Some(GameState::Playing),
+            1 => Some(GameState::Paused),
+            2 => Some(GameState::Over),
+            _ => None,
+        }
+    }
+}
+
+impl Into<GameOver> for Void {
+    fn into(self) -> GameOver {
+        unreachable!()
+    }
+}
+
+/// A model a `Game` steps, see the module docs
+pub trait Stateful<'m> {
+    type Cmd;
+    type Init: IntoIterator<Item = Self::Update> + 'm;
+    type Update;
+    type Error: Into<GameOver>;
+
+    /// The updates that set up a game, drawn before the first step
+    fn initialize(&'m mut self) -> Self::Init;
+
+    /// Advances the model, `Ok(None)` when nothing changed; an error ends
+    /// the game as its `GameOver` says. `tick` counts the steps of the
+    /// `Game` before this one, across games, so models needn't keep a
+    /// clock of their own.
+    fn step(
+        &mut self,
+        cmd: Option<Self::Cmd>,
+        tick: Tick,
+    ) -> Result<Option<Self::Update>, Self::Error>;
+
+    /// Called once a game is over, before `initialize` starts the next one
+    fn tear_down(&mut self);
+
+    fn make_game<E>(self, env: E) -> Game<Self, E>
+    where
+        Self: Sized,
+    {
+        Game {
+            model: self,
+            env,
+            schedule: Schedule::new(),
+            persistent: PersistentSet::new(),
+        }
+    }
+
+    fn zip_with<T, R, F>(self, other: R, f: F) -> ZipWith<Self, R, F>
+    where
+        R: Stateful<'m, Cmd = Self::Cmd, Error = Void>,
+        F: Fn((Self::Update, R::Update)) -> T + 'm,
+        Self::Cmd: Copy,
+        Self: Sized,
+    {
+        ZipWith {
+            left: self,
+            right: other,
+            f,
+        }
+    }
+
+    fn alternating_after<C, B>(self, other: B) -> Alternating<Self, B, C>
+    where
+        Self: Sized,
+        B: Stateful<'m, Update = Self::Update>,
+        C: Into<Option<Self::Cmd>> + Into<Option<B::Cmd>>,
+    {
+        Alternating {
+            swapped: true,
+            left: self,
+            right: other,
+            _cmd: PhantomData,
+        }
+    }
+
+    fn observe<O>(self, observer: O) -> Observe<Self, O>
+    where
+        Self: Sized,
+        O: Observer<Self::Update, Self::Error>,
+    {
+        Observe {
+            model: self,
+            observer,
+        }
+    }
+
+    fn alternating<C, B>(self, other: B) -> Alternating<Self, B, C>
+    where
+        Self: Sized,
+        B: Stateful<'m, Update = Self::Update>,
+        C: Into<Option<Self::Cmd>> + Into<Option<B::Cmd>>,
+    {
+        Alternating {
+            swapped: false,
+            left: self,
+            right: other,
+            _cmd: PhantomData,
+        }
+    }
+}
+
+pub struct ZipWith<L, R, F> {
+    left: L,
+    right: R,
+    f: F,
+}
+
+impl<'m, L, R, T, F> Stateful<'m> for ZipWith<L, R, F>
+where
+    L: Stateful<'m>,
+    R: Stateful<'m, Cmd = L::Cmd, Error = Void>,
+    F: Fn((L::Update, R::Update)) -> T + 'm,
+    L::Cmd: Copy,
+{
+    type Cmd = L::Cmd;
+    type Update = T;
+    type Init = Map<
+        Zip<
+            <L::Init as IntoIterator>::IntoIter,
+            <R::Init as IntoIterator>::IntoIter,
+        >,
+        &'m F,
+    >;
+    type Error = L::Error;
+
+    fn initialize(&'m mut self) -> Self::Init {
+        self.left
+            .initialize()
+            .into_iter()
+            .zip(self.right.initialize().into_iter())
+            .map(&self.f)
+    }
+
+    #[inline]
+    fn step(
+        &mut self,
+        cmd: Option<Self::Cmd>,
+        tick: Tick,
+    ) -> Result<Option<Self::Update>, Self::Error> {
+        let ul = self.left.step(cmd, tick)?;
+        let ur = self.right.step(cmd, tick).unwrap();
+
+        match (ul, ur) {
+            (Some(x), Some(y)) => {
+                let update = (self.f)((x, y));
+                Ok(Some(update))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn tear_down(&mut self) {
+        self.right.tear_down();
+        self.left.tear_down();
+    }
+}
+
+/// Watches the updates and errors of a model without altering them
+pub trait Observer<U, E> {
+    fn on_init(&mut self, update: &U) {
+        self.on_update(update);
+    }
+
+    fn on_update(&mut self, update: &U);
+
+    fn on_error(&mut self, _err: &E) {}
+
+    fn on_tear_down(&mut self) {}
+}
+
+impl<U, E, O: Observer<U, E>> Observer<U, E> for Rc<RefCell<O>> {
+    fn on_init(&mut self, update: &U) {
+        self.borrow_mut().on_init(update);
+    }
+
+    fn on_update(&mut self, update: &U) {
+        self.borrow_mut().on_update(update);
+    }
+
+    fn on_error(&mut self, err: &E) {
+        self.borrow_mut().on_error(err);
+    }
+
+    fn on_tear_down(&mut self) {
+        self.borrow_mut().on_tear_down();
+    }
+}
+
+/// Observes nothing while `None`, e.g. for what only some modes observe
+impl<U, E, O: Observer<U, E>> Observer<U, E> for Option<O> {
+    fn on_init(&mut self, update: &U) {
+        if let Some(ref mut observer) = *self {
+            observer.on_init(update);
+        }
+    }
+
+    fn on_update(&mut self, update: &U) {
+        if let Some(ref mut observer) = *self {
+            observer.on_update(update);
+        }
+    }
+
+    fn on_error(&mut self, err: &E) {
+        if let Some(ref mut observer) = *self {
+            observer.on_error(err);
+        }
+    }
+
+    fn on_tear_down(&mut self) {
+        if let Some(ref mut observer) = *self {
+            observer.on_tear_down();
+        }
+    }
+}
+
+pub struct Observe<M, O> {
+    model: M,
+    observer: O,
+}
+
+impl<'m, M, O> Stateful<'m> for Observe<M, O>
+where
+    M: Stateful<'m>,
+    M::Error: 'm,
+    O: Observer<M::Update, M::Error> + 'm,
+{
+    type Cmd = M::Cmd;
+    type Init =
+        ObserveInit<'m, <M::Init as IntoIterator>::IntoIter, O, M::Error>;
+    type Update = M::Update;
+    type Error = M::Error;
+
+    fn initialize(&'m mut self) -> Self::Init {
+        ObserveInit {
+            iter: self.model.initialize().into_iter(),
+            observer: &mut self.observer,
+            _error: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn step(
+        &mut self,
+        cmd: Option<Self::Cmd>,
+        tick: Tick,
+    ) -> Result<Option<Self::Update>, Self::Error> {
+        match self.model.step(cmd, tick) {
+            Ok(update) => {
+                if let Some(ref u) = update {
+                    self.observer.on_update(u);
+                }
+                Ok(update)
+            }
+            Err(err) => {
+                self.observer.on_error(&err);
+                Err(err)
+            }
+        }
+    }
+
+    fn tear_down(&mut self) {
+        self.model.tear_down();
+        self.observer.on_tear_down();
+    }
+}
+
+pub struct ObserveInit<'m, I, O: 'm, E> {
+    iter: I,
+    observer: &'m mut O,
+    _error: PhantomData<E>,
+}
+
+impl<'m, I, O, E> Iterator for ObserveInit<'m, I, O, E>
+where
+    I: Iterator,
+    O: Observer<I::Item, E>,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let update = self.iter.next()?;
+        self.observer.on_init(&update);
+        Some(update)
+    }
+}
+
+#[derive(Copy, Clone)]
+pub enum Either<A, B> {
+    Left(A),
+    Right(B),
+}
+impl<A, B> Iterator for Either<A, B>
+where
+    A: Iterator,
+    B: Iterator<Item = A::Item>,
+{
+    type Item = A::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Either::Left(ref mut a) => a.next(),
+            Either::Right(ref mut b) => b.next(),
+        }
+    }
+}
+
+pub struct Alternating<A, B, C> {
+    swapped: bool,
+    left: A,
+    right: B,
+    _cmd: PhantomData<C>,
+}
+impl<A, B, C> Alternating<A, B, C> {
+    #[inline]
+    fn current(&mut self) -> Either<&mut A, &mut B> {
+        if self.swapped {
+            Either::Right(&mut self.right)
+        } else {
+            Either::Left(&mut self.left)
+        }
+    }
+
+    fn swap(&mut self) {
+        self.swapped = !self.swapped;
+    }
+}
+
+impl<'m, Cmd, A, B> Stateful<'m> for Alternating<A, B, Cmd>
+where
+    A: Stateful<'m>,
+    B: Stateful<'m, Update = A::Update>,
+    Cmd: Into<Option<A::Cmd>> + Into<Option<B::Cmd>>,
+{
+    type Cmd = Cmd;
+    type Init = Either<
+        <A::Init as IntoIterator>::IntoIter,
+        <B::Init as IntoIterator>::IntoIter,
+    >;
+    type Update = A::Update;
+    type Error = GameOver;
+
+    fn initialize(&'m mut self) -> Self::Init {
+        match self.current() {
+            Either::Left(a) => Either::Left(a.initialize().into_iter()),
+            Either::Right(b) => Either::Right(b.initialize().into_iter()),
+        }
+    }
+
+    #[inline]
+    fn step(
+        &mut self,
+        cmd: Option<Self::Cmd>,
+        tick: Tick,
+    ) -> Result<Option<Self::Update>, Self::Error> {
+        let err: GameOver;
+
+        match self.current() {
+            Either::Left(a) => match a.step(cmd.and_then(|c| c.into()), tick) {
+                Ok(u) => return Ok(u),
+                Err(e) => {
+                    err = e.into();
+                }
+            },
+            Either::Right(b) => match b.step(cmd.and_then(|c| c.into()), tick) {
+                Ok(u) => return Ok(u),
+                Err(e) => {
+                    err = e.into();
+                }
+            },
+        }
+
+        match err {
+            GameOver::Restart => {}
+            _ => self.swap(),
+        }
+
+        Err(err)
+    }
+
+    fn tear_down(&mut self) {
+        match self.current() {
+            Either::Left(a) => a.tear_down(),
+            Either::Right(b) => b.tear_down(),
+        }
+    }
+}
+
+/// A model and the env it is drawn on, see `Stateful::make_game`
+pub struct Game<M, E> {
+    model: M,
+    env: E,
+    schedule: Schedule,
+    persistent: PersistentSet<E>,
+}
+
+impl<M, E> Game<M, E> {
+    /// Advances as `schedule` says, instead of continuously
+    pub fn with_schedule(mut self, schedule: Schedule) -> Self {
+        self.schedule = schedule;
+        self
+    }
+
+    /// Draws a frame of `renderer` on every frame from the first game on,
+    /// before the patch drawn on it if any, whether the game steps, holds
+    /// or is over. Frames drawn all at once while the game runs headless
+    /// are not shown, it skips those.
+    pub fn with_persistent(mut self, renderer: Box<Persistent<E>>) -> Self {
+        self.persistent.push(renderer);
+        self
+    }
+}
+
+pub struct CmdSender<T> {
+    inner: Rc<RefCell<InputDblBuffer<T>>>,
+}
+
+impl<T> Clone for CmdSender<T> {
+    fn clone(&self) -> Self {
+        CmdSender {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: Eq> CmdSender<T> {
+    #[inline(always)]
+    pub fn send<V: Into<Option<T>>>(&self, v: V) {
+        self.inner.borrow_mut().write(v);
+    }
+}
+
+impl<M, Cmd, U, E> Game<M, E>
+where
+    M: for<'m> Stateful<'m, Update = U, Cmd = Cmd>,
+{
+    /// Runs games one after the other, drawing each update with an `R`,
+    /// until the model quits. The generator yields once per frame, commands
+    /// sent in the meantime go into the next step. Ticks are counted from
+    /// the first game on and never reset.
+    pub fn new_game<R, Input>(
+        self,
+    ) -> (CmdSender<Cmd>, impl Generator<Yield = (), Return = ()>)
+    where
+        E: DrawGrid,
+        R: IncrRender<E, Patch = U>,
+        Input: Into<Option<Cmd>> + Copy + 'static,
+        Cmd: Eq,
+    {
+        self.run(|update, ctx| Incr(R::new_patch(update, ctx)))
+    }
+
+    /// Like `new_game`, drawing each update with the renderer `renderers`
+    /// has selected at the time, which can change while the game runs
+    pub fn new_game_with<Input>(
+        self,
+        renderers: Rc<RefCell<Renderers<E, U>>>,
+    ) -> (CmdSender<Cmd>, impl Generator<Yield = (), Return = ()>)
+    where
+        E: DrawGrid,
+        Input: Into<Option<Cmd>> + Copy + 'static,
+        Cmd: Eq,
+    {
+        self.run(move |update, ctx| renderers.borrow().new_patch(update, ctx))
+    }
+
+    /// Like `new_game`, `make` making the renderer of each update, e.g.
+    /// from a `RendererRegistry`
+    pub fn new_game_by<Input, F, D>(
+        self,
+        make: F,
+    ) -> (CmdSender<Cmd>, impl Generator<Yield = (), Return = ()>)
+    where
+        E: DrawGrid,
+        F: FnMut(U, FrameCtx) -> D,
+        D: Frames<E>,
+        Input: Into<Option<Cmd>> + Copy + 'static,
+        Cmd: Eq,
+    {
+        self.run(make)
+    }
+
+    // the games of `new_game`, `make` making the renderer of each update
+    fn run<F, D>(
+        self,
+        mut make: F,
+    ) -> (CmdSender<Cmd>, impl Generator<Yield = (), Return = ()>)
+    where
+        E: DrawGrid,
+        F: FnMut(U, FrameCtx) -> D,
+        D: Frames<E>,
+        Cmd: Eq,
+    {
+        let mut model = Box::new(self.model);
+        let env = Rc::new(RefCell::new(self.env));
+        let schedule = self.schedule;
+        let persistent = Rc::new(RefCell::new(self.persistent));
+        // the frames of the persistent renderers without a patch
+        let idle = {
+            let (env, persistent) = (env.clone(), persistent.clone());
+            move || {
+                let mut env = env.borrow_mut();
+                persistent.borrow_mut().next_frame(env.deref_mut());
+                env.end_frame();
+            }
+        };
+        let mut make = move |update: U, ctx: FrameCtx| WithPersistent {
+            frames: make(update, ctx),
+            persistent: persistent.clone(),
+        };
+
+        let buf = Rc::new(RefCell::new(InputDblBuffer::new()));
+        let mut tick: Tick = 0;
+        // the ticks cut short by `Lag::Skip` that go on being drawn
+        let mut lagging = Vec::new();
+
+        (CmdSender { inner: buf.clone() }, move || 'app: loop {
+            {
+                enter_phase(ErrorPhase::Model);
+                let iter = model.initialize();
+                for update in iter {
+                    enter_phase(ErrorPhase::Render);
+                    let renderer = make(update, FrameCtx { tick });
+                    yield_from!(draw_frames(renderer, env.clone()));
+                    enter_phase(ErrorPhase::Model);
+                }
+            }
+
+            'game: loop {
+                let (steps, at_once) = match schedule.next_frame() {
+                    Frame::Step => (1, false),
+                    Frame::Hold => {
+                        settle(&mut lagging, None, env.borrow_mut());
+                        idle();
+                        yield ();
+                        continue 'game;
+                    }
+                    Frame::Headless(ticks) => (ticks, true),
+                };
+                if at_once {
+                    settle(&mut lagging, None, env.borrow_mut());
+                }
+
+                for _ in 0..steps {
+                    enter_phase(ErrorPhase::Model);
+                    let cmd = buf.borrow_mut().read();
+                    let update = model.step(cmd, tick);
+                    let ctx = FrameCtx { tick };
+                    tick = tick.wrapping_add(1);
+
+                    match update {
+                        Ok(Some(u)) if at_once => {
+                            enter_phase(ErrorPhase::Render);
+                            let mut renderer = make(u, ctx).frames;
+                            let mut env = env.borrow_mut();
+                            let env = env.deref_mut();
+                            while renderer.next_frame(env).is_some() {}
+                        }
+                        Ok(Some(u)) => {
+                            enter_phase(ErrorPhase::Render);
+                            let mut renderer = make(u, ctx);
+                            settle(
+                                &mut lagging,
+                                Some(&renderer.frames),
+                                env.borrow_mut(),
+                            );
+                            let mut more = draw_tick(
+                                &mut renderer,
+                                &mut lagging,
+                                env.borrow_mut(),
+                            );
+                            yield ();
+                            while more.is_some() {
+                                // the next tick is drawn in this frame
+                                if schedule.is_superseded() {
+                                    lagging.push(renderer.frames);
+                                    break;
+                                }
+                                more = draw_tick(
+                                    &mut renderer,
+                                    &mut lagging,
+                                    env.borrow_mut(),
+                                );
+                                if more.is_some() {
+                                    yield ();
+                                }
+                            }
+                        }
+                        Ok(None) if at_once => {}
+                        Ok(None) => {
+                            settle(&mut lagging, None, env.borrow_mut());
+                            idle();
+                            yield ();
+                        }
+                        Err(err) => {
+                            settle(&mut lagging, None, env.borrow_mut());
+                            match err.into() {
+                                GameOver::Over
+                                | GameOver::Won
+                                | GameOver::Restart => break 'game,
+                                GameOver::Quit => break 'app,
+                            }
+                        }
+                    }
+
+                    buf.borrow_mut()
+                        .swap_when(|curr, next| curr.len() < next.len());
+                }
+
+                if at_once {
+                    yield ();
+                }
+            }
+
+            buf.borrow_mut().clear_both();
+            model.tear_down();
+        })
+    }
+}
+
+// a frame of the ticks cut short that go on, then of `renderer`'s
+fn draw_tick<E, F, D>(
+    renderer: &mut F,
+    lagging: &mut Vec<D>,
+    mut env: RefMut<E>,
+) -> Option<()>
+where
+    F: Frames<E>,
+    D: Frames<E>,
+{
+    let env = env.deref_mut();
+    let mut i = 0;
+    while i < lagging.len() {
+        if lagging[i].next_frame(env).is_some() {
+            i += 1;
+        } else {
+            lagging.remove(i);
+        }
+    }
+    renderer.next_frame(env)
+}
+
+// draws the ticks cut short to their end if `next` changes one of their
+// tiles, all of them without a `next`, the others go on alongside it
+fn settle<E, D>(lagging: &mut Vec<D>, next: Option<&D>, mut env: RefMut<E>)
+where
+    D: Frames<E>,
+{
+    let next = match next {
+        Some(next) => next.tiles(),
+        None => None,
+    };
+    let env = env.deref_mut();
+    let mut i = 0;
+    while i < lagging.len() {
+        let shared = match (lagging[i].tiles(), next.as_ref()) {
+            (Some(tiles), Some(next)) => {
+                tiles.iter().any(|tile| next.contains(tile))
+            }
+            _ => true,
+        };
+        if shared {
+            while lagging[i].next_frame(env).is_some() {}
+            lagging.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::render::*;
+    use super::super::schedule::Lag;
+    use super::*;
+    use data::*;
+
+    #[test]
+    fn test_drop() {
+        struct Empty<'a> {
+            dropped: &'a mut bool,
+        }
+        impl<'a> Drop for Empty<'a> {
+            fn drop(&mut self) {
+                *self.dropped = true;
+            }
+        }
+        impl<'a> Empty<'a> {
+            fn new(dropped: &'a mut bool) -> Self {
+                Empty { dropped }
+            }
+        }
+        impl<'a, 'm> Stateful<'m> for Empty<'a> {
+            type Cmd = ();
+            type Update = ();
+            type Init = Option<()>;
+            type Error = GameOver;
+
+            fn initialize(&'m mut self) -> Self::Init {
+                None
+            }
+
+            fn step(
+                &mut self,
+                _cmd: Option<Self::Cmd>,
+                _tick: Tick,
+            ) -> Result<Option<Self::Update>, Self::Error> {
+                Ok(Some(()))
+            }
+
+            fn tear_down(&mut self) {}
+        }
+        impl<'a> DrawGrid for Empty<'a> {
+            fn setup(
+                &mut self,
+                _tile_size: SmallNat,
+                _width: SmallNat,
+                _height: SmallNat,
+            ) {
+            }
+
+            fn clear(&mut self) {}
+
+            // returns current fill color
+            fn set_fill_color(&mut self, color: Color) -> Color {
+                color
+            }
+
+            fn circle(
+                &mut self,
+                _x: SmallNat,
+                _y: SmallNat,
+                _radius: UnitInterval,
+            ) {
+            }
+
+            fn fill_tile(
+                &mut self,
+                _x: SmallNat,
+                _y: SmallNat,
+                _dir: Direction,
+                _size: UnitInterval,
+            ) {
+            }
+
+            fn clear_tile(
+                &mut self,
+                _x: SmallNat,
+                _y: SmallNat,
+                _dir: Direction,
+                _size: UnitInterval,
+            ) {
+            }
+
+            fn show_game_over(&mut self) {}
+        }
+        struct RenderNothing<'a> {
+            _lifetime: PhantomData<&'a ()>,
+        }
+
+        impl<'a> IncrRender<Empty<'a>> for RenderNothing<'a> {
+            type Patch = ();
+
+            fn new_patch(_u: Self::Patch, _ctx: FrameCtx) -> Self {
+                RenderNothing {
+                    _lifetime: PhantomData,
+                }
+            }
+            fn render(&mut self, _env: &mut Empty) -> Option<()> {
+                Some(())
+            }
+        }
+        // -------- test below ----------
+
+        let mut model_dropped = false;
+        let mut env_dropped = false;
+
+        {
+            let game = Empty::new(&mut model_dropped)
+                .make_game(Empty::new(&mut env_dropped));
+            let (_buf, mut gen) = game.new_game::<RenderNothing, ()>();
+
+            unsafe {
+                gen.resume();
+            }
+        }
+
+        assert!(model_dropped);
+        assert!(env_dropped);
+    }
+
+    // steps with the same update forever
+    struct Forever<U>(U);
+    impl<'m, U: Copy> Stateful<'m> for Forever<U> {
+        type Cmd = ();
+        type Update = U;
+        type Init = Option<()>;
+        type Error = GameOver;
+
+        fn initialize(&'m mut self) -> Self::Init {
+            None
+        }
+
+        fn step(
+            &mut self,
+            _cmd: Option<()>,
+            _tick: Tick,
+        ) -> Result<Option<U>, GameOver> {
+            Ok(Some(self.0))
+        }
+
+        fn tear_down(&mut self) {}
+    }
+
+    // the colors set on it, in order
+    struct Marks(Vec<Color>);
+    impl DrawGrid for Marks {
+        fn setup(&mut self, _: SmallNat, _: SmallNat, _: SmallNat) {}
+
+        fn clear(&mut self) {}
+
+        fn set_fill_color(&mut self, color: Color) -> Color {
+            self.0.push(color);
+            color
+        }
+
+        fn circle(&mut self, _: SmallNat, _: SmallNat, _: UnitInterval) {}
+
+        fn fill_tile(
+            &mut self,
+            _: SmallNat,
+            _: SmallNat,
+            _: Direction,
+            _: UnitInterval,
+        ) {
+        }
+
+        fn clear_tile(
+            &mut self,
+            _: SmallNat,
+            _: SmallNat,
+            _: Direction,
+            _: UnitInterval,
+        ) {
+        }
+
+        fn show_game_over(&mut self) {}
+    }
+
+    #[test]
+    fn test_renderers_switch_while_the_game_runs() {
+        struct Paint<C>(PhantomData<C>);
+        struct Red;
+        struct Gray;
+        impl<E: DrawGrid> IncrRender<E> for Paint<Red> {
+            type Patch = ();
+
+            fn new_patch(_: (), _: FrameCtx) -> Self {
+                Paint(PhantomData)
+            }
+
+            fn render(&mut self, env: &mut E) -> Option<()> {
+                env.set_fill_color(Color::Red);
+                None
+            }
+        }
+        impl<E: DrawGrid> IncrRender<E> for Paint<Gray> {
+            type Patch = ();
+
+            fn new_patch(_: (), _: FrameCtx) -> Self {
+                Paint(PhantomData)
+            }
+
+            fn render(&mut self, env: &mut E) -> Option<()> {
+                env.set_fill_color(Color::Gray);
+                None
+            }
+        }
+
+        type Env = Rc<RefCell<Marks>>;
+        let marks = Rc::new(RefCell::new(Marks(Vec::new())));
+        let renderers = Rc::new(RefCell::new(
+            Renderers::new(boxed::<Env, Paint<Red>>)
+                .with(boxed::<Env, Paint<Gray>>),
+        ));
+
+        let (_tx, mut gen) = Forever(())
+            .make_game(marks.clone())
+            .new_game_with::<()>(renderers.clone());
+        unsafe {
+            gen.resume();
+            gen.resume();
+        }
+        assert!(renderers.borrow_mut().select(1));
+        assert!(!renderers.borrow_mut().select(2));
+        unsafe {
+            gen.resume();
+        }
+
+        let colors = &marks.borrow().0;
+        assert_eq!(colors[..], [Color::Red, Color::Red, Color::Gray]);
+    }
+
+    #[test]
+    fn test_registry_draws_each_kind_of_patch_its_own_way() {
+        // sets its color, one frame or two
+        struct Mark(Color, u8);
+        impl<E: DrawGrid> Frames<E> for Mark {
+            fn next_frame(&mut self, env: &mut E) -> Option<()> {
+                env.set_fill_color(self.0);
+                self.1 -= 1;
+                if self.1 > 0 {
+                    Some(())
+                } else {
+                    None
+                }
+            }
+        }
+
+        type Env = Rc<RefCell<Marks>>;
+        fn gray(_: u8, _: FrameCtx) -> Box<Frames<Env>> {
+            Box::new(Mark(Color::Gray, 1))
+        }
+        fn red(_: u8, _: FrameCtx) -> Box<Frames<Env>> {
+            Box::new(Mark(Color::Red, 2))
+        }
+        fn black(_: u8, _: FrameCtx) -> Box<Frames<Env>> {
+            Box::new(Mark(Color::Black, 1))
+        }
+
+        // 2 is even, but the one registered last wins it
+        let registry = RendererRegistry::new(gray)
+            .register(|n| n % 2 == 0, red)
+            .register(|n| *n == 2, black);
+
+        let marks = Rc::new(RefCell::new(Marks(Vec::new())));
+        let (_tx, mut gen) = Forever([1, 2, 4])
+            .make_game(marks.clone())
+            .new_game_by::<(), _, _>(move |parts: [u8; 3], ctx| {
+                registry.new_patches(parts.iter().cloned(), ctx)
+            });
+        unsafe {
+            gen.resume();
+            gen.resume();
+        }
+
+        // the red one takes a second frame, the others are done by then,
+        // and the next update starts as soon as it is done too
+        let colors = &marks.borrow().0;
+        assert_eq!(
+            colors[..4],
+            [Color::Gray, Color::Black, Color::Red, Color::Red]
+        );
+        assert_eq!(colors[4..], [Color::Gray, Color::Black, Color::Red]);
+    }
+
+    #[test]
+    fn test_due_ticks_cut_the_animation_short() {
+        // paints a frame red, `left` more times
+        struct Frames3 {
+            left: u8,
+        }
+        impl<E: DrawGrid> Frames<E> for Frames3 {
+            fn next_frame(&mut self, env: &mut E) -> Option<()> {
+                env.set_fill_color(Color::Red);
+                self.left -= 1;
+                if self.left > 0 {
+                    Some(())
+                } else {
+                    None
+                }
+            }
+        }
+
+        let marks = Rc::new(RefCell::new(Marks(Vec::new())));
+        let schedule = Schedule::new();
+        schedule.set_lag(Lag::Skip);
+        schedule.step(2);
+
+        let (_tx, mut gen) = Forever(())
+            .make_game(marks.clone())
+            .with_schedule(schedule.clone())
+            .new_game_by::<(), _, _>(|_, _| Frames3 { left: 3 });
+        let mut frames = || {
+            unsafe {
+                gen.resume();
+            }
+            marks.borrow().0.len()
+        };
+
+        assert_eq!(frames(), 1);
+        // the second tick is due, the first one is drawn to its end along
+        // with the first frame of the second one
+        assert_eq!(frames(), 4);
+        // which nothing is due after
+        assert_eq!(frames(), 5);
+        assert_eq!(frames(), 6);
+        assert_eq!(frames(), 6);
+    }
+
+    #[test]
+    fn test_due_ticks_leave_the_tiles_they_dont_change_animating() {
+        // paints a frame red, `left` more times, on a tile of its own
+        struct OnTile {
+            at: Coordinate,
+            left: u8,
+        }
+        impl<E: DrawGrid> Frames<E> for OnTile {
+            fn next_frame(&mut self, env: &mut E) -> Option<()> {
+                env.set_fill_color(Color::Red);
+                self.left -= 1;
+                if self.left > 0 {
+                    Some(())
+                } else {
+                    None
+                }
+            }
+
+            fn tiles(&self) -> Option<Vec<Coordinate>> {
+                Some(vec![self.at])
+            }
+        }
+
+        let marks = Rc::new(RefCell::new(Marks(Vec::new())));
+        let schedule = Schedule::new();
+        schedule.set_lag(Lag::Skip);
+
+        let (_tx, mut gen) = Forever(())
+            .make_game(marks.clone())
+            .with_schedule(schedule.clone())
+            .new_game_by::<(), _, _>(|_, ctx| OnTile {
+                at: Coordinate {
+                    x: ctx.tick as SmallNat,
+                    y: 0,
+                },
+                left: 3,
+            });
+        let mut frames = || {
+            unsafe {
+                gen.resume();
+            }
+            marks.borrow().0.len()
+        };
+
+        assert_eq!(frames(), 1);
+        // running on, the host fell a tick behind
+        schedule.catch_up(1);
+        // the first tick goes on alongside the second one
+        assert_eq!(frames(), 3);
+        assert_eq!(frames(), 5);
+        // and is done, the second one ends and a third one starts
+        assert_eq!(frames(), 7);
+    }
+
+    #[test]
+    fn test_persistent_renderers_draw_on_every_frame() {
+        // paints every frame gray
+        struct Idle;
+        impl<E: DrawGrid> Persistent<E> for Idle {
+            fn next_frame(&mut self, env: &mut E) {
+                env.set_fill_color(Color::Gray);
+            }
+        }
+        // paints two frames red
+        struct Twice(u8);
+        impl<E: DrawGrid> Frames<E> for Twice {
+            fn next_frame(&mut self, env: &mut E) -> Option<()> {
+                env.set_fill_color(Color::Red);
+                self.0 -= 1;
+                if self.0 > 0 {
+                    Some(())
+                } else {
+                    None
+                }
+            }
+        }
+
+        let marks = Rc::new(RefCell::new(Marks(Vec::new())));
+        let schedule = Schedule::new();
+        schedule.step(1);
+
+        let (_tx, mut gen) = Forever(())
+            .make_game(marks.clone())
+            .with_schedule(schedule.clone())
+            .with_persistent(Box::new(Idle))
+            .new_game_by::<(), _, _>(|_, _| Twice(2));
+        unsafe {
+            gen.resume();
+            gen.resume();
+        }
+
+        // before each frame of the tick, then on its own once it holds
+        let colors = &marks.borrow().0;
+        assert_eq!(
+            colors[..],
+            [Color::Gray, Color::Red, Color::Gray, Color::Red, Color::Gray]
+        );
+    }
+
+    #[test]
+    fn test_render_pool_keeps_entities_across_patches() {
+        // the key of an entity and what happens to it
+        type Patch = (u8, char);
+
+        // paints its color on every frame, gone once updated, and black
+        // once despawned
+        struct Dot(Color);
+        impl<E: DrawGrid> PersistentRender<E> for Dot {
+            type Patch = Patch;
+
+            fn render(&mut self, env: &mut E) {
+                env.set_fill_color(self.0);
+            }
+
+            fn update(&mut self, _: &Patch) -> bool {
+                false
+            }
+
+            fn despawn(&mut self, env: &mut E) {
+                env.set_fill_color(Color::Black);
+            }
+        }
+
+        fn lifecycle(&(key, op): &Patch) -> Option<Lifecycle<u8>> {
+            match op {
+                's' => Some(Lifecycle::Spawn(key)),
+                'u' => Some(Lifecycle::Update(key)),
+                'd' => Some(Lifecycle::Despawn(key)),
+                _ => None,
+            }
+        }
+        fn spawn(
+            key: u8,
+            _: &Patch,
+        ) -> Option<Box<PersistentRender<Marks, Patch = Patch>>> {
+            let color = if key == 0 { Color::Red } else { Color::Gray };
+            Some(Box::new(Dot(color)))
+        }
+
+        let mut pool = RenderPool::new(lifecycle, spawn);
+        let mut marks = Marks(Vec::new());
+        for &patch in &[(0, 's'), (1, 's'), (2, 's'), (0, 's'), (2, 'd')] {
+            pool.on_patch(&patch);
+        }
+        // spawned again, 0 is drawn after 1 now
+        assert_eq!(pool.keys().collect::<Vec<_>>(), [1, 0]);
+        // after the 0 spawned first and the 2, gone on the next frame
+        pool.next_frame(&mut marks);
+        let drawn = [Color::Black, Color::Black, Color::Gray, Color::Red];
+        assert_eq!(marks.0[..], drawn);
+
+        pool.on_patch(&(1, 'u'));
+        pool.on_patch(&(3, 'x'));
+        assert!(!pool.contains(1) && pool.contains(0));
+        pool.next_frame(&mut marks);
+        assert_eq!(marks.0[drawn.len()..], [Color::Black, Color::Red]);
+
+        Observer::<Patch, ()>::on_error(&mut pool, &());
+        assert_eq!(pool.len(), 0);
+    }
+}
@@ -0,0 +1,207 @@
+//! The look of the board, shared by the browser, which sets it from js,
+//! and the config files of `config::Config`
+use alloc::string::String;
+use alloc::vec::Vec;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use system::Color;
+use world::TeamId;
+
+/// How the board behind the snake is painted
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[cfg_attr(feature = "config", derive(Deserialize))]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Background {
+    Solid = 0,
+    Checkerboard = 1,
+    // falls back to `Solid` until the image has loaded
+    Image = 2,
+}
+
+impl Background {
+    pub fn from_u8(n: u8) -> Option<Self> {
+        match n {
+            0 => Some(Background::Solid),
+            1 => Some(Background::Checkerboard),
+            2 => Some(Background::Image),
+            _ => None,
+        }
+    }
+}
+
+/// Colors the snake, the food and the obstacles are drawn in
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[cfg_attr(feature = "config", derive(Deserialize))]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Palette {
+    Standard = 0,
+    // pure black and red, darker obstacles
+    HighContrast = 1,
+    // blue and orange in place of black and red, which players with
+    // deuteranopia or protanopia tell apart
+    ColorBlind = 2,
+}
+
+impl Palette {
+    pub fn from_u8(n: u8) -> Option<Self> {
+        match n {
+            0 => Some(Palette::Standard),
+            1 => Some(Palette::HighContrast),
+            2 => Some(Palette::ColorBlind),
+            _ => None,
+        }
+    }
+}
+
+/// Look of the board, colors are css colors
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "config", derive(Deserialize))]
+#[cfg_attr(feature = "config", serde(default, deny_unknown_fields))]
+pub struct Theme {
+    pub background: Background,
+    /// `Solid` color, and the light squares of `Checkerboard`
+    pub board: String,
+    /// Dark squares of `Checkerboard`
+    pub checker: String,
+    /// Url of the `Image`, stretched over the whole board
+    pub image: Option<String>,
+    /// Lines between the tiles, `grid_width` pixels wide
+    pub grid: bool,
+    pub grid_color: String,
+    pub grid_width: f64,
+    /// Every `major_every`th line is drawn twice as wide in `major_color`,
+    /// 0 for no major lines
+    pub major_every: u32,
+    pub major_color: String,
+    pub palette: Palette,
+    /// Retro look: pieces on whole pixels only, square food and heads
+    /// outlined 1px wide in `outline_color`, and images scaled without
+    /// smoothing
+    pub pixel_art: bool,
+    pub outline_color: String,
+    /// The page scales the canvas up (or down) pixelated, with css
+    /// `image-rendering: pixelated`
+    pub pixelated: bool,
+    /// Pieces that only differ by color take distinct shapes too, see
+    /// `DrawGrid::distinct_shapes`
+    pub shapes: bool,
+    /// Scanlines and a vignette over the board, like an old crt screen
+    pub crt: bool,
+    /// Snakes of each `TeamId` in turn are drawn in these, see
+    /// `team_color`
+    pub team_colors: Vec<String>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            background: Background::Solid,
+            board: "rgb(255, 255, 255)".into(),
+            checker: "rgb(242, 242, 242)".into(),
+            image: None,
+            grid: true,
+            grid_color: "rgba(0, 0, 0, 0.02)".into(),
+            grid_width: 1.0,
+            major_every: 0,
+            major_color: "rgba(0, 0, 0, 0.08)".into(),
+            palette: Palette::Standard,
+            pixel_art: false,
+            outline_color: "rgba(0, 0, 0, 0.6)".into(),
+            pixelated: false,
+            shapes: false,
+            crt: false,
+            team_colors: vec![
+                "rgb(0, 114, 178)".into(),
+                "rgb(230, 159, 0)".into(),
+            ],
+        }
+    }
+}
+
+impl Theme {
+    /// Color of the tile at `x`, `y` for a solid or checkerboard background
+    pub fn tile_color(&self, x: u32, y: u32) -> &str {
+        match self.background {
+            Background::Checkerboard if (x + y) % 2 == 1 => &self.checker,
+            _ => &self.board,
+        }
+    }
+
+    /// The css color `color` is drawn in with the `palette`
+    pub fn color(&self, color: Color) -> &'static str {
+        match (self.palette, color) {
+            (Palette::Standard, color) => color.to_rgb(),
+            (Palette::HighContrast, Color::Black) => "rgb(0, 0, 0)",
+            (Palette::HighContrast, Color::Red) => "rgb(255, 0, 0)",
+            (Palette::HighContrast, Color::Gray) => "rgb(96, 96, 96)",
+            (Palette::ColorBlind, Color::Black) => "rgb(0, 114, 178)",
+            (Palette::ColorBlind, Color::Red) => "rgb(230, 159, 0)",
+            (Palette::ColorBlind, Color::Gray) => "rgb(136, 136, 136)",
+        }
+    }
+
+    /// The css color the snakes of `team` are drawn in, the colors of
+    /// `team_colors` going round for more teams than there are colors
+    pub fn team_color(&self, team: TeamId) -> &str {
+        match self.team_colors.len() {
+            0 => self.color(Color::Black),
+            n => &self.team_colors[team.0 as usize % n],
+        }
+    }
+
+    /// Whether the `n`th grid line, counted from the top left edge of the
+    /// board, is a major one
+    pub fn is_major_line(&self, n: u32) -> bool {
+        self.major_every > 0 && n % self.major_every == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkerboard_alternates() {
+        let mut theme = Theme::default();
+        assert_eq!(theme.tile_color(0, 1), theme.board);
+
+        theme.background = Background::Checkerboard;
+        assert_eq!(theme.tile_color(0, 0), theme.board);
+        assert_eq!(theme.tile_color(0, 1), theme.checker);
+        assert_eq!(theme.tile_color(3, 2), theme.checker);
+        assert_eq!(theme.tile_color(3, 3), theme.board);
+    }
+
+    #[test]
+    fn test_major_lines() {
+        let mut theme = Theme::default();
+        assert!(!theme.is_major_line(0));
+
+        theme.major_every = 4;
+        assert!(theme.is_major_line(4));
+        assert!(theme.is_major_line(8));
+        assert!(!theme.is_major_line(6));
+    }
+
+    #[test]
+    fn test_color_blind_palette_has_no_red() {
+        let mut theme = Theme::default();
+        assert_eq!(theme.color(Color::Red), Color::Red.to_rgb());
+
+        theme.palette = Palette::ColorBlind;
+        assert_eq!(theme.color(Color::Red), "rgb(230, 159, 0)");
+        assert_eq!(theme.color(Color::Gray), Color::Gray.to_rgb());
+    }
+
+    #[test]
+    fn test_team_colors_go_round() {
+        let mut theme = Theme::default();
+        assert_eq!(theme.team_color(TeamId(2)), theme.team_color(TeamId(0)));
+        assert!(theme.team_color(TeamId(1)) != theme.team_color(TeamId(0)));
+
+        theme.team_colors.clear();
+        assert_eq!(theme.team_color(TeamId(1)), Color::Black.to_rgb());
+    }
+}
@@ -0,0 +1,243 @@
+use data::{Command, Direction};
+use i18n::{Locale, Text};
+use system::{Stateful, Tick};
+use world::{TickDelta, WorldUpdate, PLAYER};
+
+/// What starts a step of the tutorial, counted from the end of the previous
+/// step
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Trigger {
+    Ticks(u32),
+    FoodEaten(u32),
+}
+
+/// Input a step of the tutorial waits for
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Expect {
+    Turn(Direction),
+    AnyTurn,
+    Sprint,
+}
+
+impl Expect {
+    fn accepts(self, cmd: Command) -> bool {
+        match (self, cmd) {
+            (Expect::Turn(dir), Command::Turn(turn)) => dir == turn,
+            (Expect::AnyTurn, Command::Turn(_)) => true,
+            (Expect::Sprint, Command::Sprint(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+/// A step of a tutorial script: once `after` happens the world is paused and
+/// `prompt` shown, in the locale of the `Tutorial`, until the player gives
+/// the `expect`ed input
+#[derive(Debug, Copy, Clone)]
+pub struct Step {
+    pub after: Trigger,
+    pub prompt: Text,
+    pub expect: Expect,
+}
+
+/// The tutorial of the browser game, for a snake starting out heading east
+pub const BASICS: &[Step] = &[
+    Step {
+        after: Trigger::Ticks(3),
+        prompt: Text::PressDown,
+        expect: Expect::Turn(Direction::South),
+    },
+    Step {
+        after: Trigger::Ticks(4),
+        prompt: Text::PressRight,
+        expect: Expect::Turn(Direction::East),
+    },
+    Step {
+        after: Trigger::Ticks(2),
+        prompt: Text::HoldToSprint,
+        expect: Expect::Sprint,
+    },
+    Step {
+        after: Trigger::FoodEaten(1),
+        prompt: Text::WellDone,
+        expect: Expect::AnyTurn,
+    },
+];
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Phase {
+    Running { ticks: u32, food: u32 },
+    // the prompt goes out on the tick after the trigger
+    Prompting,
+    Waiting,
+    // and is hidden on the tick before the world resumes
+    Resuming(Command),
+    Done,
+}
+
+/// Wraps the world and walks the player through `script`, the world doesn't
+/// step while a prompt waits for input
+pub struct Tutorial<M> {
+    model: M,
+    script: &'static [Step],
+    locale: Locale,
+    next: usize,
+    phase: Phase,
+}
+
+impl<M> Tutorial<M> {
+    pub fn new(model: M, script: &'static [Step]) -> Self {
+        Tutorial {
+            model,
+            script,
+            locale: Locale::default(),
+            next: 0,
+            phase: Self::start(script),
+        }
+    }
+
+    /// Shows the prompts in `locale` rather than in english
+    pub fn with_locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.phase == Phase::Done
+    }
+
+    fn start(script: &[Step]) -> Phase {
+        if script.is_empty() {
+            Phase::Done
+        } else {
+            Phase::Running { ticks: 0, food: 0 }
+        }
+    }
+
+    fn triggered(&self, ticks: u32, food: u32) -> bool {
+        match self.script[self.next].after {
+            Trigger::Ticks(n) => ticks >= n,
+            Trigger::FoodEaten(n) => food >= n,
+        }
+    }
+}
+
+impl<'m, M> Stateful<'m> for Tutorial<M>
+where
+    M: Stateful<'m, Cmd = Command, Update = TickDelta>,
+{
+    type Cmd = Command;
+    type Init = M::Init;
+    type Update = TickDelta;
+    type Error = M::Error;
+
+    fn initialize(&'m mut self) -> Self::Init {
+        self.model.initialize()
+    }
+
+    fn step(
+        &mut self,
+        cmd: Option<Command>,
+        tick: Tick,
+    ) -> Result<Option<TickDelta>, M::Error> {
+        match self.phase {
+            Phase::Running { ticks, food } => {
+                let update = self.model.step(cmd, tick)?;
+
+                let ate = |u: WorldUpdate| match u {
+                    WorldUpdate::FoodEaten { snake: PLAYER, .. } => true,
+                    _ => false,
+                };
+                let food = match update {
+                    Some(ref delta) if delta.changes().any(ate) => food + 1,
+                    _ => food,
+                };
+                let ticks = ticks + 1;
+
+                self.phase = if self.triggered(ticks, food) {
+                    Phase::Prompting
+                } else {
+                    Phase::Running { ticks, food }
+                };
+
+                Ok(update)
+            }
+            Phase::Prompting => {
+                self.phase = Phase::Waiting;
+                let prompt = self.locale.text(self.script[self.next].prompt);
+
+                Ok(Some(TickDelta::from(WorldUpdate::Prompt(Some(prompt)))))
+            }
+            Phase::Waiting => match cmd {
+                Some(cmd) if self.script[self.next].expect.accepts(cmd) => {
+                    self.phase = Phase::Resuming(cmd);
+                    Ok(Some(TickDelta::from(WorldUpdate::Prompt(None))))
+                }
+                Some(Command::Restart) | Some(Command::Quit) => {
+                    self.model.step(cmd, tick)
+                }
+                _ => Ok(None),
+            },
+            Phase::Resuming(expected) => {
+                self.next += 1;
+                self.phase = if self.next < self.script.len() {
+                    Phase::Running { ticks: 0, food: 0 }
+                } else {
+                    Phase::Done
+                };
+
+                self.model.step(Some(expected), tick)
+            }
+            Phase::Done => self.model.step(cmd, tick),
+        }
+    }
+
+    fn tear_down(&mut self) {
+        self.next = 0;
+        self.phase = Self::start(self.script);
+        self.model.tear_down();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::SmallRng;
+
+    use data::Wrapping;
+    use world::World;
+
+    const SCRIPT: &[Step] = &[Step {
+        after: Trigger::Ticks(2),
+        prompt: Text::PressDown,
+        expect: Expect::Turn(Direction::South),
+    }];
+
+    #[test]
+    fn test_waits_for_the_expected_input() {
+        let world: World<SmallRng, Wrapping> = World::from_ascii(indoc!(
+            "
+            ..........
+            .>>>>.....
+            ..........
+            .........."
+        ));
+        let mut tutorial = Tutorial::new(world, SCRIPT);
+
+        Stateful::step(&mut tutorial, None, 0).unwrap();
+        Stateful::step(&mut tutorial, None, 0).unwrap();
+
+        let delta = Stateful::step(&mut tutorial, None, 0).unwrap().unwrap();
+        assert_matches!(delta.effects[0], Some(WorldUpdate::Prompt(Some(_))));
+        // the world stands still until the snake turns south
+        let east = Some(Command::Turn(Direction::East));
+        assert_matches!(Stateful::step(&mut tutorial, east, 0), Ok(None));
+        assert_matches!(Stateful::step(&mut tutorial, None, 0), Ok(None));
+
+        let south = Some(Command::Turn(Direction::South));
+        let delta = Stateful::step(&mut tutorial, south, 0).unwrap().unwrap();
+        assert_matches!(delta.effects[0], Some(WorldUpdate::Prompt(None)));
+        Stateful::step(&mut tutorial, None, 0).unwrap();
+        assert!(tutorial.is_done());
+    }
+}
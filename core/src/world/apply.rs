@@ -0,0 +1,165 @@
+use rand::Rng;
+
+use data::{Block, BoundingBehavior, Coordinate, Direction, SnakeId};
+
+use super::{Result, SnakeState, UpdateError, World, WorldUpdate};
+
+impl<R: Rng, BB: BoundingBehavior> World<R, BB> {
+    /// Changes the world the way `update` says it changed. Stepping only
+    /// decides what happens next and applies that, so a log of the updates
+    /// a world stepped through, applied in order to one starting from the
+    /// same board, puts it where the other is: the snakes, the food and the
+    /// pickups on it and how the game ended. What an `Arena`, `Hazards`,
+    /// `Lasers` and the `Countdown` do still happens as they step, their
+    /// updates only report it, as do the ones loading a level.
+    pub fn apply(&mut self, update: &WorldUpdate) {
+        match *update {
+            WorldUpdate::SetBlock { block, at } => {
+                if block == Block::Food {
+                    self.food = Some(at);
+                }
+                self.set_block(at, block);
+            }
+            WorldUpdate::HeadMoved { snake, at, dir, .. } => {
+                self.advance(snake, at, dir)
+            }
+            WorldUpdate::Bend {
+                snake,
+                at,
+                corner,
+                to,
+                ..
+            } => {
+                self.set_block(corner, Block::Snake(to, snake));
+                self.advance(snake, at, to);
+            }
+            WorldUpdate::Teetering { snake, at, dir } => {
+                self.set_block(at, Block::Snake(dir, snake));
+                self.snakes[snake.0 as usize].teetering = true;
+            }
+            WorldUpdate::TailRetracted { snake, at, .. } => {
+                {
+                    let snake = &mut self.snakes[snake.0 as usize];
+                    snake.body.pop_tail();
+                    if let Some(tail) = snake.body.tail() {
+                        snake.tail = tail;
+                    }
+                    // what is left of a dead one withers away
+                    if !snake.alive {
+                        snake.length = snake.body.len() as u32;
+                    }
+                    snake.state = SnakeState::Eaten;
+                }
+                // a ghost may still be on the tile further up a body, or
+                // a snake that went through or over it
+                let covering = self
+                    .snakes
+                    .iter()
+                    .find(|s| s.body.contains(at))
+                    .map(|s| s.id);
+                match (covering, self.get_block(at).snake()) {
+                    (Some(owner), Some(dir)) => {
+                        self.set_block(at, Block::Snake(dir, owner))
+                    }
+                    _ => self.vacate(at),
+                }
+            }
+            // the tiles it grows by, `Grew` takes them one at a time
+            WorldUpdate::FoodEaten { snake, .. } => {
+                let snake = &mut self.snakes[snake.0 as usize];
+                snake.owed += self.rules.growth();
+                snake.eaten += 1;
+                snake.state = SnakeState::Eaten;
+            }
+            WorldUpdate::Grew { snake, length } => {
+                let snake = &mut self.snakes[snake.0 as usize];
+                snake.length = length;
+                snake.owed = snake.owed.saturating_sub(1);
+                snake.state = SnakeState::Eaten;
+            }
+            // its body stays on the board until it withers
+            WorldUpdate::SnakeDied { snake, .. } => {
+                let snake = &mut self.snakes[snake.0 as usize];
+                snake.alive = false;
+                snake.input.clear();
+                snake.teetering = false;
+                snake.half = None;
+            }
+            WorldUpdate::FoodSpawned { at } => {
+                self.set_block(at, Block::Food);
+                self.food = Some(at);
+            }
+            WorldUpdate::FoodMoved { from, to } => {
+                self.set_block(from, Block::Empty);
+                self.set_block(to, Block::Food);
+                self.food = Some(to);
+            }
+            WorldUpdate::PowerUpSpawned { at, power_up } => {
+                self.set_block(at, Block::PowerUp(power_up));
+                if let Some(power_ups) = self.power_ups.as_mut() {
+                    power_ups.place(at, power_up);
+                }
+            }
+            WorldUpdate::PowerUpTaken { power_up, .. } => {
+                if let Some(power_ups) = self.power_ups.as_mut() {
+                    power_ups.take(power_up);
+                }
+            }
+            WorldUpdate::Paused(paused) => self.paused = paused,
+            WorldUpdate::Debug(debug) => self.debug = debug,
+            WorldUpdate::Died { reason } => self.dying = Some(reason),
+            WorldUpdate::Won => self.dying = Some(UpdateError::BoardFilled),
+            _ => {}
+        }
+    }
+
+    // the head of `snake` onto `at` heading `dir`, consuming what was
+    // there on the next step; another snake's tile it may move onto (see
+    // `Versus`) is nothing to consume
+    fn advance(&mut self, snake: SnakeId, at: Coordinate, dir: Direction) {
+        let entered = match self.get_block(at) {
+            Block::Snake(_, owner) if owner != snake => Block::Empty,
+            block => block,
+        };
+        {
+            let snake = &mut self.snakes[snake.0 as usize];
+            snake.state = SnakeState::Consuming(entered);
+            snake.teetering = false;
+            snake.head = at;
+            snake.body.push_head(at);
+        }
+        self.set_block(at, Block::Snake(dir, snake));
+    }
+}
+
+// what a head moving onto `block` consumes, or the crash it is; a `ghost`
+// goes over bodies
+pub(super) fn entering(block: Block, ghost: bool) -> Result<Block> {
+    match block {
+        Block::Snake(..) if ghost => Ok(block),
+        Block::Empty
+        | Block::Food
+        | Block::Checkpoint
+        | Block::Looming
+        | Block::PowerUp(_) => Ok(block),
+        Block::Snake(..) => Err(UpdateError::CollideBody),
+        Block::Obstacle => Err(UpdateError::CollideObstacle),
+        Block::OutOfBound => Err(UpdateError::OutOfBound),
+    }
+}
+
+// the heading a snake heading `heading` turns to when asked to head `dir`,
+// none if it keeps going: into its own neck (`into_neck`) only if reversing
+// is allowed
+pub(super) fn turn(
+    heading: Direction,
+    dir: Direction,
+    into_neck: bool,
+    allow_reverse: bool,
+) -> Option<Direction> {
+    if (into_neck && !allow_reverse) || heading == dir {
+        None
+    } else {
+        Some(dir)
+    }
+}
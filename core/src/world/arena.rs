@@ -0,0 +1,106 @@
+use data::{Coordinate, SmallNat};
+
+// the arena stops shrinking before it gets narrower than this, in tiles
+const MIN_SIZE: SmallNat = 4;
+
+/// The shrinking board of battle royale games: every `every` ticks the
+/// outermost open ring of tiles turns into obstacles, until the arena left
+/// is too small to shrink any further
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Arena {
+    every: u32,
+    ticks: u32,
+    // rings closed so far, counted in from the edge of the board
+    closed: SmallNat,
+}
+
+impl Arena {
+    pub fn new(every: u32) -> Self {
+        Arena {
+            every: ::std::cmp::max(1, every),
+            ticks: 0,
+            closed: 0,
+        }
+    }
+
+    pub fn closed(&self) -> SmallNat {
+        self.closed
+    }
+
+    pub fn reset(&mut self) {
+        self.ticks = 0;
+        self.closed = 0;
+    }
+
+    /// Counts a tick on a `width` x `height` board, the inset of the ring to
+    /// close now if it is time for one
+    pub fn tick(
+        &mut self,
+        width: SmallNat,
+        height: SmallNat,
+    ) -> Option<SmallNat> {
+        let left = |n: SmallNat| n.saturating_sub(2 * (self.closed + 1));
+        if left(width) < MIN_SIZE || left(height) < MIN_SIZE {
+            return None;
+        }
+
+        self.ticks += 1;
+        if self.ticks < self.every {
+            return None;
+        }
+
+        self.ticks = 0;
+        self.closed += 1;
+        Some(self.closed - 1)
+    }
+
+    /// The tiles `inset` tiles in from the edge of a `width` x `height`
+    /// board, each once
+    pub fn ring(
+        width: SmallNat,
+        height: SmallNat,
+        inset: SmallNat,
+    ) -> impl Iterator<Item = Coordinate> {
+        let right = width.saturating_sub(inset + 1);
+        let bottom = height.saturating_sub(inset + 1);
+
+        iproduct!(inset..=right, inset..=bottom)
+            .map(|(x, y)| Coordinate { x, y })
+            .filter(move |c| {
+                c.x == inset || c.y == inset || c.x == right || c.y == bottom
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_closes_rings_until_too_small() {
+        let mut arena = Arena::new(2);
+        let insets: Vec<_> =
+            (0..10).filter_map(|_| arena.tick(12, 10)).collect();
+
+        // 12x10 -> 10x8 -> 8x6 -> 6x4, which is as small as it gets
+        assert_eq!(insets, vec![0, 1, 2]);
+        assert_eq!(arena.closed(), 3);
+
+        arena.reset();
+        assert_eq!(arena.tick(12, 10), None);
+        assert_eq!(arena.tick(12, 10), Some(0));
+    }
+
+    #[test]
+    fn test_ring_covers_the_edge_once() {
+        let ring: Vec<_> = Arena::ring(5, 4, 0).collect();
+        assert_eq!(ring.len(), 14);
+        assert!(ring.iter().all(|c| {
+            c.x == 0 || c.y == 0 || c.x == 4 || c.y == 3
+        }));
+
+        let inner: Vec<_> = Arena::ring(5, 4, 1).collect();
+        assert_eq!(inner.len(), 6);
+    }
+}
@@ -0,0 +1,116 @@
+use alloc::collections::vec_deque::{self, VecDeque};
+use std::iter::FromIterator;
+
+use data::Coordinate;
+
+/// The tiles of a snake in order, kept next to the grid so that bots,
+/// serializers and renderers get at the body without walking the board
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct SnakeBody {
+    // head first
+    blocks: VecDeque<Coordinate>,
+}
+
+impl SnakeBody {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn push_head(&mut self, at: Coordinate) {
+        self.blocks.push_front(at);
+    }
+
+    /// Drops the last block of the snake, the tile it left
+    pub fn pop_tail(&mut self) -> Option<Coordinate> {
+        self.blocks.pop_back()
+    }
+
+    pub fn head(&self) -> Option<Coordinate> {
+        self.blocks.front().cloned()
+    }
+
+    pub fn tail(&self) -> Option<Coordinate> {
+        self.blocks.back().cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    /// Drops every block, keeping the memory for the next snake
+    pub fn clear(&mut self) {
+        self.blocks.clear();
+    }
+
+    /// Whether the snake is on `at`, in time linear in its length
+    pub fn contains(&self, at: Coordinate) -> bool {
+        self.blocks.contains(&at)
+    }
+
+    /// The blocks from the head to the tail
+    pub fn iter(&self) -> vec_deque::Iter<Coordinate> {
+        self.blocks.iter()
+    }
+}
+
+/// Collects a snake from its tail to its head, the order `SnakeIter` walks
+/// it in
+impl FromIterator<Coordinate> for SnakeBody {
+    fn from_iter<I: IntoIterator<Item = Coordinate>>(iter: I) -> Self {
+        let mut body = SnakeBody::new();
+        for at in iter {
+            body.push_head(at);
+        }
+        body
+    }
+}
+
+impl<'a> IntoIterator for &'a SnakeBody {
+    type Item = &'a Coordinate;
+    type IntoIter = vec_deque::Iter<'a, Coordinate>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    fn at(x: u16, y: u16) -> Coordinate {
+        Coordinate { x, y }
+    }
+
+    #[test]
+    fn test_moves_like_a_snake() {
+        let mut body: SnakeBody =
+            vec![at(0, 0), at(1, 0)].into_iter().collect();
+        assert_eq!(body.head(), Some(at(1, 0)));
+        assert_eq!(body.tail(), Some(at(0, 0)));
+
+        body.push_head(at(2, 0));
+        assert_eq!(body.pop_tail(), Some(at(0, 0)));
+
+        assert_eq!(body.len(), 2);
+        assert!(body.contains(at(2, 0)));
+        assert!(!body.contains(at(0, 0)));
+        assert_eq!(
+            body.iter().cloned().collect::<Vec<_>>(),
+            vec![at(2, 0), at(1, 0)]
+        );
+    }
+
+    #[test]
+    fn test_empty_body() {
+        let mut body = SnakeBody::new();
+        assert!(body.is_empty());
+        assert_eq!(body.head(), None);
+        assert_eq!(body.pop_tail(), None);
+    }
+}
@@ -1,19 +1,25 @@
+use alloc::boxed::Box;
 use alloc::vec::Vec;
 use std::convert::From;
 use std::marker::PhantomData;
 
+use arraydeque::ArrayDeque;
 use rand::{Rng, SeedableRng};
 
 use data::{
     Block, BoundingBehavior, Coordinate, Direction, Grid, SmallNat, Wrapping,
 };
 
-use super::{SnakeIter, SnakeState, World};
+use super::{
+    Cadence, Occupancy, Rules, Scratch, Snake, SnakeIter, TeamId,
+    UniformRandom, Versus, World, PLAYER,
+};
 
 #[derive(Copy, Clone)]
 pub struct WorldBuilder<BB: BoundingBehavior = Wrapping> {
     width: SmallNat,
     height: SmallNat,
+    rules: Rules,
 
     _bounding_behavior: PhantomData<BB>,
 }
@@ -23,6 +29,7 @@ impl<BB: BoundingBehavior> WorldBuilder<BB> {
         WorldBuilder {
             width: 10,
             height: 10,
+            rules: Rules::default(),
 
             _bounding_behavior: PhantomData,
         }
@@ -35,6 +42,11 @@ impl<BB: BoundingBehavior> WorldBuilder<BB> {
         self.height = height;
         self
     }
+    /// The rules the world plays by, the classic ones by default
+    pub fn rules(&mut self, rules: Rules) -> &mut Self {
+        self.rules = rules;
+        self
+    }
     pub fn set_snake(self, x: SmallNat, y: SmallNat) -> SnakeBuilder<BB> {
         assert!(x < self.width && y < self.height);
 
@@ -47,6 +59,7 @@ impl<BB: BoundingBehavior> WorldBuilder<BB> {
             tail,
             next_head: tail,
             snake_len: 0,
+            rules: self.rules,
 
             _bounding_behavior: PhantomData,
         }
@@ -59,6 +72,7 @@ pub struct SnakeBuilder<BB: BoundingBehavior> {
     next_head: Coordinate,
     tail: Coordinate,
     snake_len: u32,
+    rules: Rules,
 
     _bounding_behavior: PhantomData<BB>,
 }
@@ -92,9 +106,13 @@ impl<BB: BoundingBehavior> SnakeBuilder<BB> {
         self,
         seed: R::Seed,
     ) -> World<R, BB> {
-        assert!(self.snake_len > 1);
+        self.build_with_rng(R::from_seed(seed))
+    }
 
-        let rng = R::from_seed(seed);
+    /// The world owns `rng` and draws every food position from it, so a
+    /// scripted rng (see `FakeRng`) decides where food shows up
+    pub fn build_with_rng<R: Rng>(self, rng: R) -> World<R, BB> {
+        assert!(self.snake_len > 1);
 
         let initial_snake: Vec<(Coordinate, Direction)>;
 
@@ -103,17 +121,41 @@ impl<BB: BoundingBehavior> SnakeBuilder<BB> {
 
             initial_snake = iter.collect();
         }
+        let occupancy = Occupancy::new(&self.grid);
+        let mut snake = Snake::new(PLAYER, TeamId(0), &initial_snake);
+        snake.input.set_allow_reverse(self.rules.allow_reverse);
 
         World {
             grid: self.grid,
+            occupancy,
+            snakes: vec![snake],
+            mover: 0,
 
-            state: SnakeState::Eaten,
-
-            tail: self.tail,
-            head: self.head,
+            paused: false,
+            debug: false,
+            grace: false,
+            versus: Versus::default(),
+            struck: None,
 
-            initial_snake,
+            checkpoints: Vec::new(),
             rng,
+            spawn: Box::new(UniformRandom),
+            arena: None,
+            countdown: None,
+            lives: None,
+            hazards: None,
+            lasers: None,
+            power_ups: None,
+            cadence: Cadence::new(),
+            now: 0,
+            rules: self.rules,
+
+            pending: ArrayDeque::new(),
+            scratch: Scratch::new(),
+            dying: None,
+            food: None,
+            restored: false,
+            respawned: false,
 
             _bounding_behavior: PhantomData,
         }
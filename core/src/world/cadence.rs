@@ -0,0 +1,74 @@
+use alloc::vec::Vec;
+
+use system::Tick;
+
+/// The parts of a world that can step slower than the game
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Actor {
+    /// The snakes, any turn sent in between waits for the next move
+    Snake,
+    /// The rings of a shrinking `Arena`, which counts its own ticks only
+    /// on the ones it is due
+    Arena,
+}
+
+/// How often each `Actor` of a world steps: on every tick, unless
+/// registered with a divisor `n`, in which case on every `n`th tick of the
+/// game only. Sub-systems ask `is_due` rather than keeping a count of
+/// their own, so that they all agree on the tick and a replay steps each
+/// of them where the game did.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Cadence {
+    divisors: Vec<(Actor, Tick)>,
+}
+
+impl Cadence {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Steps `actor` on every `divisor`th tick from now on, replacing the
+    /// divisor it had; 0 counts as 1
+    pub fn register(&mut self, actor: Actor, divisor: Tick) -> &mut Self {
+        let divisor = ::std::cmp::max(1, divisor);
+        self.divisors.retain(|&(a, _)| a != actor);
+        self.divisors.push((actor, divisor));
+        self
+    }
+
+    pub fn divisor(&self, actor: Actor) -> Tick {
+        self.divisors
+            .iter()
+            .find(|&&(a, _)| a == actor)
+            .map_or(1, |&(_, divisor)| divisor)
+    }
+
+    /// Whether `actor` steps on `tick`
+    pub fn is_due(&self, actor: Actor, tick: Tick) -> bool {
+        tick % self.divisor(actor) == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_actors_step_on_their_own_divisor() {
+        let mut cadence = Cadence::new();
+        cadence.register(Actor::Snake, 2).register(Actor::Arena, 3);
+
+        let due = |actor| (0..6).filter(|&t| cadence.is_due(actor, t)).count();
+        assert_eq!(due(Actor::Snake), 3);
+        assert_eq!(due(Actor::Arena), 2);
+    }
+
+    #[test]
+    fn test_unregistered_actors_step_every_tick() {
+        let mut cadence = Cadence::new();
+        assert!((0..4).all(|t| cadence.is_due(Actor::Snake, t)));
+
+        cadence.register(Actor::Snake, 4).register(Actor::Snake, 0);
+        assert_eq!(cadence.divisor(Actor::Snake), 1);
+    }
+}
@@ -0,0 +1,55 @@
+/// The clock of time attack games, in ticks rather than seconds so that a
+/// replay runs out of time exactly where the game did: `budget` ticks to
+/// start with and `bonus` more for each food eaten
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Countdown {
+    budget: u32,
+    bonus: u32,
+    left: u32,
+}
+
+impl Countdown {
+    pub fn new(budget: u32, bonus: u32) -> Self {
+        Countdown {
+            budget,
+            bonus,
+            left: budget,
+        }
+    }
+
+    pub fn left(&self) -> u32 {
+        self.left
+    }
+
+    pub fn reset(&mut self) {
+        self.left = self.budget;
+    }
+
+    /// Counts down a tick in which the snake ate `food` times, `false` once
+    /// the time is up
+    pub fn tick(&mut self, food: u32) -> bool {
+        self.left = self.left.saturating_sub(1) + food * self.bonus;
+        self.left > 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_food_buys_time() {
+        let mut countdown = Countdown::new(3, 5);
+        assert!(countdown.tick(0));
+        assert!(countdown.tick(1));
+        assert_eq!(countdown.left(), 6);
+
+        for _ in 0..5 {
+            assert!(countdown.tick(0));
+        }
+        assert!(!countdown.tick(0));
+
+        countdown.reset();
+        assert_eq!(countdown.left(), 3);
+    }
+}
@@ -0,0 +1,128 @@
+use system::Observer;
+
+use super::{UpdateError, WorldUpdate};
+
+// eating reports `FoodEaten` and `Grew` (and `Died` once the snake fills
+// the board), a shrinking arena `ArenaShrunk` and `Died`, a timed game the
+// `Clock`, with room for an `InvalidInput` or a `Teetering`, a `NextTurn`,
+// a hazard, a laser and what the power-ups do (`PowerUpSpawned`,
+// `PowerUpTaken`, `PowerUpEnded` and a `FoodMoved`)
+const MAX_EFFECTS: usize = 14;
+// the moves of the other snakes, a tail, a head and what they ate each, or
+// their deaths and the bodies withering after
+const MAX_RIVAL_CHANGES: usize = 16;
+
+/// Everything that changed in the world during one tick, which `World`
+/// steps with: a whole move of the player's snake along with the food it
+/// ate, the moves of the others (`rivals`) and the rest (`effects`), so
+/// that a tick can be drawn (or sent) in one go.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct TickDelta {
+    /// `TailRetracted`, unless the snake grew
+    pub tail: Option<WorldUpdate>,
+    /// `HeadMoved` or `Bend`
+    pub head: Option<WorldUpdate>,
+    /// `FoodSpawned`
+    pub food: Option<WorldUpdate>,
+    /// What the other snakes did, in the order they did it
+    pub rivals: [Option<WorldUpdate>; MAX_RIVAL_CHANGES],
+    /// Anything else, in the order it happened
+    pub effects: [Option<WorldUpdate>; MAX_EFFECTS],
+}
+
+impl TickDelta {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Files `update` under the part of the tick it belongs to
+    pub fn push(&mut self, update: WorldUpdate) {
+        let slot = match update {
+            WorldUpdate::TailRetracted { .. } => &mut self.tail,
+            WorldUpdate::HeadMoved { .. } | WorldUpdate::Bend { .. } => {
+                &mut self.head
+            }
+            WorldUpdate::FoodSpawned { .. } => &mut self.food,
+            _ => {
+                let free = self.effects.iter_mut().find(|e| e.is_none());
+                debug_assert!(free.is_some(), "too many effects in a tick");
+
+                if let Some(slot) = free {
+                    *slot = Some(update);
+                }
+                return;
+            }
+        };
+
+        debug_assert!(slot.is_none(), "{:?} twice in a tick", update);
+        *slot = Some(update);
+    }
+
+    /// Files `update`, a change of a snake other than the player's, after
+    /// the changes of the snakes that moved before it
+    pub fn push_rival(&mut self, update: WorldUpdate) {
+        if let (WorldUpdate::FoodSpawned { .. }, None) = (update, self.food) {
+            self.food = Some(update);
+            return;
+        }
+
+        let free = self.rivals.iter_mut().find(|e| e.is_none());
+        debug_assert!(free.is_some(), "too many rival changes in a tick");
+
+        if let Some(slot) = free {
+            *slot = Some(update);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes().next().is_none()
+    }
+
+    /// The changes in the order they are drawn: the tail leaves its tile
+    /// before the head moves on, then food, the other snakes and effects
+    pub fn changes<'a>(&'a self) -> impl Iterator<Item = WorldUpdate> + 'a {
+        self.tail
+            .iter()
+            .chain(self.head.iter())
+            .chain(self.food.iter())
+            .chain(self.rivals.iter().flat_map(|e| e.iter()))
+            .chain(self.effects.iter().flat_map(|e| e.iter()))
+            .cloned()
+    }
+}
+
+impl From<WorldUpdate> for TickDelta {
+    fn from(update: WorldUpdate) -> Self {
+        let mut delta = TickDelta::new();
+        delta.push(update);
+        delta
+    }
+}
+
+/// Hands an observer of single `WorldUpdate`s every change of each tick
+pub struct PerChange<O>(pub O);
+
+impl<O> Observer<TickDelta, UpdateError> for PerChange<O>
+where
+    O: Observer<WorldUpdate, UpdateError>,
+{
+    fn on_init(&mut self, delta: &TickDelta) {
+        for update in delta.changes() {
+            self.0.on_init(&update);
+        }
+    }
+
+    fn on_update(&mut self, delta: &TickDelta) {
+        for update in delta.changes() {
+            self.0.on_update(&update);
+        }
+    }
+
+    fn on_error(&mut self, err: &UpdateError) {
+        self.0.on_error(err);
+    }
+
+    fn on_tear_down(&mut self) {
+        self.0.on_tear_down();
+    }
+}
@@ -0,0 +1,109 @@
+use alloc::vec::Vec;
+
+use data::Coordinate;
+
+/// Obstacles that show up over time: every `every` ticks one looms on a
+/// random empty tile (`Block::Looming`), which blinks for `warning` ticks
+/// before it turns solid. The snake can still cross a looming tile, and one
+/// it is on when the warning is over waits for it to move off.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Hazards {
+    every: u32,
+    warning: u32,
+    ticks: u32,
+    // tiles blinking, with the ticks of warning they have left
+    looming: Vec<(Coordinate, u32)>,
+    raised: Vec<Coordinate>,
+}
+
+impl Hazards {
+    pub fn new(every: u32, warning: u32) -> Self {
+        Hazards {
+            every: ::std::cmp::max(1, every),
+            warning,
+            ticks: 0,
+            looming: Vec::new(),
+            raised: Vec::new(),
+        }
+    }
+
+    pub fn warning(&self) -> u32 {
+        self.warning
+    }
+
+    pub fn reset(&mut self) {
+        self.ticks = 0;
+        self.looming.clear();
+        self.raised.clear();
+    }
+
+    /// The looming tiles and the ticks of warning they have left
+    pub fn looming<'a>(
+        &'a self,
+    ) -> impl Iterator<Item = (Coordinate, u32)> + 'a {
+        self.looming.iter().cloned()
+    }
+
+    pub fn is_looming(&self, at: Coordinate) -> bool {
+        self.looming.iter().any(|&(a, _)| a == at)
+    }
+
+    /// Every tile with a hazard, raised ones first
+    pub fn tiles<'a>(&'a self) -> impl Iterator<Item = Coordinate> + 'a {
+        let looming = self.looming.iter().map(|&(at, _)| at);
+        self.raised.iter().cloned().chain(looming)
+    }
+
+    /// Counts a tick off the warnings, whether a new hazard is due
+    pub fn tick(&mut self) -> bool {
+        for hazard in &mut self.looming {
+            hazard.1 = hazard.1.saturating_sub(1);
+        }
+
+        self.ticks += 1;
+        if self.ticks < self.every {
+            return false;
+        }
+        self.ticks = 0;
+        true
+    }
+
+    /// Starts the warning of a hazard on `at`
+    pub fn warn(&mut self, at: Coordinate) {
+        self.looming.push((at, self.warning));
+    }
+
+    /// Turns the looming tile on `at` solid
+    pub fn raise(&mut self, at: Coordinate) {
+        self.looming.retain(|&(a, _)| a != at);
+        self.raised.push(at);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hazard_looms_before_it_is_raised() {
+        let at = Coordinate { x: 1, y: 2 };
+        let mut hazards = Hazards::new(3, 2);
+
+        assert!(!hazards.tick());
+        assert!(!hazards.tick());
+        assert!(hazards.tick());
+
+        hazards.warn(at);
+        hazards.tick();
+        assert_eq!(hazards.looming().next(), Some((at, 1)));
+        hazards.tick();
+        assert_eq!(hazards.looming().next(), Some((at, 0)));
+
+        hazards.raise(at);
+        assert!(!hazards.is_looming(at));
+        assert_eq!(hazards.tiles().next(), Some(at));
+
+        hazards.reset();
+        assert_eq!(hazards.tiles().count(), 0);
+    }
+}
@@ -0,0 +1,123 @@
+use arraydeque::ArrayDeque;
+
+use constants::TURN_BUFFER_SIZE;
+use data::Direction;
+
+// pending turns, at most one is applied per tile the snake advances
+type TurnBuffer = ArrayDeque<[Direction; TURN_BUFFER_SIZE]>;
+
+/// A turn that would send the snake straight back into its own neck
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct InvalidInput(pub Direction);
+
+/// Input stage sitting between raw commands and the snake: buffers turns
+/// and rejects the ones reversing the direction the snake will be heading
+/// by the time they apply, unless the rules allow it.
+#[derive(Clone)]
+pub struct InputFilter {
+    turns: TurnBuffer,
+    allow_reverse: bool,
+}
+
+impl InputFilter {
+    pub fn new() -> Self {
+        InputFilter {
+            turns: ArrayDeque::new(),
+            allow_reverse: false,
+        }
+    }
+
+    /// Queues turns straight back like any other, see
+    /// `Rules::allow_reverse`
+    pub fn set_allow_reverse(&mut self, allow: bool) {
+        self.allow_reverse = allow;
+    }
+
+    /// `heading` is the current direction of the snake head, used when no
+    /// turn is pending
+    pub fn push(
+        &mut self,
+        heading: Direction,
+        dir: Direction,
+    ) -> Result<(), InvalidInput> {
+        let heading = match self.turns.back() {
+            Some(last) => *last,
+            None => heading,
+        };
+
+        if dir == heading {
+            Ok(())
+        } else if dir == heading.opposite() && !self.allow_reverse {
+            Err(InvalidInput(dir))
+        } else {
+            // buffer full: drop the newest turn, earlier ones still apply
+            let _ = self.turns.push_back(dir);
+            Ok(())
+        }
+    }
+
+    /// The turn the snake takes on its next move, if any
+    pub fn next(&self) -> Option<Direction> {
+        self.turns.front().cloned()
+    }
+
+    #[inline]
+    pub fn pop(&mut self) -> Option<Direction> {
+        self.turns.pop_front()
+    }
+
+    pub fn clear(&mut self) {
+        self.turns.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reverse_of_pending_turn_is_rejected() {
+        let mut filter = InputFilter::new();
+
+        assert_eq!(filter.push(Direction::East, Direction::North), Ok(()));
+        assert_eq!(
+            filter.push(Direction::East, Direction::South),
+            Err(InvalidInput(Direction::South))
+        );
+        assert_eq!(filter.push(Direction::East, Direction::West), Ok(()));
+        assert_eq!(filter.next(), Some(Direction::North));
+
+        assert_eq!(filter.pop(), Some(Direction::North));
+        assert_eq!(filter.pop(), Some(Direction::West));
+        assert_eq!(filter.pop(), None);
+    }
+
+    #[test]
+    fn test_reverse_is_queued_when_allowed() {
+        let mut filter = InputFilter::new();
+        filter.set_allow_reverse(true);
+
+        assert_eq!(filter.push(Direction::East, Direction::West), Ok(()));
+        assert_eq!(filter.pop(), Some(Direction::West));
+    }
+
+    quickcheck! {
+        fn filter_never_queues_a_reversal(dirs: Vec<Direction>) -> bool {
+            let heading = Direction::East;
+            let mut filter = InputFilter::new();
+
+            for dir in dirs {
+                let _ = filter.push(heading, dir);
+            }
+
+            let mut prev = heading;
+            while let Some(dir) = filter.pop() {
+                if dir == prev || dir == prev.opposite() {
+                    return false;
+                }
+                prev = dir;
+            }
+            true
+        }
+    }
+}
@@ -0,0 +1,173 @@
+//! Consistency checks of a `World` after every tick, compiled in with the
+//! `debug-invariants` feature only.
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use rand::Rng;
+
+use data::{Block, BoundingBehavior, Coordinate, Direction};
+
+use super::{Snake, World};
+
+impl<R: Rng, BB: BoundingBehavior> World<R, BB> {
+    /// Panics, after logging what went wrong and the board it went wrong
+    /// on, unless the snakes, their bodies, the food and the occupancy index
+    /// all agree with the grid
+    pub(super) fn check_invariants(&self) {
+        let violations = self.violations();
+        if violations.is_empty() {
+            return;
+        }
+
+        for violation in &violations {
+            warn!("world invariant violated: {}", violation);
+        }
+        let snake = &self.snakes[0];
+        warn!(
+            "head {:?}, tail {:?}, length {}, food {:?}, state {:?}\n{}",
+            snake.head,
+            snake.tail,
+            snake.length,
+            self.food,
+            snake.state,
+            self.dump()
+        );
+        panic!("{} world invariant(s) violated", violations.len());
+    }
+
+    fn violations(&self) -> Vec<String> {
+        let mut violations = Vec::new();
+        let (width, height) = (self.grid.width(), self.grid.height());
+
+        let mut snake = 0;
+        let mut food = Vec::new();
+        for (x, y) in iproduct!(0..width, 0..height) {
+            let at = Coordinate { x, y };
+            let block = self.grid[at];
+            match block {
+                Block::Snake(..) => snake += 1,
+                Block::Food => food.push(at),
+                Block::OutOfBound => {
+                    violations.push(format!("out of bound block at {:?}", at))
+                }
+                _ => {}
+            }
+            if self.occupancy.is_empty_at(at) != (block == Block::Empty) {
+                violations.push(format!("occupancy stale at {:?}", at));
+            }
+        }
+
+        // a ghost's body overlaps bodies where it crossed over them, and
+        // so does a snake's that went through or killed another, see
+        // `Versus`
+        let bodies: usize = self.snakes.iter().map(|s| s.body.len()).sum();
+        let crossing = self.rules.ghost || self.snakes.len() > 1;
+        let overlaps = crossing && snake < bodies;
+        if snake != bodies && !overlaps {
+            violations.push(format!(
+                "{} snake blocks on the grid, {} in the body",
+                snake, bodies
+            ));
+        }
+        for snake in &self.snakes {
+            self.check_snake(snake, &mut violations);
+        }
+
+        match (self.food, food.as_slice()) {
+            (None, []) => {}
+            (Some(at), [on_grid]) if at == *on_grid => {}
+            (expected, found) => violations.push(format!(
+                "food expected at {:?}, found at {:?}",
+                expected, found
+            )),
+        }
+
+        violations
+    }
+
+    // the body of `snake` agrees with its length and ends, and the grid
+    fn check_snake(&self, snake: &Snake, violations: &mut Vec<String>) {
+        if snake.body.len() as u32 != snake.length {
+            violations.push(format!(
+                "length {} but a body of {}",
+                snake.length,
+                snake.body.len()
+            ));
+        }
+        for &at in &snake.body {
+            if self.grid[at].snake().is_none() {
+                violations.push(format!("body at {:?} is off the snake", at));
+            }
+        }
+        // the body of a dead one withers away, ends and all
+        if snake.body.is_empty() && !snake.alive {
+            return;
+        }
+        if snake.body.head() != Some(snake.head) {
+            violations.push(format!("body ends at {:?}", snake.body.head()));
+        }
+        if snake.body.tail() != Some(snake.tail) {
+            violations.push(format!("body starts at {:?}", snake.body.tail()));
+        }
+    }
+
+    // the board in ascii, arrows for the snakes and `@` for the player's head
+    fn dump(&self) -> String {
+        let (width, height) = (self.grid.width(), self.grid.height());
+        let mut dump = String::new();
+
+        for y in 0..height {
+            for x in 0..width {
+                let at = Coordinate { x, y };
+                dump.push(match self.grid[at] {
+                    _ if at == self.snakes[0].head => '@',
+                    Block::Empty => '.',
+                    Block::Food => '*',
+                    Block::Obstacle => '#',
+                    Block::Checkpoint => 'c',
+                    Block::Looming => '%',
+                    Block::PowerUp(_) => 'm',
+                    Block::OutOfBound => '!',
+                    Block::Snake(Direction::North, _) => '^',
+                    Block::Snake(Direction::South, _) => 'v',
+                    Block::Snake(Direction::East, _) => '>',
+                    Block::Snake(Direction::West, _) => '<',
+                });
+            }
+            dump.push('\n');
+        }
+        dump
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::SmallRng;
+
+    use super::*;
+    use data::Wrapping;
+
+    #[test]
+    fn test_fresh_world_is_consistent() {
+        let mut world: World<SmallRng, Wrapping> =
+            World::from_ascii("*.........\n.>>>>.....\n..........");
+        world.food = Some(Coordinate { x: 0, y: 0 });
+
+        assert!(world.violations().is_empty());
+        for _ in 0..20 {
+            world.tick(None).unwrap();
+            assert!(world.violations().is_empty(), "{}", world.dump());
+        }
+    }
+
+    #[test]
+    fn test_catches_a_lost_body_block() {
+        let mut world: World<SmallRng, Wrapping> =
+            World::from_ascii("..........\n.>>>>.....\n..........");
+        world.snakes[0].body.pop_tail();
+
+        let violations = world.violations();
+        assert_eq!(violations.len(), 3);
+        assert!(violations[0].contains("4 snake blocks"));
+    }
+}
@@ -0,0 +1,126 @@
+use data::Line;
+
+/// What a laser does on a tick of `Lasers`
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Sweep {
+    /// Time to aim at a new line, see `Lasers::aim`
+    Due,
+    /// The laser sweeps `line` in `left` ticks
+    Warning { line: Line, left: u32 },
+    /// `line` is deadly on this tick
+    Firing(Line),
+    /// The laser is off `line` again
+    Cleared(Line),
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Phase {
+    Idle,
+    Warning(Line, u32),
+    Firing(Line),
+}
+
+/// Line hazards: `every` ticks after the last sweep a laser aims at a row
+/// or column, warns of it for `warning` ticks and then sweeps it for a
+/// single tick, which a snake on it doesn't survive
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Lasers {
+    every: u32,
+    warning: u32,
+    ticks: u32,
+    phase: Phase,
+}
+
+impl Lasers {
+    pub fn new(every: u32, warning: u32) -> Self {
+        Lasers {
+            every: ::std::cmp::max(1, every),
+            warning,
+            ticks: 0,
+            phase: Phase::Idle,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.ticks = 0;
+        self.phase = Phase::Idle;
+    }
+
+    /// The line warned of or swept, if any
+    pub fn line(&self) -> Option<Line> {
+        match self.phase {
+            Phase::Idle => None,
+            Phase::Warning(line, _) | Phase::Firing(line) => Some(line),
+        }
+    }
+
+    /// Counts a tick, `None` if the laser does nothing on it
+    pub fn tick(&mut self) -> Option<Sweep> {
+        match self.phase {
+            Phase::Idle => {
+                self.ticks += 1;
+                if self.ticks < self.every {
+                    return None;
+                }
+                self.ticks = 0;
+                Some(Sweep::Due)
+            }
+            Phase::Warning(line, left) if left > 1 => {
+                self.phase = Phase::Warning(line, left - 1);
+                Some(Sweep::Warning {
+                    line,
+                    left: left - 1,
+                })
+            }
+            Phase::Warning(line, _) => {
+                self.phase = Phase::Firing(line);
+                Some(Sweep::Firing(line))
+            }
+            Phase::Firing(line) => {
+                self.phase = Phase::Idle;
+                Some(Sweep::Cleared(line))
+            }
+        }
+    }
+
+    /// Aims at `line`, swept right away in a game without warnings
+    pub fn aim(&mut self, line: Line) -> Sweep {
+        if self.warning == 0 {
+            self.phase = Phase::Firing(line);
+            return Sweep::Firing(line);
+        }
+
+        self.phase = Phase::Warning(line, self.warning);
+        Sweep::Warning {
+            line,
+            left: self.warning,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_laser_warns_then_fires_for_a_tick() {
+        let line = Line::Column(3);
+        let mut lasers = Lasers::new(2, 2);
+
+        assert_eq!(lasers.tick(), None);
+        assert_eq!(lasers.tick(), Some(Sweep::Due));
+        assert_eq!(lasers.aim(line), Sweep::Warning { line, left: 2 });
+
+        let sweeps: Vec<_> = (0..3).filter_map(|_| lasers.tick()).collect();
+        assert_eq!(
+            sweeps,
+            vec![
+                Sweep::Warning { line, left: 1 },
+                Sweep::Firing(line),
+                Sweep::Cleared(line),
+            ]
+        );
+        assert_eq!(lasers.line(), None);
+    }
+}
@@ -0,0 +1,67 @@
+/// The lives of a game: a crash costs one and puts the snake back where it
+/// started, shielded for `shield` ticks in which it stops short of walls and
+/// itself rather than crashing. The game is over once the last one is lost.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Lives {
+    total: u32,
+    left: u32,
+    shield: u32,
+    shielded: u32,
+}
+
+impl Lives {
+    pub fn new(total: u32, shield: u32) -> Self {
+        let total = ::std::cmp::max(1, total);
+        Lives {
+            total,
+            left: total,
+            shield,
+            shielded: 0,
+        }
+    }
+
+    pub fn left(&self) -> u32 {
+        self.left
+    }
+
+    pub fn is_shielded(&self) -> bool {
+        self.shielded > 0
+    }
+
+    pub fn reset(&mut self) {
+        self.left = self.total;
+        self.shielded = 0;
+    }
+
+    /// Takes a life for a crash, `false` if it was the last one
+    pub fn lose(&mut self) -> bool {
+        self.left = self.left.saturating_sub(1);
+        self.shielded = self.shield;
+        self.left > 0
+    }
+
+    /// Counts down a tick of the shield
+    pub fn tick(&mut self) {
+        self.shielded = self.shielded.saturating_sub(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_the_last_life_ends_the_game() {
+        let mut lives = Lives::new(2, 1);
+        assert!(lives.lose());
+        assert_eq!(lives.left(), 1);
+
+        assert!(lives.is_shielded());
+        lives.tick();
+        assert!(!lives.is_shielded());
+
+        assert!(!lives.lose());
+        lives.reset();
+        assert_eq!(lives.left(), 2);
+    }
+}
@@ -0,0 +1,1815 @@
+use alloc::boxed::Box;
+use alloc::collections::vec_deque;
+use alloc::vec::Vec;
+use std::iter::{Map, Rev};
+use std::marker::PhantomData;
+
+use arraydeque::ArrayDeque;
+
+use rand::Rng;
+
+use bot::Autopilot;
+use constants::{MAGNET_RADIUS, MAX_SNAKES};
+use data::{
+    Block, Bounding, BoundingBehavior, Command, Coordinate, Direction,
+    Direction8, Grid, Line, PowerUp, SmallNat, Wrapping,
+};
+use system::{GameOver, Stateful, Tick};
+
+pub use data::{SnakeId, PLAYER};
+
+pub use self::arena::Arena;
+pub use self::body::SnakeBody;
+pub use self::builder::WorldBuilder;
+pub use self::cadence::{Actor, Cadence};
+pub use self::countdown::Countdown;
+pub use self::delta::{PerChange, TickDelta};
+pub use self::hazards::Hazards;
+pub use self::input_filter::{InputFilter, InvalidInput};
+pub use self::lasers::{Lasers, Sweep};
+pub use self::lives::Lives;
+pub use self::occupancy::Occupancy;
+pub use self::power_ups::PowerUps;
+pub use self::rules::Rules;
+pub use self::snapshot::Snapshot;
+pub use self::spawn::{
+    AwayFromHead, NearWalls, Scripted, SpawnPolicy, UniformRandom,
+};
+pub use self::versus::{Contender, HeadOn, Versus};
+pub use self::view::WorldView;
+
+use self::apply::{entering, turn};
+use self::scratch::Scratch;
+use self::view::DIRECTIONS;
+
+mod apply;
+mod arena;
+mod body;
+mod builder;
+mod cadence;
+mod countdown;
+mod delta;
+mod hazards;
+mod input_filter;
+mod lasers;
+#[cfg(feature = "debug-invariants")]
+mod invariants;
+mod lives;
+mod occupancy;
+mod power_ups;
+mod rules;
+mod scratch;
+mod snapshot;
+mod spawn;
+mod versus;
+mod view;
+#[cfg(any(test, feature = "test-utils"))]
+mod test_utils;
+#[cfg(any(test, feature = "test-utils"))]
+pub use self::test_utils::FakeRng;
+#[cfg(test)]
+mod tests;
+
+type Result<T> = ::std::result::Result<T, UpdateError>;
+
+/// What changed in the world, one per step of `World::step` (or item of its
+/// `Initializer`), and everything renderers, observers and the js event
+/// bridge get to see of it. A `TickDelta` gathers those of a whole tick.
+///
+/// A game starts with `LevelLoaded` and a `SetBlock` for the food and each
+/// block of each snake, tail first. Each step of a running game then either
+/// moves the head of a snake (`HeadMoved` or `Bend`) or, on the next step,
+/// its tail (`TailRetracted`), the player's and then the others' in turn.
+/// Unless the head moved onto food: the tail stays and the following steps
+/// are `FoodEaten`, `Grew` and `FoodSpawned`. A snake other than the
+/// player's that crashes is a `SnakeDied`, the game goes on without it,
+/// unless it is an ally of the player's: then `Died` follows, as it does
+/// the player's own `SnakeDied` when another snake kills it (see `Versus`).
+/// In a shrinking `Arena`, a tick ends with `ArenaShrunk` whenever a ring closes,
+/// and in a timed game with the `Clock`, which loading the level sets too.
+/// With a grace period (see `World::set_grace`) the move that would crash
+/// is a `Teetering` first. A game with `Lives` starts over with `Respawned`
+/// rather than `LevelLoaded` after a life is lost, the board as it was.
+/// The head crossing a checkpoint (see `World::set_checkpoints`) is a
+/// `CheckpointReached` after its move. `Hazards` loom with a
+/// `HazardWarning` each tick until they are raised with `HazardRaised`,
+/// `Lasers` warn of the line they sweep (`LaserWarning`) before they fire
+/// on it (`LaserFired`) and go off with `LaserCleared`. `PowerUps` show up
+/// with `PowerUpSpawned`, the snake crossing one is a `PowerUpTaken`, and
+/// `PowerUpEnded` once it runs out; a magnet moves the food (`FoodMoved`).
+/// `World::apply` makes the change most of them stand for, the way the
+/// world changes as it steps.
+#[derive(Debug, Copy, Clone)]
+pub enum WorldUpdate {
+    /// New `width` x `height` board, empty until the `SetBlock`s after it
+    LevelLoaded { width: SmallNat, height: SmallNat },
+    /// The snake starts over on the `width` x `height` board it lost a life
+    /// on, which the `SetBlock`s after it draw again like after
+    /// `LevelLoaded`
+    Respawned { width: SmallNat, height: SmallNat },
+    /// `block` placed at `at` while loading the level
+    SetBlock { block: Block, at: Coordinate },
+    /// The head of `snake` moved onto `at` from `neck`, heading `dir`
+    HeadMoved {
+        snake: SnakeId,
+        at: Coordinate,
+        neck: Coordinate,
+        dir: Direction,
+    },
+    /// The head of `snake` moved onto `at` right after turning on
+    /// `corner`, which the snake enters heading `from` and leaves heading
+    /// `to`
+    Bend {
+        snake: SnakeId,
+        at: Coordinate,
+        corner: Coordinate,
+        from: Direction,
+        to: Direction,
+    },
+    /// The tail of `snake` left `at`, heading `dir`
+    TailRetracted {
+        snake: SnakeId,
+        at: Coordinate,
+        dir: Direction,
+    },
+    /// `snake` swallowed the food at `at`, where its head is
+    FoodEaten { snake: SnakeId, at: Coordinate },
+    /// `snake` is one block longer, `length` blocks in all
+    Grew { snake: SnakeId, length: u32 },
+    /// New food at `at`
+    FoodSpawned { at: Coordinate },
+    /// The food moved from `from` onto `to`, pulled by a magnet
+    FoodMoved { from: Coordinate, to: Coordinate },
+    /// A pickup of `power_up` showed up on `at`
+    PowerUpSpawned { at: Coordinate, power_up: PowerUp },
+    /// The head crossed the pickup on `at`, `power_up` is on
+    PowerUpTaken { at: Coordinate, power_up: PowerUp },
+    /// `power_up` ran out
+    PowerUpEnded { power_up: PowerUp },
+    /// The ring of tiles `inset` tiles in from the edge of the `width` x
+    /// `height` board turned into obstacles, but for the snake on it, see
+    /// `Arena::ring`
+    ArenaShrunk {
+        inset: SmallNat,
+        width: SmallNat,
+        height: SmallNat,
+    },
+    /// The tile on `at` blinks, it turns solid in `left` ticks, or once
+    /// the snake is off it
+    HazardWarning { at: Coordinate, left: u32 },
+    /// The looming tile on `at` is an obstacle now
+    HazardRaised { at: Coordinate },
+    /// A laser sweeps `line` in `left` ticks
+    LaserWarning { line: Line, left: u32 },
+    /// The laser is on `line`, which is deadly for this tick
+    LaserFired { line: Line },
+    /// The laser is off `line` again
+    LaserCleared { line: Line },
+    /// The head of `snake` stopped on `at` short of a crash heading `dir`,
+    /// which happens on its next move unless the snake turns away in time
+    Teetering {
+        snake: SnakeId,
+        at: Coordinate,
+        dir: Direction,
+    },
+    /// The head crossed the checkpoint on `at`, which is gone once the snake
+    /// leaves it, see `Checkpoints`
+    CheckpointReached { at: Coordinate },
+    /// Ticks `left` on the `Countdown` of a timed game
+    Clock { left: u32 },
+    /// `Lives` left, set when loading the level and after a respawn
+    Lives { left: u32 },
+    /// A turn rejected by the `InputFilter`, nothing changed
+    InvalidInput(Direction),
+    /// The head on `at` turns `dir` on its next move, one of the turns
+    /// still waiting in the `InputFilter` at the end of a tick
+    NextTurn { at: Coordinate, dir: Direction },
+    /// The game was paused (`true`) or resumed
+    Paused(bool),
+    /// The debug overlay was turned on (`true`) or off
+    Debug(bool),
+    /// Text to show over the board (`None` hides it), from `Tutorial`
+    Prompt(Option<&'static str>),
+    /// The snake crashed, the world stops with `reason` on the next step
+    Died { reason: UpdateError },
+    /// `snake`, not the player's, crashed with `reason` and is out of the
+    /// game
+    SnakeDied { snake: SnakeId, reason: UpdateError },
+    /// The snake covers the board, the world stops with `BoardFilled` on
+    /// the next step
+    Won,
+    /// Game over screen, from `Dead` rather than the world
+    Dead,
+}
+
+// `FoodEaten` is followed by `Grew` and `FoodSpawned`, and maybe by an
+// `InvalidInput` that came along with it
+type PendingUpdates = ArrayDeque<[WorldUpdate; 3]>;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum UpdateError {
+    HeadDetached,
+    TailDetached,
+    OutOfBound,
+    CollideBody,
+    CollideObstacle,
+    // the countdown of a timed game ran out
+    TimeUp,
+    // the snake fills the board, which wins the game
+    BoardFilled,
+    // a crash with a life to spare, the game goes on with a respawn
+    LifeLost,
+    Restart,
+    Quit,
+}
+
+impl UpdateError {
+    /// Whether the snake ran into something, rather than the game ending
+    /// some other way
+    pub fn is_crash(self) -> bool {
+        match self {
+            UpdateError::OutOfBound
+            | UpdateError::CollideBody
+            | UpdateError::CollideObstacle => true,
+            _ => false,
+        }
+    }
+}
+
+impl Into<GameOver> for UpdateError {
+    fn into(self) -> GameOver {
+        match self {
+            UpdateError::Restart | UpdateError::LifeLost => GameOver::Restart,
+            UpdateError::Quit => GameOver::Quit,
+            UpdateError::BoardFilled => GameOver::Won,
+            _ => GameOver::Over,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+enum SnakeState {
+    Eaten,
+    Consuming(Block),
+}
+
+/// Tells apart the teams snakes play in, see `Versus::pass_through_team`
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct TeamId(pub u8);
+
+/// A snake of a `World` and everything about it that moves with it: its
+/// ends and the tiles in between, its length and the turns queued for it.
+/// The board only holds the directions of its tiles, and whose they are.
+/// A snake that died stays out of the game until the next one, its body
+/// withering away a tile a tick.
+#[derive(Clone)]
+pub struct Snake {
+    id: SnakeId,
+    team: TeamId,
+    alive: bool,
+    // the tiles it starts each game on, tail first
+    start: Vec<(Coordinate, Direction)>,
+    // what steers it in place of the commands of its moves, if anything
+    pilot: Option<Autopilot>,
+    state: SnakeState,
+    head: Coordinate,
+    tail: Coordinate,
+    body: SnakeBody,
+    input: InputFilter,
+    // stopped short of a crash, it doesn't get a second chance
+    teetering: bool,
+    length: u32,
+    // tiles it still grows by for the food it ate, see `Rules::growth`
+    owed: u32,
+    // food it ate since it started, which speeds it up, see `Rules::pace`
+    eaten: u32,
+    // the diagonal it is heading along, if the rules let it, and the one
+    // it turns to on its next move
+    diagonal: Option<Direction8>,
+    veer: Option<Direction8>,
+    // the second half of the diagonal move it is making
+    half: Option<Direction>,
+}
+
+impl Snake {
+    // a snake of `team` on `tiles`, tail first, about to move
+    fn new(
+        id: SnakeId,
+        team: TeamId,
+        tiles: &[(Coordinate, Direction)],
+    ) -> Self {
+        let ends = (tiles.first(), tiles.last());
+        let (tail, head) = match ends {
+            (Some(&(tail, _)), Some(&(head, _))) => (tail, head),
+            _ => panic!("a snake needs a tile at least"),
+        };
+
+        Snake {
+            id,
+            team,
+            alive: true,
+            start: tiles.to_vec(),
+            pilot: None,
+            state: SnakeState::Eaten,
+            head,
+            tail,
+            body: tiles.iter().map(|&(at, _)| at).collect(),
+            input: InputFilter::new(),
+            teetering: false,
+            length: tiles.len() as u32,
+            owed: 0,
+            eaten: 0,
+            diagonal: None,
+            veer: None,
+            half: None,
+        }
+    }
+
+    pub fn id(&self) -> SnakeId {
+        self.id
+    }
+
+    pub fn team(&self) -> TeamId {
+        self.team
+    }
+
+    /// Whether the snake is still in the game, see `WorldUpdate::SnakeDied`
+    pub fn is_alive(&self) -> bool {
+        self.alive
+    }
+
+    pub fn head(&self) -> Coordinate {
+        self.head
+    }
+
+    pub fn tail(&self) -> Coordinate {
+        self.tail
+    }
+
+    /// The snake from head to tail, as of the last step
+    pub fn body(&self) -> &SnakeBody {
+        &self.body
+    }
+
+    pub fn length(&self) -> u32 {
+        self.length
+    }
+}
+
+pub struct World<R, BB: BoundingBehavior = Wrapping> {
+    grid: Grid,
+    occupancy: Occupancy,
+    // the player's first, each at the index of its id
+    snakes: Vec<Snake>,
+    // the index of the snake making its move, the player's in between
+    mover: usize,
+
+    paused: bool,
+    debug: bool,
+    grace: bool,
+    versus: Versus,
+    // the snake the mover's head killed on its last step, out of the game
+    // once the step is over
+    struck: Option<SnakeId>,
+
+    checkpoints: Vec<Coordinate>,
+    rng: R,
+    spawn: Box<SpawnPolicy>,
+    arena: Option<Arena>,
+    countdown: Option<Countdown>,
+    lives: Option<Lives>,
+    hazards: Option<Hazards>,
+    lasers: Option<Lasers>,
+    power_ups: Option<PowerUps>,
+    cadence: Cadence,
+    // the tick of the game being stepped, see `Cadence`
+    now: Tick,
+    rules: Rules,
+
+    pending: PendingUpdates,
+    scratch: Scratch,
+    // a crash reported as `Died`, returned by the next step
+    dying: Option<UpdateError>,
+    food: Option<Coordinate>,
+    // put back from a `Snapshot`, the next game carries on from it
+    restored: bool,
+    // the snake lost a life and is back at the start, the next game carries
+    // on from there
+    respawned: bool,
+
+    _bounding_behavior: PhantomData<BB>,
+}
+
+impl<'a, R: Rng + 'a, BB: BoundingBehavior + 'static> Stateful<'a>
+    for World<R, BB>
+{
+    type Cmd = Command;
+    type Update = TickDelta;
+    type Init = Map<Initializer<'a, R, BB>, fn(WorldUpdate) -> TickDelta>;
+
+    type Error = UpdateError;
+
+    fn initialize(&'a mut self) -> Self::Init {
+        let restored = ::std::mem::replace(&mut self.restored, false);
+        let respawned = ::std::mem::replace(&mut self.respawned, false);
+        let food_at = match self.food {
+            Some(at) if restored || respawned => Some(at),
+            _ => self.spawn_food(),
+        };
+
+        let init = if respawned {
+            Initializer::Respawned(&*self, food_at)
+        } else {
+            Initializer::WorldSize(&*self, food_at)
+        };
+        init.map(TickDelta::from as fn(WorldUpdate) -> TickDelta)
+    }
+
+    fn tear_down(&mut self) {
+        self.reset();
+    }
+
+    #[inline(always)]
+    fn step(
+        &mut self,
+        cmd: Option<Self::Cmd>,
+        tick: Tick,
+    ) -> Result<Option<Self::Update>> {
+        self.now = tick;
+        if let Some(err) = self.dying.take() {
+            return Err(self.lose_life(err));
+        }
+
+        let (was_paused, was_debug) = (self.paused, self.debug);
+
+        let dir = match cmd {
+            Some(cmd) => self.command(cmd)?,
+            None => None,
+        };
+
+        if self.paused != was_paused {
+            let paused = WorldUpdate::Paused(self.paused);
+            return Ok(Some(TickDelta::from(paused)));
+        } else if self.debug != was_debug {
+            let debug = WorldUpdate::Debug(self.debug);
+            return Ok(Some(TickDelta::from(debug)));
+        } else if self.paused {
+            return Ok(None);
+        }
+
+        match self.tick(dir) {
+            // nothing was due on this tick
+            Ok(ref delta) if delta.is_empty() => Ok(None),
+            Ok(delta) => {
+                if self.debug {
+                    debug!("{:?}", delta);
+                }
+                Ok(Some(delta))
+            }
+            Err(err) => match err {
+                UpdateError::HeadDetached | UpdateError::TailDetached => {
+                    panic!("Game breaking bug, snake invairant violation")
+                }
+                _ => {
+                    info!("world stopped: {:?}", err);
+                    let died = WorldUpdate::Died { reason: err };
+                    self.apply(&died);
+                    Ok(Some(TickDelta::from(died)))
+                }
+            },
+        }
+    }
+}
+
+impl<R: Rng, BB: BoundingBehavior> World<R, BB> {
+    pub fn rng(&self) -> &R {
+        &self.rng
+    }
+
+    /// The player's snake from head to tail, as of the last step
+    pub fn body(&self) -> &SnakeBody {
+        &self.snake().body
+    }
+
+    /// The player's snake
+    pub fn snake(&self) -> &Snake {
+        &self.snakes[0]
+    }
+
+    /// Every snake of the world, the player's first, in the order of their
+    /// ids
+    pub fn snakes(&self) -> &[Snake] {
+        &self.snakes
+    }
+
+    pub fn rules(&self) -> Rules {
+        self.rules
+    }
+
+    /// The world as bots and renderers see it, without a way to change it
+    pub fn view(&self) -> WorldView {
+        self.view_of(PLAYER)
+    }
+
+    /// The world as `snake` sees it, the view a bot steering it decides on
+    pub fn view_of(&self, snake: SnakeId) -> WorldView {
+        let (food, now, wraps) = (self.food, self.now, self.wraps());
+        WorldView::new(&self.grid, &self.snakes, snake, food, now, wraps)
+    }
+
+    // whether the snake comes back on the other side of an edge it crosses
+    fn wraps(&self) -> bool {
+        self.rules.wrap && BB::WRAPS
+    }
+
+    /// Swaps the rng, e.g. to restore the state saved with `rng` before a
+    /// replay, returning the previous one
+    pub fn replace_rng(&mut self, rng: R) -> R {
+        ::std::mem::replace(&mut self.rng, rng)
+    }
+
+    pub fn set_spawn_policy<S: SpawnPolicy + 'static>(&mut self, spawn: S) {
+        self.spawn = Box::new(spawn);
+    }
+
+    /// Plays in a shrinking `arena`, or on the whole board with `None`.
+    /// Once its first ring closes the edges of the board are walls, so the
+    /// snake no longer wraps around them whatever the `BoundingBehavior`.
+    pub fn set_arena(&mut self, arena: Option<Arena>) {
+        self.arena = arena;
+    }
+
+    /// Plays against the clock, the game is over once `countdown` runs out
+    pub fn set_countdown(&mut self, countdown: Option<Countdown>) {
+        self.countdown = countdown;
+    }
+
+    /// Raises obstacles on random empty tiles as `hazards` says, or none
+    /// with `None`
+    pub fn set_hazards(&mut self, hazards: Option<Hazards>) {
+        self.hazards = hazards;
+    }
+
+    /// Sweeps random rows and columns of the board with `lasers`, or none
+    /// with `None`
+    pub fn set_lasers(&mut self, lasers: Option<Lasers>) {
+        self.lasers = lasers;
+    }
+
+    /// Puts pickups on the board as `power_ups` says, or none with `None`
+    pub fn set_power_ups(&mut self, power_ups: Option<PowerUps>) {
+        self.power_ups = power_ups;
+    }
+
+    /// Lets `autopilot` steer the player's snake whenever it is engaged, in
+    /// place of the turns the world is stepped with
+    pub fn set_autopilot(&mut self, autopilot: Option<Autopilot>) {
+        self.snakes[0].pilot = autopilot;
+    }
+
+    /// Adds a snake of `team` on `tiles`, tail first, steered by `pilot`
+    /// from now on; from the next game on as well, on the same tiles. None
+    /// if a tile isn't empty or the world has `MAX_SNAKES` already. A snake
+    /// of the player's team is an ally: its crash ends the game (or costs
+    /// a life, which puts it back at the start too).
+    pub fn add_snake(
+        &mut self,
+        tiles: &[(Coordinate, Direction)],
+        team: TeamId,
+        pilot: Autopilot,
+    ) -> Option<SnakeId> {
+        let free = tiles.iter().all(|&(at, _)| {
+            self.grid.get(at).map_or(false, |block| block.is_empty())
+        });
+        if tiles.is_empty() || !free || self.snakes.len() >= MAX_SNAKES {
+            return None;
+        }
+
+        let id = SnakeId(self.snakes.len() as u8);
+        let mut snake = Snake::new(id, team, tiles);
+        snake.input.set_allow_reverse(self.rules.allow_reverse);
+        pilot.engage(true);
+        snake.pilot = Some(pilot);
+        self.snakes.push(snake);
+        self.place_snake(id.0 as usize);
+        Some(id)
+    }
+
+    /// Gives the snake `lives`, or a single one with `None`
+    pub fn set_lives(&mut self, lives: Option<Lives>) {
+        self.lives = lives;
+    }
+
+    /// Lays the `checkpoints` of a campaign level on the board, on each game
+    /// from now on, except where they would be on the snake or the food
+    pub fn set_checkpoints(&mut self, checkpoints: Vec<Coordinate>) {
+        self.checkpoints = checkpoints;
+        self.place_checkpoints();
+    }
+
+    /// Forgives a crash into a wall or the body once in a row: the snake
+    /// stops short of it (`Teetering`) for a tick, in which a turn away
+    /// saves it
+    pub fn set_grace(&mut self, grace: bool) {
+        self.grace = grace;
+    }
+
+    /// Settles the crashes of the snakes into each other as `versus` says,
+    /// see `Versus::resolve`; by `Versus::default` unless set
+    pub fn set_versus(&mut self, versus: Versus) {
+        self.versus = versus;
+    }
+
+    /// Steps the snake and the arena at the rates `cadence` says, each on
+    /// every tick by default
+    pub fn set_cadence(&mut self, cadence: Cadence) {
+        self.cadence = cadence;
+    }
+
+    // handles commands besides turning, the returned direction is the turn
+    // to feed into `step`
+    fn command(&mut self, cmd: Command) -> Result<Option<Direction>> {
+        match cmd {
+            // sprinting is only a matter of render speed for the world
+            Command::Turn(dir) | Command::Sprint(dir) if !self.paused => {
+                Ok(Some(dir))
+            }
+            Command::Turn(_) | Command::Sprint(_) => Ok(None),
+            // the snake turns by direction only
+            Command::RotateLeft | Command::RotateRight => Ok(None),
+            Command::Diagonal(dir) if self.rules.diagonal && !self.paused => {
+                self.veer(dir);
+                Ok(None)
+            }
+            Command::Diagonal(_) => Ok(None),
+            Command::Pause => {
+                self.paused = !self.paused;
+                Ok(None)
+            }
+            Command::DebugToggle => {
+                self.debug = !self.debug;
+                Ok(None)
+            }
+            Command::Restart => Err(UpdateError::Restart),
+            Command::Quit => Err(UpdateError::Quit),
+        }
+    }
+
+    // heads the snake along the diagonal `dir` from its next move on, in
+    // place of the turns queued for it; straight back only if the rules
+    // allow it
+    fn veer(&mut self, dir: Direction8) {
+        let back = self.mover().diagonal == Some(dir.opposite());
+        if back && !self.rules.allow_reverse {
+            return;
+        }
+        self.mover_mut().input.clear();
+        self.mover_mut().veer = Some(dir);
+    }
+
+    /// Steps through a whole move of the snake, its head and then its tail
+    /// or the food it ate, which takes `step` two or more calls. A snake
+    /// that isn't due on this tick of its `Cadence` stays put, keeping
+    /// `cmd` for its next move. The other snakes move after the player's,
+    /// each steered by its own pilot.
+    pub fn tick(&mut self, cmd: Option<Direction>) -> Result<TickDelta> {
+        let mut delta = TickDelta::new();
+
+        if self.snake_due(0) {
+            let steered = self.steer(PLAYER);
+            self.move_snake(steered.or(cmd), &mut delta)?;
+        } else if let Some(dir) = cmd {
+            if let Some(rejected) = self.queue_turn(dir)? {
+                delta.push(rejected);
+            }
+        }
+        if self.dying.is_none() {
+            self.move_rivals(&mut delta)?;
+        }
+
+        let (next, at) = (self.snakes[0].input.next(), self.snakes[0].head);
+        if let (Some(dir), None) = (next, self.dying) {
+            delta.push(WorldUpdate::NextTurn { at, dir });
+        }
+
+        let arena_due = self.cadence.is_due(Actor::Arena, self.now);
+        if self.dying.is_none() && arena_due {
+            self.shrink(&mut delta);
+        }
+        if self.dying.is_none() {
+            self.raise_hazards(&mut delta);
+        }
+        if self.dying.is_none() {
+            self.sweep(&mut delta);
+        }
+        if self.dying.is_none() {
+            self.use_power_ups(&mut delta);
+        }
+        if self.dying.is_none() {
+            self.count_down(&mut delta);
+        }
+        if let Some(lives) = self.lives.as_mut() {
+            lives.tick();
+        }
+
+        #[cfg(feature = "debug-invariants")]
+        self.check_invariants();
+
+        Ok(delta)
+    }
+
+    // whether the snake at `i` moves on this tick, every so many of it as
+    // its `Cadence` says times the pace it is at by the rules
+    fn snake_due(&self, i: usize) -> bool {
+        let divisor = self.cadence.divisor(Actor::Snake);
+        self.now % (divisor * self.rules.pace(self.snakes[i].eaten)) == 0
+    }
+
+    // the turn the pilot of `snake` takes, if it has one engaged
+    fn steer(&self, snake: SnakeId) -> Option<Direction> {
+        let pilot = self.snakes[snake.0 as usize].pilot.as_ref()?;
+        pilot.steer(&self.view_of(snake))
+    }
+
+    // moves the snakes besides the player's that are due, one after the
+    // other; a crash takes the one that made it out of the game, while the
+    // bodies of those out of it wither
+    fn move_rivals(&mut self, delta: &mut TickDelta) -> Result<()> {
+        for i in 1..self.snakes.len() {
+            if !self.snakes[i].alive {
+                self.wither(i, delta);
+                continue;
+            } else if !self.snake_due(i) {
+                continue;
+            }
+
+            let steered = self.steer(self.snakes[i].id);
+            self.mover = i;
+            let moved = match steered {
+                // a rival's turn that doesn't go through is just dropped
+                Some(dir) => self.queue_turn(dir).map(|_| ()),
+                None => Ok(()),
+            };
+            let moved = moved.and_then(|()| self.move_snake(None, delta));
+            self.mover = 0;
+
+            match moved {
+                Ok(()) => {}
+                Err(UpdateError::HeadDetached) => return moved,
+                Err(UpdateError::TailDetached) => return moved,
+                Err(reason) => {
+                    self.pending.clear();
+                    self.kill(i, reason, delta);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // takes the snake at `i` out of the game for `reason`, its body stays
+    // on the board until it withers; the team of the player's loses with
+    // any of them, so an ally's crash is the player's as well
+    fn kill(&mut self, i: usize, reason: UpdateError, delta: &mut TickDelta) {
+        let snake = self.snakes[i].id;
+        info!("snake {:?} died: {:?}", snake, reason);
+        let died = WorldUpdate::SnakeDied { snake, reason };
+        self.apply(&died);
+        delta.push_rival(died);
+
+        if self.snakes[i].team == self.snakes[0].team && self.dying.is_none() {
+            info!("world stopped: {:?}", reason);
+            let died = WorldUpdate::Died { reason };
+            self.apply(&died);
+            delta.push(died);
+        }
+    }
+
+    // the body of the dead snake at `i`, a tile shorter each tick
+    fn wither(&mut self, i: usize, delta: &mut TickDelta) {
+        let (snake, tail) = (self.snakes[i].id, self.snakes[i].body.tail());
+        if let Some(at) = tail {
+            let dir = self.get_block(at).snake().unwrap_or(Direction::East);
+            let withered = WorldUpdate::TailRetracted { snake, at, dir };
+            self.apply(&withered);
+            delta.push_rival(withered);
+        }
+    }
+
+    // the snake making its move, the player's unless a rival is moving
+    fn mover(&self) -> &Snake {
+        &self.snakes[self.mover]
+    }
+
+    fn mover_mut(&mut self) -> &mut Snake {
+        &mut self.snakes[self.mover]
+    }
+
+    fn move_snake(
+        &mut self,
+        cmd: Option<Direction>,
+        delta: &mut TickDelta,
+    ) -> Result<()> {
+        let mut cmd = cmd;
+
+        loop {
+            let stepped = self.step(cmd.take());
+            // whether the mover lives on or not
+            if let Some(struck) = self.struck.take() {
+                let reason = UpdateError::CollideBody;
+                self.kill(struck.0 as usize, reason, delta);
+            }
+            match stepped? {
+                Some(update) if self.mover == 0 => delta.push(update),
+                Some(update) => delta.push_rival(update),
+                None => {}
+            }
+
+            // a diagonal move is done once both its halves are
+            let snake = self.mover();
+            match snake.state {
+                SnakeState::Eaten
+                    if self.pending.is_empty() && snake.half.is_none() =>
+                {
+                    break
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    // runs the clock of a timed game, food the player ate this tick buys
+    // time
+    fn count_down(&mut self, delta: &mut TickDelta) {
+        let ate = |u: &WorldUpdate| match *u {
+            WorldUpdate::FoodEaten { snake: PLAYER, .. } => true,
+            _ => false,
+        };
+        let food = delta.changes().filter(ate).count() as u32;
+
+        let (running, left) = match self.countdown.as_mut() {
+            Some(countdown) => (countdown.tick(food), countdown.left()),
+            None => return,
+        };
+        delta.push(WorldUpdate::Clock { left });
+
+        if !running {
+            let reason = UpdateError::TimeUp;
+            info!("world stopped: {:?}", reason);
+            let died = WorldUpdate::Died { reason };
+            self.apply(&died);
+            delta.push(died);
+        }
+    }
+
+    // closes the next ring of the arena once it is due
+    fn shrink(&mut self, delta: &mut TickDelta) {
+        let (width, height) = (self.grid.width(), self.grid.height());
+        let inset = match self.arena.as_mut() {
+            Some(arena) => match arena.tick(width, height) {
+                Some(inset) => inset,
+                None => return,
+            },
+            None => return,
+        };
+
+        let mut died = None;
+        let mut food_lost = false;
+        for at in Arena::ring(width, height, inset) {
+            match self.get_block(at) {
+                // the snake stays whole, if only for its last tick
+                Block::Snake(_, PLAYER) => {
+                    died = Some(UpdateError::CollideObstacle)
+                }
+                Block::Snake(_, snake) => {
+                    let i = snake.0 as usize;
+                    if self.snakes[i].alive {
+                        let reason = UpdateError::CollideObstacle;
+                        self.kill(i, reason, delta);
+                    }
+                }
+                Block::Food => {
+                    food_lost = true;
+                    self.food = None;
+                    self.set_block(at, Block::Obstacle);
+                }
+                Block::PowerUp(_) => {
+                    if let Some(power_ups) = self.power_ups.as_mut() {
+                        power_ups.lose_pickup();
+                    }
+                    self.set_block(at, Block::Obstacle);
+                }
+                _ => self.set_block(at, Block::Obstacle),
+            }
+        }
+        debug!("arena closed ring {}", inset);
+        delta.push(WorldUpdate::ArenaShrunk {
+            inset,
+            width,
+            height,
+        });
+
+        // with nowhere left for food the snake plays on, until the next
+        // ring closes on it
+        if food_lost {
+            if let Some(at) = self.spawn_food() {
+                // replaces any food spawned on the ring earlier in the tick
+                delta.food = Some(WorldUpdate::FoodSpawned { at });
+            }
+        }
+
+        if let Some(reason) = died {
+            info!("world stopped: {:?}", reason);
+            let died = WorldUpdate::Died { reason };
+            self.apply(&died);
+            delta.push(died);
+        }
+    }
+
+    // warns of the next hazard, one at a time, and raises it once its
+    // warning is over and the snake isn't on it
+    fn raise_hazards(&mut self, delta: &mut TickDelta) {
+        // taken out of the scratch for the loop to set blocks
+        let mut looming =
+            ::std::mem::replace(&mut self.scratch.tiles, Vec::new());
+        looming.clear();
+
+        let (due, warning) = match self.hazards.as_mut() {
+            Some(hazards) => {
+                let idle = hazards.looming().next().is_none();
+                let due = hazards.tick() && idle;
+                looming.extend(hazards.looming());
+                (due, hazards.warning())
+            }
+            None => (false, 0),
+        };
+
+        for &(at, left) in &looming {
+            match self.get_block(at) {
+                Block::Snake(..) => {}
+                _ if left == 0 => {
+                    self.set_block(at, Block::Obstacle);
+                    if let Some(hazards) = self.hazards.as_mut() {
+                        hazards.raise(at);
+                    }
+                    debug!("hazard raised at {:?}", at);
+                    delta.push(WorldUpdate::HazardRaised { at });
+                }
+                _ => delta.push(WorldUpdate::HazardWarning { at, left }),
+            }
+        }
+        self.scratch.tiles = looming;
+
+        if !due {
+            return;
+        }
+        if let Some(at) = self.occupancy.random(&mut self.rng) {
+            self.set_block(at, Block::Looming);
+            if let Some(hazards) = self.hazards.as_mut() {
+                hazards.warn(at);
+            }
+            let left = warning;
+            delta.push(WorldUpdate::HazardWarning { at, left });
+        }
+    }
+
+    // steps the laser, a snake on the line it fires on dies unless it is
+    // shielded
+    fn sweep(&mut self, delta: &mut TickDelta) {
+        let sweep = match self.lasers.as_mut().and_then(|l| l.tick()) {
+            Some(Sweep::Due) => {
+                let line = self.random_line();
+                match self.lasers.as_mut() {
+                    Some(lasers) => lasers.aim(line),
+                    None => return,
+                }
+            }
+            Some(sweep) => sweep,
+            None => return,
+        };
+
+        match sweep {
+            Sweep::Warning { line, left } => {
+                delta.push(WorldUpdate::LaserWarning { line, left })
+            }
+            Sweep::Firing(line) => {
+                debug!("laser fired on {:?}", line);
+                delta.push(WorldUpdate::LaserFired { line });
+
+                for i in 1..self.snakes.len() {
+                    let hit = {
+                        let snake = &self.snakes[i];
+                        let body = &snake.body;
+                        snake.alive && body.iter().any(|&at| line.contains(at))
+                    };
+                    if hit {
+                        self.kill(i, UpdateError::CollideObstacle, delta);
+                    }
+                }
+
+                let shielded = self.lives.map_or(false, |l| l.is_shielded());
+                let body = &self.snakes[0].body;
+                let hit = body.iter().any(|&at| line.contains(at));
+                if hit && !shielded {
+                    let reason = UpdateError::CollideObstacle;
+                    info!("world stopped: {:?}", reason);
+                    let died = WorldUpdate::Died { reason };
+                    self.apply(&died);
+                    delta.push(died);
+                }
+            }
+            Sweep::Cleared(line) => {
+                delta.push(WorldUpdate::LaserCleared { line })
+            }
+            Sweep::Due => {}
+        }
+    }
+
+    fn random_line(&mut self) -> Line {
+        if self.rng.gen() {
+            Line::Row(self.rng.gen_range(0, self.grid.height()))
+        } else {
+            Line::Column(self.rng.gen_range(0, self.grid.width()))
+        }
+    }
+
+    // spawns a pickup once it is due and has the power-up on do its thing
+    fn use_power_ups(&mut self, delta: &mut TickDelta) {
+        let (due, ended) = match self.power_ups.as_mut() {
+            Some(power_ups) => (power_ups.due(), power_ups.count_down()),
+            None => return,
+        };
+
+        if let Some(power_up) = ended {
+            delta.push(WorldUpdate::PowerUpEnded { power_up });
+        }
+        if due {
+            if let Some(at) = self.occupancy.random(&mut self.rng) {
+                let power_up = PowerUp::Magnet;
+                let spawned = WorldUpdate::PowerUpSpawned { at, power_up };
+                self.apply(&spawned);
+                delta.push(spawned);
+            }
+        }
+
+        let magnet = self
+            .power_ups
+            .map_or(false, |p| p.is_active(PowerUp::Magnet));
+        if magnet {
+            self.pull_food(delta);
+        }
+    }
+
+    // moves food within reach of a magnet a tile closer to the head, along
+    // the shortest way around whatever is in between
+    fn pull_food(&mut self, delta: &mut TickDelta) {
+        let from = match self.food {
+            Some(at) => at,
+            None => return,
+        };
+        let mut scratch =
+            ::std::mem::replace(&mut self.scratch, Scratch::new());
+        self.reach_from_head(MAGNET_RADIUS, &mut scratch);
+        let closer = self.closer_to_head(from, &scratch.tiles);
+        self.scratch = scratch;
+
+        if let Some(to) = closer {
+            let moved = WorldUpdate::FoodMoved { from, to };
+            self.apply(&moved);
+            delta.push(moved);
+        }
+    }
+
+    // an empty tile next to `from` that is fewer moves away from the head,
+    // as `distances` from `reach_from_head` count them
+    fn closer_to_head(
+        &self,
+        from: Coordinate,
+        distances: &[(Coordinate, u32)],
+    ) -> Option<Coordinate> {
+        let distance = |at: Coordinate| {
+            distances
+                .iter()
+                .find(|&&(tile, _)| tile == at)
+                .map(|&(_, moves)| moves)
+        };
+
+        // food next to the head stays for the snake to eat
+        let moves = match distance(from) {
+            Some(moves) if moves > 1 => moves,
+            _ => return None,
+        };
+        self.neighbors(from).find(|&at| {
+            self.grid[at] == Block::Empty
+                && distance(at).map_or(false, |d| d < moves)
+        })
+    }
+
+    // fills `scratch.tiles` with the tiles the head reaches in up to
+    // `radius` moves over empty tiles and food, each with the fewest moves
+    // it takes
+    fn reach_from_head(&self, radius: u32, scratch: &mut Scratch) {
+        let reached = &mut scratch.tiles;
+        let frontier = &mut scratch.frontier;
+        reached.clear();
+        frontier.clear();
+        let head = self.snakes[0].head;
+        reached.push((head, 0));
+        frontier.push_back((head, 0));
+
+        while let Some((at, moves)) = frontier.pop_front() {
+            if moves >= radius {
+                continue;
+            }
+            for next in self.neighbors(at) {
+                let open = match self.grid[next] {
+                    Block::Empty | Block::Food => true,
+                    _ => false,
+                };
+                if open && reached.iter().all(|&(tile, _)| tile != next) {
+                    reached.push((next, moves + 1));
+                    frontier.push_back((next, moves + 1));
+                }
+            }
+        }
+    }
+
+    // the tiles next to `at` on the board
+    fn neighbors<'b>(
+        &'b self,
+        at: Coordinate,
+    ) -> impl Iterator<Item = Coordinate> + 'b {
+        DIRECTIONS.iter().filter_map(move |&dir| self.ahead(at, dir))
+    }
+
+    // the tile a move `dir` from `at` lands on, none off an edge that
+    // doesn't wrap by the rules
+    fn ahead(&self, at: Coordinate, dir: Direction) -> Option<Coordinate> {
+        let moved = at.move_towards(dir);
+        if self.rules.wrap {
+            moved.inside::<BB>(&self.grid)
+        } else {
+            moved.inside::<Bounding>(&self.grid)
+        }
+    }
+
+    // buffers a turn for the next move, `InvalidInput` if it is rejected
+    fn queue_turn(&mut self, dir: Direction) -> Result<Option<WorldUpdate>> {
+        let heading = self
+            .get_block(self.mover().head)
+            .snake_or_err(UpdateError::HeadDetached)?;
+
+        let snake = self.mover_mut();
+        let rejected = snake.input.push(heading, dir).err();
+        if rejected.is_none() {
+            snake.veer = None;
+        }
+        Ok(rejected.map(|InvalidInput(dir)| WorldUpdate::InvalidInput(dir)))
+    }
+
+    fn step(&mut self, cmd: Option<Direction>) -> Result<Option<WorldUpdate>> {
+        let rejected = match cmd {
+            Some(dir) => self.queue_turn(dir)?,
+            None => None,
+        };
+
+        let update = match self.pending.pop_front() {
+            Some(update) => update,
+            None => {
+                let update = match self.mover().state {
+                    SnakeState::Eaten => self.motion()?,
+                    SnakeState::Consuming(block) => self.digest(block)?,
+                };
+                self.apply(&update);
+                if let WorldUpdate::FoodEaten { .. } = update {
+                    self.grow();
+                }
+                update
+            }
+        };
+
+        // reported on the next step, once for a reverse key held down, which
+        // doesn't stop the snake
+        if let Some(rejected) = rejected {
+            let reported = |u: &WorldUpdate| match *u {
+                WorldUpdate::InvalidInput(_) => true,
+                _ => false,
+            };
+            if !reported(&update) && !self.pending.iter().any(reported) {
+                let _ = self.pending.push_back(rejected);
+            }
+        }
+        Ok(Some(update))
+    }
+
+    // the move of the head, or it stopping short of a crash
+    fn motion(&mut self) -> Result<WorldUpdate> {
+        let neck = self.mover().head;
+        let heading = self
+            .get_block(neck)
+            .snake_or_err(UpdateError::HeadDetached)?;
+        let half = self.mover_mut().half.take();
+        let next = match half {
+            Some(half) => Some(half),
+            None => self.next_turn(heading),
+        };
+        let turned = match next {
+            Some(dir) => {
+                let into_neck = neck
+                    .move_towards(dir)
+                    .inside::<BB>(&self.grid)
+                    .map_or(false, |at| self.is_neck(at));
+                turn(heading, dir, into_neck, self.rules.allow_reverse)
+            }
+            None => None,
+        };
+        let dir = turned.unwrap_or(heading);
+        let ahead = self.ahead(neck, dir);
+
+        let shielded = self.lives.map_or(false, |l| l.is_shielded());
+        let snake = self.mover().id;
+        if shielded || (self.grace && !self.mover().teetering) {
+            let enters = |at| self.collide(at).0.is_ok();
+            let crashes = !ahead.map_or(false, enters);
+            if crashes {
+                // and doesn't go on with a diagonal move
+                self.mover_mut().half = None;
+                debug!("teetering at {:?}", neck);
+                return Ok(WorldUpdate::Teetering { snake, at: neck, dir });
+            }
+        }
+
+        let at = ahead.ok_or(UpdateError::OutOfBound)?;
+        let (entered, struck) = self.collide(at);
+        self.struck = struck;
+        entered?;
+        Ok(match turned {
+            Some(to) => WorldUpdate::Bend {
+                snake,
+                at,
+                corner: neck,
+                from: heading,
+                to,
+            },
+            None => WorldUpdate::HeadMoved { snake, at, neck, dir },
+        })
+    }
+
+    // whether the mover's head can move onto `at`, and the snake it kills
+    // there if any: running into another snake is settled by `Versus`,
+    // except into the body of a dead one, which is in the way like any
+    fn collide(&self, at: Coordinate) -> (Result<()>, Option<SnakeId>) {
+        let (block, ghost) = (self.get_block(at), self.rules.ghost);
+        let mover = self.mover();
+        let other = match block.owner() {
+            Some(other) if other != mover.id && !ghost => {
+                &self.snakes[other.0 as usize]
+            }
+            _ => return (entering(block, ghost).map(|_| ()), None),
+        };
+        if !other.alive {
+            return (Err(UpdateError::CollideBody), None);
+        }
+
+        // its tail moves on too, unless it is growing
+        let mut body = mover.body.iter();
+        if mover.owed == 0 {
+            body.next_back();
+        }
+        let mut other_body = other.body.iter();
+        other_body.next();
+        let contenders = [
+            Contender {
+                id: mover.id,
+                team: mover.team,
+                head: at,
+                body,
+            },
+            Contender {
+                id: other.id,
+                team: other.team,
+                head: other.head,
+                body: other_body,
+            },
+        ];
+
+        let (mut died, mut struck) = (false, None);
+        for dead in self.versus.resolve(&contenders) {
+            if dead == mover.id {
+                died = true;
+            } else {
+                struck = Some(dead);
+            }
+        }
+        if died {
+            (Err(UpdateError::CollideBody), struck)
+        } else {
+            (Ok(()), struck)
+        }
+    }
+
+    // the turn of the snake heading `heading` on its next move: the one
+    // queued for it, or half of the diagonal it heads along, the half
+    // across `heading` first so that it never runs into its neck, with the
+    // other half left for the same tick
+    fn next_turn(&mut self, heading: Direction) -> Option<Direction> {
+        let snake = self.mover_mut();
+        if let Some(dir) = snake.veer.take() {
+            snake.diagonal = Some(dir);
+        } else if let Some(dir) = snake.input.pop() {
+            snake.diagonal = None;
+            return Some(dir);
+        }
+
+        let (a, b) = match snake.diagonal.map(Direction8::parts) {
+            Some((a, Some(b))) => (a, b),
+            _ => return None,
+        };
+        let (first, second) = if a.axis() == heading.axis() {
+            (b, a)
+        } else {
+            (a, b)
+        };
+        snake.half = Some(second);
+        Some(first)
+    }
+
+    // what follows the move onto `block`: the tail, or growing for food
+    fn digest(&mut self, block: Block) -> Result<WorldUpdate> {
+        let (snake, at) = (self.mover().id, self.mover().head);
+        match entering(block, self.rules.ghost)? {
+            Block::Food => Ok(WorldUpdate::FoodEaten { snake, at }),
+            block => {
+                self.cross(block);
+
+                // the tail stays put while the snake grows
+                if self.mover().owed > 0 {
+                    let length = self.mover().length + 1;
+                    return Ok(WorldUpdate::Grew { snake, length });
+                }
+
+                let tail = self.mover().tail;
+                let tail_dir = self
+                    .get_block(tail)
+                    .snake_or_err(UpdateError::TailDetached)?;
+                tail.move_towards(tail_dir)
+                    .inside::<BB>(&self.grid)
+                    .ok_or(UpdateError::OutOfBound)?;
+
+                Ok(WorldUpdate::TailRetracted {
+                    snake,
+                    at: tail,
+                    dir: tail_dir,
+                })
+            }
+        }
+    }
+
+    // the snake a tile longer for the food it ate and new food, on the next
+    // steps
+    fn grow(&mut self) {
+        let (snake, length) = (self.mover().id, self.mover().length + 1);
+        self.emit(WorldUpdate::Grew { snake, length });
+
+        match self.spawn_food() {
+            Some(at) => {
+                let spawned = WorldUpdate::FoodSpawned { at };
+                let _ = self.pending.push_back(spawned);
+            }
+            // nowhere left to go, for the other snakes to crash
+            None if self.mover != 0 => {}
+            None => {
+                info!("the snake fills the board");
+                self.emit(WorldUpdate::Won);
+            }
+        }
+    }
+
+    // what the head crossed on its way, besides food, on the next step
+    fn cross(&mut self, block: Block) {
+        // the others knock pickups and checkpoints off the board
+        if self.mover != 0 {
+            if let (Block::PowerUp(_), Some(power_ups)) =
+                (block, self.power_ups.as_mut())
+            {
+                power_ups.lose_pickup();
+            }
+            return;
+        }
+
+        let at = self.mover().head;
+        let crossed = match block {
+            Block::Checkpoint => {
+                debug!("checkpoint reached at {:?}", at);
+                WorldUpdate::CheckpointReached { at }
+            }
+            Block::PowerUp(power_up) => {
+                debug!("{:?} taken at {:?}", power_up, at);
+                WorldUpdate::PowerUpTaken { at, power_up }
+            }
+            _ => return,
+        };
+        self.emit(crossed);
+    }
+
+    // applies `update` now and reports it on the next step
+    fn emit(&mut self, update: WorldUpdate) {
+        self.apply(&update);
+        let _ = self.pending.push_back(update);
+    }
+
+    // `None` once the snake fills the board
+    fn spawn_food(&mut self) -> Option<Coordinate> {
+        let head = self.snakes[0].head;
+        let preferred = self.spawn.spawn(&self.grid, head, &mut self.rng);
+
+        let coord = match preferred {
+            Some(coord) if self.occupancy.is_empty_at(coord) => coord,
+            _ => self.occupancy.random(&mut self.rng)?,
+        };
+        debug_assert_eq!(self.grid[coord], Block::Empty);
+
+        self.apply(&WorldUpdate::FoodSpawned { at: coord });
+        trace!("food spawned at {:?}", coord);
+        Some(coord)
+    }
+
+    // a crash with a life to spare puts the snake back at the start, which
+    // the game reloads the board for
+    fn lose_life(&mut self, err: UpdateError) -> UpdateError {
+        match self.lives.as_mut() {
+            Some(lives) if err.is_crash() && lives.lose() => {
+                info!("life lost, {} left", lives.left());
+            }
+            _ => return err,
+        }
+
+        self.respawn();
+        UpdateError::LifeLost
+    }
+
+    // clears the snake off the board and puts it back where it started,
+    // the rest of the board stays as it is
+    fn respawn(&mut self) {
+        let body =
+            ::std::mem::replace(&mut self.snakes[0].body, SnakeBody::new());
+        for &at in &body {
+            self.vacate(at);
+        }
+        self.snakes[0].body = body;
+
+        self.snakes[0].input.clear();
+        self.pending.clear();
+        self.snakes[0].teetering = false;
+        self.place_snake(0);
+        for i in 1..self.snakes.len() {
+            let ally = self.snakes[i].team == self.snakes[0].team;
+            if ally && !self.snakes[i].alive {
+                self.revive(i);
+            }
+        }
+
+        // food under the snake now spawns again
+        if self.food.map_or(false, |at| self.grid[at] != Block::Food) {
+            self.food = None;
+        }
+        self.respawned = true;
+    }
+
+    fn reset(&mut self) {
+        // the game carries on after a respawn
+        if self.respawned {
+            return;
+        }
+
+        debug!("resetting world");
+        // in the memory of the last game
+        let (width, height) = (self.grid.width(), self.grid.height());
+        self.grid.reset(width, height);
+        self.occupancy.reset(&self.grid);
+        for snake in &mut self.snakes {
+            snake.input.clear();
+            snake.teetering = false;
+        }
+        self.pending.clear();
+        self.paused = false;
+        self.dying = None;
+        self.food = None;
+        if let Some(arena) = self.arena.as_mut() {
+            arena.reset();
+        }
+        if let Some(countdown) = self.countdown.as_mut() {
+            countdown.reset();
+        }
+        if let Some(lives) = self.lives.as_mut() {
+            lives.reset();
+        }
+        if let Some(hazards) = self.hazards.as_mut() {
+            hazards.reset();
+        }
+        if let Some(lasers) = self.lasers.as_mut() {
+            lasers.reset();
+        }
+        if let Some(power_ups) = self.power_ups.as_mut() {
+            power_ups.reset();
+        }
+
+        for i in 0..self.snakes.len() {
+            self.place_snake(i);
+        }
+        self.place_checkpoints();
+    }
+
+    // a tile the snake left, still looming if a hazard is on its way there
+    fn vacate(&mut self, at: Coordinate) {
+        let looming = self.hazards.as_ref().map_or(false, |h| h.is_looming(at));
+        if looming {
+            self.set_block(at, Block::Looming);
+        } else {
+            self.set_block(at, Block::Empty);
+        }
+    }
+
+    fn place_checkpoints(&mut self) {
+        let checkpoints =
+            ::std::mem::replace(&mut self.checkpoints, Vec::new());
+        for &at in &checkpoints {
+            if self.grid.get(at) == Some(&Block::Empty) {
+                self.set_block(at, Block::Checkpoint);
+            }
+        }
+        self.checkpoints = checkpoints;
+    }
+
+    // puts the dead snake at `i` back where it started, what is left of
+    // its body gone, unless another snake is in the way there
+    fn revive(&mut self, i: usize) {
+        let body =
+            ::std::mem::replace(&mut self.snakes[i].body, SnakeBody::new());
+        for &at in &body {
+            self.vacate(at);
+        }
+        self.snakes[i].body = body;
+
+        let free = self.snakes[i].start.iter().all(|&(at, _)| {
+            self.grid.get(at).map_or(false, |block| block.owner().is_none())
+        });
+        if free {
+            self.snakes[i].input.clear();
+            self.snakes[i].teetering = false;
+            self.place_snake(i);
+        }
+    }
+
+    fn place_snake(&mut self, i: usize) {
+        let start = ::std::mem::replace(&mut self.snakes[i].start, Vec::new());
+        let id = self.snakes[i].id;
+        for &(at, dir) in &start {
+            self.set_block(at, Block::Snake(dir, id));
+        }
+
+        let snake = &mut self.snakes[i];
+        snake.tail = start[0].0;
+        snake.head = start[start.len() - 1].0;
+        snake.alive = true;
+        snake.state = SnakeState::Eaten;
+        snake.length = start.len() as u32;
+        snake.owed = 0;
+        snake.eaten = 0;
+        snake.diagonal = None;
+        snake.veer = None;
+        snake.half = None;
+        // tail first, like collecting it
+        snake.body.clear();
+        for &(at, _) in &start {
+            snake.body.push_head(at);
+        }
+        snake.start = start;
+    }
+
+    fn is_neck(&self, coord: Coordinate) -> bool {
+        self.mover().body.iter().nth(1) == Some(&coord)
+    }
+
+    #[inline(always)]
+    fn get_block(&self, coord: Coordinate) -> Block {
+        self.grid[coord]
+    }
+
+    #[inline(always)]
+    fn set_block<B: Into<Block>>(&mut self, coord: Coordinate, b: B) {
+        let block = b.into();
+        self.grid[coord] = block;
+        self.occupancy.update(coord, block);
+    }
+}
+
+pub struct SnakeIter<'a, BB: BoundingBehavior> {
+    grid: &'a Grid,
+    at: Coordinate,
+
+    _bounding_behavior: PhantomData<BB>,
+}
+
+impl<'a, BB: BoundingBehavior> SnakeIter<'a, BB> {
+    pub fn new(grid: &'a Grid, at: Coordinate) -> Self {
+        SnakeIter {
+            grid,
+            at,
+            _bounding_behavior: PhantomData,
+        }
+    }
+}
+
+impl<'a, BB: BoundingBehavior> Iterator for SnakeIter<'a, BB> {
+    type Item = (Coordinate, Direction);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let block = self.grid[self.at];
+
+        match block.snake() {
+            Some(dir) => {
+                let current = self.at;
+                let next =
+                    current.move_towards(dir).inside::<BB>(&self.grid)?;
+
+                self.at = next;
+
+                Some((current, dir))
+            }
+            _ => None,
+        }
+    }
+}
+
+pub enum Initializer<'a, R, BB: BoundingBehavior> {
+    // no food when the snake fills the board from the start
+    WorldSize(&'a World<R, BB>, Option<Coordinate>),
+    Respawned(&'a World<R, BB>, Option<Coordinate>),
+    Clock(&'a World<R, BB>, Option<Coordinate>),
+    Lives(&'a World<R, BB>, Option<Coordinate>),
+    // the rings a restored arena had closed, up to the given inset
+    Arena(&'a World<R, BB>, SmallNat, Option<Coordinate>),
+    // the checkpoints not crossed yet, from the given index
+    Checkpoints(&'a World<R, BB>, usize, Option<Coordinate>),
+    // the hazards raised or looming, from the given index
+    Hazards(&'a World<R, BB>, usize, Option<Coordinate>),
+    PowerUp(&'a World<R, BB>, Option<Coordinate>),
+    FoodAt(&'a World<R, BB>, Option<Coordinate>),
+    // the tiles of the snake at the given index left, tail first
+    Bodies(&'a World<R, BB>, usize, Rev<vec_deque::Iter<'a, Coordinate>>),
+    Done,
+}
+
+impl<'a, R, BB: BoundingBehavior> Initializer<'a, R, BB> {
+    // the body of the snake at `i`, tail first
+    fn bodies(world: &'a World<R, BB>, i: usize) -> Self {
+        Initializer::Bodies(world, i, world.snakes[i].body.iter().rev())
+    }
+}
+
+impl<'a, R: Rng, BB: BoundingBehavior> Iterator for Initializer<'a, R, BB> {
+    type Item = WorldUpdate;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match ::std::mem::replace(self, Initializer::Done) {
+            Initializer::Done => None,
+            Initializer::WorldSize(world, at) => {
+                *self = Initializer::Clock(world, at);
+
+                Some(WorldUpdate::LevelLoaded {
+                    width: world.grid.width(),
+                    height: world.grid.height(),
+                })
+            }
+            Initializer::Respawned(world, at) => {
+                *self = Initializer::Clock(world, at);
+
+                Some(WorldUpdate::Respawned {
+                    width: world.grid.width(),
+                    height: world.grid.height(),
+                })
+            }
+            Initializer::Clock(world, at) => {
+                *self = Initializer::Lives(world, at);
+
+                match world.countdown {
+                    Some(countdown) => Some(WorldUpdate::Clock {
+                        left: countdown.left(),
+                    }),
+                    None => self.next(),
+                }
+            }
+            Initializer::Lives(world, at) => {
+                *self = Initializer::Arena(world, 0, at);
+
+                match world.lives {
+                    Some(lives) => Some(WorldUpdate::Lives {
+                        left: lives.left(),
+                    }),
+                    None => self.next(),
+                }
+            }
+            Initializer::Arena(world, inset, at) => {
+                let closed = world.arena.map_or(0, |arena| arena.closed());
+                if inset >= closed {
+                    *self = Initializer::Checkpoints(world, 0, at);
+                    return self.next();
+                }
+
+                *self = Initializer::Arena(world, inset + 1, at);
+                Some(WorldUpdate::ArenaShrunk {
+                    inset,
+                    width: world.grid.width(),
+                    height: world.grid.height(),
+                })
+            }
+            Initializer::Checkpoints(world, index, food_at) => {
+                let next = world
+                    .checkpoints
+                    .iter()
+                    .enumerate()
+                    .skip(index)
+                    .find(|&(_, &at)| world.grid[at] == Block::Checkpoint);
+
+                match next {
+                    Some((i, &at)) => {
+                        *self = Initializer::Checkpoints(world, i + 1, food_at);
+                        Some(WorldUpdate::SetBlock {
+                            block: Block::Checkpoint,
+                            at,
+                        })
+                    }
+                    None => {
+                        *self = Initializer::Hazards(world, 0, food_at);
+                        self.next()
+                    }
+                }
+            }
+            Initializer::Hazards(world, index, food_at) => {
+                let next = world
+                    .hazards
+                    .iter()
+                    .flat_map(|hazards| hazards.tiles())
+                    .enumerate()
+                    .skip(index)
+                    .find(|&(_, at)| match world.grid[at] {
+                        Block::Obstacle | Block::Looming => true,
+                        _ => false,
+                    });
+
+                match next {
+                    Some((i, at)) => {
+                        *self = Initializer::Hazards(world, i + 1, food_at);
+                        Some(WorldUpdate::SetBlock {
+                            block: world.grid[at],
+                            at,
+                        })
+                    }
+                    None => {
+                        *self = Initializer::PowerUp(world, food_at);
+                        self.next()
+                    }
+                }
+            }
+            Initializer::PowerUp(world, food_at) => {
+                *self = Initializer::FoodAt(world, food_at);
+
+                match world.power_ups.and_then(|p| p.pickup()) {
+                    Some((at, power_up)) => Some(WorldUpdate::SetBlock {
+                        block: Block::PowerUp(power_up),
+                        at,
+                    }),
+                    None => self.next(),
+                }
+            }
+            Initializer::FoodAt(world, Some(at)) => {
+                *self = Initializer::bodies(world, 0);
+
+                Some(WorldUpdate::SetBlock {
+                    block: Block::Food,
+                    at,
+                })
+            }
+            Initializer::FoodAt(world, None) => {
+                *self = Initializer::bodies(world, 0);
+                self.next()
+            }
+            Initializer::Bodies(world, i, mut tiles) => match tiles.next() {
+                Some(&at) => {
+                    *self = Initializer::Bodies(world, i, tiles);
+                    Some(WorldUpdate::SetBlock {
+                        block: world.grid[at],
+                        at,
+                    })
+                }
+                None if i + 1 < world.snakes.len() => {
+                    *self = Initializer::bodies(world, i + 1);
+                    self.next()
+                }
+                None => None,
+            },
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use alloc::vec::Vec;
+
+use rand::Rng;
+
+use data::{Block, Coordinate, Grid};
+
+// slot of the tiles which aren't empty
+const TAKEN: u32 = ::std::u32::MAX;
+
+/// Index of the empty tiles of a board, kept up to date by the world as it
+/// sets blocks, so that food goes on a tile known to be empty in constant
+/// time however crowded the board gets
+#[derive(Clone)]
+pub struct Occupancy {
+    empty: Vec<Coordinate>,
+    // position of each tile in `empty`
+    slots: Grid<u32>,
+}
+
+impl Occupancy {
+    pub fn new(grid: &Grid) -> Self {
+        let mut occupancy = Occupancy {
+            empty: Vec::new(),
+            slots: Grid::filled(1, 1, TAKEN),
+        };
+        occupancy.reset(grid);
+        occupancy
+    }
+
+    /// Indexes `grid` over, in the memory of the board indexed before
+    pub fn reset(&mut self, grid: &Grid) {
+        self.empty.clear();
+        self.slots.refill(grid.width(), grid.height(), TAKEN);
+
+        for (at, &block) in grid.iter() {
+            self.update(at, block);
+        }
+    }
+
+    pub fn empty_count(&self) -> usize {
+        self.empty.len()
+    }
+
+    /// No empty tile is left, the snake fills the board
+    pub fn is_full(&self) -> bool {
+        self.empty.is_empty()
+    }
+
+    pub fn is_empty_at(&self, at: Coordinate) -> bool {
+        self.slots.get(at).map_or(false, |slot| *slot != TAKEN)
+    }
+
+    /// Records `block` having been set at `at`
+    pub fn update(&mut self, at: Coordinate, block: Block) {
+        match (block == Block::Empty, self.is_empty_at(at)) {
+            (true, false) => self.insert(at),
+            (false, true) => self.remove(at),
+            _ => {}
+        }
+    }
+
+    /// Any empty tile, with equal odds
+    pub fn random<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<Coordinate> {
+        if self.is_full() {
+            None
+        } else {
+            Some(self.empty[rng.gen_range(0, self.empty.len())])
+        }
+    }
+
+    fn insert(&mut self, at: Coordinate) {
+        if let Some(slot) = self.slots.get_mut(at) {
+            *slot = self.empty.len() as u32;
+            self.empty.push(at);
+        }
+    }
+
+    fn remove(&mut self, at: Coordinate) {
+        let slot = match self.slots.get_mut(at) {
+            Some(slot) => ::std::mem::replace(slot, TAKEN) as usize,
+            None => return,
+        };
+
+        self.empty.swap_remove(slot);
+        let moved = self.empty.get(slot).cloned();
+        if let Some(moved) = moved.and_then(|at| self.slots.get_mut(at)) {
+            *moved = slot as u32;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data::Direction;
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_tracks_empty_tiles() {
+        let mut grid = Grid::empty(4, 3);
+        let head = Coordinate { x: 1, y: 2 };
+        grid[head] = Block::Food;
+
+        let mut occupancy = Occupancy::new(&grid);
+        assert_eq!(occupancy.empty_count(), 11);
+        assert!(!occupancy.is_empty_at(head));
+
+        let corner = Coordinate { x: 0, y: 0 };
+        occupancy.update(corner, Block::from(Direction::East));
+        occupancy.update(head, Block::Empty);
+        assert_eq!(occupancy.empty_count(), 11);
+        assert!(occupancy.is_empty_at(head));
+        assert!(!occupancy.is_empty_at(corner));
+
+        let mut rng = SmallRng::from_seed([3; 16]);
+        for _ in 0..100 {
+            let at = occupancy.random(&mut rng).unwrap();
+            assert!(occupancy.is_empty_at(at));
+        }
+    }
+}
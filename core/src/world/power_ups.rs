@@ -0,0 +1,105 @@
+use data::{Coordinate, PowerUp};
+
+/// The power-ups of a game: once `every` ticks go by without a pickup on
+/// the board one shows up, and the snake crossing it turns its `PowerUp`
+/// on for `duration` ticks, one at a time
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct PowerUps {
+    every: u32,
+    duration: u32,
+    ticks: u32,
+    pickup: Option<(Coordinate, PowerUp)>,
+    // the power-up on and the ticks it has left
+    active: Option<(PowerUp, u32)>,
+}
+
+impl PowerUps {
+    pub fn new(every: u32, duration: u32) -> Self {
+        PowerUps {
+            every: ::std::cmp::max(1, every),
+            duration,
+            ticks: 0,
+            pickup: None,
+            active: None,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.ticks = 0;
+        self.pickup = None;
+        self.active = None;
+    }
+
+    /// The pickup on the board, if any
+    pub fn pickup(&self) -> Option<(Coordinate, PowerUp)> {
+        self.pickup
+    }
+
+    pub fn is_active(&self, power_up: PowerUp) -> bool {
+        self.active.map_or(false, |(on, _)| on == power_up)
+    }
+
+    /// Counts a tick without a pickup on the board, whether it is time for
+    /// a new one
+    pub fn due(&mut self) -> bool {
+        if self.pickup.is_some() {
+            return false;
+        }
+
+        self.ticks += 1;
+        if self.ticks < self.every {
+            return false;
+        }
+        self.ticks = 0;
+        true
+    }
+
+    /// Counts a tick off the power-up on, the one that ran out if any
+    pub fn count_down(&mut self) -> Option<PowerUp> {
+        let (power_up, left) = self.active?;
+        if left > 1 {
+            self.active = Some((power_up, left - 1));
+            return None;
+        }
+        self.active = None;
+        Some(power_up)
+    }
+
+    pub fn place(&mut self, at: Coordinate, power_up: PowerUp) {
+        self.pickup = Some((at, power_up));
+    }
+
+    /// The pickup went under a wall, another one is due in `every` ticks
+    pub fn lose_pickup(&mut self) {
+        self.pickup = None;
+    }
+
+    /// Turns on the pickup the snake crossed, in place of any other
+    pub fn take(&mut self, power_up: PowerUp) {
+        self.pickup = None;
+        self.active = Some((power_up, self.duration));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_power_up_runs_out() {
+        let mut power_ups = PowerUps::new(2, 2);
+
+        assert!(!power_ups.due());
+        assert!(power_ups.due());
+        power_ups.place(Coordinate { x: 1, y: 1 }, PowerUp::Magnet);
+        // no other pickup while this one is on the board
+        assert!(!power_ups.due());
+        assert!(!power_ups.due());
+
+        power_ups.take(PowerUp::Magnet);
+        assert!(power_ups.is_active(PowerUp::Magnet));
+        assert_eq!(power_ups.count_down(), None);
+        assert_eq!(power_ups.count_down(), Some(PowerUp::Magnet));
+        assert!(!power_ups.is_active(PowerUp::Magnet));
+    }
+}
@@ -0,0 +1,145 @@
+use system::Tick;
+
+/// The mechanics of a game that can be turned on or off, fixed for the
+/// life of a `World` (see `WorldBuilder::rules`). Replays record them, so
+/// a game plays back under the rules it was played with.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "config", derive(Deserialize))]
+#[cfg_attr(feature = "config", serde(default, deny_unknown_fields))]
+pub struct Rules {
+    /// The snake leaves the board on one edge and comes back on the other;
+    /// without it the edges are walls, as they always are on a `Bounding`
+    /// board
+    pub wrap: bool,
+    /// A turn straight back is taken, rather than rejected as
+    /// `InvalidInput`, and the snake runs into its neck
+    pub allow_reverse: bool,
+    /// The snake also heads along diagonals, on `Command::Diagonal`: each
+    /// of its moves then goes a tile along both axes, one after the other
+    pub diagonal: bool,
+    /// The snake passes over its own body rather than crashing into it
+    pub ghost: bool,
+    /// Tiles the snake grows by for each food, 0 counts as 1
+    pub growth: u8,
+    /// Ticks from one move of the snake to the next at the start of a game,
+    /// on top of its `Cadence`; 0 counts as 1
+    pub pace: u8,
+    /// Food the snake eats for each tick its moves come sooner, down to one
+    /// on every tick; 0 keeps the pace it started with
+    pub speed_up: u8,
+}
+
+// bits of the first byte of `Rules::to_bytes`
+const WRAP: u8 = 1;
+const ALLOW_REVERSE: u8 = 1 << 1;
+const DIAGONAL: u8 = 1 << 2;
+const GHOST: u8 = 1 << 3;
+
+impl Rules {
+    /// Bytes of `to_bytes`
+    pub const LEN: usize = 4;
+
+    pub fn growth(&self) -> u32 {
+        ::std::cmp::max(1, u32::from(self.growth))
+    }
+
+    /// Ticks from one move of the snake to the next once it has eaten
+    /// `eaten` food, along the speed curve of `pace` and `speed_up`
+    pub fn pace(&self, eaten: u32) -> Tick {
+        let pace = ::std::cmp::max(1, Tick::from(self.pace));
+        let sooner = match self.speed_up {
+            0 => 0,
+            speed_up => eaten / u32::from(speed_up),
+        };
+        ::std::cmp::max(1, pace.saturating_sub(sooner))
+    }
+
+    pub fn to_bytes(&self) -> [u8; Rules::LEN] {
+        let mut flags = 0;
+        if self.wrap {
+            flags |= WRAP;
+        }
+        if self.allow_reverse {
+            flags |= ALLOW_REVERSE;
+        }
+        if self.diagonal {
+            flags |= DIAGONAL;
+        }
+        if self.ghost {
+            flags |= GHOST;
+        }
+        [flags, self.growth, self.pace, self.speed_up]
+    }
+
+    /// The rules of `to_bytes`, none with flags this version doesn't know
+    pub fn from_bytes(bytes: [u8; Rules::LEN]) -> Option<Self> {
+        let flags = bytes[0];
+        if flags & !(WRAP | ALLOW_REVERSE | DIAGONAL | GHOST) != 0 {
+            return None;
+        }
+
+        Some(Rules {
+            wrap: flags & WRAP != 0,
+            allow_reverse: flags & ALLOW_REVERSE != 0,
+            diagonal: flags & DIAGONAL != 0,
+            ghost: flags & GHOST != 0,
+            growth: bytes[1],
+            pace: bytes[2],
+            speed_up: bytes[3],
+        })
+    }
+}
+
+// the classic game: wrapping edges, four ways to go and no turning back,
+// crashing into the body, a tile of growth per food and a move on every
+// tick throughout
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            wrap: true,
+            allow_reverse: false,
+            diagonal: false,
+            ghost: false,
+            growth: 1,
+            pace: 1,
+            speed_up: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_rules_round_trip() {
+        let rules = Rules {
+            wrap: false,
+            allow_reverse: true,
+            diagonal: true,
+            ghost: true,
+            growth: 3,
+            pace: 4,
+            speed_up: 2,
+        };
+        assert_eq!(Rules::from_bytes(rules.to_bytes()), Some(rules));
+        assert_eq!(Rules::from_bytes([1 << 7, 1, 1, 0]), None);
+        assert_eq!(Rules { growth: 0, ..rules }.growth(), 1);
+    }
+
+    #[test]
+    fn test_snake_speeds_up_along_the_curve() {
+        let rules = Rules {
+            pace: 4,
+            speed_up: 2,
+            ..Rules::default()
+        };
+        let paces: Vec<_> = (0..8).map(|eaten| rules.pace(eaten)).collect();
+        assert_eq!(paces, vec![4, 4, 3, 3, 2, 2, 1, 1]);
+        assert_eq!(rules.pace(100), 1);
+
+        assert_eq!(Rules::default().pace(100), 1);
+        assert_eq!(Rules { pace: 0, ..rules }.pace(0), 1);
+    }
+}
@@ -0,0 +1,23 @@
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+
+use data::Coordinate;
+
+/// Buffers a `World` reuses from tick to tick instead of allocating new
+/// ones, which only grow until they fit what the game needs. None of it
+/// outlives the step that fills it, so it isn't part of a `Snapshot`.
+#[derive(Debug, Clone, Default)]
+pub struct Scratch {
+    /// Tiles with a count each: the looming hazards with the ticks of
+    /// warning they have left, or the tiles the head reaches with the moves
+    /// it takes
+    pub tiles: Vec<(Coordinate, u32)>,
+    /// Tiles still to search from, in the order they were reached
+    pub frontier: VecDeque<(Coordinate, u32)>,
+}
+
+impl Scratch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
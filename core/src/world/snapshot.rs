@@ -0,0 +1,104 @@
+use alloc::vec::Vec;
+
+use data::{BoundingBehavior, Coordinate, Grid, PLAYER};
+use history::Rewind;
+
+use super::{
+    Arena, Countdown, Hazards, Lasers, Lives, Occupancy, PendingUpdates,
+    PowerUps, Snake, SnakeBody, UpdateError, World, WorldView,
+};
+use system::Tick;
+
+/// Everything about a `World` that changes while it is played, as it was at
+/// the start of a tick. The spawn policy isn't part of it, a `Scripted` one
+/// carries on with its script after a `restore`.
+#[derive(Clone)]
+pub struct Snapshot<R> {
+    grid: Grid,
+    occupancy: Occupancy,
+    snakes: Vec<Snake>,
+    food: Option<Coordinate>,
+    paused: bool,
+    rng: R,
+    arena: Option<Arena>,
+    countdown: Option<Countdown>,
+    lives: Option<Lives>,
+    hazards: Option<Hazards>,
+    lasers: Option<Lasers>,
+    power_ups: Option<PowerUps>,
+    pending: PendingUpdates,
+    dying: Option<UpdateError>,
+    // only for `view`, the world steps on from the tick it is given
+    now: Tick,
+    wraps: bool,
+}
+
+impl<R> Snapshot<R> {
+    /// The world as it was when the snapshot was taken
+    pub fn view(&self) -> WorldView {
+        let (food, now, wraps) = (self.food, self.now, self.wraps);
+        WorldView::new(&self.grid, &self.snakes, PLAYER, food, now, wraps)
+    }
+
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    pub fn head(&self) -> Coordinate {
+        self.snakes[0].head()
+    }
+
+    pub fn body(&self) -> &SnakeBody {
+        self.snakes[0].body()
+    }
+
+    pub fn length(&self) -> u32 {
+        self.snakes[0].length()
+    }
+}
+
+impl<R: Clone, BB: BoundingBehavior> Rewind for World<R, BB> {
+    type Snapshot = Snapshot<R>;
+
+    fn snapshot(&self) -> Snapshot<R> {
+        Snapshot {
+            grid: self.grid.clone(),
+            occupancy: self.occupancy.clone(),
+            snakes: self.snakes.clone(),
+            food: self.food,
+            paused: self.paused,
+            rng: self.rng.clone(),
+            arena: self.arena,
+            countdown: self.countdown,
+            lives: self.lives,
+            hazards: self.hazards.clone(),
+            lasers: self.lasers,
+            power_ups: self.power_ups,
+            pending: self.pending.clone(),
+            dying: self.dying,
+            now: self.now,
+            wraps: self.wraps(),
+        }
+    }
+
+    // the next `initialize` loads the restored board rather than a new one
+    fn restore(&mut self, snapshot: &Snapshot<R>) {
+        let snapshot = snapshot.clone();
+
+        self.grid = snapshot.grid;
+        self.occupancy = snapshot.occupancy;
+        self.snakes = snapshot.snakes;
+        self.food = snapshot.food;
+        self.paused = snapshot.paused;
+        self.rng = snapshot.rng;
+        self.arena = snapshot.arena;
+        self.countdown = snapshot.countdown;
+        self.lives = snapshot.lives;
+        self.hazards = snapshot.hazards;
+        self.lasers = snapshot.lasers;
+        self.power_ups = snapshot.power_ups;
+        self.pending = snapshot.pending;
+        self.dying = snapshot.dying;
+        self.restored = true;
+    }
+}
@@ -0,0 +1,168 @@
+use alloc::vec::Vec;
+
+use rand::{Rng, RngCore};
+
+use data::{Block, Coordinate, Grid, SmallNat};
+
+// rejection sampling gives up on the preferred tiles after this many tries
+const ATTEMPTS: usize = 64;
+
+/// Decides where food shows up, queried by the world whenever food is eaten
+/// (and when a game starts)
+pub trait SpawnPolicy {
+    /// An empty tile for the next food. The world only takes it if its
+    /// `Occupancy` agrees the tile is empty, and falls back to `UniformRandom`
+    /// otherwise or on `None`
+    fn spawn(
+        &mut self,
+        grid: &Grid,
+        head: Coordinate,
+        rng: &mut RngCore,
+    ) -> Option<Coordinate>;
+}
+
+/// Any empty tile, with equal odds: a single random tile, and when that
+/// one is taken the world draws from its `Occupancy` index instead, so that
+/// a crowded board doesn't take ever more tries
+#[derive(Debug, Copy, Clone, Default)]
+pub struct UniformRandom;
+
+impl SpawnPolicy for UniformRandom {
+    fn spawn(
+        &mut self,
+        grid: &Grid,
+        _head: Coordinate,
+        rng: &mut RngCore,
+    ) -> Option<Coordinate> {
+        let coord = grid.random_coordinate(rng);
+        Some(coord).filter(|coord| grid[*coord] == Block::Empty)
+    }
+}
+
+/// An empty tile at least `min_distance` tiles (manhattan) from the head of
+/// the snake, so that food never lands in its mouth
+#[derive(Debug, Copy, Clone)]
+pub struct AwayFromHead {
+    pub min_distance: SmallNat,
+}
+
+impl SpawnPolicy for AwayFromHead {
+    fn spawn(
+        &mut self,
+        grid: &Grid,
+        head: Coordinate,
+        rng: &mut RngCore,
+    ) -> Option<Coordinate> {
+        let min_distance = self.min_distance as u32;
+
+        sample(grid, rng, |coord| {
+            let dx = (coord.x as i32 - head.x as i32).abs() as u32;
+            let dy = (coord.y as i32 - head.y as i32).abs() as u32;
+            dx + dy >= min_distance
+        })
+    }
+}
+
+/// An empty tile within `margin` tiles of the edge of the board
+#[derive(Debug, Copy, Clone)]
+pub struct NearWalls {
+    pub margin: SmallNat,
+}
+
+impl SpawnPolicy for NearWalls {
+    fn spawn(
+        &mut self,
+        grid: &Grid,
+        _head: Coordinate,
+        rng: &mut RngCore,
+    ) -> Option<Coordinate> {
+        let (width, height) = (grid.width(), grid.height());
+        let margin = self.margin;
+
+        sample(grid, rng, |coord| {
+            coord.x < margin
+                || coord.y < margin
+                || coord.x + margin >= width
+                || coord.y + margin >= height
+        })
+    }
+}
+
+/// Food at fixed positions, in order, as tutorial levels and tests want it.
+/// Once the script runs out (or its next tile is taken) the world falls
+/// back to `UniformRandom`.
+#[derive(Debug, Clone)]
+pub struct Scripted {
+    coords: Vec<Coordinate>,
+    next: usize,
+}
+
+impl Scripted {
+    pub fn new(coords: Vec<Coordinate>) -> Self {
+        Scripted { coords, next: 0 }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.next >= self.coords.len()
+    }
+}
+
+impl SpawnPolicy for Scripted {
+    fn spawn(
+        &mut self,
+        grid: &Grid,
+        _head: Coordinate,
+        _rng: &mut RngCore,
+    ) -> Option<Coordinate> {
+        let coord = *self.coords.get(self.next)?;
+        self.next += 1;
+
+        grid.get(coord).filter(|b| **b == Block::Empty).map(|_| coord)
+    }
+}
+
+fn sample<F>(grid: &Grid, rng: &mut RngCore, accept: F) -> Option<Coordinate>
+where
+    F: Fn(Coordinate) -> bool,
+{
+    (0..ATTEMPTS)
+        .map(|_| grid.random_coordinate(rng))
+        .find(|coord| grid[*coord] == Block::Empty && accept(*coord))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_policies_pick_empty_tiles() {
+        let grid = Grid::empty(16, 16);
+        let head = Coordinate { x: 8, y: 8 };
+        let mut rng = SmallRng::from_seed([7; 16]);
+
+        for _ in 0..100 {
+            let mut away = AwayFromHead { min_distance: 6 };
+            if let Some(c) = away.spawn(&grid, head, &mut rng) {
+                let d = (c.x as i32 - 8).abs() + (c.y as i32 - 8).abs();
+                assert!(d >= 6);
+            }
+
+            let mut walls = NearWalls { margin: 2 };
+            if let Some(c) = walls.spawn(&grid, head, &mut rng) {
+                assert!(c.x < 2 || c.y < 2 || c.x >= 14 || c.y >= 14);
+            }
+        }
+
+        let mut scripted =
+            Scripted::new(vec![Coordinate { x: 1, y: 2 }, head]);
+        assert_eq!(
+            scripted.spawn(&grid, head, &mut rng),
+            Some(Coordinate { x: 1, y: 2 })
+        );
+        assert_eq!(scripted.spawn(&grid, head, &mut rng), Some(head));
+        assert!(scripted.is_done());
+        assert_eq!(scripted.spawn(&grid, head, &mut rng), None);
+    }
+}
@@ -0,0 +1,250 @@
+use std::fmt;
+use std::marker::PhantomData;
+
+use arraydeque::ArrayDeque;
+use rand::rngs::SmallRng;
+use rand::{Error, Rng, RngCore, SeedableRng};
+
+use super::{
+    Cadence, Occupancy, Rules, Scratch, Snake, SnakeIter, TeamId,
+    UniformRandom, Versus, World, PLAYER,
+};
+use data::{Block, BoundingBehavior, Coordinate, Direction, Grid};
+
+impl<BB: BoundingBehavior> World<SmallRng, BB> {
+    pub fn from_ascii(string: &str) -> Self {
+        World::from_ascii_with_rng(string, SmallRng::from_seed([123; 16]))
+    }
+}
+
+impl<R: Rng, BB: BoundingBehavior> World<R, BB> {
+    pub fn from_ascii_with_rng(string: &str, rng: R) -> Self {
+        let grid = chars_from_ascii_grid(string)
+            .map(|(coord, c)| match c {
+                '*' => (coord, Block::Food),
+                '#' => (coord, Block::Obstacle),
+                'c' => (coord, Block::Checkpoint),
+                '>' => (coord, Block::from(Direction::East)),
+                '<' => (coord, Block::from(Direction::West)),
+                'v' => (coord, Block::from(Direction::South)),
+                '^' => (coord, Block::from(Direction::North)),
+                _ => (coord, Block::Empty),
+            })
+            .collect();
+
+        let initial_snake: Vec<_>;
+
+        let tail = find_snake_tail::<BB>(&grid).unwrap();
+        {
+            let iter: SnakeIter<BB> = SnakeIter::new(&grid, tail);
+            initial_snake = iter.collect();
+        }
+        let grid_food = grid
+            .iter()
+            .find(|&(_, &block)| block == Block::Food)
+            .map(|(at, _)| at);
+        let checkpoints = chars_from_ascii_grid(string)
+            .filter(|&(_, c)| c == 'c')
+            .map(|(coord, _)| coord)
+            .collect();
+
+        World {
+            occupancy: Occupancy::new(&grid),
+            grid,
+            snakes: vec![Snake::new(PLAYER, TeamId(0), &initial_snake)],
+            mover: 0,
+
+            paused: false,
+            debug: false,
+            grace: false,
+            versus: Versus::default(),
+            struck: None,
+
+            checkpoints,
+            rng,
+            spawn: Box::new(UniformRandom),
+            arena: None,
+            countdown: None,
+            lives: None,
+            hazards: None,
+            lasers: None,
+            power_ups: None,
+            cadence: Cadence::new(),
+            now: 0,
+            rules: Rules::default(),
+
+            pending: ArrayDeque::new(),
+            scratch: Scratch::new(),
+            dying: None,
+            food: grid_food,
+            restored: false,
+            respawned: false,
+
+            _bounding_behavior: PhantomData,
+        }
+    }
+
+    /// The world playing by `rules` rather than the classic ones, like
+    /// `WorldBuilder::rules`
+    pub fn with_rules(mut self, rules: Rules) -> Self {
+        self.rules = rules;
+        self.snakes[0].input.set_allow_reverse(rules.allow_reverse);
+        self
+    }
+}
+
+/// Rng replaying scripted words, `FakeRng::food_at` scripts the positions
+/// food spawns at; it panics once the script runs out
+pub struct FakeRng {
+    words: Vec<u32>,
+    next: usize,
+}
+
+impl FakeRng {
+    pub fn new(words: Vec<u32>) -> Self {
+        FakeRng { words, next: 0 }
+    }
+
+    /// Spawns food at each of `coords` in turn, on a `width` x `height` board
+    pub fn food_at(width: u16, height: u16, coords: &[(u16, u16)]) -> Self {
+        // `gen_range` keeps the high half of `word * range`
+        let word = |n: u16, range: u16| {
+            let range = range as u64;
+            (((n as u64) << 32) + range - 1) / range
+        };
+        let words = coords
+            .iter()
+            .flat_map(|&(x, y)| vec![word(x, width), word(y, height)])
+            .map(|w| w as u32)
+            .collect();
+
+        FakeRng::new(words)
+    }
+}
+
+impl RngCore for FakeRng {
+    fn next_u32(&mut self) -> u32 {
+        let word = *self
+            .words
+            .get(self.next)
+            .expect("FakeRng ran out of scripted words");
+        self.next += 1;
+        word
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let lo = self.next_u32() as u64;
+        let hi = self.next_u32() as u64;
+        (hi << 32) | lo
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(4) {
+            let word = self.next_u32();
+            for (i, byte) in chunk.iter_mut().enumerate() {
+                *byte = (word >> (i * 8)) as u8;
+            }
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl fmt::Display for Block {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Block::Empty => '.'.fmt(f),
+            Block::Snake(..) => 'o'.fmt(f),
+            Block::Food => '*'.fmt(f),
+            Block::Obstacle => '#'.fmt(f),
+            Block::Checkpoint => 'c'.fmt(f),
+            Block::Looming => '%'.fmt(f),
+            Block::PowerUp(_) => 'm'.fmt(f),
+            Block::OutOfBound => "".fmt(f),
+        }
+    }
+}
+
+impl fmt::Display for Grid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let height = self.height();
+
+        for y in 0..height {
+            for (_, block) in self.iter_row(y) {
+                write!(f, "{}", block)?;
+            }
+            if y < height - 1 {
+                write!(f, "\n")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn chars_from_ascii_grid<'a>(
+    string: &'a str,
+) -> impl Iterator<Item = (Coordinate, char)> + 'a {
+    string
+        .lines()
+        .filter(|line| !line.is_empty())
+        .enumerate()
+        .scan(0, |width, (line_no, line)| {
+            let line_len = line.len();
+
+            if *width == 0 || line_len == *width {
+                *width = line_len;
+                Some((line_no, line))
+            } else {
+                None
+            }
+        })
+        .flat_map(|(line_no, line)| {
+            line.chars().enumerate().map(move |(col, ch)| {
+                (Coordinate::from_usizes(col, line_no), ch)
+            })
+        })
+}
+
+pub fn find_snake_tail<BB: BoundingBehavior>(
+    grid: &Grid,
+) -> Option<Coordinate> {
+    let snake_coords = grid
+        .iter()
+        .filter(|&(_, block)| block.snake().is_some())
+        .map(|(coord, _)| coord);
+
+    for coord in snake_coords {
+        let block = grid[coord];
+
+        let dir = block.snake().unwrap();
+
+        let next_block = coord
+            .move_towards(dir)
+            .inside::<BB>(grid)
+            .map(|c| grid[c])
+            .unwrap_or(Block::OutOfBound);
+
+        let prev_block = coord
+            .move_towards(dir.opposite())
+            .inside::<BB>(grid)
+            .map(|c| grid[c])
+            .unwrap_or(Block::OutOfBound);
+
+        match (prev_block.snake(), next_block.snake()) {
+            (None, Some(_)) => {
+                // is tail
+                return Some(coord);
+            }
+            (None, None) => {
+                // only snake block
+                return Some(coord);
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
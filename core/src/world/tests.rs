@@ -0,0 +1,1530 @@
+use rand::rngs::SmallRng;
+
+use super::*;
+
+use data::{Bounding, Wrapping};
+
+#[test]
+fn test_two_steps_inchworm() {
+    let snake_string = indoc!(
+        "
+        ..........
+        .>>>>.....
+        ..........
+        ....*.....
+        .........."
+    );
+    let step_1 = indoc!(
+        "
+        ..........
+        .ooooo....
+        ..........
+        ....*.....
+        .........."
+    );
+    let step_2 = indoc!(
+        "
+        ..........
+        ..oooo....
+        ..........
+        ....*.....
+        .........."
+    );
+
+    let mut world: World<SmallRng, Wrapping> = World::from_ascii(snake_string);
+
+    world.step(None).unwrap();
+
+    assert_eq!(&step_1, &world.grid.to_string());
+
+    world.step(None).unwrap();
+
+    assert_eq!(&step_2, &world.grid.to_string());
+}
+
+#[test]
+fn test_eat_food() {
+    let snake_string = indoc!(
+        "
+        ..........
+        .>>>v.....
+        ..........
+        ....*.....
+        .........."
+    );
+    let digesting = indoc!(
+        "
+        ..........
+        ..ooo.....
+        ....o.....
+        ....o.....
+        .........."
+    );
+
+    let mut world: World<SmallRng, Wrapping> = World::from_ascii(snake_string);
+
+    world.step(None).unwrap();
+    world.step(None).unwrap();
+    world.step(None).unwrap();
+
+    assert_eq!(&digesting, &world.grid.to_string());
+
+    assert_matches!(
+        world.step(None).unwrap(),
+        Some(WorldUpdate::FoodEaten { .. })
+    );
+    assert_matches!(
+        world.step(None).unwrap(),
+        Some(WorldUpdate::Grew { length: 5, .. })
+    );
+
+    // food is generated randomly, no assumptions on its position
+    match world.step(None).unwrap() {
+        Some(WorldUpdate::FoodSpawned { at }) => {
+            assert_eq!(world.grid[at], Block::Food);
+        }
+        update => panic!("expected the food to respawn, got {:?}", update),
+    }
+}
+
+#[test]
+fn test_wrapping() {
+    let snake_string = indoc!(
+        "
+        ..........
+        .>>>>.....
+        ..........
+        ....*.....
+        .........."
+    );
+    let afterwards = indoc!(
+        "
+        ..........
+        o......ooo
+        ..........
+        ....*.....
+        .........."
+    );
+
+    let mut world: World<SmallRng, Wrapping> = World::from_ascii(snake_string);
+
+    for _ in 0..12 {
+        world.step(None).unwrap();
+    }
+
+    assert_eq!(&afterwards, &world.grid.to_string());
+}
+
+#[test]
+fn test_bounding() {
+    let snake_string = indoc!(
+        "
+        ..........
+        .>>>>.....
+        ..........
+        ....*.....
+        .........."
+    );
+    let afterwards = indoc!(
+        "
+        ..........
+        ......oooo
+        ..........
+        ....*.....
+        .........."
+    );
+
+    let mut world: World<SmallRng, Bounding> = World::from_ascii(snake_string);
+
+    while let Ok(_) = world.step(None) {}
+
+    assert_matches!(world.step(None), Err(UpdateError::OutOfBound));
+
+    assert_eq!(&afterwards, &world.grid.to_string());
+}
+
+#[test]
+fn test_edges_are_walls_by_the_rules() {
+    let mut world: World<SmallRng, Wrapping> =
+        World::from_ascii("..........\n.>>>>.....\n..........");
+    world.rules.wrap = false;
+
+    while let Ok(_) = world.step(None) {}
+
+    assert_matches!(world.step(None), Err(UpdateError::OutOfBound));
+    assert_eq!(world.snakes[0].head, Coordinate { x: 9, y: 1 });
+}
+
+#[test]
+fn test_growth_keeps_the_tail_for_a_few_moves() {
+    let rng = FakeRng::food_at(10, 3, &[(0, 0)]);
+    let mut world: World<FakeRng, Wrapping> =
+        World::from_ascii_with_rng("..........\n.>>*......\n..........", rng);
+    world.rules.growth = 3;
+
+    let grew = |update| match update {
+        Some(WorldUpdate::Grew { length, .. }) => Some(length),
+        _ => None,
+    };
+    let lengths: Vec<_> = (0..10)
+        .filter_map(|_| grew(world.step(None).unwrap()))
+        .collect();
+    assert_eq!(lengths, vec![3, 4, 5]);
+    assert_eq!(world.body().len(), 5);
+}
+
+#[test]
+fn test_reverse_turn_runs_into_the_neck_by_the_rules() {
+    let mut world: World<SmallRng, Wrapping> =
+        World::from_ascii("..........\n.>>>>.....\n..........");
+    world.rules.allow_reverse = true;
+    world.snakes[0].input.set_allow_reverse(true);
+
+    assert_matches!(
+        world.tick(Some(Direction::West)),
+        Err(UpdateError::CollideBody)
+    );
+}
+
+#[test]
+fn test_ghost_goes_over_its_body_by_the_rules() {
+    let mut world: World<SmallRng, Wrapping> =
+        World::from_ascii("..........\n.>>>>.....\n..........");
+    world.rules.allow_reverse = true;
+    world.rules.ghost = true;
+    world.snakes[0].input.set_allow_reverse(true);
+
+    world.tick(Some(Direction::West)).unwrap();
+    world.tick(None).unwrap();
+    world.tick(None).unwrap();
+
+    assert_eq!(world.snakes[0].head, Coordinate { x: 1, y: 1 });
+    assert_eq!(world.body().len(), 4);
+    // the tiles it crossed over twice are still its own
+    assert_eq!(
+        world.grid[Coordinate { x: 3, y: 1 }],
+        Block::Snake(Direction::West, PLAYER)
+    );
+}
+
+#[test]
+fn test_snake_heads_along_a_diagonal_by_the_rules() {
+    let ascii = "..........\n.>>>>.....\n..........\n..........";
+    let mut world: World<SmallRng, Wrapping> = World::from_ascii(ascii);
+    let diagonal = Command::Diagonal(Direction8::SouthEast);
+
+    world.command(diagonal).unwrap();
+    world.tick(None).unwrap();
+    assert_eq!(world.snakes[0].head, Coordinate { x: 5, y: 1 });
+
+    world.rules.diagonal = true;
+    world.command(diagonal).unwrap();
+    world.tick(None).unwrap();
+    assert_eq!(world.snakes[0].head, Coordinate { x: 6, y: 2 });
+    world.tick(None).unwrap();
+    assert_eq!(world.snakes[0].head, Coordinate { x: 7, y: 3 });
+    assert_eq!(world.body().len(), 4);
+
+    // a straight turn takes over from the diagonal
+    world.tick(Some(Direction::North)).unwrap();
+    world.tick(None).unwrap();
+    assert_eq!(world.snakes[0].head, Coordinate { x: 7, y: 1 });
+}
+
+#[test]
+fn test_snake_moves_at_the_pace_of_the_rules() {
+    let mut world: World<SmallRng, Wrapping> =
+        World::from_ascii("..........\n.>>>>.....\n..........");
+    world.rules.pace = 2;
+
+    let moves = (0..6)
+        .filter(|&now| {
+            world.now = now;
+            !world.tick(None).unwrap().is_empty()
+        })
+        .count();
+    assert_eq!(moves, 3);
+}
+
+impl<R: Rng, BB: BoundingBehavior> World<R, BB> {
+    fn head_dir(&self) -> Direction {
+        self.get_block(self.snakes[0].head).snake().unwrap()
+    }
+    fn with_direction<F>(&mut self, dir: Direction, f: F)
+    where
+        F: Fn(Direction, Direction),
+    {
+        let prev_dir = self.head_dir();
+        self.set_direction(dir).unwrap();
+        let next_dir = self.head_dir();
+
+        f(prev_dir, next_dir);
+
+        let head = self.snakes[0].head;
+        self.set_block(head, prev_dir);
+    }
+}
+
+#[test]
+fn test_set_direction_one_block_snake() {
+    let snake_string = indoc!(
+        "
+        ..........
+        ....>.....
+        ..........
+        ....*.....
+        .........."
+    );
+    let mut world: World<SmallRng, Bounding> = World::from_ascii(snake_string);
+
+    world.with_direction(Direction::North, |_prev_dir, next_dir| {
+        assert_eq!(next_dir, Direction::North);
+    });
+
+    world.with_direction(Direction::East, |_prev_dir, next_dir| {
+        assert_eq!(next_dir, Direction::East);
+    });
+
+    world.with_direction(Direction::South, |_prev_dir, next_dir| {
+        assert_eq!(next_dir, Direction::South);
+    });
+
+    world.with_direction(Direction::West, |_prev_dir, next_dir| {
+        assert_eq!(next_dir, Direction::West);
+    });
+}
+
+#[test]
+fn test_set_direction_straight() {
+    let snake_string = indoc!(
+        "
+        ..........
+        .>>>>.....
+        ..........
+        ....*.....
+        .........."
+    );
+    let mut world: World<SmallRng, Bounding> = World::from_ascii(snake_string);
+
+    world.with_direction(Direction::North, |_prev_dir, next_dir| {
+        assert_eq!(next_dir, Direction::North);
+    });
+
+    world.with_direction(Direction::East, |_prev_dir, next_dir| {
+        assert_eq!(next_dir, Direction::East);
+    });
+
+    world.with_direction(Direction::South, |_prev_dir, next_dir| {
+        assert_eq!(next_dir, Direction::South);
+    });
+
+    world.with_direction(Direction::West, |prev_dir, next_dir| {
+        assert_eq!(next_dir, prev_dir);
+    });
+}
+
+#[test]
+fn test_set_direction_turning() {
+    let snake_string = indoc!(
+        "
+        ..........
+        .>>>^.....
+        ..........
+        ....*.....
+        .........."
+    );
+    let mut world: World<SmallRng, Bounding> = World::from_ascii(snake_string);
+
+    world.with_direction(Direction::North, |_prev_dir, next_dir| {
+        assert_eq!(next_dir, Direction::North);
+    });
+
+    world.with_direction(Direction::East, |_prev_dir, next_dir| {
+        assert_eq!(next_dir, Direction::East);
+    });
+
+    world.with_direction(Direction::South, |_prev_dir, next_dir| {
+        assert_eq!(next_dir, Direction::South);
+    });
+
+    world.with_direction(Direction::West, |prev_dir, next_dir| {
+        assert_eq!(next_dir, prev_dir);
+    });
+}
+
+#[test]
+fn test_set_direction_when_consuming_does_not_kill_it() {
+    // gh issue: https://github.com/yiransheng/rust-snake-wasm/issues/11
+    let snake_string = indoc!(
+        "
+        ..........
+        .>>>>.....
+        ..........
+        ....*.....
+        .........."
+    );
+    let mut world: World<SmallRng, Bounding> = World::from_ascii(snake_string);
+
+    world.step(None);
+    world.step(Some(Direction::North));
+    assert_matches!(world.step(Some(Direction::West)), Ok(_));
+}
+
+#[test]
+fn test_death() {
+    let snake_string = indoc!(
+        "
+        ..........
+        >>>>>>v...
+        ......v...
+        ..^<<<<...
+        .........*"
+    );
+    let afterwards = indoc!(
+        "
+        ..........
+        .oooooo...
+        ..o...o...
+        ..ooooo...
+        .........*"
+    );
+
+    let mut world: World<SmallRng, Bounding> = World::from_ascii(snake_string);
+
+    while let Ok(_) = world.step(None) {}
+
+    assert_matches!(world.step(None), Err(UpdateError::CollideBody));
+
+    assert_eq!(&afterwards, &world.grid.to_string());
+}
+
+#[test]
+fn test_death_is_reported_before_the_error() {
+    let snake_string = indoc!(
+        "
+        ..........
+        ........>>
+        ..........
+        ....*.....
+        .........."
+    );
+
+    let mut world: World<SmallRng, Bounding> = World::from_ascii(snake_string);
+
+    let delta = Stateful::step(&mut world, None, 0).unwrap().unwrap();
+    assert_matches!(
+        delta.effects[0],
+        Some(WorldUpdate::Died {
+            reason: UpdateError::OutOfBound,
+        })
+    );
+    assert!(delta.head.is_none());
+    assert_matches!(
+        Stateful::step(&mut world, None, 0),
+        Err(UpdateError::OutOfBound)
+    );
+}
+
+#[test]
+fn test_debug_toggle_is_reported() {
+    let snake_string = indoc!(
+        "
+        ..........
+        .>>>>.....
+        .........."
+    );
+
+    let mut world: World<SmallRng, Wrapping> = World::from_ascii(snake_string);
+
+    let toggle = Some(Command::DebugToggle);
+    let delta = Stateful::step(&mut world, toggle, 0).unwrap().unwrap();
+    assert_matches!(delta.effects[0], Some(WorldUpdate::Debug(true)));
+    assert!(delta.head.is_none());
+
+    let delta = Stateful::step(&mut world, toggle, 1).unwrap().unwrap();
+    assert_matches!(delta.effects[0], Some(WorldUpdate::Debug(false)));
+}
+
+#[test]
+fn test_tick_moves_both_ends() {
+    let snake_string = indoc!(
+        "
+        ..........
+        .>>>>.....
+        ..........
+        .........."
+    );
+
+    let mut world: World<SmallRng, Wrapping> = World::from_ascii(snake_string);
+
+    let delta = world.tick(Some(Direction::East)).unwrap();
+
+    assert_matches!(
+        delta.tail,
+        Some(WorldUpdate::TailRetracted {
+            at: Coordinate { x: 1, y: 1 },
+            ..
+        })
+    );
+    assert_matches!(
+        delta.head,
+        Some(WorldUpdate::HeadMoved {
+            at: Coordinate { x: 5, y: 1 },
+            ..
+        })
+    );
+    // the tail is drawn first
+    assert_matches!(
+        delta.changes().next(),
+        Some(WorldUpdate::TailRetracted { .. })
+    );
+
+    // a rejected turn still moves the snake
+    let delta = world.tick(Some(Direction::West)).unwrap();
+
+    assert_matches!(
+        delta.effects[0],
+        Some(WorldUpdate::InvalidInput(Direction::West))
+    );
+    assert!(delta.head.is_some() && delta.tail.is_some());
+}
+
+#[test]
+fn test_held_reverse_key_keeps_the_snake_moving() {
+    let snake_string = indoc!(
+        "
+        ..........
+        .>>>>.....
+        ..........
+        .........."
+    );
+    let afterwards = indoc!(
+        "
+        ..........
+        ...oooo...
+        ..........
+        .........."
+    );
+
+    let mut world: World<SmallRng, Wrapping> = World::from_ascii(snake_string);
+
+    assert_matches!(
+        world.step(Some(Direction::West)),
+        Ok(Some(WorldUpdate::HeadMoved { .. }))
+    );
+    assert_matches!(
+        world.step(Some(Direction::West)),
+        Ok(Some(WorldUpdate::InvalidInput(Direction::West)))
+    );
+    world.step(Some(Direction::West)).unwrap();
+    world.step(Some(Direction::West)).unwrap();
+    world.step(None).unwrap();
+    world.step(None).unwrap();
+
+    assert_eq!(&afterwards, &world.grid.to_string());
+    assert_eq!(world.head_dir(), Direction::East);
+}
+
+#[test]
+fn test_rapid_turns_apply_on_consecutive_moves() {
+    let snake_string = indoc!(
+        "
+        ..........
+        .>>>>.....
+        ..........
+        ....*.....
+        .........."
+    );
+    let afterwards = indoc!(
+        "
+        ....oo....
+        ...ooo....
+        ..........
+        ....*.....
+        .........."
+    );
+
+    let mut world: World<SmallRng, Bounding> = World::from_ascii(snake_string);
+
+    world.step(None).unwrap();
+    // both turns arrive before the snake advances into the next tile
+    world.step(Some(Direction::North)).unwrap();
+    world.step(Some(Direction::West)).unwrap();
+    world.step(None).unwrap();
+    world.step(None).unwrap();
+
+    assert_eq!(&afterwards, &world.grid.to_string());
+    assert_eq!(world.head_dir(), Direction::West);
+}
+
+#[test]
+fn test_scripted_food() {
+    let snake_string = indoc!(
+        "
+        ..........
+        .>>>v.....
+        ..........
+        ....*.....
+        .........."
+    );
+    // (4, 2) is taken by the snake by then, so the food goes on a tile from
+    // the index of empty ones instead
+    let rng = FakeRng::food_at(10, 5, &[(4, 2), (7, 0)]);
+
+    let mut world: World<FakeRng, Wrapping> =
+        World::from_ascii_with_rng(snake_string, rng);
+
+    world.step(None).unwrap();
+    world.step(None).unwrap();
+    world.step(None).unwrap();
+
+    assert_matches!(
+        world.step(None).unwrap(),
+        Some(WorldUpdate::FoodEaten { .. })
+    );
+    assert_matches!(world.step(None).unwrap(), Some(WorldUpdate::Grew { .. }));
+    match world.step(None).unwrap() {
+        Some(WorldUpdate::FoodSpawned { at }) => {
+            assert_ne!(at, Coordinate { x: 4, y: 2 });
+            assert_eq!(world.grid[at], Block::Food);
+        }
+        update => panic!("expected the food to respawn, got {:?}", update),
+    }
+}
+
+#[test]
+fn test_food_only_spawns_on_empty_tiles() {
+    use rand::{Rng, SeedableRng};
+    use replay::initial_world;
+
+    let mut turns = SmallRng::from_seed([9; 16]);
+    let mut world = initial_world(8, 6, 42, Rules::default());
+
+    for _ in 0..2000 {
+        // the board as the updates tell it, checked before each food lands
+        let mut board = Grid::empty(8, 6);
+        let apply = |board: &mut Grid, update: WorldUpdate| match update {
+            WorldUpdate::SetBlock { block, at } => board[at] = block,
+            WorldUpdate::HeadMoved { at, dir, .. }
+            | WorldUpdate::Bend { at, to: dir, .. } => {
+                board[at] = dir.into()
+            }
+            WorldUpdate::TailRetracted { at, .. } => board[at] = Block::Empty,
+            WorldUpdate::FoodSpawned { at } => {
+                assert_eq!(board[at], Block::Empty, "food spawned on {:?}", at);
+                board[at] = Block::Food;
+            }
+            _ => {}
+        };
+
+        for delta in world.initialize() {
+            delta.changes().for_each(|u| apply(&mut board, u));
+        }
+
+        for _ in 0..500 {
+            let turn = match turns.gen_range(0, 12) {
+                n @ 0...3 => Direction::from_u8(n).map(Command::Turn),
+                _ => None,
+            };
+
+            match Stateful::step(&mut world, turn, 0) {
+                Ok(Some(delta)) => {
+                    delta.changes().for_each(|u| apply(&mut board, u))
+                }
+                Ok(None) => {}
+                Err(_) => break,
+            }
+        }
+
+        Stateful::tear_down(&mut world);
+    }
+}
+
+#[test]
+fn test_filling_the_board_wins() {
+    let mut world: World<SmallRng, Wrapping> = World::from_ascii(">>>*.");
+
+    let delta = Stateful::step(&mut world, None, 0).unwrap().unwrap();
+    assert_matches!(
+        delta.food,
+        Some(WorldUpdate::FoodSpawned {
+            at: Coordinate { x: 4, y: 0 },
+        })
+    );
+
+    let delta = Stateful::step(&mut world, None, 0).unwrap().unwrap();
+    assert!(delta.food.is_none());
+    assert_matches!(delta.effects[2], Some(WorldUpdate::Won));
+    let over: GameOver = Stateful::step(&mut world, None, 0)
+        .unwrap_err()
+        .into();
+    assert_matches!(over, GameOver::Won);
+}
+
+#[test]
+fn test_arena_closes_in() {
+    let snake_string = indoc!(
+        "
+        ..........
+        ..........
+        ..........
+        ..>>>.....
+        ..........
+        ..........
+        ..........
+        .........."
+    );
+    let shrunk = indoc!(
+        "
+        ##########
+        #........#
+        #........#
+        #...ooo..#
+        #........#
+        #........#
+        #........#
+        ##########"
+    );
+
+    let mut world: World<SmallRng, Wrapping> = World::from_ascii(snake_string);
+    world.set_arena(Some(Arena::new(2)));
+
+    let delta = Stateful::step(&mut world, None, 0).unwrap().unwrap();
+    assert!(delta.effects[0].is_none());
+
+    let delta = Stateful::step(&mut world, None, 0).unwrap().unwrap();
+    assert_matches!(
+        delta.effects[0],
+        Some(WorldUpdate::ArenaShrunk { inset: 0, .. })
+    );
+    assert_eq!(&shrunk, &world.grid.to_string());
+
+    // the second ring closes on the head
+    Stateful::step(&mut world, None, 0).unwrap();
+    let delta = Stateful::step(&mut world, None, 0).unwrap().unwrap();
+    assert_matches!(
+        delta.effects[1],
+        Some(WorldUpdate::Died {
+            reason: UpdateError::CollideObstacle,
+        })
+    );
+    assert_matches!(
+        Stateful::step(&mut world, None, 0),
+        Err(UpdateError::CollideObstacle)
+    );
+}
+
+#[test]
+fn test_arena_closing_on_the_food_does_not_win() {
+    let snake_string = indoc!(
+        "
+        *.....
+        .####.
+        .####.
+        .####.
+        .>>.#.
+        ......"
+    );
+
+    let mut world: World<SmallRng, Wrapping> = World::from_ascii(snake_string);
+    world.set_arena(Some(Arena::new(1)));
+    // the tail stays put, the snake fills the arena once it moves
+    world.snakes[0].owed = 1;
+
+    let delta = Stateful::step(&mut world, None, 0).unwrap().unwrap();
+    let over = |u: WorldUpdate| match u {
+        WorldUpdate::Died { .. } | WorldUpdate::Won => true,
+        _ => false,
+    };
+    assert!(!delta.changes().any(over));
+    assert!(delta.food.is_none());
+
+    // the snake plays on without food, into the walls
+    let delta = Stateful::step(&mut world, None, 0).unwrap().unwrap();
+    assert_matches!(
+        delta.effects[0],
+        Some(WorldUpdate::Died {
+            reason: UpdateError::CollideObstacle,
+        })
+    );
+}
+
+#[test]
+fn test_walls_stop_the_wrap_around() {
+    let snake_string = indoc!(
+        "
+        #>>..
+        ....."
+    );
+    let mut world: World<SmallRng, Wrapping> = World::from_ascii(snake_string);
+
+    assert_matches!(world.tick(None), Ok(_));
+    assert_matches!(world.tick(None), Ok(_));
+    // (0, 0) is where the head would have wrapped to
+    assert_matches!(world.tick(None), Err(UpdateError::CollideObstacle));
+}
+
+#[test]
+fn test_clock_runs_out() {
+    let mut world: World<SmallRng, Wrapping> = World::from_ascii(">>......");
+    world.set_countdown(Some(Countdown::new(2, 5)));
+
+    let delta = Stateful::step(&mut world, None, 0).unwrap().unwrap();
+    assert_matches!(delta.effects[0], Some(WorldUpdate::Clock { left: 1 }));
+
+    let delta = Stateful::step(&mut world, None, 0).unwrap().unwrap();
+    assert_matches!(delta.effects[0], Some(WorldUpdate::Clock { left: 0 }));
+    assert_matches!(
+        delta.effects[1],
+        Some(WorldUpdate::Died {
+            reason: UpdateError::TimeUp,
+        })
+    );
+    assert_matches!(
+        Stateful::step(&mut world, None, 0),
+        Err(UpdateError::TimeUp)
+    );
+}
+
+#[test]
+fn test_turn_reports_the_corner() {
+    let snake_string = indoc!(
+        "
+        ..........
+        .>>>>.....
+        ..........
+        .........."
+    );
+
+    let mut world: World<SmallRng, Wrapping> = World::from_ascii(snake_string);
+
+    assert_matches!(
+        world.step(Some(Direction::South)),
+        Ok(Some(WorldUpdate::Bend {
+            at: Coordinate { x: 4, y: 2 },
+            corner: Coordinate { x: 4, y: 1 },
+            from: Direction::East,
+            to: Direction::South,
+            ..
+        }))
+    );
+}
+
+#[test]
+fn test_head_and_tail_updates() {
+    let snake_string = indoc!(
+        "
+        ..........
+        .>>>>.....
+        ..........
+        .........."
+    );
+
+    let mut world: World<SmallRng, Wrapping> = World::from_ascii(snake_string);
+
+    assert_matches!(
+        world.step(None),
+        Ok(Some(WorldUpdate::HeadMoved {
+            at: Coordinate { x: 5, y: 1 },
+            neck: Coordinate { x: 4, y: 1 },
+            dir: Direction::East,
+            ..
+        }))
+    );
+    assert_matches!(
+        world.step(None),
+        Ok(Some(WorldUpdate::TailRetracted {
+            at: Coordinate { x: 1, y: 1 },
+            dir: Direction::East,
+            ..
+        }))
+    );
+}
+
+#[test]
+fn test_body_follows_the_snake() {
+    let snake_string = indoc!(
+        "
+        ..........
+        .>>>v.....
+        ..........
+        ....*.....
+        .........."
+    );
+
+    let mut world: World<SmallRng, Wrapping> = World::from_ascii(snake_string);
+    let walked = |world: &World<SmallRng, Wrapping>| -> Vec<Coordinate> {
+        let tail = world.snakes[0].tail;
+        let iter: SnakeIter<Wrapping> = SnakeIter::new(&world.grid, tail);
+        let mut snake: Vec<_> = iter.map(|(at, _)| at).collect();
+        snake.reverse();
+        snake
+    };
+
+    // around the corner, onto the food and through the growth
+    for _ in 0..6 {
+        world.step(None).unwrap();
+        let body: Vec<_> = world.body().iter().cloned().collect();
+        assert_eq!(body, walked(&world));
+    }
+    assert_eq!(world.body().head(), Some(world.snakes[0].head));
+    assert_eq!(world.body().len(), 5);
+}
+
+#[test]
+fn test_slow_snake_keeps_its_turn() {
+    let mut world: World<SmallRng, Wrapping> =
+        World::from_ascii("..........\n.>>>>.....\n..........");
+    let mut cadence = Cadence::new();
+    cadence.register(Actor::Snake, 2);
+    world.set_cadence(cadence);
+
+    let delta = Stateful::step(&mut world, None, 0).unwrap().unwrap();
+    assert!(delta.head.is_some());
+
+    // the turn waits for the next move, shown on the head until then
+    let south = Some(Command::Turn(Direction::South));
+    let delta = Stateful::step(&mut world, south, 1).unwrap().unwrap();
+    assert!(delta.head.is_none());
+    assert_matches!(
+        delta.effects[0],
+        Some(WorldUpdate::NextTurn {
+            at: Coordinate { x: 5, y: 1 },
+            dir: Direction::South,
+        })
+    );
+
+    let delta = Stateful::step(&mut world, None, 2).unwrap().unwrap();
+    assert_matches!(
+        delta.head,
+        Some(WorldUpdate::Bend {
+            at: Coordinate { x: 5, y: 2 },
+            ..
+        })
+    );
+    assert!(delta.effects[0].is_none());
+}
+
+#[test]
+fn test_grace_period_saves_a_turning_snake() {
+    let mut world: World<SmallRng, Wrapping> =
+        World::from_ascii("....\n>>>#\n....\n....");
+    world.set_grace(true);
+
+    let delta = world.tick(None).unwrap();
+    assert_matches!(
+        delta.effects[0],
+        Some(WorldUpdate::Teetering {
+            at: Coordinate { x: 2, y: 1 },
+            dir: Direction::East,
+            ..
+        })
+    );
+    assert!(delta.head.is_none() && delta.tail.is_none());
+
+    let delta = world.tick(Some(Direction::South)).unwrap();
+    assert_matches!(delta.head, Some(WorldUpdate::Bend { .. }));
+    assert_eq!(world.snakes[0].head, Coordinate { x: 2, y: 2 });
+}
+
+#[test]
+fn test_grace_period_runs_out() {
+    let mut world: World<SmallRng, Wrapping> =
+        World::from_ascii("....\n>>>#\n....\n....");
+    world.set_grace(true);
+
+    assert_matches!(world.tick(None), Ok(_));
+    assert_matches!(world.tick(None), Err(UpdateError::CollideObstacle));
+}
+
+#[test]
+fn test_lost_life_respawns_the_snake() {
+    let mut world: World<SmallRng, Wrapping> =
+        World::from_ascii("......\n.>>>#.\n......\n..*...");
+    world.food = Some(Coordinate { x: 2, y: 3 });
+    world.set_lives(Some(Lives::new(2, 3)));
+
+    Stateful::step(&mut world, None, 0).unwrap();
+    assert_matches!(
+        Stateful::step(&mut world, None, 1),
+        Err(UpdateError::LifeLost)
+    );
+    world.tear_down();
+
+    let init: Vec<_> = world
+        .initialize()
+        .filter_map(|delta| delta.changes().next())
+        .collect();
+    assert_matches!(init[0], WorldUpdate::Respawned { width: 6, .. });
+    assert_matches!(init[1], WorldUpdate::Lives { left: 1 });
+    // the food stays where it was, the snake is back at the start
+    assert_matches!(
+        init[2],
+        WorldUpdate::SetBlock {
+            block: Block::Food,
+            at: Coordinate { x: 2, y: 3 },
+        }
+    );
+    assert_eq!(world.snakes[0].head, Coordinate { x: 3, y: 1 });
+
+    // shielded, the snake stops short of the wall
+    let delta = Stateful::step(&mut world, None, 2).unwrap().unwrap();
+    assert_matches!(delta.effects[0], Some(WorldUpdate::Teetering { .. }));
+}
+
+#[test]
+fn test_last_life_ends_the_game() {
+    let mut world: World<SmallRng, Wrapping> =
+        World::from_ascii("......\n.>>>#.\n......");
+    world.set_lives(Some(Lives::new(1, 3)));
+
+    Stateful::step(&mut world, None, 0).unwrap();
+    assert_matches!(
+        Stateful::step(&mut world, None, 1),
+        Err(UpdateError::CollideObstacle)
+    );
+}
+
+#[test]
+fn test_checkpoint_is_crossed_once_per_game() {
+    let mut world: World<SmallRng, Wrapping> =
+        World::from_ascii("......\n.>>c..\n......");
+    let checkpoint = Coordinate { x: 3, y: 1 };
+
+    let delta = world.tick(None).unwrap();
+    assert!(delta.changes().any(|u| match u {
+        WorldUpdate::CheckpointReached { at } => at == checkpoint,
+        _ => false,
+    }));
+    world.tick(None).unwrap();
+    assert_eq!(world.grid[checkpoint], Block::from(Direction::East));
+
+    // laid again for the next game, and drawn when the level loads
+    world.tear_down();
+    let init: Vec<_> = world
+        .initialize()
+        .filter_map(|delta| delta.changes().next())
+        .collect();
+    assert_matches!(
+        init[1],
+        WorldUpdate::SetBlock {
+            block: Block::Checkpoint,
+            at: Coordinate { x: 3, y: 1 },
+        }
+    );
+}
+
+#[test]
+fn test_hazard_blinks_before_it_turns_solid() {
+    let mut world: World<SmallRng, Wrapping> =
+        World::from_ascii("......\n.>>...\n......");
+    let mut cadence = Cadence::new();
+    cadence.register(Actor::Snake, 100);
+    world.set_cadence(cadence);
+    world.set_hazards(Some(Hazards::new(1, 2)));
+
+    let delta = Stateful::step(&mut world, None, 1).unwrap().unwrap();
+    let at = match delta.effects[0] {
+        Some(WorldUpdate::HazardWarning { at, left: 2 }) => at,
+        other => panic!("expected a warning, got {:?}", other),
+    };
+    assert_eq!(world.grid[at], Block::Looming);
+
+    let delta = Stateful::step(&mut world, None, 2).unwrap().unwrap();
+    assert_matches!(
+        delta.effects[0],
+        Some(WorldUpdate::HazardWarning { left: 1, .. })
+    );
+
+    let delta = Stateful::step(&mut world, None, 3).unwrap().unwrap();
+    assert_matches!(
+        delta.effects[0],
+        Some(WorldUpdate::HazardRaised { at: raised }) if raised == at
+    );
+    assert_eq!(world.grid[at], Block::Obstacle);
+}
+
+#[test]
+fn test_laser_kills_the_snake_on_its_line() {
+    let mut world: World<SmallRng, Wrapping> =
+        World::from_ascii("......\n.>>...\n......");
+    let mut lasers = Lasers::new(100, 1);
+    lasers.aim(Line::Row(1));
+    world.set_lasers(Some(lasers));
+
+    let delta = Stateful::step(&mut world, None, 0).unwrap().unwrap();
+    assert_matches!(
+        delta.effects[0],
+        Some(WorldUpdate::LaserFired {
+            line: Line::Row(1)
+        })
+    );
+    assert_matches!(delta.effects[1], Some(WorldUpdate::Died { .. }));
+    assert_matches!(
+        Stateful::step(&mut world, None, 1),
+        Err(UpdateError::CollideObstacle)
+    );
+}
+
+#[test]
+fn test_magnet_pulls_food_around_an_obstacle() {
+    let mut world: World<SmallRng, Wrapping> =
+        World::from_ascii(".......\n.>>.#*.\n.......");
+    let mut cadence = Cadence::new();
+    cadence.register(Actor::Snake, 100);
+    world.set_cadence(cadence);
+    let mut power_ups = PowerUps::new(100, 10);
+    power_ups.take(PowerUp::Magnet);
+    world.set_power_ups(Some(power_ups));
+    let food = Coordinate { x: 5, y: 1 };
+    world.food = Some(food);
+
+    // the wall is in the way, the food goes round it
+    let to = Coordinate { x: 5, y: 0 };
+    let delta = Stateful::step(&mut world, None, 1).unwrap().unwrap();
+    assert_matches!(
+        delta.effects[0],
+        Some(WorldUpdate::FoodMoved { from, to: moved })
+            if from == food && moved == to
+    );
+    assert_eq!(world.grid[food], Block::Empty);
+    assert_eq!(world.grid[to], Block::Food);
+    assert_eq!(world.food, Some(to));
+}
+
+#[test]
+fn test_steady_ticks_do_not_allocate() {
+    let mut world: World<SmallRng, Wrapping> = World::from_ascii(
+        "........................\n\
+         .>>.....................\n\
+         ...................###..\n\
+         ...................#*#..\n\
+         ...................###..",
+    );
+    // a hazard looms for the whole test and the magnet searches the board
+    // around the head for the walled in food on every tick
+    world.set_hazards(Some(Hazards::new(1, 1000)));
+    let mut power_ups = PowerUps::new(1000, 1000);
+    power_ups.take(PowerUp::Magnet);
+    world.set_power_ups(Some(power_ups));
+    world.food = Some(Coordinate { x: 20, y: 3 });
+    // an ally going round column 3 crosses the player's row, both passing
+    // through each other's bodies, never their heads
+    world.set_versus(Versus {
+        head_on: HeadOn::LongerWins,
+        pass_through_team: true,
+    });
+    let tiles: Vec<_> = (1..5)
+        .map(|y| (Coordinate { x: 3, y }, Direction::South))
+        .collect();
+    let pilot = rival_heading(Direction::South);
+    world.add_snake(&tiles, TeamId(0), pilot).unwrap();
+
+    // the buffers grow to fit while the snake goes round once
+    for _ in 0..24 {
+        world.tick(None).unwrap();
+    }
+    let allocations = ::counting_alloc::allocations(|| {
+        for _ in 0..48 {
+            world.tick(None).unwrap();
+        }
+    });
+    assert_eq!(allocations, 0);
+    assert!(world.snakes().iter().all(|snake| snake.is_alive()));
+}
+
+#[test]
+fn test_restarts_do_not_allocate() {
+    let mut world: World<SmallRng, Wrapping> = World::from_ascii(
+        "............\n\
+         .>>>........\n\
+         ......*.....\n\
+         ............",
+    );
+    let play = |world: &mut World<SmallRng, Wrapping>| {
+        for _ in world.initialize() {}
+        for tick in 0..30 {
+            Stateful::step(world, None, tick).unwrap();
+        }
+        world.tear_down();
+    };
+
+    // the first game sizes the buffers
+    play(&mut world);
+    let allocations = ::counting_alloc::allocations(|| {
+        for _ in 0..3 {
+            play(&mut world);
+        }
+    });
+    assert_eq!(allocations, 0);
+}
+
+#[test]
+fn test_snapshot_view_shows_the_world_as_it_was() {
+    use history::Rewind;
+
+    let mut world: World<SmallRng, Wrapping> = World::from_ascii(indoc!(
+        "
+        ......
+        .>>.*.
+        ......"
+    ));
+    let snapshot = world.snapshot();
+    world.tick(None).unwrap();
+    world.tick(None).unwrap();
+
+    let (then, now) = (snapshot.view(), world.view());
+    assert_eq!(then.head(), Coordinate { x: 2, y: 1 });
+    assert_eq!(now.head(), Coordinate { x: 4, y: 1 });
+    assert_eq!(then.foods().collect::<Vec<_>>(), [Coordinate { x: 4, y: 1 }]);
+    assert_eq!(then.snakes().map(Snake::length).collect::<Vec<_>>(), [2]);
+    assert!(then.wraps() && now.wraps());
+    assert_eq!(then.grid()[Coordinate { x: 4, y: 1 }], Block::Food);
+}
+
+#[test]
+fn test_moves_are_decided_without_a_board() {
+    use super::apply::{entering, turn};
+    use data::Direction::{East, North, West};
+
+    assert_eq!(entering(Block::Food, false), Ok(Block::Food));
+    assert_eq!(entering(Block::Looming, false), Ok(Block::Looming));
+    assert_eq!(
+        entering(Block::from(North), false),
+        Err(UpdateError::CollideBody)
+    );
+    assert_eq!(
+        entering(Block::from(North), true),
+        Ok(Block::from(North))
+    );
+    assert_eq!(
+        entering(Block::OutOfBound, true),
+        Err(UpdateError::OutOfBound)
+    );
+
+    assert_eq!(turn(East, North, false, false), Some(North));
+    assert_eq!(turn(East, East, false, false), None);
+    // back into its neck, only if the rules let it
+    assert_eq!(turn(East, West, true, false), None);
+    assert_eq!(turn(East, West, true, true), Some(West));
+}
+
+#[test]
+fn test_applying_the_updates_of_a_game_replays_it() {
+    let board = indoc!(
+        "
+        ........
+        .>>.*...
+        ........
+        ........"
+    );
+    let mut world: World<SmallRng, Wrapping> = World::from_ascii(board);
+    // rolls none of the dice, the updates say where the food went
+    let mut follower: World<SmallRng, Wrapping> = World::from_ascii(board);
+
+    for tick in 0..30 {
+        let dir = match tick {
+            3 => Some(Direction::South),
+            5 => Some(Direction::East),
+            _ => None,
+        };
+        let delta = world.tick(dir).unwrap();
+        for update in delta.changes() {
+            follower.apply(&update);
+        }
+        assert_eq!(follower.grid.to_string(), world.grid.to_string());
+    }
+
+    let (snake, followed) = (world.snake(), follower.snake());
+    assert!(snake.length() >= 3);
+    assert_eq!(followed.length(), snake.length());
+    assert_eq!(followed.head(), snake.head());
+    assert_eq!(followed.tail(), snake.tail());
+    assert!(followed.body().iter().eq(snake.body().iter()));
+    assert_eq!(follower.view().food(), world.view().food());
+}
+
+// a bot that heads one way, for rivals to move predictably
+struct Heading(Direction);
+
+impl ::bot::Policy for Heading {
+    fn decide(&mut self, _: &WorldView) -> Direction {
+        self.0
+    }
+}
+
+fn rival_heading(dir: Direction) -> ::bot::Autopilot {
+    ::bot::Autopilot::new(Box::new(Heading(dir)))
+}
+
+#[test]
+fn test_rivals_move_after_the_player_by_their_pilots() {
+    let mut world: World<SmallRng, Wrapping> = World::from_ascii(
+        "..........\n.>>>......\n..........\n..........\n..........",
+    );
+    let tiles = [
+        (Coordinate { x: 1, y: 3 }, Direction::East),
+        (Coordinate { x: 2, y: 3 }, Direction::East),
+    ];
+    let north = rival_heading(Direction::North);
+    let rival = world.add_snake(&tiles, TeamId(1), north.clone());
+    assert_eq!(rival, Some(SnakeId(1)));
+    // the tiles are taken now
+    assert_eq!(world.add_snake(&tiles, TeamId(1), north), None);
+
+    let delta = world.tick(None).unwrap();
+    assert_matches!(
+        delta.rivals[0],
+        Some(WorldUpdate::Bend {
+            snake: SnakeId(1),
+            at: Coordinate { x: 2, y: 2 },
+            ..
+        })
+    );
+    assert_matches!(
+        delta.rivals[1],
+        Some(WorldUpdate::TailRetracted {
+            snake: SnakeId(1),
+            at: Coordinate { x: 1, y: 3 },
+            ..
+        })
+    );
+    assert_eq!(world.snake().head(), Coordinate { x: 4, y: 1 });
+    assert_eq!(world.snakes()[1].head(), Coordinate { x: 2, y: 2 });
+    assert_eq!(
+        world.get_block(Coordinate { x: 2, y: 2 }),
+        Block::Snake(Direction::North, SnakeId(1))
+    );
+    // the rival sees the world from its own head
+    assert_eq!(world.view_of(SnakeId(1)).head(), Coordinate { x: 2, y: 2 });
+}
+
+#[test]
+fn test_a_rival_crash_leaves_the_player_playing() {
+    let mut world: World<SmallRng, Wrapping> = World::from_ascii(
+        "..........\n.>>.......\n..........\n....#.....\n..........",
+    );
+    let tiles = [
+        (Coordinate { x: 2, y: 3 }, Direction::East),
+        (Coordinate { x: 3, y: 3 }, Direction::East),
+    ];
+    world.add_snake(&tiles, TeamId(1), rival_heading(Direction::East));
+
+    let delta = world.tick(None).unwrap();
+    assert_matches!(
+        delta.rivals[0],
+        Some(WorldUpdate::SnakeDied {
+            snake: SnakeId(1),
+            reason: UpdateError::CollideObstacle,
+        })
+    );
+    assert!(!world.snakes()[1].is_alive());
+
+    // its body withers a tile a tick, the player none the wiser
+    world.tick(None).unwrap();
+    assert_eq!(world.snakes()[1].length(), 1);
+    world.tick(None).unwrap();
+    assert_eq!(world.snakes()[1].length(), 0);
+    assert_eq!(world.get_block(Coordinate { x: 3, y: 3 }), Block::Empty);
+    assert_eq!(world.snake().head(), Coordinate { x: 5, y: 1 });
+
+    // and it is back for the next game
+    world.reset();
+    assert!(world.snakes()[1].is_alive());
+    assert_eq!(world.snakes()[1].head(), Coordinate { x: 3, y: 3 });
+}
+
+#[test]
+fn test_the_player_crashes_into_a_rival() {
+    let mut world: World<SmallRng, Wrapping> = World::from_ascii(
+        "..........\n.>>.......\n..........\n..........\n..........",
+    );
+    let tiles = [
+        (Coordinate { x: 3, y: 0 }, Direction::South),
+        (Coordinate { x: 3, y: 1 }, Direction::South),
+        (Coordinate { x: 3, y: 2 }, Direction::South),
+    ];
+    world.add_snake(&tiles, TeamId(1), rival_heading(Direction::South));
+
+    assert_eq!(world.tick(None).err(), Some(UpdateError::CollideBody));
+}
+
+#[test]
+fn test_an_ally_crash_costs_the_player_a_life() {
+    let mut world: World<SmallRng, Wrapping> = World::from_ascii(
+        "..........\n.>>.......\n..........\n....#.....\n..........",
+    );
+    world.set_lives(Some(Lives::new(2, 3)));
+    let tiles = [
+        (Coordinate { x: 2, y: 3 }, Direction::East),
+        (Coordinate { x: 3, y: 3 }, Direction::East),
+    ];
+    world.add_snake(&tiles, TeamId(0), rival_heading(Direction::East));
+
+    let delta = Stateful::step(&mut world, None, 0).unwrap().unwrap();
+    assert_matches!(delta.rivals[0], Some(WorldUpdate::SnakeDied { .. }));
+    assert!(delta.changes().any(|u| match u {
+        WorldUpdate::Died { reason } => reason == UpdateError::CollideObstacle,
+        _ => false,
+    }));
+    assert_matches!(
+        Stateful::step(&mut world, None, 1),
+        Err(UpdateError::LifeLost)
+    );
+
+    // both are back at the start
+    assert!(world.snakes()[1].is_alive());
+    assert_eq!(world.snakes()[1].head(), Coordinate { x: 3, y: 3 });
+    assert_eq!(world.snake().head(), Coordinate { x: 2, y: 1 });
+}
+
+const VERSUS: [Versus; 4] = [
+    Versus {
+        head_on: HeadOn::LongerWins,
+        pass_through_team: false,
+    },
+    Versus {
+        head_on: HeadOn::LongerWins,
+        pass_through_team: true,
+    },
+    Versus {
+        head_on: HeadOn::BothDie,
+        pass_through_team: false,
+    },
+    Versus {
+        head_on: HeadOn::BothDie,
+        pass_through_team: true,
+    },
+];
+
+// plays `ticks` ticks of the player heading east on `board` and a rival
+// of `team` on `tiles` heading `dir` under each of `VERSUS`, whether the
+// player and the rival are still alive after each
+fn play_versus(
+    board: &str,
+    tiles: &[(Coordinate, Direction)],
+    team: TeamId,
+    ticks: usize,
+) -> Vec<(bool, bool)> {
+    VERSUS
+        .iter()
+        .map(|&versus| {
+            let mut world: World<SmallRng, Wrapping> =
+                World::from_ascii(board);
+            world.set_versus(versus);
+            let dir = tiles[0].1;
+            world.add_snake(tiles, team, rival_heading(dir)).unwrap();
+            for _ in 0..ticks {
+                world.tick(None).unwrap();
+            }
+            let snakes = world.snakes();
+            (snakes[0].is_alive(), snakes[1].is_alive())
+        })
+        .collect()
+}
+
+// a rival heading west on row 1, its head on (7, 1)
+fn westward(length: u16) -> Vec<(Coordinate, Direction)> {
+    (0..length)
+        .rev()
+        .map(|i| (Coordinate { x: 7 + i, y: 1 }, Direction::West))
+        .collect()
+}
+
+#[test]
+fn test_versus_head_on() {
+    let board = "............\n.>>>........\n............";
+
+    // the rival runs into the head of the player on the second tick
+    let shorter = play_versus(board, &westward(2), TeamId(1), 2);
+    assert_eq!(
+        shorter,
+        vec![(true, false), (true, false), (false, false), (false, false)]
+    );
+    let even = play_versus(board, &westward(3), TeamId(1), 2);
+    assert_eq!(even, vec![(false, false); 4]);
+    let longer = play_versus(board, &westward(4), TeamId(1), 2);
+    assert_eq!(
+        longer,
+        vec![(false, true), (false, true), (false, false), (false, false)]
+    );
+
+    // teammates don't go through each other's heads
+    let team = play_versus(board, &westward(2), TeamId(0), 2);
+    assert_eq!(team, shorter);
+}
+
+#[test]
+fn test_versus_the_player_dies_of_a_rival_running_into_its_head() {
+    let mut world: World<SmallRng, Wrapping> =
+        World::from_ascii("............\n.>>>........\n............");
+    world.add_snake(&westward(4), TeamId(1), rival_heading(Direction::West));
+
+    world.tick(None).unwrap();
+    let delta = world.tick(None).unwrap();
+    assert_matches!(
+        delta.rivals[0],
+        Some(WorldUpdate::SnakeDied {
+            snake: PLAYER,
+            reason: UpdateError::CollideBody,
+        })
+    );
+    assert!(delta.changes().any(|u| match u {
+        WorldUpdate::Died { reason } => reason == UpdateError::CollideBody,
+        _ => false,
+    }));
+    // the winner's head is on the tile
+    let at = Coordinate { x: 5, y: 1 };
+    assert_eq!(world.snakes()[1].head(), at);
+    assert_eq!(world.get_block(at).owner(), Some(SnakeId(1)));
+}
+
+#[test]
+fn test_versus_head_into_body() {
+    let board = "..........\n.>>>>.....\n..........\n..........\n..........";
+    // heading north into the body of the player on the first tick
+    let tiles = [
+        (Coordinate { x: 3, y: 3 }, Direction::North),
+        (Coordinate { x: 3, y: 2 }, Direction::North),
+    ];
+
+    let rivals = play_versus(board, &tiles, TeamId(1), 3);
+    assert_eq!(rivals, vec![(true, false); 4]);
+
+    let team = play_versus(board, &tiles, TeamId(0), 3);
+    assert_eq!(
+        team,
+        vec![(true, false), (true, true), (true, false), (true, true)]
+    );
+}
+
+#[test]
+fn test_versus_teammates_pass_through_bodies() {
+    let mut world: World<SmallRng, Wrapping> = World::from_ascii(
+        "..........\n.>>>>.....\n..........\n..........\n..........",
+    );
+    world.set_versus(Versus {
+        head_on: HeadOn::LongerWins,
+        pass_through_team: true,
+    });
+    let tiles = [
+        (Coordinate { x: 3, y: 3 }, Direction::North),
+        (Coordinate { x: 3, y: 2 }, Direction::North),
+    ];
+    world.add_snake(&tiles, TeamId(0), rival_heading(Direction::North));
+    let crossing = Coordinate { x: 3, y: 1 };
+
+    // on the tile both are on, the one that went through last shows
+    world.tick(None).unwrap();
+    assert_eq!(world.get_block(crossing).owner(), Some(SnakeId(1)));
+    world.tick(None).unwrap();
+    assert!(world.snake().body().contains(crossing));
+    assert!(world.snakes()[1].body().contains(crossing));
+
+    // the player's tail leaves it to the ally, whose own tail leaves it
+    // empty on the same tick
+    world.tick(None).unwrap();
+    assert!(!world.snake().body().contains(crossing));
+    assert_eq!(world.get_block(crossing), Block::Empty);
+    assert!(world.snakes().iter().all(|snake| snake.is_alive()));
+}
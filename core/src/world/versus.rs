@@ -0,0 +1,100 @@
+use alloc::collections::vec_deque;
+
+use data::Coordinate;
+
+use super::{SnakeId, TeamId};
+
+/// Who loses when two snakes run head first into each other
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum HeadOn {
+    // both die when they are as long as each other
+    LongerWins,
+    BothDie,
+}
+
+/// How the snakes of a world crash into one another, each snake's crashes
+/// into itself and the board aside, see `World::set_versus`
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Versus {
+    pub head_on: HeadOn,
+    /// Snakes of a team go through each other's bodies rather than dying
+    /// on them; their heads still meet
+    pub pass_through_team: bool,
+}
+
+/// A snake as `Versus::resolve` sees it once it moved, `head` the tile it
+/// moved onto and `body` those behind it, its neck first. The body is
+/// walked where the snake keeps it, so that a crash doesn't allocate.
+#[derive(Debug, Clone)]
+pub struct Contender<'a> {
+    pub id: SnakeId,
+    pub team: TeamId,
+    pub head: Coordinate,
+    pub body: vec_deque::Iter<'a, Coordinate>,
+}
+
+impl<'a> Contender<'a> {
+    pub fn length(&self) -> usize {
+        self.body.len() + 1
+    }
+
+    // whether `self` and `other` moved onto the same tile, or swapped
+    // their heads and necks
+    fn meets(&self, other: &Contender) -> bool {
+        let necks = (self.body.clone().next(), other.body.clone().next());
+        let swapped = match necks {
+            (Some(&neck), Some(&other_neck)) => {
+                self.head == other_neck && other.head == neck
+            }
+            _ => false,
+        };
+        self.head == other.head || swapped
+    }
+}
+
+impl Versus {
+    /// The snakes of `snakes` that die of their move, in the order given,
+    /// each once however many snakes it crashed into
+    pub fn resolve<'s>(
+        &'s self,
+        snakes: &'s [Contender<'s>],
+    ) -> impl Iterator<Item = SnakeId> + 's {
+        snakes
+            .iter()
+            .enumerate()
+            .filter(move |&(i, snake)| {
+                snakes
+                    .iter()
+                    .enumerate()
+                    .filter(|&(j, _)| i != j)
+                    .any(|(_, other)| self.kills(other, snake))
+            })
+            .map(|(_, snake)| snake.id)
+    }
+
+    // whether `victim` dies running into `other`
+    fn kills(&self, other: &Contender, victim: &Contender) -> bool {
+        if victim.meets(other) {
+            return match self.head_on {
+                HeadOn::LongerWins => victim.length() <= other.length(),
+                HeadOn::BothDie => true,
+            };
+        }
+
+        let teammates = victim.team == other.team;
+        if teammates && self.pass_through_team {
+            return false;
+        }
+        other.body.clone().any(|&at| at == victim.head)
+    }
+}
+
+// last snake standing on head-on crashes, every snake for itself
+impl Default for Versus {
+    fn default() -> Self {
+        Versus {
+            head_on: HeadOn::LongerWins,
+            pass_through_team: false,
+        }
+    }
+}
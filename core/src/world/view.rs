@@ -0,0 +1,166 @@
+use std::{option, slice};
+
+use data::{Block, Coordinate, Direction, Grid, SmallNat, SnakeId};
+use system::Tick;
+
+use super::Snake;
+
+pub(super) static DIRECTIONS: [Direction; 4] = [
+    Direction::North,
+    Direction::East,
+    Direction::South,
+    Direction::West,
+];
+
+/// A world as it is between two steps, for what only looks at it: the
+/// board, the snakes and the food, the tick and whether the edges wrap
+/// around. Bots pick their moves from one (see `bot::Policy`), and a view
+/// of a `Snapshot` shows the world as it was at some point in the past.
+/// It only borrows the world, so it is as cheap to take as to copy.
+#[derive(Clone, Copy)]
+pub struct WorldView<'a> {
+    grid: &'a Grid,
+    snakes: &'a [Snake],
+    snake: &'a Snake,
+    heading: Direction,
+    food: Option<Coordinate>,
+    tick: Tick,
+    wraps: bool,
+}
+
+impl<'a> WorldView<'a> {
+    // the view of the snake of `id` among `snakes`
+    pub(super) fn new(
+        grid: &'a Grid,
+        snakes: &'a [Snake],
+        id: SnakeId,
+        food: Option<Coordinate>,
+        tick: Tick,
+        wraps: bool,
+    ) -> Self {
+        let snake = &snakes[id.0 as usize];
+        // the head is always a snake block, pointing where it goes next
+        let heading = grid[snake.head()].snake().unwrap_or(Direction::East);
+
+        WorldView {
+            grid,
+            snakes,
+            snake,
+            heading,
+            food,
+            tick,
+            wraps,
+        }
+    }
+
+    pub fn grid(&self) -> &'a Grid {
+        self.grid
+    }
+
+    pub fn width(&self) -> SmallNat {
+        self.grid.width()
+    }
+
+    pub fn height(&self) -> SmallNat {
+        self.grid.height()
+    }
+
+    /// The block at `at`, `OutOfBound` off the board
+    pub fn block(&self, at: Coordinate) -> Block {
+        self.grid[at]
+    }
+
+    /// The snake the world is seen by, the player's unless the view is
+    /// another's (see `World::view_of`)
+    pub fn snake(&self) -> &'a Snake {
+        self.snake
+    }
+
+    /// Every snake of the world, in the order of their ids, dead ones too
+    pub fn snakes(&self) -> slice::Iter<'a, Snake> {
+        self.snakes.iter()
+    }
+
+    pub fn head(&self) -> Coordinate {
+        self.snake.head()
+    }
+
+    /// Where the snake goes if it doesn't turn
+    pub fn heading(&self) -> Direction {
+        self.heading
+    }
+
+    pub fn food(&self) -> Option<Coordinate> {
+        self.food
+    }
+
+    /// Every food on the board
+    pub fn foods(&self) -> option::IntoIter<Coordinate> {
+        self.food.into_iter()
+    }
+
+    /// The tick of the last step, see `Stateful::step`
+    pub fn tick(&self) -> Tick {
+        self.tick
+    }
+
+    pub fn wraps(&self) -> bool {
+        self.wraps
+    }
+
+    /// The tile next to `at` towards `dir`, none past an edge that doesn't
+    /// wrap
+    pub fn ahead(&self, at: Coordinate, dir: Direction) -> Option<Coordinate> {
+        let moved = at.move_towards(dir);
+        let (width, height) = (self.width(), self.height());
+        if self.wraps {
+            Some(moved.wrap_inside(width, height))
+        } else {
+            moved.bound_inside(width, height)
+        }
+    }
+
+    /// Whether the snake can move onto `at` without crashing. Its tail
+    /// isn't open either: the head moves before it does.
+    pub fn is_open(&self, at: Coordinate) -> bool {
+        match self.grid[at] {
+            Block::Empty
+            | Block::Food
+            | Block::Checkpoint
+            | Block::Looming
+            | Block::PowerUp(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Moves the snake can make from its head without turning back, with
+    /// the tile each one moves onto, open or not
+    pub fn moves(&self) -> impl Iterator<Item = (Direction, Coordinate)> + 'a {
+        let back = self.heading.opposite();
+        self.neighbors(self.head()).filter(move |&(dir, _)| dir != back)
+    }
+
+    /// Moves from `at` onto the tiles next to it, the edges permitting
+    pub fn neighbors(
+        &self,
+        at: Coordinate,
+    ) -> impl Iterator<Item = (Direction, Coordinate)> + 'a {
+        let view = *self;
+        DIRECTIONS
+            .iter()
+            .filter_map(move |&dir| view.ahead(at, dir).map(|at| (dir, at)))
+    }
+
+    /// The fewest moves from `from` to `to`, ignoring what is in between
+    pub fn distance(&self, from: Coordinate, to: Coordinate) -> u32 {
+        let span = |a: SmallNat, b: SmallNat, size: SmallNat| {
+            let d = (a as i32 - b as i32).abs() as u32;
+            if self.wraps {
+                ::std::cmp::min(d, size as u32 - d)
+            } else {
+                d
+            }
+        };
+        span(from.x, to.x, self.width()) + span(from.y, to.y, self.height())
+    }
+}
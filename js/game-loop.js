@@ -117,7 +117,7 @@ export class Keybox {
 }
 
 export class GameLoop {
-  _keybox: Keybox;
+  _keybox: ?Keybox;
 
   _onEnterFrame: Function;
   _rafId: ?AnimationFrameID;
@@ -140,11 +140,14 @@ export class GameLoop {
     this._onKeyDown = this._onKeyDown.bind(this);
     this._onKeyUp = this._onKeyUp.bind(this);
 
-    this._keybox = new Keybox();
+    // the on screen keybox is optional for embedding pages
+    if (document.querySelector(keyboxSelector)) {
+      this._keybox = new Keybox();
 
-    const unlisten = this._keybox.addKeyLisitener(this._onKeybox.bind(this));
+      const unlisten = this._keybox.addKeyLisitener(this._onKeybox.bind(this));
 
-    this._unlistens.push(unlisten);
+      this._unlistens.push(unlisten);
+    }
   }
 
   running(): boolean {
@@ -224,6 +227,10 @@ export class GameLoop {
     switch (e.keyCode) {
       case 13:
       //case 27:
+      case 80: // p, pause
+      case 192: // `, debug toggle
+      case 190: // ., step once
+      case 191: // /, fast forward
       case 37:
       case 38:
       case 39:
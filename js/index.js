@@ -2,6 +2,59 @@ import styles from './styles.css';
 
 const js = import("./snake_wasm");
 
+const removeGame = game => {
+  if (game) {
+    game.stop();
+    game.free();
+  }
+  document
+    .querySelectorAll(
+      'canvas, .snake-prompt, .snake-attract, .snake-hud, .snake-announcer'
+    )
+    .forEach(element => element.remove());
+};
+
 js.then(js => {
-  js.main();
+  const locale = js.browser_locale();
+  document.querySelectorAll('.demo-menu option').forEach(option => {
+    option.textContent = js.translate(js.Text[option.dataset.text], locale);
+  });
+
+  const library = new js.ReplayLibrary();
+  const replayMenu = document.querySelector('.replay-menu');
+
+  const demos = {
+    snake: js => js.main(),
+    life: js => js.life(new js.GameConfig()),
+    blocks: js => js.blocks(new js.GameConfig()),
+    // nothing runs until a replay is picked from the replay menu
+    replays: js => {
+      library.list().then(names => {
+        replayMenu.innerHTML = '<option value=""></option>';
+        names.forEach(name => replayMenu.add(new Option(name, name)));
+        replayMenu.hidden = false;
+      });
+      return null;
+    },
+  };
+
+  let game = demos.snake(js);
+
+  document.querySelector('.demo-menu').addEventListener('change', event => {
+    removeGame(game);
+    replayMenu.hidden = true;
+
+    game = demos[event.target.value](js);
+  });
+
+  replayMenu.addEventListener('change', event => {
+    removeGame(game);
+    game = null;
+
+    library.load(event.target.value).then(bytes => {
+      if (bytes) {
+        game = js.play_replay(bytes, new js.GameConfig());
+      }
+    });
+  });
 });
@@ -1,3 +1,253 @@
 /* tslint:disable */
-export function main(): void;
+export function main(): GameHandle;
 
+export function new_game(arg0: GameConfig): GameHandle;
+
+export function daily_challenge(): GameHandle;
+
+export function browser_locale(): Locale;
+
+export function browser_motion(): MotionPolicy;
+
+export function translate(arg0: Text, arg1: Locale): string;
+
+export function init(arg0: any): GameHandle;
+
+export function life(arg0: GameConfig): DemoHandle;
+
+export function blocks(arg0: GameConfig): DemoHandle;
+
+export function play_replay(arg0: Uint8Array, arg1: GameConfig): DemoHandle;
+
+export enum Direction {North,South,East,West,}
+export enum Color {Red,Black,Gray,}
+export enum MotionPolicy {Full,Reduced,}
+export enum Strategy {Greedy,AStar,Hamiltonian,Random,}
+export enum GameState {Playing,Paused,Over,}
+export enum Tile {Empty,Snake,Food,Obstacle,Checkpoint,Looming,PowerUp,}
+export enum ErrorPhase {Setup,Model,Render,}
+export enum EventKind {Started,FoodEaten,FoodSpawned,InvalidInput,Paused,Resumed,Died,AchievementUnlocked,Combo,ComboLost,TeamLost,PowerUpTaken,QualityChanged,GameOver,Won,}
+export type EventPayload = {x: number, y: number, length?: number} | {dir: Direction} | {reason: DeathReason, seed: number} | {seed: number} | {achievement: Achievement} | {multiplier: number, points: number} | {team: number, score: number} | {tier: QualityTier} | GameSummary;
+export type GameSummary = {duration: number, ticks: number, length: number, food: number, cause: DeathReason, coverage_percent: number};
+export enum LogLevel {Trace,Debug,Info,Warn,Off,}
+export enum DeathReason {OutOfBound,CollideBody,CollideObstacle,TimeUp,}
+export enum Achievement {Gourmet,Survivor,Hoarder,}
+export enum Spawn {UniformRandom,AwayFromHead,NearWalls,Scripted,}
+export enum Renderer {Animated,Snapped,}
+export enum Background {Solid,Checkerboard,Image,}
+export enum Palette {Standard,HighContrast,ColorBlind,}
+export enum PowerSaving {Off,On,Auto,}
+export enum QualityTier {Low,Medium,High,}
+export enum Locale {En,Fr,}
+export enum Text {GameOver,Time,Lives,Magnet,Team,PressDown,PressRight,HoldToSprint,WellDone,Snake,Life,Blocks,Replays,Length,FoodEaten,Coverage,OutOfBound,CollideBody,CollideObstacle,TimeUp,FoodEatenLength,GameOverScore,Started,Paused,Resumed,Won,PressAnyKey,}
+
+export class GameConfig {
+free(): void;
+width: number
+height: number
+seed: number
+spawn: Spawn
+tutorial: boolean
+shrink_every: number
+time_budget: number
+time_bonus: number
+hazard_every: number
+laser_every: number
+magnet_every: number
+grace: boolean
+lives: number
+team: boolean
+autopilot: Strategy
+announce: boolean
+haptics: boolean
+timeline_len: number
+frame_skip: boolean
+power_saving: PowerSaving
+adaptive_quality: boolean
+constructor();
+
+set_food_script(arg0: Uint16Array): void;
+
+set_checkpoints(arg0: Uint16Array): void;
+
+set_leaderboard(arg0: string): void;
+
+set_palette(arg0: Palette): void;
+
+set_pixel_art(arg0: boolean): void;
+
+set_pixelated(arg0: boolean): void;
+
+set_shapes(arg0: boolean): void;
+
+tile_to_client(arg0: number, arg1: number): Float64Array;
+
+on_resize(arg0: (width: number, height: number) => void): void;
+
+set_crt(arg0: boolean): void;
+
+set_motion(arg0: MotionPolicy): void;
+
+set_locale(arg0: Locale): void;
+
+set_wrap(arg0: boolean): void;
+
+set_allow_reverse(arg0: boolean): void;
+
+set_growth(arg0: number): void;
+
+set_diagonal(arg0: boolean): void;
+
+set_ghost(arg0: boolean): void;
+
+set_speed_curve(arg0: number, arg1: number): void;
+
+set_opponent(arg0: Strategy): void;
+
+set_background(arg0: Background): void;
+
+set_board_colors(arg0: string, arg1: string): void;
+
+set_grid(arg0: boolean): void;
+
+set_grid_style(arg0: string, arg1: number): void;
+
+set_major_grid(arg0: number, arg1: string): void;
+
+set_background_image(arg0: string): void;
+
+}
+export class GameHandle {
+free(): void;
+start(): boolean;
+
+stop(): boolean;
+
+set_direction(arg0: Direction): void;
+
+pause(): void;
+
+step_once(): void;
+
+run_ticks(arg0: number): void;
+
+run_continuously(): void;
+
+skip_lagging_frames(arg0: boolean): void;
+
+idle_frames(): number;
+
+is_stepping(): boolean;
+
+restart(): void;
+
+state(): GameState;
+
+width(): number;
+
+height(): number;
+
+time_left(): number | undefined;
+
+observation(): Uint8Array;
+
+on_event(arg0: (kind: EventKind, payload: EventPayload | null) => void): void;
+
+on_error(arg0: (phase: ErrorPhase, message: string) => void): void;
+
+games_played(): number;
+
+total_food(): number;
+
+average_length(): number;
+
+max_length(): number;
+
+deaths(arg0: DeathReason): number;
+
+rating(): number;
+
+suggested_opponent(): Strategy;
+
+heatmap(): Uint32Array;
+
+export_telemetry(): string;
+
+game_summary(): GameSummary | null;
+
+show_heatmap(arg0: boolean): void;
+
+quality_tier(): QualityTier;
+
+set_renderer(arg0: Renderer): void;
+
+renderer(): Renderer;
+
+set_crt(arg0: boolean): void;
+
+set_theme(arg0: GameConfig): void;
+
+save_settings(): void;
+
+is_unlocked(arg0: Achievement): boolean;
+
+score(): number;
+
+combo(): number;
+
+screenshot(): Promise<Blob>;
+
+export_clip(): Promise<Blob>;
+
+history(): Array<{tick: number, length: number, tiles: Uint8Array, updates: Array<string>}>;
+
+timeline(): Array<{tick: number, kind: EventKind, payload: EventPayload | null}>;
+
+jump_to(arg0: number): boolean;
+
+replay(): Uint8Array;
+
+replay_hash(): number;
+
+list_replays(): Promise<string[]>;
+
+save_replay(arg0: string): Promise<any>;
+
+load_replay(arg0: string): Promise<Uint8Array | undefined>;
+
+delete_replay(arg0: string): Promise<any>;
+
+submit_score(arg0: string, arg1: number, arg2: number, arg3: (error: string | null) => void): void;
+
+top_scores(arg0: number, arg1: (error: string | null, scores?: Array<{name: string, score: number, seed: number, replay_hash: number}>) => void): void;
+
+set_log_level(arg0: LogLevel): void;
+
+log_level(): LogLevel;
+
+}
+export class ReplayLibrary {
+free(): void;
+constructor();
+
+list(): Promise<string[]>;
+
+save(arg0: string, arg1: Uint8Array): Promise<any>;
+
+load(arg0: string): Promise<Uint8Array | undefined>;
+
+delete(arg0: string): Promise<any>;
+
+}
+export class DemoHandle {
+free(): void;
+
+start(): boolean;
+
+stop(): boolean;
+
+pause(): void;
+
+restart(): void;
+
+}
@@ -1,19 +1,34 @@
 use piston_window::Key;
-use snake_wasm::data::Direction;
-use snake_wasm::CtrlEvent;
+use snake_core::data::{Command, Direction};
+use snake_core::CtrlEvent;
 
 #[derive(Copy, Clone, Eq, PartialEq)]
 pub struct KeyWrapper {
     key: Key,
+    held: bool,
 }
-impl Into<Option<Direction>> for KeyWrapper {
-    fn into(self) -> Option<Direction> {
-        match self.key {
-            Key::Up => Some(Direction::North),
-            Key::Down => Some(Direction::South),
-            Key::Left => Some(Direction::West),
-            Key::Right => Some(Direction::East),
-            _ => None,
+impl KeyWrapper {
+    // a press arriving before the key is released is a key repeat
+    pub fn held(key: Key) -> Self {
+        KeyWrapper { key, held: true }
+    }
+}
+impl Into<Option<Command>> for KeyWrapper {
+    fn into(self) -> Option<Command> {
+        let dir = match self.key {
+            Key::Up => Direction::North,
+            Key::Down => Direction::South,
+            Key::Left => Direction::West,
+            Key::Right => Direction::East,
+            Key::P if !self.held => return Some(Command::Pause),
+            Key::R if !self.held => return Some(Command::Restart),
+            _ => return None,
+        };
+
+        if self.held {
+            Some(Command::Sprint(dir))
+        } else {
+            Some(Command::Turn(dir))
         }
     }
 }
@@ -25,6 +40,6 @@ impl Into<Option<CtrlEvent>> for KeyWrapper {
 
 impl From<Key> for KeyWrapper {
     fn from(key: Key) -> Self {
-        KeyWrapper { key }
+        KeyWrapper { key, held: false }
     }
 }
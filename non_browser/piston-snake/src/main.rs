@@ -1,19 +1,19 @@
 #![feature(generators, generator_trait)]
 
 extern crate piston_window;
-extern crate snake_wasm;
+extern crate snake_core;
 extern crate vec_map;
 
 use std::ops::Generator;
 
 use piston_window::*;
 
-use snake_wasm::acceleration::{RenderSpeed, VariableFrame};
-use snake_wasm::data::{Direction, Wrapping};
-use snake_wasm::dead::Dead;
-use snake_wasm::system::{Either, Stateful};
-use snake_wasm::world::{World, WorldBuilder};
-use snake_wasm::{SmallRng, WorldUpdate, WorldUpdateDraw};
+use snake_core::acceleration::{RenderSpeed, VariableFrame};
+use snake_core::data::{Direction, Wrapping};
+use snake_core::dead::Dead;
+use snake_core::system::{Either, Stateful};
+use snake_core::world::{World, WorldBuilder};
+use snake_core::{SmallRng, TickDelta, TickDraw};
 
 use self::channel::channel;
 use self::constants::*;
@@ -68,9 +68,10 @@ fn main() {
         .make_game(TileUpdate::new(tx));
 
     let (tx, mut generator) = game
-        .new_game::<WorldUpdateDraw<VariableFrame<WorldUpdate>>, KeyWrapper>();
+        .new_game::<TickDraw<VariableFrame<TickDelta>>, KeyWrapper>();
 
     let mut tiles = Tiles::new();
+    let mut pressed: Option<Key> = None;
 
     let mut logic_tick = throttle(1.0 / 60.0, move |_event: &Event| unsafe {
         generator.resume();
@@ -78,7 +79,17 @@ fn main() {
 
     while let Some(event) = window.next() {
         if let Some(Button::Keyboard(key)) = event.press_args() {
-            tx.send(KeyWrapper::from(key));
+            if pressed == Some(key) {
+                tx.send(KeyWrapper::held(key));
+            } else {
+                pressed = Some(key);
+                tx.send(KeyWrapper::from(key));
+            }
+        }
+        if let Some(Button::Keyboard(key)) = event.release_args() {
+            if pressed == Some(key) {
+                pressed = None;
+            }
         }
 
         logic_tick.run(&event);
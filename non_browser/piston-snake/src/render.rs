@@ -1,8 +1,8 @@
 use piston_window::types::Color;
 use piston_window::Rectangle;
 
-use snake_wasm::data::{Coordinate, Direction, SmallNat};
-use snake_wasm::{
+use snake_core::data::{Coordinate, Direction, SmallNat};
+use snake_core::{
     partial_tile, Color as GameColor, DrawGrid, Either, UnitInterval,
 };
 
@@ -138,6 +138,7 @@ impl TileUpdate {
         match self.game_color {
             GameColor::Black => [0.13, 0.13, 0.13, 1.0],
             GameColor::Red => [0.95, 0.04, 0.04, 1.0],
+            GameColor::Gray => [0.53, 0.53, 0.53, 1.0],
         }
     }
 }
@@ -1,7 +1,7 @@
 use std::io::Read;
 
-use snake_wasm::data::Direction;
-use snake_wasm::CtrlEvent;
+use snake_core::data::{Command, Direction};
+use snake_core::CtrlEvent;
 
 #[derive(Copy, Clone, Eq, PartialEq)]
 pub struct Key {
@@ -26,20 +26,23 @@ impl Key {
 impl Into<Option<CtrlEvent>> for Key {
     fn into(self) -> Option<CtrlEvent> {
         match self.byte[0] {
-            b' ' => Some(CtrlEvent::StartGame),
+            b' ' | b'r' => Some(CtrlEvent::StartGame),
             b'q' => Some(CtrlEvent::QuitGame),
             _ => None,
         }
     }
 }
 
-impl Into<Option<Direction>> for Key {
-    fn into(self) -> Option<Direction> {
+impl Into<Option<Command>> for Key {
+    fn into(self) -> Option<Command> {
         match self.byte[0] {
-            b'k' | b'w' => Some(Direction::North),
-            b'j' | b's' => Some(Direction::South),
-            b'h' | b'a' => Some(Direction::West),
-            b'l' | b'd' => Some(Direction::East),
+            b'k' | b'w' => Some(Command::Turn(Direction::North)),
+            b'j' | b's' => Some(Command::Turn(Direction::South)),
+            b'h' | b'a' => Some(Command::Turn(Direction::West)),
+            b'l' | b'd' => Some(Command::Turn(Direction::East)),
+            b'p' => Some(Command::Pause),
+            b'r' => Some(Command::Restart),
+            b'q' => Some(Command::Quit),
             _ => None,
         }
     }
@@ -1,20 +1,23 @@
 #![feature(generators, generator_trait)]
 
-extern crate snake_wasm;
+extern crate snake_core;
 extern crate termion;
 
+use std::env;
 use std::io::stdout;
 use std::ops::{Generator, GeneratorState};
+use std::process;
 use std::thread::sleep;
 use std::time::{Duration, Instant};
 
 use termion::async_stdin;
 
-use snake_wasm::data::{Direction, Wrapping};
-use snake_wasm::dead::Dead;
-use snake_wasm::system::Stateful;
-use snake_wasm::world::{World, WorldBuilder};
-use snake_wasm::{SmallRng, WorldUpdateDraw};
+use snake_core::config::Config;
+use snake_core::data::{Direction, Wrapping};
+use snake_core::dead::Dead;
+use snake_core::system::Stateful;
+use snake_core::world::{World, WorldBuilder};
+use snake_core::{SmallRng, TickDraw};
 
 use self::key::Key;
 use self::term_env::TermEnv;
@@ -22,14 +25,20 @@ use self::term_env::TermEnv;
 mod key;
 mod term_env;
 
-fn main() {
-    let stdout = stdout();
-    let term_env = TermEnv::wrap(stdout.lock());
-
-    let mut stdin = async_stdin();
+// the world of the config file given as the first argument, if one is
+fn world() -> World<SmallRng, Wrapping> {
+    if let Some(path) = env::args().nth(1) {
+        match Config::from_file(&path) {
+            Ok(config) => return config.world(),
+            Err(err) => {
+                eprintln!("{}: {}", path, err);
+                process::exit(2);
+            }
+        }
+    }
 
     let facing = Direction::East;
-    let world: World<SmallRng, Wrapping> = WorldBuilder::new()
+    WorldBuilder::new()
         .width(48)
         .height(24)
         .set_snake(1, 1)
@@ -37,11 +46,20 @@ fn main() {
         .extend(facing)
         .extend(facing)
         .extend(facing)
-        .build_with_seed([123; 16]);
+        .build_with_seed([123; 16])
+}
+
+fn main() {
+    let world = world();
+
+    let stdout = stdout();
+    let term_env = TermEnv::wrap(stdout.lock());
+
+    let mut stdin = async_stdin();
 
     let game = world.alternating::<Key, _>(Dead::new()).make_game(term_env);
 
-    let (tx, mut generator) = game.new_game::<WorldUpdateDraw, Key>();
+    let (tx, mut generator) = game.new_game::<TickDraw, Key>();
     let interval = Duration::from_millis(16);
 
     let mut before = Instant::now();
@@ -3,8 +3,8 @@ use std::io::{StdoutLock, Write};
 use termion::raw::{IntoRawMode, RawTerminal};
 use termion::{clear, cursor};
 
-use snake_wasm::data::{Direction, SmallNat};
-use snake_wasm::{Color as GameColor, DrawGrid, UnitInterval};
+use snake_core::data::{Direction, SmallNat};
+use snake_core::{Color as GameColor, DrawGrid, UnitInterval};
 
 pub struct TermEnv<W: Write> {
     stdout: W,
@@ -51,7 +51,7 @@ impl<W: Write> DrawGrid for TermEnv<W> {
 
         writeln!(
             self.stdout,
-            "{}Movements: h,j,k,l Quit: q Restart: Space",
+            "{}Movements: h,j,k,l Pause: p Restart: r Quit: q",
             cursor::Goto(1, self.height + 2),
         )
         .unwrap();
@@ -85,7 +85,9 @@ impl<W: Write> DrawGrid for TermEnv<W> {
         dir: Direction,
         size: UnitInterval,
     ) {
-        let c = if size.scale(10.0) > 7.5 {
+        let c = if self.color == GameColor::Gray {
+            '#'
+        } else if size.scale(10.0) > 7.5 {
             'o'
         } else {
             match dir {
@@ -108,4 +110,30 @@ impl<W: Write> DrawGrid for TermEnv<W> {
     }
 
     fn show_game_over(&mut self) {}
+
+    fn invalid_input(&mut self, _dir: Direction) {
+        // terminal bell
+        write!(self.stdout, "\x07").unwrap();
+    }
+
+    fn show_text(&mut self, text: &str) {
+        write!(
+            self.stdout,
+            "{}{}{}",
+            cursor::Goto(1, self.height + 3),
+            clear::CurrentLine,
+            text,
+        )
+        .unwrap();
+    }
+
+    fn hide_text(&mut self) {
+        write!(
+            self.stdout,
+            "{}{}",
+            cursor::Goto(1, self.height + 3),
+            clear::CurrentLine,
+        )
+        .unwrap();
+    }
 }
@@ -0,0 +1,12 @@
+pub const BACKGROUND_COLOR: [u8; 4] = [238, 238, 238, 255];
+pub const SNAKE_COLOR: [u8; 4] = [34, 34, 34, 255];
+pub const FOOD_COLOR: [u8; 4] = [240, 10, 10, 255];
+pub const OBSTACLE_COLOR: [u8; 4] = [136, 136, 136, 255];
+
+pub const TILE_SIZE: u32 = 16;
+pub const WIDTH: u32 = 48;
+pub const HEIGHT: u32 = 32;
+
+/// Ticks of a headless run
+#[cfg(not(feature = "native-window"))]
+pub const HEADLESS_TICKS: u32 = 100_000;
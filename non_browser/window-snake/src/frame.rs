@@ -0,0 +1,165 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use snake_core::data::{Direction, SmallNat};
+use snake_core::{partial_tile, Color as GameColor, DrawGrid, UnitInterval};
+
+use crate::constants::*;
+
+/// RGBA pixels of the whole window, shared between the env the game draws
+/// into and the window which presents it
+pub struct Frame {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl Frame {
+    pub fn new(width: u32, height: u32) -> Self {
+        let mut frame = Frame {
+            width,
+            height,
+            pixels: vec![0; (width * height * 4) as usize],
+        };
+        frame.fill(BACKGROUND_COLOR);
+        frame
+    }
+
+    // only presented by the window
+    #[cfg_attr(not(feature = "native-window"), allow(dead_code))]
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    fn fill(&mut self, color: [u8; 4]) {
+        for pixel in self.pixels.chunks_mut(4) {
+            pixel.copy_from_slice(&color);
+        }
+    }
+
+    fn put(&mut self, x: u32, y: u32, color: [u8; 4]) {
+        if x < self.width && y < self.height {
+            let i = ((y * self.width + x) * 4) as usize;
+            self.pixels[i..i + 4].copy_from_slice(&color);
+        }
+    }
+
+    fn fill_rect(&mut self, x: f64, y: f64, w: f64, h: f64, color: [u8; 4]) {
+        let (x, y) = (x.round() as u32, y.round() as u32);
+        let (w, h) = (w.round() as u32, h.round() as u32);
+
+        for py in y..y + h {
+            for px in x..x + w {
+                self.put(px, py, color);
+            }
+        }
+    }
+
+    fn darken(&mut self) {
+        for pixel in self.pixels.chunks_mut(4) {
+            for channel in &mut pixel[..3] {
+                *channel /= 2;
+            }
+        }
+    }
+}
+
+pub struct FrameEnv {
+    frame: Rc<RefCell<Frame>>,
+    tile_size: f64,
+    game_color: GameColor,
+}
+
+impl FrameEnv {
+    pub fn new(frame: Rc<RefCell<Frame>>) -> Self {
+        FrameEnv {
+            frame,
+            tile_size: TILE_SIZE as f64,
+            game_color: GameColor::Black,
+        }
+    }
+
+    #[inline]
+    fn color(&self) -> [u8; 4] {
+        match self.game_color {
+            GameColor::Black => SNAKE_COLOR,
+            GameColor::Red => FOOD_COLOR,
+            GameColor::Gray => OBSTACLE_COLOR,
+        }
+    }
+}
+
+impl DrawGrid for FrameEnv {
+    fn setup(
+        &mut self,
+        _tile_size: SmallNat,
+        _width: SmallNat,
+        _height: SmallNat,
+    ) {
+        // the window is sized from the constants, not by the game
+        self.clear();
+    }
+
+    fn clear(&mut self) {
+        self.frame.borrow_mut().fill(BACKGROUND_COLOR);
+    }
+
+    fn set_fill_color(&mut self, color: GameColor) -> GameColor {
+        let prev_color = self.game_color;
+        self.game_color = color;
+
+        prev_color
+    }
+
+    fn circle(&mut self, x: SmallNat, y: SmallNat, radius: UnitInterval) {
+        let tile_size = self.tile_size;
+        let color = self.color();
+
+        let cx = x as f64 * tile_size + tile_size / 2.0;
+        let cy = y as f64 * tile_size + tile_size / 2.0;
+        let r = radius.scale(tile_size) * 0.5;
+
+        let mut frame = self.frame.borrow_mut();
+        let (x0, y0) = (x as u32 * TILE_SIZE, y as u32 * TILE_SIZE);
+
+        for py in y0..y0 + TILE_SIZE {
+            for px in x0..x0 + TILE_SIZE {
+                // distance from the center of the pixel
+                let dx = px as f64 + 0.5 - cx;
+                let dy = py as f64 + 0.5 - cy;
+                if dx * dx + dy * dy <= r * r {
+                    frame.put(px, py, color);
+                }
+            }
+        }
+    }
+
+    fn fill_tile(
+        &mut self,
+        x: SmallNat,
+        y: SmallNat,
+        dir: Direction,
+        size: UnitInterval,
+    ) {
+        let (x, y, w, h) = partial_tile(self.tile_size, x, y, dir, size);
+        let color = self.color();
+
+        self.frame.borrow_mut().fill_rect(x, y, w, h, color);
+    }
+
+    fn clear_tile(
+        &mut self,
+        x: SmallNat,
+        y: SmallNat,
+        dir: Direction,
+        size: UnitInterval,
+    ) {
+        let (x, y, w, h) = partial_tile(self.tile_size, x, y, dir, size);
+
+        self.frame.borrow_mut().fill_rect(x, y, w, h, BACKGROUND_COLOR);
+    }
+
+    fn show_game_over(&mut self) {
+        self.frame.borrow_mut().darken();
+    }
+}
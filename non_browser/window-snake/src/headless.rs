@@ -0,0 +1,51 @@
+use std::cell::RefCell;
+use std::ops::{Generator, GeneratorState};
+use std::rc::Rc;
+use std::time::Instant;
+
+use snake_core::acceleration::{RenderSpeed, VariableFrame};
+use snake_core::data::{Command, Wrapping};
+use snake_core::dead::Dead;
+use snake_core::system::Stateful;
+use snake_core::world::World;
+use snake_core::{SmallRng, TickDelta, TickDraw};
+
+use crate::constants::*;
+use crate::frame::{Frame, FrameEnv};
+use crate::FACING;
+
+/// Runs the same game loop as the window without presenting the frames,
+/// turning now and then so that the snake eats, dies and restarts
+pub fn run(world: World<SmallRng, Wrapping>, frame: Rc<RefCell<Frame>>) {
+    let game = world
+        .zip_with(RenderSpeed::new(FACING), VariableFrame::pack)
+        .alternating::<Command, _>(Dead::new())
+        .make_game(FrameEnv::new(frame));
+
+    let (tx, mut generator) = game
+        .new_game::<TickDraw<VariableFrame<TickDelta>>, Command>();
+
+    let mut dir = FACING;
+    let start = Instant::now();
+
+    for tick in 0..HEADLESS_TICKS {
+        if tick % 97 == 0 {
+            dir = dir.turn_right();
+            tx.send(Command::Turn(dir));
+        }
+
+        unsafe {
+            if let GeneratorState::Complete(_) = generator.resume() {
+                break;
+            }
+        }
+    }
+
+    let elapsed = start.elapsed();
+    println!(
+        "{} ticks in {:?} ({:?} per tick)",
+        HEADLESS_TICKS,
+        elapsed,
+        elapsed / HEADLESS_TICKS,
+    );
+}
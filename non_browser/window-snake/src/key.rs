@@ -0,0 +1,46 @@
+use winit::event::VirtualKeyCode;
+
+use snake_core::data::{Command, Direction};
+use snake_core::CtrlEvent;
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub struct KeyWrapper {
+    key: VirtualKeyCode,
+    held: bool,
+}
+impl KeyWrapper {
+    // winit repeats the press of a key which is held down
+    pub fn held(key: VirtualKeyCode) -> Self {
+        KeyWrapper { key, held: true }
+    }
+}
+impl Into<Option<Command>> for KeyWrapper {
+    fn into(self) -> Option<Command> {
+        let dir = match self.key {
+            VirtualKeyCode::Up | VirtualKeyCode::K => Direction::North,
+            VirtualKeyCode::Down | VirtualKeyCode::J => Direction::South,
+            VirtualKeyCode::Left | VirtualKeyCode::H => Direction::West,
+            VirtualKeyCode::Right | VirtualKeyCode::L => Direction::East,
+            VirtualKeyCode::P if !self.held => return Some(Command::Pause),
+            VirtualKeyCode::R if !self.held => return Some(Command::Restart),
+            _ => return None,
+        };
+
+        if self.held {
+            Some(Command::Sprint(dir))
+        } else {
+            Some(Command::Turn(dir))
+        }
+    }
+}
+impl Into<Option<CtrlEvent>> for KeyWrapper {
+    fn into(self) -> Option<CtrlEvent> {
+        Some(CtrlEvent::StartGame)
+    }
+}
+
+impl From<VirtualKeyCode> for KeyWrapper {
+    fn from(key: VirtualKeyCode) -> Self {
+        KeyWrapper { key, held: false }
+    }
+}
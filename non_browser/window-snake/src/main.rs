@@ -0,0 +1,48 @@
+#![feature(generators, generator_trait)]
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use snake_core::data::{Direction, Wrapping};
+use snake_core::world::{World, WorldBuilder};
+use snake_core::SmallRng;
+
+use self::constants::*;
+use self::frame::Frame;
+
+mod constants;
+mod frame;
+#[cfg(not(feature = "native-window"))]
+mod headless;
+#[cfg(feature = "native-window")]
+mod key;
+#[cfg(feature = "native-window")]
+mod window;
+
+pub const FACING: Direction = Direction::East;
+
+pub fn new_world() -> World<SmallRng, Wrapping> {
+    WorldBuilder::new()
+        .width(WIDTH as u16)
+        .height(HEIGHT as u16)
+        .set_snake(1, 1)
+        .extend(FACING)
+        .extend(FACING)
+        .extend(FACING)
+        .extend(FACING)
+        .build_with_seed([123; 16])
+}
+
+pub fn new_frame() -> Rc<RefCell<Frame>> {
+    Rc::new(RefCell::new(Frame::new(WIDTH * TILE_SIZE, HEIGHT * TILE_SIZE)))
+}
+
+#[cfg(feature = "native-window")]
+fn main() {
+    window::run(new_world(), new_frame());
+}
+
+#[cfg(not(feature = "native-window"))]
+fn main() {
+    headless::run(new_world(), new_frame());
+}
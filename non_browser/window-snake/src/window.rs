@@ -0,0 +1,112 @@
+use std::cell::RefCell;
+use std::ops::Generator;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use pixels::{Pixels, SurfaceTexture};
+use winit::dpi::LogicalSize;
+use winit::event::{
+    ElementState, Event, KeyboardInput, VirtualKeyCode, WindowEvent,
+};
+use winit::event_loop::{ControlFlow, EventLoop};
+use winit::window::WindowBuilder;
+
+use snake_core::acceleration::{RenderSpeed, VariableFrame};
+use snake_core::data::Wrapping;
+use snake_core::dead::Dead;
+use snake_core::system::Stateful;
+use snake_core::world::World;
+use snake_core::{SmallRng, TickDelta, TickDraw};
+
+use crate::constants::*;
+use crate::frame::{Frame, FrameEnv};
+use crate::key::KeyWrapper;
+use crate::FACING;
+
+const TICK: Duration = Duration::from_micros(16_667);
+
+pub fn run(world: World<SmallRng, Wrapping>, frame: Rc<RefCell<Frame>>) {
+    let width = WIDTH * TILE_SIZE;
+    let height = HEIGHT * TILE_SIZE;
+
+    let event_loop = EventLoop::new();
+    let window = WindowBuilder::new()
+        .with_title("Snake")
+        .with_inner_size(LogicalSize::new(width as f64, height as f64))
+        .with_resizable(false)
+        .build(&event_loop)
+        .unwrap();
+
+    let size = window.inner_size();
+    let surface = SurfaceTexture::new(size.width, size.height, &window);
+    let mut pixels = Pixels::new(width, height, surface).unwrap();
+
+    let game = world
+        .zip_with(RenderSpeed::new(FACING), VariableFrame::pack)
+        .alternating::<KeyWrapper, _>(Dead::new())
+        .make_game(FrameEnv::new(frame.clone()));
+
+    let (tx, mut generator) = game
+        .new_game::<TickDraw<VariableFrame<TickDelta>>, KeyWrapper>();
+
+    let mut pressed: Option<VirtualKeyCode> = None;
+    let mut last_tick = Instant::now();
+
+    event_loop.run(move |event, _, control_flow| {
+        *control_flow = ControlFlow::Poll;
+
+        match event {
+            Event::WindowEvent { event, .. } => match event {
+                WindowEvent::CloseRequested => {
+                    *control_flow = ControlFlow::Exit;
+                }
+                WindowEvent::KeyboardInput {
+                    input:
+                        KeyboardInput {
+                            virtual_keycode: Some(key),
+                            state,
+                            ..
+                        },
+                    ..
+                } => match state {
+                    ElementState::Pressed if key == VirtualKeyCode::Escape => {
+                        *control_flow = ControlFlow::Exit;
+                    }
+                    ElementState::Pressed => {
+                        if pressed == Some(key) {
+                            tx.send(KeyWrapper::held(key));
+                        } else {
+                            pressed = Some(key);
+                            tx.send(KeyWrapper::from(key));
+                        }
+                    }
+                    ElementState::Released => {
+                        if pressed == Some(key) {
+                            pressed = None;
+                        }
+                    }
+                },
+                _ => {}
+            },
+            Event::MainEventsCleared => {
+                // the game loop is drawn at a fixed rate, whatever the rate
+                // of the display
+                if last_tick.elapsed() >= TICK {
+                    last_tick = Instant::now();
+                    unsafe {
+                        generator.resume();
+                    }
+                    window.request_redraw();
+                }
+            }
+            Event::RedrawRequested(_) => {
+                pixels.get_frame().copy_from_slice(frame.borrow().pixels());
+
+                if pixels.render().is_err() {
+                    *control_flow = ControlFlow::Exit;
+                }
+            }
+            _ => {}
+        }
+    });
+}
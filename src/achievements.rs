@@ -0,0 +1,268 @@
+use alloc::rc::Rc;
+use std::cell::RefCell;
+
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "achievements")]
+use web_sys::Storage;
+
+#[cfg(feature = "achievements")]
+use constants::{
+    ACHIEVEMENT_FILL_PERCENT, ACHIEVEMENT_FOOD, ACHIEVEMENT_TICKS,
+};
+#[cfg(feature = "achievements")]
+use data::Block;
+use events::EventBus;
+#[cfg(feature = "achievements")]
+use events::GameEvent;
+use system::Observer;
+use world::{UpdateError, WorldUpdate, PLAYER};
+
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Achievement {
+    // eat ACHIEVEMENT_FOOD food in one game
+    Gourmet = 0,
+    // survive ACHIEVEMENT_TICKS moves in one game
+    Survivor = 1,
+    // cover ACHIEVEMENT_FILL_PERCENT of the board
+    Hoarder = 2,
+}
+
+impl Achievement {
+    pub const ALL: [Achievement; 3] = [
+        Achievement::Gourmet,
+        Achievement::Survivor,
+        Achievement::Hoarder,
+    ];
+
+    #[cfg(feature = "achievements")]
+    #[inline(always)]
+    fn bit(self) -> u32 {
+        1 << (self as u32)
+    }
+}
+
+// what the current game has done so far
+#[cfg(feature = "achievements")]
+#[derive(Debug, Copy, Clone, Default)]
+struct Progress {
+    food: u32,
+    ticks: u32,
+    length: u32,
+    area: u32,
+}
+
+#[cfg(feature = "achievements")]
+impl Progress {
+    fn satisfies(&self, achievement: Achievement) -> bool {
+        match achievement {
+            Achievement::Gourmet => self.food >= ACHIEVEMENT_FOOD,
+            Achievement::Survivor => self.ticks >= ACHIEVEMENT_TICKS,
+            Achievement::Hoarder => {
+                let filled = self.length * 100;
+                self.area > 0 && filled >= self.area * ACHIEVEMENT_FILL_PERCENT
+            }
+        }
+    }
+}
+
+/// Observes a `World` and unlocks `Achievement`s, optionally remembering
+/// them in `localStorage` and announcing them on an `EventBus`
+#[cfg(feature = "achievements")]
+pub struct Achievements {
+    unlocked: u32,
+    progress: Progress,
+
+    storage: Option<(Storage, &'static str)>,
+    events: Option<Rc<RefCell<EventBus>>>,
+}
+
+#[cfg(feature = "achievements")]
+impl Achievements {
+    pub fn new() -> Self {
+        Achievements {
+            unlocked: 0,
+            progress: Progress::default(),
+            storage: None,
+            events: None,
+        }
+    }
+
+    /// Loads previously unlocked achievements from `localStorage[key]` and
+    /// stores new ones there; behaves like `new` when storage is unavailable
+    pub fn persisted(key: &'static str) -> Self {
+        let mut achievements = Self::new();
+
+        let storage = web_sys::window()
+            .and_then(|window| window.local_storage().ok())
+            .and_then(|storage| storage);
+
+        if let Some(storage) = storage {
+            if let Ok(Some(value)) = storage.get_item(key) {
+                achievements.unlocked = value.parse().unwrap_or(0);
+            }
+            achievements.storage = Some((storage, key));
+        }
+
+        achievements
+    }
+
+    /// Publishes `GameEvent::AchievementUnlocked` on `events`
+    pub fn with_events(mut self, events: Rc<RefCell<EventBus>>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    pub fn is_unlocked(&self, achievement: Achievement) -> bool {
+        self.unlocked & achievement.bit() != 0
+    }
+
+    fn unlock(&mut self, achievement: Achievement) {
+        self.unlocked |= achievement.bit();
+        info!("achievement unlocked: {:?}", achievement);
+
+        if let Some((ref storage, key)) = self.storage {
+            let value = format!("{}", self.unlocked);
+            if let Err(_) = storage.set_item(key, &value) {
+                warn!("failed to persist achievements");
+            }
+        }
+
+        if let Some(ref events) = self.events {
+            events
+                .borrow_mut()
+                .publish(GameEvent::AchievementUnlocked(achievement));
+        }
+    }
+
+    fn evaluate(&mut self) {
+        for &achievement in Achievement::ALL.iter() {
+            if !self.is_unlocked(achievement)
+                && self.progress.satisfies(achievement)
+            {
+                self.unlock(achievement);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "achievements")]
+impl Observer<WorldUpdate, UpdateError> for Achievements {
+    fn on_init(&mut self, update: &WorldUpdate) {
+        match *update {
+            WorldUpdate::LevelLoaded { width, height } => {
+                self.progress = Progress {
+                    area: width as u32 * height as u32,
+                    ..Progress::default()
+                };
+            }
+            WorldUpdate::Respawned { .. } => self.progress.length = 0,
+            WorldUpdate::SetBlock {
+                block: Block::Snake(_, PLAYER),
+                ..
+            } => {
+                self.progress.length += 1;
+            }
+            _ => {}
+        }
+    }
+
+    fn on_update(&mut self, update: &WorldUpdate) {
+        match *update {
+            WorldUpdate::SetBlock {
+                block: Block::Snake(_, PLAYER),
+                ..
+            }
+            | WorldUpdate::HeadMoved { snake: PLAYER, .. }
+            | WorldUpdate::Bend { snake: PLAYER, .. } => {
+                self.progress.ticks += 1;
+                self.progress.length += 1;
+            }
+            WorldUpdate::FoodEaten { snake: PLAYER, .. } => {
+                self.progress.food += 1;
+            }
+            WorldUpdate::TailRetracted { snake: PLAYER, .. } => {
+                let length = self.progress.length.saturating_sub(1);
+                self.progress.length = length;
+            }
+            _ => return,
+        }
+
+        self.evaluate();
+    }
+}
+
+/// Stands in for the tracker in builds without the `achievements` feature,
+/// it never unlocks anything
+#[cfg(not(feature = "achievements"))]
+pub struct Achievements;
+
+#[cfg(not(feature = "achievements"))]
+impl Achievements {
+    pub fn new() -> Self {
+        Achievements
+    }
+
+    pub fn persisted(_key: &'static str) -> Self {
+        Achievements
+    }
+
+    pub fn with_events(self, _events: Rc<RefCell<EventBus>>) -> Self {
+        self
+    }
+
+    pub fn is_unlocked(&self, _achievement: Achievement) -> bool {
+        false
+    }
+}
+
+#[cfg(not(feature = "achievements"))]
+impl Observer<WorldUpdate, UpdateError> for Achievements {
+    fn on_update(&mut self, _update: &WorldUpdate) {}
+}
+
+#[cfg(all(test, feature = "achievements"))]
+mod tests {
+    use alloc::boxed::Box;
+    use alloc::vec::Vec;
+
+    use rand::rngs::SmallRng;
+
+    use super::*;
+    use data::Wrapping;
+    use system::Stateful;
+    use world::{PerChange, World};
+
+    #[test]
+    fn test_fill_half_the_board() {
+        let world: World<SmallRng, Wrapping> = World::from_ascii(indoc!(
+            "
+            >>>*
+            ...."
+        ));
+
+        let events = Rc::new(RefCell::new(EventBus::new()));
+        let unlocked = Rc::new(RefCell::new(Vec::new()));
+        {
+            let unlocked = unlocked.clone();
+            events.borrow_mut().subscribe(Box::new(move |e: &GameEvent| {
+                if let GameEvent::AchievementUnlocked(a) = *e {
+                    unlocked.borrow_mut().push(a);
+                }
+            }));
+        }
+
+        let achievements =
+            Rc::new(RefCell::new(Achievements::new().with_events(events)));
+        let mut observed = world.observe(PerChange(achievements.clone()));
+
+        for _ in observed.initialize() {}
+        assert!(!achievements.borrow().is_unlocked(Achievement::Hoarder));
+
+        observed.step(None, 0).unwrap();
+
+        assert!(achievements.borrow().is_unlocked(Achievement::Hoarder));
+        assert!(!achievements.borrow().is_unlocked(Achievement::Gourmet));
+        assert_eq!(*unlocked.borrow(), vec![Achievement::Hoarder]);
+    }
+}
@@ -0,0 +1,146 @@
+use alloc::rc::Rc;
+use alloc::string::String;
+use std::cell::RefCell;
+
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement, Node};
+
+use canvas::EnvError;
+use events::{EventSink, GameEvent};
+use i18n::{Locale, Text};
+use scoring::Scoring;
+
+// out of sight, but still read out: `hidden` or `display: none` would
+// silence it
+const VISUALLY_HIDDEN: &str = "position: absolute; width: 1px; \
+                               height: 1px; overflow: hidden; \
+                               clip: rect(0 0 0 0); white-space: nowrap";
+
+/// Follows the `EventBus` and reads the game out to screen readers, through
+/// an aria live region that isn't shown on the page
+pub struct Announcer {
+    region: HtmlElement,
+    locale: Locale,
+    scoring: Option<Rc<RefCell<Scoring>>>,
+}
+
+impl Announcer {
+    /// Creates the live region and appends it to `parent`
+    pub fn new(parent: &Element, locale: Locale) -> Result<Self, EnvError> {
+        let parent: &Node = parent.as_ref();
+        let document = parent.owner_document().ok_or(EnvError::Detached)?;
+
+        let region = document
+            .create_element("div")?
+            .dyn_into::<HtmlElement>()
+            .map_err(|_| EnvError::CreateCanvas)?;
+        region.set_class_name("snake-announcer");
+        region.set_attribute("role", "status")?;
+        region.set_attribute("aria-live", "polite")?;
+        region.set_attribute("style", VISUALLY_HIDDEN)?;
+        parent.append_child(region.as_ref() as &Node)?;
+
+        Ok(Announcer {
+            region,
+            locale,
+            scoring: None,
+        })
+    }
+
+    /// Reads out the score of `scoring` when the game is over
+    pub fn with_scoring(mut self, scoring: Rc<RefCell<Scoring>>) -> Self {
+        self.scoring = Some(scoring);
+        self
+    }
+}
+
+impl EventSink for Announcer {
+    fn on_event(&mut self, event: &GameEvent) {
+        // scoring publishes combos while it counts the food, it is only
+        // read once the game is over
+        let score = match *event {
+            GameEvent::GameOver(_) => self
+                .scoring
+                .as_ref()
+                .map_or(0, |scoring| scoring.borrow().score()),
+            _ => 0,
+        };
+
+        if let Some(text) = announcement(event, self.locale, score) {
+            let region: &Node = self.region.as_ref();
+            region.set_text_content(Some(&text));
+        }
+    }
+}
+
+// what to read out on `event`, if anything, `score` being the score of the
+// game when it is over
+fn announcement(
+    event: &GameEvent,
+    locale: Locale,
+    score: u32,
+) -> Option<String> {
+    let text = |text| locale.text(text);
+
+    match *event {
+        GameEvent::Started => Some(text(Text::Started).into()),
+        GameEvent::FoodEaten { length, .. } => {
+            Some(format!("{} {}", text(Text::FoodEatenLength), length))
+        }
+        GameEvent::Paused(true) => Some(text(Text::Paused).into()),
+        GameEvent::Paused(false) => Some(text(Text::Resumed).into()),
+        GameEvent::Won { .. } => Some(text(Text::Won).into()),
+        // once, after `Died`, with the summary read out line by line
+        GameEvent::GameOver(summary) => {
+            let mut lines = summary.lines(locale);
+            lines[0] = format!("{} {}", text(Text::GameOverScore), score);
+            Some(lines.join(". "))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data::Coordinate;
+    use stats::DeathReason;
+    use telemetry::GameSummary;
+    use world::UpdateError;
+
+    #[test]
+    fn test_announces_food_and_game_over() {
+        let eaten = GameEvent::FoodEaten {
+            at: Coordinate { x: 1, y: 2 },
+            length: 12,
+        };
+        assert_eq!(
+            announcement(&eaten, Locale::En, 0),
+            Some("food eaten, length 12".into())
+        );
+
+        let died = GameEvent::Died {
+            reason: UpdateError::CollideBody,
+            seed: 0,
+        };
+        assert_eq!(announcement(&died, Locale::En, 240), None);
+
+        let over = GameEvent::GameOver(GameSummary {
+            duration: 83_500,
+            ticks: 420,
+            length: 14,
+            food: 5,
+            cause: DeathReason::CollideBody,
+            coverage_percent: 37,
+        });
+        assert_eq!(
+            announcement(&over, Locale::En, 240),
+            Some(
+                "game over, score 240. ran into its own tail. time 1:23. \
+                 length 14. food eaten 5. board covered 37%"
+                    .into()
+            )
+        );
+        assert_eq!(announcement(&GameEvent::ComboLost, Locale::En, 0), None);
+    }
+}
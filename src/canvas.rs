@@ -1,46 +1,880 @@
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+use std::cell::{Cell, RefCell};
 use std::f64::consts::PI;
+use std::fmt;
+use std::ops::Range;
 
-use wasm_bindgen::JsCast;
-use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+use js_sys::Function;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{
+    CanvasRenderingContext2d, Element, HtmlCanvasElement, HtmlElement,
+    HtmlImageElement, Node,
+};
 
-use constants::TILE_SIZE;
-use data::{Direction, SmallNat};
-use system::{Color, DrawGrid, UnitInterval};
+use constants::{POPUP_FRAMES, TILE_SIZE};
+use data::{
+    ConfigError, Coordinate, Direction, Line, PowerUp, SmallNat, SnakeId,
+};
+use errors::{ErrorPhase, ErrorReporter};
+use i18n::{Locale, Text};
+use layers::Layers;
+use power::PowerSaver;
+use quality::QualityTier;
+use scoring::{Scoring, Team};
+use stats::Stats;
+use system::{
+    partial_tile, Color, DrawGrid, Effects, Layer, MotionPolicy,
+    UnitInterval,
+};
+use telemetry::Telemetry;
+use theme::{Background, Theme};
+
+// the game over summary: its box, the size of its title and other lines
+// and the room around them, in pixels
+const PANEL_COLOR: &str = "rgba(255, 255, 255, 0.8)";
+const PANEL_WIDTH: f64 = 300.0;
+const TITLE_SIZE: f64 = 36.0;
+const LINE_SIZE: f64 = 20.0;
+const PANEL_PADDING: f64 = 16.0;
+
+/// Failure to set up the browser render environment
+#[derive(Debug)]
+pub enum EnvError {
+    NoDocument,
+    NoBody,
+    NoElement(String),
+    Detached,
+    CreateCanvas,
+    NoContext,
+    Config(ConfigError),
+    // exception thrown by a dom call
+    Js(JsValue),
+}
+
+impl fmt::Display for EnvError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            EnvError::NoDocument => write!(f, "no global document"),
+            EnvError::NoBody => {
+                write!(f, "document.body is not available, pass a parent")
+            }
+            EnvError::NoElement(ref selector) => {
+                write!(f, "no element matches {}", selector)
+            }
+            EnvError::Detached => {
+                write!(f, "parent element does not belong to a document")
+            }
+            EnvError::CreateCanvas => write!(f, "failed to create a canvas"),
+            EnvError::NoContext => write!(f, "2d canvas context unavailable"),
+            EnvError::Config(err) => write!(f, "{}", err),
+            EnvError::Js(ref err) => match err.as_string() {
+                Some(msg) => write!(f, "dom error: {}", msg),
+                None => write!(f, "dom error"),
+            },
+        }
+    }
+}
+
+impl From<JsValue> for EnvError {
+    fn from(err: JsValue) -> Self {
+        EnvError::Js(err)
+    }
+}
+
+impl From<ConfigError> for EnvError {
+    fn from(err: ConfigError) -> Self {
+        EnvError::Config(err)
+    }
+}
+
+impl From<EnvError> for JsValue {
+    fn from(err: EnvError) -> Self {
+        match err {
+            // keep the original exception, it carries a stack
+            EnvError::Js(err) => err,
+            err => js_sys::Error::new(&format!("snake: {}", err)).into(),
+        }
+    }
+}
+
+pub fn document() -> Result<web_sys::Document, EnvError> {
+    web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or(EnvError::NoDocument)
+}
+
+pub fn document_body() -> Result<Element, EnvError> {
+    let body = document()?.body().ok_or(EnvError::NoBody)?;
+
+    Ok(body.into())
+}
+
+pub fn query_element(selector: &str) -> Result<Element, EnvError> {
+    document()?
+        .query_selector(selector)?
+        .ok_or_else(|| EnvError::NoElement(selector.into()))
+}
+
+/// The box of tile `x`, `y` of a `width` x `height` board on a canvas
+/// shown at `client` css pixels, as `[left, top, width, height]` from the
+/// top left corner of the canvas
+pub fn tile_box(
+    x: SmallNat,
+    y: SmallNat,
+    (width, height): (SmallNat, SmallNat),
+    client: (f64, f64),
+) -> [f64; 4] {
+    let tile_width = client.0 / width.max(1) as f64;
+    let tile_height = client.1 / height.max(1) as f64;
+
+    [
+        x as f64 * tile_width,
+        y as f64 * tile_height,
+        tile_width,
+        tile_height,
+    ]
+}
+
+/// Tells the host page when the board canvas is shown at another size,
+/// css or the window resizing it, for the elements it anchored to tiles
+/// to follow. Checked once a frame, and only read from the page once a
+/// callback is set.
+pub struct Resizes {
+    canvas: HtmlCanvasElement,
+    shown: (i32, i32),
+    callback: Option<Function>,
+}
+
+impl Resizes {
+    pub fn new(canvas: HtmlCanvasElement) -> Self {
+        Resizes {
+            canvas,
+            shown: (0, 0),
+            callback: None,
+        }
+    }
+
+    /// The css pixels the canvas is shown at
+    pub fn client_size(&self) -> (f64, f64) {
+        let canvas: &Element = self.canvas.as_ref();
+        (canvas.client_width() as f64, canvas.client_height() as f64)
+    }
+
+    /// `callback(width, height)` is called with the css size of the canvas
+    /// on the first check and every time it changes after that
+    pub fn set_callback(&mut self, callback: Function) {
+        self.shown = (0, 0);
+        self.callback = Some(callback);
+    }
+
+    pub fn check(&mut self) {
+        let callback = match self.callback {
+            Some(ref callback) => callback,
+            None => return,
+        };
+
+        let canvas: &Element = self.canvas.as_ref();
+        let shown = (canvas.client_width(), canvas.client_height());
+        if shown != self.shown {
+            self.shown = shown;
+            let _ = callback.call2(
+                &JsValue::NULL,
+                &JsValue::from_f64(shown.0 as f64),
+                &JsValue::from_f64(shown.1 as f64),
+            );
+        }
+    }
+}
+
+// points floating up from the food they were for, `x` and `y` the pixel
+// at the bottom middle of the text when it pops up
+struct Popup {
+    x: f64,
+    y: f64,
+    text: String,
+}
 
 pub struct CanvasEnv {
     canvas: HtmlCanvasElement,
+    // `show_text` goes in an element after the canvas, so that hiding it
+    // doesn't need a redraw of the board
+    caption: HtmlElement,
+    // that any key plays, over the demo games of an attract mode
+    attract: HtmlElement,
+    // the clock of timed games, the lives left, the power-up on and the
+    // score of the team, likewise, hidden in games without any
+    hud: HtmlElement,
+    // the context of `canvas`, which the layers are drawn onto at the end
+    // of every frame
+    screen: CanvasRenderingContext2d,
+    layers: Layers,
+    // the bands shown and the popups, redrawn on the effects layer on the
+    // frames one of them changed or moves
+    bands: Vec<(Line, Color, UnitInterval)>,
+    effects_changed: bool,
+    floating: Effects<Popup>,
+    // off on a small copy of the board
+    popups: bool,
+    // scanlines and a vignette over all of the above, composited last, see
+    // `Layers::paint_crt`
+    crt: bool,
+    clock: Option<u32>,
+    lives: Option<u32>,
+    power_up: Option<PowerUp>,
+    // the context of `layer`
     gc: CanvasRenderingContext2d,
+    layer: Layer,
     tile_size: f64,
+    // in place of the one of `setup`, see `with_tile_size`
+    fixed_tile_size: Option<SmallNat>,
     color: Color,
+    reporter: ErrorReporter,
+    stats: Option<Rc<RefCell<Stats>>>,
+    // the heatmap so far is drawn on each frame, see `show_debug`
+    debug: bool,
+    telemetry: Option<Rc<RefCell<Telemetry>>>,
+    scoring: Option<Rc<RefCell<Scoring>>>,
+    team: Option<Rc<RefCell<Team>>>,
+    locale: Locale,
+    motion: MotionPolicy,
+    theme: Theme,
+    image: Option<HtmlImageElement>,
+    // the background was painted before its image loaded
+    image_pending: bool,
+    saver: Option<PowerSaver>,
+    quality: Option<Rc<Cell<QualityTier>>>,
+    // the tier drawn at
+    shown_tier: QualityTier,
 }
 impl CanvasEnv {
-    pub fn new() -> Self {
-        let document = web_sys::window().unwrap().document().unwrap();
+    /// Creates a canvas and appends it to `parent`
+    pub fn new(
+        parent: &Element,
+        reporter: ErrorReporter,
+    ) -> Result<Self, EnvError> {
+        let parent: &Node = parent.as_ref();
+
+        let document = parent.owner_document().ok_or(EnvError::Detached)?;
+        let env = Self::create(&document, reporter)?;
+
+        parent.append_child(env.hud.as_ref() as &Node)?;
+        parent.append_child(env.canvas.as_ref() as &Node)?;
+        parent.append_child(env.caption.as_ref() as &Node)?;
+        parent.append_child(env.attract.as_ref() as &Node)?;
+
+        Ok(env)
+    }
+
+    /// Creates a canvas without the heads up display, captions or effects
+    /// of the main one and appends it to `parent`, for a small copy of the
+    /// board drawn along with it, see `system::Fanout`
+    pub fn mini(
+        parent: &Element,
+        reporter: ErrorReporter,
+    ) -> Result<Self, EnvError> {
+        let parent: &Node = parent.as_ref();
+
+        let document = parent.owner_document().ok_or(EnvError::Detached)?;
+        let mut env = Self::create(&document, reporter)?;
+        env.popups = false;
+
+        parent.append_child(env.canvas.as_ref() as &Node)?;
+
+        Ok(env)
+    }
+
+    /// Creates a canvas which isn't put on the page, to render a copy of
+    /// the board into
+    pub fn offscreen(reporter: ErrorReporter) -> Result<Self, EnvError> {
+        Self::create(&document()?, reporter)
+    }
+
+    fn create(
+        document: &web_sys::Document,
+        reporter: ErrorReporter,
+    ) -> Result<Self, EnvError> {
         let canvas = document
-            .create_element("canvas")
-            .unwrap()
-            .dyn_into::<web_sys::HtmlCanvasElement>()
-            .unwrap();
+            .create_element("canvas")?
+            .dyn_into::<HtmlCanvasElement>()
+            .map_err(|_| EnvError::CreateCanvas)?;
+
+        let caption = document
+            .create_element("div")?
+            .dyn_into::<HtmlElement>()
+            .map_err(|_| EnvError::CreateCanvas)?;
+        caption.set_class_name("snake-prompt");
+        caption.set_hidden(true);
 
-        (document.body().unwrap().as_ref() as &web_sys::Node)
-            .append_child(canvas.as_ref() as &web_sys::Node)
-            .unwrap();
+        let hud = document
+            .create_element("div")?
+            .dyn_into::<HtmlElement>()
+            .map_err(|_| EnvError::CreateCanvas)?;
+        hud.set_class_name("snake-hud");
+        hud.set_hidden(true);
+
+        let attract = document
+            .create_element("div")?
+            .dyn_into::<HtmlElement>()
+            .map_err(|_| EnvError::CreateCanvas)?;
+        attract.set_class_name("snake-attract");
+        attract.set_hidden(true);
 
         let context = canvas
-            .get_context("2d")
-            .unwrap()
-            .unwrap()
-            .dyn_into::<web_sys::CanvasRenderingContext2d>()
-            .unwrap();
+            .get_context("2d")?
+            .ok_or(EnvError::NoContext)?
+            .dyn_into::<CanvasRenderingContext2d>()
+            .map_err(|_| EnvError::NoContext)?;
+
+        let layers = Layers::new(document)?;
 
-        context.set_fill_style(&Color::Black.to_rgb().into());
+        let gc = layers.gc(Layer::Board).clone();
+        gc.set_fill_style(&Color::Black.to_rgb().into());
 
-        CanvasEnv {
+        Ok(CanvasEnv {
             canvas,
-            gc: context,
+            caption,
+            attract,
+            hud,
+            screen: context,
+            layers,
+            bands: Vec::new(),
+            effects_changed: false,
+            floating: Effects::new(),
+            popups: true,
+            crt: false,
+            clock: None,
+            lives: None,
+            power_up: None,
+            gc,
+            layer: Layer::Board,
             tile_size: TILE_SIZE as f64,
+            fixed_tile_size: None,
             color: Color::Black,
+            reporter,
+            stats: None,
+            debug: false,
+            telemetry: None,
+            scoring: None,
+            team: None,
+            locale: Locale::default(),
+            motion: MotionPolicy::default(),
+            theme: Theme::default(),
+            image: None,
+            image_pending: false,
+            saver: None,
+            quality: None,
+            shown_tier: QualityTier::High,
+        })
+    }
+
+    fn update_hud(&self) {
+        let mut text = String::new();
+        if let Some(left) = self.clock {
+            let label = self.locale.text(Text::Time);
+            text.push_str(&format!("{} {}", label, left));
+        }
+        if let Some(left) = self.lives {
+            if !text.is_empty() {
+                text.push_str("  ");
+            }
+            let label = self.locale.text(Text::Lives);
+            text.push_str(&format!("{} {}", label, left));
+        }
+        if let Some(power_up) = self.power_up {
+            if !text.is_empty() {
+                text.push_str("  ");
+            }
+            text.push_str(self.locale.text(match power_up {
+                PowerUp::Magnet => Text::Magnet,
+            }));
+        }
+        if let Some(ref team) = self.team {
+            if !text.is_empty() {
+                text.push_str("  ");
+            }
+            let label = self.locale.text(Text::Team);
+            text.push_str(&format!("{} {}", label, team.borrow().score()));
+        }
+
+        let hud: &Node = self.hud.as_ref();
+        hud.set_text_content(Some(&text));
+        self.hud.set_hidden(false);
+    }
+
+    // the crt pass drawn anew at the size of the board, see `Theme::crt`
+    fn paint_crt(&self) {
+        if self.layers.paint_crt().is_err() {
+            self.reporter.report(ErrorPhase::Setup, "vignette not drawn");
+        }
+    }
+
+    // the pixels of a row or column of tiles
+    fn band_rect(&self, line: Line) -> (f64, f64, f64, f64) {
+        let s = self.tile_size;
+        let width = self.canvas.width() as f64;
+        let height = self.canvas.height() as f64;
+
+        match line {
+            Line::Row(y) => (0.0, y as f64 * s, width, s),
+            Line::Column(x) => (x as f64 * s, 0.0, s, height),
+        }
+    }
+
+    /// Says that any key plays over the board while `shown`, e.g. over
+    /// the demo games of `bot::AttractMode`
+    pub fn show_attract(&self, shown: bool) {
+        let attract: &Node = self.attract.as_ref();
+        attract.set_text_content(Some(self.locale.text(Text::PressAnyKey)));
+        self.attract.set_hidden(!shown);
+    }
+
+    pub fn canvas(&self) -> &HtmlCanvasElement {
+        &self.canvas
+    }
+
+    /// Shows the layers as they are on the canvas, which every frame does
+    /// once it is drawn, with the crt pass over them at `QualityTier::High`
+    pub fn present(&self) {
+        let crt = self.crt && self.shown_tier == QualityTier::High;
+        if self.layers.composite(&self.screen, crt).is_err() {
+            self.reporter.report(ErrorPhase::Render, "layers not drawn");
+        }
+    }
+
+    /// Turns the crt pass on or off while the game runs, from the next
+    /// frame on
+    pub fn set_crt(&mut self, on: bool) {
+        self.crt = on;
+    }
+
+    pub fn crt(&self) -> bool {
+        self.crt
+    }
+
+    /// Paints the board as `theme` says, the image of `Background::Image`
+    /// starts loading right away
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.set_theme(theme);
+        self
+    }
+
+    /// Draws as `theme` says from now on, the background at once and the
+    /// rest as it is drawn again, see `draw::redraw_view`. An image shows
+    /// on the first frame after it loaded.
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.image = match (theme.background, theme.image.as_ref()) {
+            (Background::Image, Some(url)) => match HtmlImageElement::new() {
+                Ok(image) => {
+                    image.set_src(url);
+                    Some(image)
+                }
+                Err(_) => {
+                    let msg = "failed to create the background image";
+                    self.reporter.report(ErrorPhase::Setup, msg);
+                    None
+                }
+            },
+            _ => None,
+        };
+        self.gc.set_fill_style(&theme.color(self.color).into());
+        self.crt = theme.crt;
+        self.theme = theme;
+        self.apply_pixel_art();
+        self.effects_changed = true;
+        self.redraw_all();
+    }
+
+    /// Overlays the heatmap of `stats` on the game over screen when
+    /// `Stats::overlay` is set, or throughout the game with the debug
+    /// overlay on
+    pub fn with_stats(mut self, stats: Rc<RefCell<Stats>>) -> Self {
+        self.stats = Some(stats);
+        self
+    }
+
+    /// Sums up how the game went on the game over screen, from the
+    /// `Telemetry::summary` of `telemetry`, rather than only ending it
+    pub fn with_telemetry(mut self, telemetry: Rc<RefCell<Telemetry>>) -> Self {
+        self.telemetry = Some(telemetry);
+        self
+    }
+
+    /// Writes the game over screen and the heads up display in `locale`
+    pub fn with_locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    /// Draws tiles `size` pixels wide whatever size the game sets up
+    pub fn with_tile_size(mut self, size: SmallNat) -> Self {
+        self.fixed_tile_size = Some(size);
+        self
+    }
+
+    /// Snaps tiles into place and keeps popups still with
+    /// `MotionPolicy::Reduced`
+    pub fn with_motion(mut self, motion: MotionPolicy) -> Self {
+        self.motion = motion;
+        self
+    }
+
+    /// Leaves the popups out while `saver` is on
+    pub fn with_power_saver(mut self, saver: PowerSaver) -> Self {
+        self.saver = Some(saver);
+        self
+    }
+
+    /// Draws at the tier `quality` says, from the frame after it changed:
+    /// the crt pass is hidden below `QualityTier::High`, and popups and
+    /// image smoothing are off at `Low`
+    pub fn with_quality(mut self, quality: Rc<Cell<QualityTier>>) -> Self {
+        self.quality = Some(quality);
+        self
+    }
+
+    /// Pops up the points of each food eaten as `scoring` counts them
+    pub fn with_scoring(mut self, scoring: Rc<RefCell<Scoring>>) -> Self {
+        self.scoring = Some(scoring);
+        self
+    }
+
+    /// Shows the score of all of `team` in the heads up display, updated
+    /// on each food
+    pub fn with_team(mut self, team: Rc<RefCell<Team>>) -> Self {
+        self.team = Some(team);
+        self
+    }
+
+    fn draw_heatmap(&self, stats: &Stats) {
+        let (width, height) = stats.heatmap_size();
+
+        for (x, y) in iproduct!(0..width, 0..height) {
+            let at = Coordinate {
+                x: x as SmallNat,
+                y: y as SmallNat,
+            };
+            let heat = stats.heat(at);
+            if heat == 0.0 {
+                continue;
+            }
+
+            let style = format!("rgba(255, 0, 0, {})", heat * 0.6);
+            self.gc.set_fill_style(&style.into());
+            self.gc.fill_rect(
+                x as f64 * self.tile_size,
+                y as f64 * self.tile_size,
+                self.tile_size,
+                self.tile_size,
+            );
+        }
+
+        self.gc.set_fill_style(&self.theme.color(self.color).into());
+    }
+
+    // the heatmap of the game so far, anew on the debug layer
+    fn draw_debug(&mut self) {
+        if let Some(stats) = self.stats.clone() {
+            self.layers.clear(Layer::Debug);
+            let prev_layer = self.set_layer(Layer::Debug);
+            self.draw_heatmap(&stats.borrow());
+            self.set_layer(prev_layer);
+        }
+    }
+
+    // `lines` in a box in the middle of the board, the first one larger as
+    // its title
+    fn draw_panel(&self, lines: &[String]) {
+        let width = PANEL_WIDTH.min(self.canvas.width() as f64);
+        let rest = lines.len().saturating_sub(1) as f64;
+        let height = TITLE_SIZE + rest * LINE_SIZE + 2.0 * PANEL_PADDING;
+        let x = (self.canvas.width() as f64 - width) / 2.0;
+        let y = (self.canvas.height() as f64 - height) / 2.0;
+
+        self.gc.save();
+        self.gc.set_fill_style(&PANEL_COLOR.into());
+        self.gc.fill_rect(x, y, width, height);
+
+        self.gc.set_fill_style(&self.theme.color(self.color).into());
+        self.gc.set_text_align("center");
+        self.gc.set_text_baseline("top");
+        let (middle, mut top) = (x + width / 2.0, y + PANEL_PADDING);
+        for (i, line) in lines.iter().enumerate() {
+            let size = if i == 0 { TITLE_SIZE } else { LINE_SIZE };
+            let font = if i == 0 { "serif" } else { "sans-serif" };
+            self.gc.set_font(&format!("{}px {}", size, font));
+            if let Err(_) = self.gc.fill_text(line, middle, top) {
+                self.reporter.report(ErrorPhase::Render, "fill_text failed");
+            }
+            top += size;
+        }
+        self.gc.restore();
+    }
+
+    fn loaded_image(&self) -> Option<&HtmlImageElement> {
+        self.image
+            .as_ref()
+            .filter(|image| image.complete() && image.natural_width() > 0)
+    }
+
+    /// Background and grid lines of the whole board, on the layer under
+    /// the others
+    pub fn redraw_all(&mut self) {
+        let width = self.canvas.width() as f64;
+        let height = self.canvas.height() as f64;
+        let loaded = self.loaded_image().is_some();
+        self.image_pending = self.image.is_some() && !loaded;
+
+        let prev_layer = self.set_layer(Layer::Background);
+        self.paint_background(0.0, 0.0, width, height);
+        self.stroke_grid_within(0.0, 0.0, width, height);
+        self.set_layer(prev_layer);
+    }
+
+    // paints the background of the `w` x `h` pixels at `x`, `y`
+    fn paint_background(&self, x: f64, y: f64, w: f64, h: f64) {
+        if w <= 0.0 || h <= 0.0 {
+            return;
+        }
+
+        if let Some(image) = self.loaded_image() {
+            let scale_x =
+                image.natural_width() as f64 / self.canvas.width() as f64;
+            let scale_y =
+                image.natural_height() as f64 / self.canvas.height() as f64;
+
+            let drawn = self
+                .gc
+                .draw_image_with_html_image_element_and_sw_and_sh_and_dx_and_dy_and_dw_and_dh(
+                    image,
+                    x * scale_x,
+                    y * scale_y,
+                    w * scale_x,
+                    h * scale_y,
+                    x,
+                    y,
+                    w,
+                    h,
+                );
+            if drawn.is_err() {
+                self.reporter.report(ErrorPhase::Render, "draw_image failed");
+            }
+            return;
+        }
+
+        if self.theme.background != Background::Checkerboard {
+            self.gc.set_fill_style(&self.theme.board.as_str().into());
+            self.gc.fill_rect(x, y, w, h);
+        } else {
+            // each tile the rect overlaps in its own color
+            let s = self.tile_size;
+            let clamp = |v: f64, lo: f64, hi: f64| {
+                if v < lo {
+                    lo
+                } else if v > hi {
+                    hi
+                } else {
+                    v
+                }
+            };
+
+            let columns = tiles_spanned(x, w, s);
+            let rows = tiles_spanned(y, h, s);
+
+            for (tx, ty) in iproduct!(columns, rows) {
+                let (left, top) = (tx as f64 * s, ty as f64 * s);
+                let x0 = clamp(x, left, left + s);
+                let y0 = clamp(y, top, top + s);
+                let x1 = clamp(x + w, left, left + s);
+                let y1 = clamp(y + h, top, top + s);
+
+                let color = self.theme.tile_color(tx, ty);
+                self.gc.set_fill_style(&color.into());
+                self.gc.fill_rect(x0, y0, x1 - x0, y1 - y0);
+            }
+        }
+
+        self.gc.set_fill_style(&self.theme.color(self.color).into());
+    }
+
+    // grid lines crossing the `w` x `h` pixels at `x`, `y`, clipped to them
+    // so that the parts outside aren't stroked twice
+    fn stroke_grid_within(&self, x: f64, y: f64, w: f64, h: f64) {
+        let theme = &self.theme;
+        if !theme.grid {
+            return;
+        }
+
+        self.gc.save();
+        self.gc.begin_path();
+        self.gc.rect(x, y, w, h);
+        self.gc.clip();
+
+        self.gc.set_line_width(theme.grid_width);
+        self.gc.set_stroke_style(&theme.grid_color.as_str().into());
+        self.grid_path(x, y, w, h, false);
+        self.gc.stroke();
+
+        if theme.major_every > 0 {
+            self.gc.set_line_width(theme.grid_width * 2.0);
+            self.gc.set_stroke_style(&theme.major_color.as_str().into());
+            self.grid_path(x, y, w, h, true);
+            self.gc.stroke();
+        }
+
+        self.gc.restore();
+    }
+
+    // the inner grid lines across the given pixels, either the major ones
+    // or the rest
+    fn grid_path(&self, x: f64, y: f64, w: f64, h: f64, major: bool) {
+        let s = self.tile_size;
+        let width = self.canvas.width() as f64;
+        let height = self.canvas.height() as f64;
+
+        let columns = tiles_spanned(x, w, s);
+        let rows = tiles_spanned(y, h, s);
+
+        self.gc.begin_path();
+        for col in columns.start..columns.end + 1 {
+            let line_x = col as f64 * s;
+            let inner = line_x > 0.0 && line_x < width;
+            if inner && self.theme.is_major_line(col) == major {
+                self.gc.move_to(line_x, y);
+                self.gc.line_to(line_x, y + h);
+            }
+        }
+        for row in rows.start..rows.end + 1 {
+            let line_y = row as f64 * s;
+            let inner = line_y > 0.0 && line_y < height;
+            if inner && self.theme.is_major_line(row) == major {
+                self.gc.move_to(x, line_y);
+                self.gc.line_to(x + w, line_y);
+            }
+        }
+    }
+
+    // what is under the snake once it moved on: the layers below, the
+    // background and the grid lines, of exactly the pixels given
+    fn restore_rect(&self, x: f64, y: f64, w: f64, h: f64) {
+        if w <= 0.0 || h <= 0.0 {
+            return;
+        }
+
+        self.gc.clear_rect(x, y, w, h);
+    }
+
+    // the bands and popups on the effects layer, in place of what it had
+    fn draw_effects(&mut self) {
+        let gc = self.layers.gc(Layer::Effects);
+        let s = self.tile_size;
+        let reporter = &self.reporter;
+        let still = self.motion.is_reduced();
+        let width = self.canvas.width() as f64;
+        let height = self.canvas.height() as f64;
+
+        gc.clear_rect(0.0, 0.0, width, height);
+        for &(line, color, alpha) in &self.bands {
+            let (x, y, w, h) = self.band_rect(line);
+            gc.set_fill_style(&color.to_rgba(alpha).into());
+            gc.fill_rect(x, y, w, h);
+        }
+        if self.floating.is_empty() {
+            return;
+        }
+
+        gc.set_font(&format!("bold {}px sans-serif", s));
+        gc.set_text_align("center");
+        gc.set_fill_style(&self.theme.color(Color::Black).into());
+
+        self.floating.advance(|popup, t| {
+            let t = if still { UnitInterval::min_value() } else { t };
+            gc.set_global_alpha(t.complement().scale(1.0));
+            let y = popup.y - t.scale(s);
+            if gc.fill_text(&popup.text, popup.x, y).is_err() {
+                reporter.report(ErrorPhase::Render, "fill_text failed");
+            }
+        });
+        gc.set_global_alpha(1.0);
+    }
+
+    // `x`, `y`, `w`, `h` on whole pixels in pixel art mode
+    fn crisp(&self, x: f64, y: f64, w: f64, h: f64) -> (f64, f64, f64, f64) {
+        if !self.theme.pixel_art {
+            return (x, y, w, h);
         }
+
+        let (x0, y0) = (whole_pixel(x), whole_pixel(y));
+        (x0, y0, whole_pixel(x + w) - x0, whole_pixel(y + h) - y0)
+    }
+
+    // a piece of the board, outlined in pixel art mode
+    fn fill_piece(&self, x: f64, y: f64, w: f64, h: f64) {
+        let (x, y, w, h) = self.crisp(x, y, w, h);
+        self.gc.fill_rect(x, y, w, h);
+
+        if self.theme.pixel_art && w >= 2.0 && h >= 2.0 {
+            self.gc.set_line_width(1.0);
+            self.gc
+                .set_stroke_style(&self.theme.outline_color.as_str().into());
+            // on the middle of the pixels, which a line at a whole pixel
+            // would straddle and blur
+            self.gc.stroke_rect(x + 0.5, y + 0.5, w - 1.0, h - 1.0);
+        }
+    }
+
+    // catches up with the tier of `with_quality`, `present` leaves the crt
+    // pass out below `High`
+    fn apply_quality(&mut self) {
+        let tier = match self.quality {
+            Some(ref quality) => quality.get(),
+            None => return,
+        };
+        if tier == self.shown_tier {
+            return;
+        }
+        self.shown_tier = tier;
+        self.apply_pixel_art();
+    }
+
+    // applies the pixel art settings, which resizing the canvas resets
+    fn apply_pixel_art(&self) {
+        let low = self.shown_tier == QualityTier::Low;
+        let smoothing = !self.theme.pixel_art && !low;
+        self.screen.set_image_smoothing_enabled(smoothing);
+        for gc in self.layers.gcs() {
+            gc.set_image_smoothing_enabled(smoothing);
+        }
+
+        let rendering = if self.theme.pixelated { "pixelated" } else { "auto" };
+        let style = self.canvas.style();
+        if style.set_property("image-rendering", rendering).is_err() {
+            self.reporter.report(ErrorPhase::Setup, "image-rendering not set");
+        }
+    }
+}
+
+// indices of the tiles of size `s` covering `len` pixels from `from`
+fn tiles_spanned(from: f64, len: f64, s: f64) -> Range<u32> {
+    let first = (from / s) as u32;
+    let end = (from + len) / s;
+    // `f64::ceil` needs std
+    let last = if ((end as u32) as f64) < end {
+        end as u32 + 1
+    } else {
+        end as u32
+    };
+
+    first..last
+}
+
+// the nearest whole pixel, `f64::round` needs std as well
+fn whole_pixel(v: f64) -> f64 {
+    if v <= 0.0 {
+        0.0
+    } else {
+        (v + 0.5) as u32 as f64
     }
 }
 
@@ -51,6 +885,7 @@ impl DrawGrid for CanvasEnv {
         width: SmallNat,
         height: SmallNat,
     ) {
+        let tile_size = self.fixed_tile_size.unwrap_or(tile_size);
         self.tile_size = tile_size as f64;
 
         let width_pixel: u32 = (width * tile_size) as u32;
@@ -58,43 +893,57 @@ impl DrawGrid for CanvasEnv {
 
         self.canvas.set_width(width_pixel);
         self.canvas.set_height(height_pixel);
+        self.layers.resize(width_pixel, height_pixel);
+        // which resets the fill style too
+        self.gc.set_fill_style(&self.theme.color(self.color).into());
+        debug!("canvas set up at {}x{} pixels", width_pixel, height_pixel);
+        // until a timed game shows its clock
+        self.clock = None;
+        self.lives = None;
+        self.power_up = None;
+        self.hud.set_hidden(true);
+        self.bands.clear();
+        self.floating.clear();
+        self.paint_crt();
+        self.apply_pixel_art();
 
-        self.gc.set_stroke_style(&"rgba(0, 0, 0, 0.02)".into());
-
-        for x in 1..width {
-            let x: f64 = (x * tile_size) as f64;
-            self.gc.begin_path();
-            self.gc.move_to(x, 0.0);
-            self.gc.line_to(x, height_pixel as f64);
-            self.gc.stroke();
-        }
-
-        for y in 1..height {
-            let y: f64 = (y * tile_size) as f64;
-            self.gc.begin_path();
-            self.gc.move_to(0.0, y);
-            self.gc.line_to(height_pixel as f64, y);
-            self.gc.stroke();
-        }
+        self.redraw_all();
     }
 
     fn clear(&mut self) {
-        self.gc.clear_rect(
-            0.0,
-            0.0,
-            self.canvas.width() as f64,
-            self.canvas.height() as f64,
-        );
+        let width = self.canvas.width() as f64;
+        let height = self.canvas.height() as f64;
+
+        for &layer in &Layer::ALL[1..] {
+            self.layers.clear(layer);
+        }
+        self.bands.clear();
+        self.floating.clear();
+        self.with_layer(Layer::Background, |env| {
+            env.paint_background(0.0, 0.0, width, height)
+        });
     }
 
     // returns current fill color
     fn set_fill_color(&mut self, color: Color) -> Color {
         let prev_color = self.color;
         self.color = color;
-        self.gc.set_fill_style(&color.to_rgb().into());
+        self.gc.set_fill_style(&self.theme.color(color).into());
         prev_color
     }
 
+    // the snakes of the team shown in the heads up display fill in its
+    // color, the others in the fill color
+    fn set_snake(&mut self, snake: SnakeId) {
+        let style: JsValue = match self.team {
+            Some(ref team) if team.borrow().has(snake) => {
+                self.theme.team_color(team.borrow().id()).into()
+            }
+            _ => self.theme.color(self.color).into(),
+        };
+        self.gc.set_fill_style(&style);
+    }
+
     #[inline(always)]
     fn fill_tile(
         &mut self,
@@ -105,7 +954,7 @@ impl DrawGrid for CanvasEnv {
     ) {
         let (x, y, w, h) = partial_tile(self.tile_size, x, y, dir, size);
 
-        self.gc.fill_rect(x, y, w, h);
+        self.fill_piece(x, y, w, h);
     }
 
     #[inline(always)]
@@ -117,12 +966,145 @@ impl DrawGrid for CanvasEnv {
         size: UnitInterval,
     ) {
         let (x, y, w, h) = partial_tile(self.tile_size, x, y, dir, size);
+        let (x, y, w, h) = self.crisp(x, y, w, h);
 
-        self.gc.clear_rect(x, y, w, h);
-        self.gc.stroke_rect(x, y, self.tile_size, self.tile_size);
+        self.restore_rect(x, y, w, h);
+    }
+
+    fn fill_head(
+        &mut self,
+        x: SmallNat,
+        y: SmallNat,
+        dir: Direction,
+        size: UnitInterval,
+    ) {
+        // square heads, tails and corners in pixel art
+        if self.theme.pixel_art {
+            return self.fill_tile(x, y, dir, size);
+        }
+
+        let s = self.tile_size;
+        let r = s / 2.0;
+        let short = size.scale(s);
+        let (x0, y0) = (x as f64 * s, y as f64 * s);
+
+        // a rect up to a half disc on the leading edge; early in the
+        // animation the disc reaches back into the neck, which is filled
+        let (mut x, mut y, mut w, mut h) = partial_tile(s, x, y, dir, size);
+        let (cx, cy) = match dir {
+            Direction::East => (x0 + short - r, y0 + r),
+            Direction::West => (x0 + s - short + r, y0 + r),
+            Direction::South => (x0 + r, y0 + short - r),
+            Direction::North => (x0 + r, y0 + s - short + r),
+        };
+        let shorten = |v: f64| if v > r { v - r } else { 0.0 };
+        match dir {
+            Direction::East => w = shorten(w),
+            Direction::West => {
+                x += w - shorten(w);
+                w = shorten(w);
+            }
+            Direction::South => h = shorten(h),
+            Direction::North => {
+                y += h - shorten(h);
+                h = shorten(h);
+            }
+        }
+
+        self.gc.fill_rect(x, y, w, h);
+        self.gc.begin_path();
+        match self.gc.arc(cx, cy, r, 0.0, 2.0 * PI) {
+            Ok(_) => self.gc.fill(),
+            Err(_) => self.reporter.report(ErrorPhase::Render, "arc failed"),
+        }
+    }
+
+    fn clear_tail(
+        &mut self,
+        x: SmallNat,
+        y: SmallNat,
+        dir: Direction,
+        size: UnitInterval,
+    ) {
+        if self.theme.pixel_art {
+            return self.clear_tile(x, y, dir, size);
+        }
+
+        let s = self.tile_size;
+        let (x0, y0) = (x as f64 * s, y as f64 * s);
+
+        self.restore_rect(x0, y0, s, s);
+
+        // what is left of the tile narrows down as the tail moves on
+        let left = size.complement();
+        let (x, y, w, h) = partial_tile(s, x, y, dir.opposite(), left);
+        let thickness = left.scale(s * 0.6) + s * 0.4;
+        let inset = (s - thickness) / 2.0;
+
+        match dir {
+            Direction::East | Direction::West => {
+                self.gc.fill_rect(x, y + inset, w, thickness)
+            }
+            Direction::North | Direction::South => {
+                self.gc.fill_rect(x + inset, y, thickness, h)
+            }
+        }
+    }
+
+    fn fill_corner(
+        &mut self,
+        x: SmallNat,
+        y: SmallNat,
+        from: Direction,
+        to: Direction,
+    ) {
+        if self.theme.pixel_art {
+            return self.fill_tile(x, y, to, UnitInterval::max_value());
+        }
+
+        let s = self.tile_size;
+        let (x0, y0) = (x as f64 * s, y as f64 * s);
+
+        // round off the corner of the tile away from both the side the
+        // snake comes in from and the side it leaves through
+        let right = from == Direction::East || to == Direction::West;
+        let bottom = from == Direction::South || to == Direction::North;
+        let outer = match (right, bottom) {
+            (false, false) => 0,
+            (true, false) => 1,
+            (true, true) => 2,
+            (false, true) => 3,
+        };
+        // clockwise from the top left
+        let corners = [(x0, y0), (x0 + s, y0), (x0 + s, y0 + s), (x0, y0 + s)];
+        let corner = |i: usize| corners[(outer + i) % 4];
+
+        self.restore_rect(x0, y0, s, s);
+
+        let (inner_x, inner_y) = corner(2);
+        let (before_x, before_y) = corner(3);
+        let (outer_x, outer_y) = corner(0);
+        let (after_x, after_y) = corner(1);
+
+        self.gc.begin_path();
+        self.gc.move_to(inner_x, inner_y);
+        self.gc.line_to(before_x, before_y);
+        match self.gc.arc_to(outer_x, outer_y, after_x, after_y, s / 2.0) {
+            Ok(_) => {
+                self.gc.line_to(after_x, after_y);
+                self.gc.close_path();
+                self.gc.fill();
+            }
+            Err(_) => self.reporter.report(ErrorPhase::Render, "arc failed"),
+        }
     }
 
     fn circle(&mut self, x: SmallNat, y: SmallNat, radius: UnitInterval) {
+        // square food in pixel art
+        if self.theme.pixel_art {
+            return self.square(x, y, radius);
+        }
+
         let x = x as f64 * self.tile_size;
         let y = y as f64 * self.tile_size;
 
@@ -130,63 +1112,186 @@ impl DrawGrid for CanvasEnv {
         let r = radius.scale(r_full);
 
         self.gc.begin_path();
-        let _ = self.gc.arc(x + r_full, y + r_full, r, 0.0, 2.0 * PI);
+        match self.gc.arc(x + r_full, y + r_full, r, 0.0, 2.0 * PI) {
+            Ok(_) => self.gc.fill(),
+            Err(_) => self.reporter.report(ErrorPhase::Render, "arc failed"),
+        }
+    }
+
+    fn triangle(&mut self, x: SmallNat, y: SmallNat, size: UnitInterval) {
+        let s = self.tile_size;
+        let h = size.scale(s);
+        let (cx, cy) = (x as f64 * s + s / 2.0, y as f64 * s + s / 2.0);
+
+        self.gc.begin_path();
+        self.gc.move_to(cx, cy - h / 2.0);
+        self.gc.line_to(cx + h / 2.0, cy + h / 2.0);
+        self.gc.line_to(cx - h / 2.0, cy + h / 2.0);
+        self.gc.close_path();
         self.gc.fill();
     }
 
+    fn square(&mut self, x: SmallNat, y: SmallNat, size: UnitInterval) {
+        let s = self.tile_size;
+        let w = size.scale(s);
+        let inset = (s - w) / 2.0;
+
+        self.fill_piece(x as f64 * s + inset, y as f64 * s + inset, w, w);
+    }
+
+    fn distinct_shapes(&self) -> bool {
+        self.theme.shapes
+    }
+
+    fn motion(&self) -> MotionPolicy {
+        self.motion
+    }
+
+    fn has_layers(&self) -> bool {
+        true
+    }
+
+    fn set_layer(&mut self, layer: Layer) -> Layer {
+        let prev_layer = self.layer;
+        if layer != prev_layer {
+            self.layer = layer;
+            self.gc = self.layers.gc(layer).clone();
+            self.gc.set_fill_style(&self.theme.color(self.color).into());
+        }
+        prev_layer
+    }
+
     fn show_game_over(&mut self) {
-        // best effort of centering text
-        let x = self.canvas.width() as f64 / 2.0 - 120.0;
-        let y = self.canvas.height() as f64 / 2.0 - 24.0;
+        if let Some(stats) = self.stats.clone() {
+            let stats = stats.borrow();
+            if stats.overlay() {
+                let prev_layer = self.set_layer(Layer::Debug);
+                self.draw_heatmap(&stats);
+                self.set_layer(prev_layer);
+            }
+        }
 
-        self.gc.set_font("36px serif");
-        let _ = self.gc.fill_text("Game Over", x, y);
+        let summary = match self.telemetry {
+            Some(ref telemetry) => telemetry.borrow().summary(),
+            None => None,
+        };
+        let lines = match summary {
+            Some(summary) => summary.lines(self.locale),
+            None => vec![self.locale.text(Text::GameOver).into()],
+        };
+        let prev_layer = self.set_layer(Layer::Hud);
+        self.draw_panel(&lines);
+        self.set_layer(prev_layer);
     }
-}
 
-pub fn partial_tile(
-    tile_size: f64,
-    x: SmallNat,
-    y: SmallNat,
-    dir: Direction,
-    size: UnitInterval,
-) -> (f64, f64, f64, f64) {
-    let x0 = x as f64 * tile_size;
-    let y0 = y as f64 * tile_size;
-
-    let long = tile_size;
-    let short = size.scale(tile_size);
-
-    let x;
-    let y;
-    let w;
-    let h;
-
-    match dir {
-        Direction::East => {
-            x = x0;
-            y = y0;
-            w = short;
-            h = long;
-        }
-        Direction::West => {
-            x = x0 + long - short;
-            y = y0;
-            w = short;
-            h = long;
-        }
-        Direction::South => {
-            x = x0;
-            y = y0;
-            w = long;
-            h = short;
-        }
-        Direction::North => {
-            x = x0;
-            y = y0 + long - short;
-            w = long;
-            h = short;
-        }
-    }
-    (x, y, w, h)
+    // a gray arrowhead in the middle of the head, pointing `dir`
+    fn show_debug(&mut self, on: bool) {
+        self.debug = on;
+        self.layers.clear(Layer::Debug);
+    }
+
+    fn show_next_turn(&mut self, x: SmallNat, y: SmallNat, dir: Direction) {
+        let s = self.tile_size;
+        let r = s / 4.0;
+        let (cx, cy) = (x as f64 * s + s / 2.0, y as f64 * s + s / 2.0);
+        let (dx, dy) = match dir {
+            Direction::East => (1.0, 0.0),
+            Direction::West => (-1.0, 0.0),
+            Direction::South => (0.0, 1.0),
+            Direction::North => (0.0, -1.0),
+        };
+        // the base is across from the tip, `(-dy, dx)` runs along it
+        let (bx, by) = (cx - dx * r, cy - dy * r);
+
+        self.with_fill_color(Color::Gray, |env| {
+            env.gc.begin_path();
+            env.gc.move_to(cx + dx * r, cy + dy * r);
+            env.gc.line_to(bx - dy * r, by + dx * r);
+            env.gc.line_to(bx + dy * r, by - dx * r);
+            env.gc.close_path();
+            env.gc.fill();
+        });
+    }
+
+    fn show_text(&mut self, text: &str) {
+        let caption: &Node = self.caption.as_ref();
+
+        caption.set_text_content(Some(text));
+        self.caption.set_hidden(false);
+    }
+
+    fn hide_text(&mut self) {
+        self.caption.set_hidden(true);
+    }
+
+    fn show_clock(&mut self, left: u32) {
+        self.clock = Some(left);
+        self.update_hud();
+    }
+
+    fn show_lives(&mut self, left: u32) {
+        self.lives = Some(left);
+        self.update_hud();
+    }
+
+    fn show_power_up(&mut self, power_up: PowerUp, active: bool) {
+        self.power_up = if active { Some(power_up) } else { None };
+        self.update_hud();
+    }
+
+    fn show_band(&mut self, line: Line, color: Color, alpha: UnitInterval) {
+        self.bands.retain(|&(shown, _, _)| shown != line);
+        self.bands.push((line, color, alpha));
+        self.effects_changed = true;
+    }
+
+    fn hide_band(&mut self, line: Line) {
+        self.bands.retain(|&(shown, _, _)| shown != line);
+        self.effects_changed = true;
+    }
+
+    fn food_eaten(&mut self, x: SmallNat, y: SmallNat) {
+        let gain = match self.scoring {
+            Some(ref scoring) => scoring.borrow().last_gain(),
+            None => None,
+        };
+
+        if self.team.is_some() {
+            self.update_hud();
+        }
+
+        // popups are redrawn on every frame they move
+        let saving = self.saver.as_ref().map_or(false, |saver| saver.is_on());
+        if !self.popups || saving || self.shown_tier == QualityTier::Low {
+            return;
+        }
+
+        if let Some(gain) = gain {
+            let s = self.tile_size;
+            let popup = Popup {
+                x: x as f64 * s + s / 2.0,
+                y: y as f64 * s,
+                text: format!("{}", gain),
+            };
+            self.floating.push(popup, POPUP_FRAMES);
+        }
+    }
+
+    // rises a tile over its popup, fading out, or stays put with reduced
+    // motion, then shows the layers as they are now
+    fn end_frame(&mut self) {
+        self.apply_quality();
+        if self.image_pending && self.loaded_image().is_some() {
+            self.redraw_all();
+        }
+        if self.effects_changed || !self.floating.is_empty() {
+            self.effects_changed = false;
+            self.draw_effects();
+        }
+        if self.debug {
+            self.draw_debug();
+        }
+
+        self.present();
+    }
 }
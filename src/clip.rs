@@ -0,0 +1,120 @@
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use std::cell::Cell;
+use std::ops::{Generator, GeneratorState};
+
+use js_sys::{Array, Promise};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{
+    Blob, BlobEvent, BlobPropertyBag, MediaRecorder, MediaRecorderOptions,
+};
+
+use canvas::{CanvasEnv, EnvError};
+use constants::{CLIP_FPS, CLIP_FRAME_MS, CLIP_MIME};
+use data::Command;
+use draw::TickDraw;
+use errors::ErrorReporter;
+use system::Stateful;
+use theme::Theme;
+use world::TickDelta;
+
+/// Renders `model` frame by frame into an offscreen canvas until it quits,
+/// as a `Playback` does once its replay runs out, while a `MediaRecorder`
+/// records the canvas. Resolves to a WebM `Blob` of the clip.
+pub fn record<M>(
+    model: M,
+    theme: &Theme,
+    reporter: ErrorReporter,
+) -> Result<Promise, EnvError>
+where
+    M: 'static + for<'m> Stateful<'m, Cmd = Command, Update = TickDelta>,
+{
+    let window = web_sys::window().ok_or(EnvError::NoDocument)?;
+
+    let env = CanvasEnv::offscreen(reporter)?.with_theme(theme.clone());
+    let stream = env
+        .canvas()
+        .capture_stream_with_frame_request_rate(CLIP_FPS)?;
+
+    let mut options = MediaRecorderOptions::new();
+    options.mime_type(CLIP_MIME);
+    let recorder =
+        MediaRecorder::new_with_media_stream_and_media_recorder_options(
+            &stream, &options,
+        )?;
+
+    let chunks = Array::new();
+    let on_data = {
+        let chunks = chunks.clone();
+        Closure::wrap(Box::new(move |event: BlobEvent| {
+            if let Some(data) = event.data() {
+                chunks.push(&data);
+            }
+        }) as Box<FnMut(_)>)
+    };
+    recorder.set_ondataavailable(Some(on_data.as_ref().unchecked_ref()));
+
+    let promise = Promise::new(&mut |resolve, reject| {
+        let chunks = chunks.clone();
+        let on_stop = Closure::wrap(Box::new(move || {
+            let mut kind = BlobPropertyBag::new();
+            kind.type_(CLIP_MIME);
+
+            let blob =
+                Blob::new_with_blob_sequence_and_options(&chunks, &kind);
+            let _ = match blob {
+                Ok(blob) => resolve.call1(&JsValue::NULL, &blob),
+                Err(err) => reject.call1(&JsValue::NULL, &err),
+            };
+        }) as Box<FnMut()>);
+
+        recorder.set_onstop(Some(on_stop.as_ref().unchecked_ref()));
+        on_stop.forget();
+    });
+
+    let (_, mut generator) =
+        model.make_game(env).new_game::<TickDraw, Command>();
+
+    // one frame of the game per interval, as the game loop draws one per
+    // animation frame
+    let interval = Rc::new(Cell::new(None));
+    let each_frame = {
+        let interval = interval.clone();
+        let window = window.clone();
+        let recorder = recorder.clone();
+
+        Closure::wrap(Box::new(move || {
+            if interval.get().is_none() {
+                return;
+            }
+
+            if let GeneratorState::Complete(()) = unsafe { generator.resume() }
+            {
+                if let Some(handle) = interval.take() {
+                    window.clear_interval_with_handle(handle);
+                }
+                let _ = recorder.stop();
+            }
+        }) as Box<FnMut()>)
+    };
+
+    recorder.start()?;
+    let handle = window
+        .set_interval_with_callback_and_timeout_and_arguments_0(
+            each_frame.as_ref().unchecked_ref(),
+            CLIP_FRAME_MS,
+        )
+        .map_err(|err| {
+            let _ = recorder.stop();
+            err
+        })?;
+    interval.set(Some(handle));
+
+    // the callbacks live as long as the recording, which ends on its own,
+    // leaking them is the price of not tracking it
+    on_data.forget();
+    each_frame.forget();
+
+    Ok(promise)
+}
@@ -0,0 +1,21 @@
+use std::fmt;
+
+use wasm_bindgen::JsValue;
+use web_sys::console;
+
+use log::{self, LogLevel};
+
+/// Routes the engine log to the browser console
+pub fn install() {
+    log::set_logger(write);
+}
+
+fn write(level: LogLevel, args: fmt::Arguments) {
+    let msg = JsValue::from_str(&::alloc::fmt::format(args));
+
+    match level {
+        LogLevel::Trace | LogLevel::Debug => console::debug_1(&msg),
+        LogLevel::Info => console::info_1(&msg),
+        _ => console::warn_1(&msg),
+    }
+}
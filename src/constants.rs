@@ -1,2 +1,76 @@
-pub const TILE_SIZE: u32 = 16;
-pub const ANIMATION_FRAME_COUNT: u8 = 8;
+pub use snake_core::constants::{
+    ANIMATION_FRAME_COUNT, MAX_BOARD_SIDE, TILE_SIZE,
+};
+
+use bot::Strategy;
+use data::SmallNat;
+
+#[cfg(feature = "achievements")]
+pub const ACHIEVEMENT_FOOD: u32 = 10;
+#[cfg(feature = "achievements")]
+pub const ACHIEVEMENT_TICKS: u32 = 500;
+#[cfg(feature = "achievements")]
+pub const ACHIEVEMENT_FILL_PERCENT: u32 = 50;
+pub const ACHIEVEMENTS_KEY: &str = "snake-wasm.achievements";
+// frames without a key on the game over screen before a demo game starts,
+// and that the game over screen of a demo game stays up
+pub const ATTRACT_AFTER: u32 = 10 * FRAMES_PER_SECOND;
+pub const ATTRACT_BETWEEN: u32 = 2 * FRAMES_PER_SECOND;
+pub const BLOCKS_GOAL: u32 = 20;
+pub const BLOCKS_WIDTH: SmallNat = 10;
+// the opponent of each difficulty, the easiest first, see `Ratings`
+pub const BOT_DIFFICULTIES: [Strategy; 4] = [
+    Strategy::Random,
+    Strategy::Greedy,
+    Strategy::Hamiltonian,
+    Strategy::AStar,
+];
+// tiles of the bots playing along, the ally of a team game and the opponent
+pub const BOT_LENGTH: SmallNat = 5;
+// a longer stall is the tab having been hidden, not a slow device
+pub const CATCH_UP_TICKS: u32 = 20;
+#[cfg(feature = "clip")]
+pub const CLIP_FPS: f64 = 60.0;
+#[cfg(feature = "clip")]
+pub const CLIP_FRAME_MS: i32 = 16;
+#[cfg(feature = "clip")]
+pub const CLIP_MIME: &str = "video/webm";
+pub const COMBO_MAX: u32 = 8;
+pub const COMBO_WINDOW: u32 = 40;
+pub const EVENT_HISTORY_LEN: u32 = 256;
+pub const FAST_FORWARD_TICKS: u32 = 10;
+pub const FOOD_POINTS: u32 = 10;
+// the frame rate ticks are paced for, see `Pacer`
+pub const FRAMES_PER_SECOND: u32 = 60;
+pub const HISTORY_LEN: usize = 600;
+// the share of a full charge `PowerSaving::Auto` saves power below
+pub const LOW_BATTERY: f64 = 0.2;
+#[cfg(feature = "leaderboard")]
+pub const LEADERBOARD_ATTEMPTS: u32 = 3;
+#[cfg(feature = "leaderboard")]
+pub const LEADERBOARD_RETRY_DELAY: u32 = 500;
+// pixels a tile of `GameBuilder::mini_view` takes
+pub const MINI_TILE_SIZE: SmallNat = 2;
+pub const PHOTO_SCALE: SmallNat = 2;
+// half a second at 60 frames per second
+pub const POPUP_FRAMES: u8 = 30;
+// milliseconds a frame may take, half of one at 60 frames per second to
+// leave the browser the rest, see `AdaptiveQuality`
+pub const QUALITY_BUDGET_MS: f64 = 8.0;
+// the frames the tier is picked on, two seconds' worth
+pub const QUALITY_FRAMES: usize = 120;
+// the share of the budget frames are to stay under for a higher tier
+pub const QUALITY_HEADROOM: f64 = 0.5;
+#[cfg(feature = "replays")]
+pub const REPLAYS_DB: &str = "snake-wasm";
+#[cfg(feature = "replays")]
+pub const REPLAYS_DB_VERSION: u32 = 1;
+#[cfg(feature = "replays")]
+pub const REPLAYS_STORE: &str = "replays";
+pub const RATING_DIFFICULTY_STEP: i32 = 200;
+pub const RATING_K: i32 = 32;
+pub const RATING_START: i32 = 1200;
+pub const RATINGS_KEY: &str = "snake-wasm.ratings";
+pub const SETTINGS_KEY: &str = "snake-wasm.settings";
+pub const SPAWN_MIN_DISTANCE: SmallNat = 8;
+pub const SPAWN_WALL_MARGIN: SmallNat = 2;
@@ -0,0 +1,71 @@
+use data::SmallNat;
+use js_api::GameConfig;
+
+const MILLIS_PER_DAY: f64 = 86_400_000.0;
+
+/// A game everyone plays on the same (UTC) day: the seed and the board size
+/// are derived from the number of days since the unix epoch
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct DailyChallenge {
+    day: u32,
+}
+
+impl DailyChallenge {
+    pub fn from_day(day: u32) -> Self {
+        DailyChallenge { day }
+    }
+
+    pub fn from_unix_millis(millis: f64) -> Self {
+        Self::from_day((millis / MILLIS_PER_DAY) as u32)
+    }
+
+    pub fn today() -> Self {
+        Self::from_unix_millis(js_sys::Date::now())
+    }
+
+    pub fn day(&self) -> u32 {
+        self.day
+    }
+
+    pub fn seed(&self) -> u32 {
+        // murmur3 finalizer, so that consecutive days look unrelated
+        let mut h = self.day.wrapping_add(0x9e37_79b9);
+        h ^= h >> 16;
+        h = h.wrapping_mul(0x85eb_ca6b);
+        h ^= h >> 13;
+        h = h.wrapping_mul(0xc2b2_ae35);
+        h ^ (h >> 16)
+    }
+
+    pub fn config(&self) -> GameConfig {
+        let seed = self.seed();
+
+        let mut config = GameConfig::new();
+        config.set_seed(seed);
+        config.set_width(32 + (seed % 5) as SmallNat * 8);
+        config.set_height(16 + (seed >> 8) as SmallNat % 3 * 8);
+        config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_day_same_board() {
+        let morning = DailyChallenge::from_unix_millis(1_540_000_000_000.0);
+        let evening = DailyChallenge::from_unix_millis(
+            1_540_000_000_000.0 + MILLIS_PER_DAY / 4.0,
+        );
+        let tomorrow = DailyChallenge::from_day(morning.day() + 1);
+
+        assert_eq!(morning, evening);
+        assert_eq!(morning.seed(), evening.seed());
+        assert_ne!(morning.seed(), tomorrow.seed());
+
+        let config = morning.config();
+        assert!(config.width() >= 32 && config.width() <= 64);
+        assert!(config.height() >= 16 && config.height() <= 32);
+    }
+}
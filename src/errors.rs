@@ -0,0 +1,76 @@
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use alloc::string::ToString;
+use std::cell::RefCell;
+
+use js_sys::Function;
+use wasm_bindgen::prelude::*;
+
+// the phase is tracked by the game loop in the core crate
+pub use system::{current_phase, enter_phase, ErrorPhase};
+
+/// Forwards errors to the `on_error` callback of a `GameHandle`, as
+/// `callback(phase, message)`
+#[derive(Clone)]
+pub struct ErrorReporter {
+    callback: Rc<RefCell<Option<Function>>>,
+}
+
+impl ErrorReporter {
+    pub fn new() -> Self {
+        ErrorReporter {
+            callback: Rc::new(RefCell::new(None)),
+        }
+    }
+
+    pub fn set_callback(&self, callback: Function) {
+        *self.callback.borrow_mut() = Some(callback);
+    }
+
+    pub fn report(&self, phase: ErrorPhase, message: &str) {
+        match *self.callback.borrow() {
+            Some(ref callback) => {
+                let _ = callback.call2(
+                    &JsValue::NULL,
+                    &JsValue::from_f64(phase as u8 as f64),
+                    &JsValue::from_str(message),
+                );
+            }
+            None => {
+                warn!("snake error ({:?}): {}", phase, message);
+            }
+        }
+    }
+
+    /// Reports panics with the phase they happen in, on top of logging them
+    /// to the console. The hook is set on the first call, later ones only
+    /// report to the latest reporter, e.g. the one of the game now played.
+    pub fn install_panic_hook(&self) {
+        PANIC_REPORTER.with(|reporter| {
+            *reporter.borrow_mut() = Some(self.clone());
+        });
+
+        HOOK_SET.call_once(|| set_panic_hook());
+    }
+}
+
+fn set_panic_hook() {
+    ::libstd::panic::set_hook(Box::new(|info| {
+        ::console_error_panic_hook::hook(info);
+
+        PANIC_REPORTER.with(|reporter| {
+            // the panic may come from inside a report
+            if let Ok(reporter) = reporter.try_borrow() {
+                if let Some(ref reporter) = *reporter {
+                    reporter.report(current_phase(), &info.to_string());
+                }
+            }
+        });
+    }));
+}
+
+static HOOK_SET: ::libstd::sync::Once = ::libstd::sync::ONCE_INIT;
+
+::libstd::thread_local! {
+    static PANIC_REPORTER: RefCell<Option<ErrorReporter>> = RefCell::new(None);
+}
@@ -0,0 +1,327 @@
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use std::cell::RefCell;
+
+use achievements::Achievement;
+use data::{Block, Command, Coordinate, Direction, PowerUp};
+use quality::QualityTier;
+use system::{Observer, Stateful, Tick};
+use telemetry::GameSummary;
+use world::{TeamId, UpdateError, WorldUpdate, PLAYER};
+
+/// Gameplay events derived from the world update stream, for hosts and
+/// subsystems that care about what happened rather than what to draw
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum GameEvent {
+    Started,
+    FoodEaten { at: Coordinate, length: u32 },
+    FoodSpawned { at: Coordinate },
+    PowerUpTaken { at: Coordinate, power_up: PowerUp },
+    InvalidInput(Direction),
+    Paused(bool),
+    // seed of the game that ended, for comparing scores on the same board
+    Died { reason: UpdateError, seed: u32 },
+    // the snake covered the board of the game with `seed`
+    Won { seed: u32 },
+    // how the game went, right after `Died`, see `Telemetry::with_events`
+    GameOver(GameSummary),
+    AchievementUnlocked(Achievement),
+    // food eaten as the `multiplier`th in a row, scoring `points`
+    Combo { multiplier: u32, points: u32 },
+    // too long since the last food, the multiplier is back to 1
+    ComboLost,
+    // a snake of `team` died, ending the game for all of it at `score`
+    TeamLost { team: TeamId, score: u32 },
+    // the game draws at another tier, see `AdaptiveQuality`
+    QualityChanged(QualityTier),
+}
+
+impl GameEvent {
+    /// Whether the event marks a turn in how a game went, as kept for
+    /// `EventBus::history`: starts, food, power-ups, deaths, wins,
+    /// achievements and teams losing
+    pub fn is_major(&self) -> bool {
+        match *self {
+            GameEvent::Started
+            | GameEvent::FoodEaten { .. }
+            | GameEvent::PowerUpTaken { .. }
+            | GameEvent::Died { .. }
+            | GameEvent::Won { .. }
+            | GameEvent::AchievementUnlocked(_)
+            | GameEvent::TeamLost { .. } => true,
+            _ => false,
+        }
+    }
+}
+
+pub trait EventSink {
+    fn on_event(&mut self, event: &GameEvent);
+}
+
+impl<F: FnMut(&GameEvent)> EventSink for F {
+    fn on_event(&mut self, event: &GameEvent) {
+        self(event)
+    }
+}
+
+/// Observes a `World` and publishes `GameEvent`s to every subscribed sink.
+/// It keeps the last major events of the session too, stamped with the
+/// tick they happened on when the model is `Stamped` with the bus.
+pub struct EventBus {
+    sinks: Vec<Box<EventSink>>,
+    seed: u32,
+    // oldest first, at most `history_len` of them
+    history: VecDeque<(Tick, GameEvent)>,
+    history_len: usize,
+    now: Tick,
+
+    length: u32,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        EventBus {
+            sinks: Vec::new(),
+            seed: 0,
+            history: VecDeque::new(),
+            history_len: 0,
+            now: 0,
+            length: 0,
+        }
+    }
+
+    /// Seed reported with `GameEvent::Died` and `GameEvent::Won`
+    pub fn with_seed(mut self, seed: u32) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Keeps the last `len` major events published, none by default
+    pub fn with_history(mut self, len: usize) -> Self {
+        self.history_len = len;
+        self
+    }
+
+    pub fn subscribe(&mut self, sink: Box<EventSink>) {
+        self.sinks.push(sink);
+    }
+
+    pub fn publish(&mut self, event: GameEvent) {
+        if event.is_major() && self.history_len > 0 {
+            if self.history.len() == self.history_len {
+                self.history.pop_front();
+            }
+            self.history.push_back((self.now, event));
+        }
+
+        for sink in self.sinks.iter_mut() {
+            sink.on_event(&event);
+        }
+    }
+
+    /// The major events kept, see `with_history`, oldest first with the
+    /// tick each happened on
+    pub fn history(&self) -> impl Iterator<Item = &(Tick, GameEvent)> {
+        self.history.iter()
+    }
+}
+
+impl Observer<WorldUpdate, UpdateError> for EventBus {
+    fn on_init(&mut self, update: &WorldUpdate) {
+        match *update {
+            WorldUpdate::LevelLoaded { .. } => {
+                self.length = 0;
+                self.publish(GameEvent::Started);
+            }
+            WorldUpdate::Respawned { .. } => self.length = 0,
+            WorldUpdate::SetBlock {
+                block: Block::Snake(_, PLAYER),
+                ..
+            } => {
+                self.length += 1;
+            }
+            WorldUpdate::SetBlock {
+                block: Block::Food,
+                at,
+            } => {
+                self.publish(GameEvent::FoodSpawned { at });
+            }
+            _ => {}
+        }
+    }
+
+    fn on_update(&mut self, update: &WorldUpdate) {
+        match *update {
+            WorldUpdate::SetBlock {
+                block: Block::Snake(_, PLAYER),
+                ..
+            }
+            | WorldUpdate::HeadMoved { snake: PLAYER, .. }
+            | WorldUpdate::Bend { snake: PLAYER, .. } => {
+                self.length += 1;
+            }
+            WorldUpdate::FoodEaten { snake: PLAYER, at } => {
+                let length = self.length;
+                self.publish(GameEvent::FoodEaten { at, length });
+            }
+            WorldUpdate::FoodSpawned { at } => {
+                self.publish(GameEvent::FoodSpawned { at });
+            }
+            WorldUpdate::PowerUpTaken { at, power_up } => {
+                self.publish(GameEvent::PowerUpTaken { at, power_up });
+            }
+            WorldUpdate::TailRetracted { snake: PLAYER, .. } => {
+                self.length = self.length.saturating_sub(1);
+            }
+            WorldUpdate::InvalidInput(dir) => {
+                self.publish(GameEvent::InvalidInput(dir));
+            }
+            WorldUpdate::Paused(paused) => {
+                self.publish(GameEvent::Paused(paused));
+            }
+            _ => {}
+        }
+    }
+
+    fn on_error(&mut self, err: &UpdateError) {
+        match *err {
+            UpdateError::Restart
+            | UpdateError::Quit
+            | UpdateError::LifeLost => {}
+            UpdateError::BoardFilled => {
+                let seed = self.seed;
+                self.publish(GameEvent::Won { seed });
+            }
+            reason => {
+                let seed = self.seed;
+                self.publish(GameEvent::Died { reason, seed });
+            }
+        }
+    }
+}
+
+/// Tells an `EventBus` the tick `model` is stepped on before each step, for
+/// the events of the step to be stamped with
+pub struct Stamped<M> {
+    model: M,
+    bus: Rc<RefCell<EventBus>>,
+}
+
+impl<M> Stamped<M> {
+    pub fn new(model: M, bus: Rc<RefCell<EventBus>>) -> Self {
+        Stamped { model, bus }
+    }
+}
+
+impl<'m, M> Stateful<'m> for Stamped<M>
+where
+    M: Stateful<'m, Cmd = Command>,
+{
+    type Cmd = Command;
+    type Init = M::Init;
+    type Update = M::Update;
+    type Error = M::Error;
+
+    fn initialize(&'m mut self) -> Self::Init {
+        self.model.initialize()
+    }
+
+    #[inline]
+    fn step(
+        &mut self,
+        cmd: Option<Self::Cmd>,
+        tick: Tick,
+    ) -> Result<Option<Self::Update>, Self::Error> {
+        self.bus.borrow_mut().now = tick;
+        self.model.step(cmd, tick)
+    }
+
+    fn tear_down(&mut self) {
+        self.model.tear_down();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::rc::Rc;
+    use std::cell::RefCell;
+
+    use rand::rngs::SmallRng;
+
+    use super::*;
+    use data::Wrapping;
+    use system::Stateful;
+    use world::{PerChange, World};
+
+    #[test]
+    fn test_food_eaten_event() {
+        let world: World<SmallRng, Wrapping> = World::from_ascii(indoc!(
+            "
+            ..........
+            .>>>*.....
+            .........."
+        ));
+
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let mut bus = EventBus::new();
+        {
+            let events = events.clone();
+            bus.subscribe(Box::new(move |e: &GameEvent| {
+                events.borrow_mut().push(*e);
+            }));
+        }
+
+        let mut observed = world.observe(PerChange(bus));
+
+        for _ in observed.initialize() {}
+        observed.step(None, 0).unwrap();
+        observed.step(None, 0).unwrap();
+
+        let events = events.borrow();
+
+        assert_eq!(events[0], GameEvent::Started);
+        assert!(events.contains(&GameEvent::FoodEaten {
+            at: Coordinate { x: 4, y: 1 },
+            length: 4,
+        }));
+    }
+
+    #[test]
+    fn test_history_keeps_the_last_major_events() {
+        let world: World<SmallRng, Wrapping> = World::from_ascii(indoc!(
+            "
+            ..........
+            .>>>*.....
+            .........."
+        ));
+
+        let bus = Rc::new(RefCell::new(EventBus::new().with_history(2)));
+        let mut observed =
+            Stamped::new(world, bus.clone()).observe(PerChange(bus.clone()));
+
+        for _ in observed.initialize() {}
+        observed.step(None, 1).unwrap();
+        observed.step(None, 2).unwrap();
+        bus.borrow_mut().publish(GameEvent::ComboLost);
+        bus.borrow_mut()
+            .publish(GameEvent::AchievementUnlocked(Achievement::Gourmet));
+
+        // `Started` made room for the achievement, the combo isn't kept
+        let history: Vec<_> = bus.borrow().history().cloned().collect();
+        assert_eq!(history.len(), 2);
+        assert_eq!(
+            history[0].1,
+            GameEvent::FoodEaten {
+                at: Coordinate { x: 4, y: 1 },
+                length: 4,
+            }
+        );
+        assert!(history[0].0 >= 1);
+        assert_eq!(
+            history[1],
+            (2, GameEvent::AchievementUnlocked(Achievement::Gourmet))
+        );
+    }
+}
@@ -0,0 +1,132 @@
+use js_sys::{Array, Function, Object, Reflect};
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::Navigator;
+
+use events::{EventSink, GameEvent};
+
+/// A buzz of the controller or phone, `magnitude` from 0 to 1
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Rumble {
+    millis: u32,
+    magnitude: f64,
+}
+
+/// Follows the `EventBus` and rumbles the connected gamepads on crashes
+/// and food, or vibrates the phone the page is on without any. Does
+/// nothing in browsers with neither.
+pub struct Haptics {
+    navigator: Option<Navigator>,
+}
+
+impl Haptics {
+    pub fn new() -> Self {
+        Haptics {
+            navigator: web_sys::window().map(|window| window.navigator()),
+        }
+    }
+
+    // plays `rumble` on every gamepad that has a `vibrationActuator`,
+    // whether there was any
+    fn rumble_gamepads(navigator: &Navigator, rumble: Rumble) -> bool {
+        let pads = match method(navigator, "getGamepads")
+            .and_then(|get| get.call0(navigator).ok())
+        {
+            Some(pads) => Array::from(&pads),
+            None => return false,
+        };
+
+        let params = Object::new();
+        let set = |name: &str, value: f64| {
+            let value = JsValue::from_f64(value);
+            let _ = Reflect::set(&params, &JsValue::from_str(name), &value);
+        };
+        set("duration", rumble.millis as f64);
+        set("strongMagnitude", rumble.magnitude);
+        set("weakMagnitude", rumble.magnitude);
+
+        let key = JsValue::from_str("vibrationActuator");
+        let mut rumbled = false;
+        // unplugged slots are null
+        for pad in pads.iter().filter(|pad| pad.is_object()) {
+            let actuator = match Reflect::get(&pad, &key) {
+                Ok(actuator) => actuator,
+                Err(_) => continue,
+            };
+            if !actuator.is_object() {
+                continue;
+            }
+
+            if let Some(play) = method(&actuator, "playEffect") {
+                let effect = JsValue::from_str("dual-rumble");
+                rumbled |= play.call2(&actuator, &effect, &params).is_ok();
+            }
+        }
+        rumbled
+    }
+}
+
+impl Default for Haptics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventSink for Haptics {
+    fn on_event(&mut self, event: &GameEvent) {
+        let (navigator, rumble) = match (&self.navigator, rumble(event)) {
+            (&Some(ref navigator), Some(rumble)) => (navigator, rumble),
+            _ => return,
+        };
+
+        if !Self::rumble_gamepads(navigator, rumble) {
+            // false where `navigator.vibrate` isn't supported either
+            navigator.vibrate_with_duration(rumble.millis);
+        }
+    }
+}
+
+// the function `name` of `object`, if it has one
+pub(crate) fn method(object: &JsValue, name: &str) -> Option<Function> {
+    Reflect::get(object, &JsValue::from_str(name))
+        .ok()?
+        .dyn_into::<Function>()
+        .ok()
+}
+
+fn rumble(event: &GameEvent) -> Option<Rumble> {
+    match *event {
+        GameEvent::Died { .. } => Some(Rumble {
+            millis: 300,
+            magnitude: 1.0,
+        }),
+        GameEvent::FoodEaten { .. } => Some(Rumble {
+            millis: 40,
+            magnitude: 0.3,
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data::Coordinate;
+    use world::UpdateError;
+
+    #[test]
+    fn test_crashes_rumble_harder_than_food() {
+        let eaten = GameEvent::FoodEaten {
+            at: Coordinate { x: 1, y: 2 },
+            length: 4,
+        };
+        let died = GameEvent::Died {
+            reason: UpdateError::CollideBody,
+            seed: 0,
+        };
+
+        let (eaten, died) = (rumble(&eaten).unwrap(), rumble(&died).unwrap());
+        assert!(died.millis > eaten.millis);
+        assert!(died.magnitude > eaten.magnitude);
+        assert_eq!(rumble(&GameEvent::Started), None);
+    }
+}
@@ -0,0 +1,2164 @@
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use std::cell::{Cell, RefCell};
+use std::ops::Generator;
+
+use js_sys::{Array, Function, Object, Promise, Reflect, Uint8Array};
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlCanvasElement};
+
+use acceleration::{snapped, Paced, RenderSpeed, VariableFrame};
+use achievements::{Achievement, Achievements};
+use announcer::Announcer;
+use blocks::{BlocksDraw, FallingBlocks};
+use bot::{Attract, AttractMode, Autopilot, Policy, Strategy};
+use canvas::{
+    document_body, query_element, tile_box, CanvasEnv, EnvError, Resizes,
+};
+use checkpoint::Checkpoints;
+#[cfg(feature = "clip")]
+use clip;
+use config::Config;
+use console;
+use constants::{
+    ACHIEVEMENTS_KEY, ATTRACT_AFTER, ATTRACT_BETWEEN, BLOCKS_GOAL,
+    BLOCKS_WIDTH, BOT_DIFFICULTIES, BOT_LENGTH, CATCH_UP_TICKS,
+    EVENT_HISTORY_LEN, FAST_FORWARD_TICKS, FRAMES_PER_SECOND, HISTORY_LEN,
+    MAX_BOARD_SIDE, MINI_TILE_SIZE, PHOTO_SCALE, QUALITY_BUDGET_MS,
+    RATINGS_KEY, SETTINGS_KEY, SPAWN_MIN_DISTANCE, SPAWN_WALL_MARGIN,
+    TILE_SIZE,
+};
+use data::{
+    Block, BoundingBehavior, Command, ConfigError, Coordinate, Direction,
+    Grid, Key, KeyRepeat, PowerUp, SmallNat, Wrapping,
+};
+use daily::DailyChallenge;
+use dead::Dead;
+use draw::{entities, redraw_view, TickDraw, WorldUpdateDraw};
+use errors::{enter_phase, ErrorPhase, ErrorReporter};
+use events::{EventBus, EventSink, GameEvent, Stamped};
+use haptics::Haptics;
+use history::{Entry, History, Rewind, Timeline};
+use i18n::{Locale, Text};
+use keymap::{Binding, KeyMap};
+#[cfg(feature = "leaderboard")]
+use leaderboard::{self, Leaderboard, LeaderboardError, ScoreEntry};
+use life::{LifeDraw, LifeModel};
+use log::{self, LogLevel};
+use replay::{initial_world, rng_seed, Playback, Record, Replay};
+#[cfg(feature = "replays")]
+use replays::ReplayLibrary;
+use power::{PowerSaver, PowerSaving};
+use quality::{self, AdaptiveQuality, QualityTier};
+use ratings::{RatedGame, Ratings};
+use scoring::{Combo, Scoring, Team};
+use settings::Settings;
+use stats::{Counted, DeathReason, Stats};
+use telemetry::{GameSummary, Telemetry};
+use system::{
+    boxed, CmdSender, DrawGrid, Fanout, GameState, IncrRender, Lag,
+    MotionPolicy, Observer, Pacer, Renderers, Schedule, Stateful, Tick,
+};
+use theme::{Background, Palette};
+use tutorial::{Tutorial, BASICS};
+use world::{
+    Arena, AwayFromHead, NearWalls, PerChange, Scripted, SnakeId, Snapshot,
+    TeamId, TickDelta, UniformRandom, UpdateError, World, WorldUpdate,
+    PLAYER,
+};
+
+#[wasm_bindgen(module = "./game-loop")]
+extern "C" {
+    type GameLoop;
+
+    #[wasm_bindgen(constructor)]
+    fn new(run: &Closure<FnMut(u8)>) -> GameLoop;
+
+    #[wasm_bindgen(method)]
+    fn start(this: &GameLoop) -> bool;
+
+    #[wasm_bindgen(method)]
+    fn stop(this: &GameLoop) -> bool;
+}
+
+/// Board and seed of a new game, and where to post its scores
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct GameConfig {
+    // board, seed, lives, modes, `Rules` and `Theme`, as a config file of
+    // the native frontends has them; replays record the board, seed, lives
+    // and rules
+    game: Config,
+    pub spawn: Spawn,
+    // walks new players through the controls, see `tutorial::BASICS`
+    pub tutorial: bool,
+    // a bot ally plays along in the team of the player's, from the bottom
+    // right corner: their points count together, its crash is the
+    // player's, see `Team`; replays don't record it
+    pub team: bool,
+    // what steers the snake in demo games, see `AttractMode`
+    pub autopilot: Strategy,
+    // a bot opponent playing against the player from the middle of the
+    // right side: the one with more points once the player's game is over
+    // wins it, see `Ratings`
+    opponent: Option<Strategy>,
+    // reads the game out to screen readers, see `Announcer`
+    pub announce: bool,
+    // rumbles gamepads, or vibrates phones, on crashes and food, see
+    // `Haptics`
+    pub haptics: bool,
+    // major events `GameHandle::timeline` keeps, the oldest go first
+    pub timeline_len: u32,
+    // steps the ticks a slow device missed at once rather than slow the
+    // game down, see `Pacer`
+    pub frame_skip: bool,
+    pub power_saving: PowerSaving,
+    // draws less of the looks while frames take too long, see
+    // `AdaptiveQuality`
+    pub adaptive_quality: bool,
+    // only used with the leaderboard feature
+    #[cfg_attr(not(feature = "leaderboard"), allow(dead_code))]
+    leaderboard: Option<String>,
+    food_script: Vec<Coordinate>,
+    checkpoints: Vec<Coordinate>,
+    // the language of `navigator.language` unless set
+    locale: Option<Locale>,
+    // the browser's `prefers-reduced-motion` unless set
+    motion: Option<MotionPolicy>,
+}
+
+#[wasm_bindgen]
+impl GameConfig {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> GameConfig {
+        GameConfig {
+            game: Config::default(),
+            spawn: Spawn::UniformRandom,
+            tutorial: false,
+            team: false,
+            autopilot: Strategy::AStar,
+            opponent: None,
+            announce: false,
+            haptics: true,
+            timeline_len: EVENT_HISTORY_LEN,
+            frame_skip: true,
+            power_saving: PowerSaving::Auto,
+            adaptive_quality: true,
+            leaderboard: None,
+            food_script: Vec::new(),
+            checkpoints: Vec::new(),
+            locale: None,
+            motion: None,
+        }
+    }
+
+    /// Tiles across the board, at most `MAX_BOARD_SIDE`
+    #[wasm_bindgen(getter)]
+    pub fn width(&self) -> SmallNat {
+        self.game.width
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_width(&mut self, width: SmallNat) {
+        self.game.width = width;
+    }
+
+    /// Tiles down the board, at most `MAX_BOARD_SIDE`
+    #[wasm_bindgen(getter)]
+    pub fn height(&self) -> SmallNat {
+        self.game.height
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_height(&mut self, height: SmallNat) {
+        self.game.height = height;
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn seed(&self) -> u32 {
+        self.game.seed
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_seed(&mut self, seed: u32) {
+        self.game.seed = seed;
+    }
+
+    /// Battle royale: the arena shrinks by a ring every so many ticks, 0
+    /// to play on the whole board
+    #[wasm_bindgen(getter)]
+    pub fn shrink_every(&self) -> u32 {
+        self.game.shrink_every
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_shrink_every(&mut self, ticks: u32) {
+        self.game.shrink_every = ticks;
+    }
+
+    /// Time attack: ticks on the clock to start with, 0 for no clock
+    #[wasm_bindgen(getter)]
+    pub fn time_budget(&self) -> u32 {
+        self.game.time_budget
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_time_budget(&mut self, ticks: u32) {
+        self.game.time_budget = ticks;
+    }
+
+    /// Ticks each food adds to the clock of a time attack
+    #[wasm_bindgen(getter)]
+    pub fn time_bonus(&self) -> u32 {
+        self.game.time_bonus
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_time_bonus(&mut self, ticks: u32) {
+        self.game.time_bonus = ticks;
+    }
+
+    /// Ticks between obstacles showing up on the board, each blinking for
+    /// a while first, 0 for none
+    #[wasm_bindgen(getter)]
+    pub fn hazard_every(&self) -> u32 {
+        self.game.hazard_every
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_hazard_every(&mut self, ticks: u32) {
+        self.game.hazard_every = ticks;
+    }
+
+    /// Ticks between lasers sweeping a row or column, after a warning, 0
+    /// for none
+    #[wasm_bindgen(getter)]
+    pub fn laser_every(&self) -> u32 {
+        self.game.laser_every
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_laser_every(&mut self, ticks: u32) {
+        self.game.laser_every = ticks;
+    }
+
+    /// Ticks between magnets showing up on the board, which pull food
+    /// towards the head for a while once taken, 0 for none
+    #[wasm_bindgen(getter)]
+    pub fn magnet_every(&self) -> u32 {
+        self.game.magnet_every
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_magnet_every(&mut self, ticks: u32) {
+        self.game.magnet_every = ticks;
+    }
+
+    /// A tick to turn away before crashing, see `World::set_grace`
+    #[wasm_bindgen(getter)]
+    pub fn grace(&self) -> bool {
+        self.game.grace
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_grace(&mut self, grace: bool) {
+        self.game.grace = grace;
+    }
+
+    /// Lives before the game is over, a single one with 0 or 1
+    #[wasm_bindgen(getter)]
+    pub fn lives(&self) -> u32 {
+        self.game.lives
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_lives(&mut self, lives: u32) {
+        self.game.lives = lives;
+    }
+
+    pub fn set_background(&mut self, background: Background) {
+        self.game.theme.background = background;
+    }
+
+    /// Css colors of the light and dark squares of
+    /// `Background::Checkerboard`, the light one is the `Solid` color too
+    pub fn set_board_colors(&mut self, light: String, dark: String) {
+        self.game.theme.board = light;
+        self.game.theme.checker = dark;
+    }
+
+    /// Grid lines between the tiles, on by default
+    pub fn set_grid(&mut self, shown: bool) {
+        self.game.theme.grid = shown;
+    }
+
+    /// Css color and width in pixels of the grid lines
+    pub fn set_grid_style(&mut self, color: String, width: f64) {
+        self.game.theme.grid_color = color;
+        self.game.theme.grid_width = width;
+    }
+
+    /// Draws every `every`th grid line wider and in `color`, 0 turns major
+    /// lines off
+    pub fn set_major_grid(&mut self, every: u32, color: String) {
+        self.game.theme.major_every = every;
+        self.game.theme.major_color = color;
+    }
+
+    /// Url of a picture to stretch over the board, selects
+    /// `Background::Image`
+    pub fn set_background_image(&mut self, url: String) {
+        self.game.theme.background = Background::Image;
+        self.game.theme.image = Some(url);
+    }
+
+    /// Food positions for `Spawn::Scripted`, as `[x0, y0, x1, y1, ..]`
+    pub fn set_food_script(&mut self, coords: Vec<u16>) {
+        self.spawn = Spawn::Scripted;
+        self.food_script = coords
+            .chunks(2)
+            .filter(|xy| xy.len() == 2)
+            .map(|xy| Coordinate { x: xy[0], y: xy[1] })
+            .collect();
+    }
+
+    /// Checkpoint tiles of a campaign level, as `[x0, y0, x1, y1, ..]`: a
+    /// crash after crossing one carries on from there, see `Checkpoints`
+    pub fn set_checkpoints(&mut self, coords: Vec<u16>) {
+        self.checkpoints = coords
+            .chunks(2)
+            .filter(|xy| xy.len() == 2)
+            .map(|xy| Coordinate { x: xy[0], y: xy[1] })
+            .collect();
+    }
+
+    /// Url of the leaderboard endpoint used by `GameHandle::submit_score`
+    /// and `GameHandle::top_scores`
+    pub fn set_leaderboard(&mut self, endpoint: String) {
+        self.leaderboard = Some(endpoint);
+    }
+
+    /// Colors of the snake, food and obstacles, see `Palette`
+    pub fn set_palette(&mut self, palette: Palette) {
+        self.game.theme.palette = palette;
+    }
+
+    /// Retro look, see `Theme::pixel_art`
+    pub fn set_pixel_art(&mut self, pixel_art: bool) {
+        self.game.theme.pixel_art = pixel_art;
+    }
+
+    /// Css `image-rendering: pixelated` on the canvas, for pages that
+    /// scale it
+    pub fn set_pixelated(&mut self, pixelated: bool) {
+        self.game.theme.pixelated = pixelated;
+    }
+
+    /// Draws pieces that only differ by color in distinct shapes too
+    pub fn set_shapes(&mut self, shapes: bool) {
+        self.game.theme.shapes = shapes;
+    }
+
+    /// Starts with the crt pass on, see `GameHandle::set_crt`
+    pub fn set_crt(&mut self, crt: bool) {
+        self.game.theme.crt = crt;
+    }
+
+    /// Whether tiles grow in and warnings blink, as the browser's
+    /// `prefers-reduced-motion` says by default
+    pub fn set_motion(&mut self, motion: MotionPolicy) {
+        self.motion = Some(motion);
+    }
+
+    /// Language of the strings on the board, the browser's by default
+    pub fn set_locale(&mut self, locale: Locale) {
+        self.locale = Some(locale);
+    }
+
+    /// Whether the snake comes back on the other side of an edge, as it
+    /// does by default, see `Rules::wrap`
+    pub fn set_wrap(&mut self, wrap: bool) {
+        self.game.rules.wrap = wrap;
+    }
+
+    /// Takes turns straight back, which run the snake into its neck
+    pub fn set_allow_reverse(&mut self, allow: bool) {
+        self.game.rules.allow_reverse = allow;
+    }
+
+    /// Tiles the snake grows by for each food, 1 by default
+    pub fn set_growth(&mut self, growth: u8) {
+        self.game.rules.growth = growth;
+    }
+
+    /// Lets the snake move diagonally, see `Rules::diagonal`
+    pub fn set_diagonal(&mut self, diagonal: bool) {
+        self.game.rules.diagonal = diagonal;
+    }
+
+    /// Lets the snake pass over its own body, see `Rules::ghost`
+    pub fn set_ghost(&mut self, ghost: bool) {
+        self.game.rules.ghost = ghost;
+    }
+
+    /// Starts the snake on a move every `pace` ticks, a tick sooner for
+    /// each `speed_up` food; a move on every tick throughout by default
+    pub fn set_speed_curve(&mut self, pace: u8, speed_up: u8) {
+        self.game.rules.pace = pace;
+        self.game.rules.speed_up = speed_up;
+    }
+
+    /// Plays against a bot of `strategy`, rated by its place in
+    /// `BOT_DIFFICULTIES`; `GameHandle::suggested_opponent` is a match for
+    /// the player
+    pub fn set_opponent(&mut self, strategy: Strategy) {
+        self.opponent = Some(strategy);
+    }
+}
+
+/// Where food shows up, see `world::SpawnPolicy`. Replays don't record the
+/// policy, so only games with `UniformRandom` (on a board that doesn't
+/// shrink, without a clock, hazards, lasers, magnets or a grace period)
+/// verify.
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Spawn {
+    UniformRandom = 0,
+    AwayFromHead = 1,
+    NearWalls = 2,
+    Scripted = 3,
+}
+
+impl Spawn {
+    fn from_u8(n: u8) -> Option<Self> {
+        match n {
+            0 => Some(Spawn::UniformRandom),
+            1 => Some(Spawn::AwayFromHead),
+            2 => Some(Spawn::NearWalls),
+            3 => Some(Spawn::Scripted),
+            _ => None,
+        }
+    }
+}
+
+/// How the snake is drawn moving, see `GameHandle::set_renderer`
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Renderer {
+    // a tile at a time over the frames of a tick
+    Animated = 0,
+    // each tick whole on its first frame, as with reduced motion
+    Snapped = 1,
+}
+
+// the renderers of the board, in the order of `Renderer`
+type BoardRenderers = Renderers<Fanout, VariableFrame<TickDelta>>;
+
+fn board_renderers() -> BoardRenderers {
+    Renderers::new(boxed::<_, TickDraw<VariableFrame<TickDelta>>>)
+        .with(snapped)
+}
+
+impl GameConfig {
+    // what of the config a player picks, as opposed to the page
+    fn settings(&self) -> Settings {
+        Settings {
+            palette: Some(self.game.theme.palette),
+            pixel_art: Some(self.game.theme.pixel_art),
+            shapes: Some(self.game.theme.shapes),
+            crt: Some(self.game.theme.crt),
+            motion: self.motion,
+            locale: self.locale,
+        }
+    }
+
+    // whether a game of the config can be made at all
+    fn validate(&self) -> Result<(), ConfigError> {
+        self.game.check()
+    }
+
+    fn apply_settings(&mut self, settings: &Settings) {
+        if let Some(palette) = settings.palette {
+            self.set_palette(palette);
+        }
+        if let Some(pixel_art) = settings.pixel_art {
+            self.set_pixel_art(pixel_art);
+        }
+        if let Some(shapes) = settings.shapes {
+            self.set_shapes(shapes);
+        }
+        if let Some(crt) = settings.crt {
+            self.set_crt(crt);
+        }
+        if let Some(motion) = settings.motion {
+            self.set_motion(motion);
+        }
+        if let Some(locale) = settings.locale {
+            self.set_locale(locale);
+        }
+    }
+
+    // the rules of a game besides its board and `Rules`; replays don't
+    // record them either, but for the lives
+    fn apply_rules<R: Rng, BB: BoundingBehavior>(
+        &self,
+        world: &mut World<R, BB>,
+    ) {
+        self.game.apply(world);
+        world.set_checkpoints(self.checkpoints.clone());
+
+        match self.spawn {
+            Spawn::UniformRandom => world.set_spawn_policy(UniformRandom),
+            Spawn::AwayFromHead => world.set_spawn_policy(AwayFromHead {
+                min_distance: SPAWN_MIN_DISTANCE,
+            }),
+            Spawn::NearWalls => world.set_spawn_policy(NearWalls {
+                margin: SPAWN_WALL_MARGIN,
+            }),
+            Spawn::Scripted => world
+                .set_spawn_policy(Scripted::new(self.food_script.clone())),
+        }
+    }
+
+    // the bot ally of a team game, heading west along the bottom but one
+    // row
+    fn add_ally<R: Rng, BB: BoundingBehavior>(
+        &self,
+        world: &mut World<R, BB>,
+    ) -> Option<SnakeId> {
+        if !self.team {
+            return None;
+        }
+        let (y, seed) =
+            (self.game.height.saturating_sub(2), self.game.seed ^ 1);
+        self.add_bot(world, y, TeamId(0), Strategy::AStar.policy(seed))
+    }
+
+    // the bot opponent, heading west along the middle row, and its
+    // difficulty
+    fn add_opponent<R: Rng, BB: BoundingBehavior>(
+        &self,
+        world: &mut World<R, BB>,
+    ) -> Option<(SnakeId, u8)> {
+        let strategy = self.opponent?;
+        let difficulty = BOT_DIFFICULTIES
+            .iter()
+            .position(|&bot| bot == strategy)
+            .unwrap_or(0) as u8;
+        let (y, seed) = (self.game.height / 2, self.game.seed ^ 2);
+        let policy = strategy.policy(seed);
+        let opponent = self.add_bot(world, y, TeamId(1), policy)?;
+        Some((opponent, difficulty))
+    }
+
+    // a bot of `team` heading west along row `y` from the right, steered
+    // by `policy`, unless the board has no room for it there
+    fn add_bot<R: Rng, BB: BoundingBehavior>(
+        &self,
+        world: &mut World<R, BB>,
+        y: SmallNat,
+        team: TeamId,
+        policy: Box<Policy>,
+    ) -> Option<SnakeId> {
+        if self.game.width < BOT_LENGTH + 2 || y >= self.game.height {
+            return None;
+        }
+
+        let right = self.game.width - 2;
+        let tiles: Vec<_> = (0..BOT_LENGTH)
+            .map(|i| (Coordinate { x: right - i, y }, Direction::West))
+            .collect();
+        world.add_snake(&tiles, team, Autopilot::new(policy))
+    }
+}
+
+/// Contents of a cell in `GameHandle::observation`
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Tile {
+    Empty = 0,
+    Snake = 1,
+    Food = 2,
+    Obstacle = 3,
+    Checkpoint = 4,
+    // a hazard about to turn into an obstacle
+    Looming = 5,
+    // a pickup that turns on a power-up
+    PowerUp = 6,
+}
+
+impl Tile {
+    fn from_u8(n: u8) -> Option<Self> {
+        match n {
+            0 => Some(Tile::Empty),
+            1 => Some(Tile::Snake),
+            2 => Some(Tile::Food),
+            3 => Some(Tile::Obstacle),
+            4 => Some(Tile::Checkpoint),
+            5 => Some(Tile::Looming),
+            6 => Some(Tile::PowerUp),
+            _ => None,
+        }
+    }
+
+    // a block that draws as tiles of this kind do, snakes in the player's
+    // colors
+    fn block(self) -> Block {
+        match self {
+            Tile::Empty => Block::Empty,
+            Tile::Snake => Block::Snake(Direction::East, PLAYER),
+            Tile::Food => Block::Food,
+            Tile::Obstacle => Block::Obstacle,
+            Tile::Checkpoint => Block::Checkpoint,
+            Tile::Looming => Block::Looming,
+            Tile::PowerUp => Block::PowerUp(PowerUp::Magnet),
+        }
+    }
+}
+
+/// Kind of event passed to `GameHandle::on_event` callbacks, as
+/// `callback(kind, payload)` with an object of what the kind is about, or
+/// null for `Started`, `Paused`, `Resumed` and `ComboLost`:
+/// `{x, y, length}` for `FoodEaten`, `{x, y}` of the tile for
+/// `FoodSpawned` and `PowerUpTaken`, `{dir}` for `InvalidInput`,
+/// `{reason, seed}` for `Died` with `reason` a `DeathReason`, `{seed}`
+/// for `Won`, `{achievement}`, `{multiplier, points}` for `Combo`,
+/// `{team, score}` for `TeamLost` and `{tier}` for `QualityChanged`.
+/// `GameOver` follows `Died` with the summary `game_summary` returns,
+/// there is none after `Won`.
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum EventKind {
+    Started = 0,
+    FoodEaten = 1,
+    FoodSpawned = 2,
+    InvalidInput = 3,
+    Paused = 4,
+    Resumed = 5,
+    Died = 6,
+    AchievementUnlocked = 7,
+    Combo = 8,
+    ComboLost = 9,
+    TeamLost = 10,
+    PowerUpTaken = 11,
+    QualityChanged = 12,
+    GameOver = 13,
+    Won = 14,
+}
+
+#[wasm_bindgen]
+pub struct GameHandle {
+    game_loop: GameLoop,
+    _each_tick: Closure<FnMut(u8)>,
+    tx: CmdSender<Command>,
+    observation: Rc<RefCell<Observation>>,
+    events: Rc<RefCell<EventBus>>,
+    stats: Rc<RefCell<Stats>>,
+    telemetry: Rc<RefCell<Telemetry>>,
+    achievements: Rc<RefCell<Achievements>>,
+    scoring: Rc<RefCell<Scoring>>,
+    ratings: Rc<RefCell<Ratings>>,
+    // the board first and its small copy if any, drawn anew by `set_theme`
+    envs: Vec<Rc<RefCell<CanvasEnv>>>,
+    quality: Rc<Cell<QualityTier>>,
+    resizes: Rc<RefCell<Resizes>>,
+    errors: ErrorReporter,
+    replay: Rc<RefCell<Replay>>,
+    renderers: Rc<RefCell<BoardRenderers>>,
+    #[cfg(feature = "leaderboard")]
+    leaderboard: Option<Leaderboard>,
+    #[cfg(feature = "replays")]
+    replays: ReplayLibrary,
+    // what the game was started with, to play its replay back
+    config: GameConfig,
+    schedule: Schedule,
+    timeline: Rc<RefCell<Timeline<Snapshot<SmallRng>>>>,
+}
+
+#[wasm_bindgen]
+impl GameHandle {
+    pub fn start(&self) -> bool {
+        self.game_loop.start()
+    }
+
+    pub fn stop(&self) -> bool {
+        self.game_loop.stop()
+    }
+
+    pub fn set_direction(&self, dir: Direction) {
+        self.tx.send(Command::Turn(dir));
+    }
+
+    pub fn pause(&self) {
+        self.tx.send(Command::Pause);
+    }
+
+    /// Advances the game by exactly one tick and holds it there, until
+    /// `run_continuously` (or the pause key)
+    pub fn step_once(&self) {
+        self.schedule.step(1);
+    }
+
+    /// Advances the game by `ticks` ticks within one frame, drawing only
+    /// where they end up, and holds it there
+    pub fn run_ticks(&self, ticks: u32) {
+        self.schedule.fast_forward(ticks);
+    }
+
+    /// Leaves the stepping of `step_once` and `run_ticks`
+    pub fn run_continuously(&self) {
+        self.schedule.run();
+    }
+
+    /// With `skip`, a tick that is due while the one before is still
+    /// animating, `step_once` being called faster than ticks are drawn or
+    /// a slow page falling behind with `frame_skip`, starts on the next
+    /// frame rather than wait for it. The tiles it changes are drawn to
+    /// their end at once, the others go on animating.
+    pub fn skip_lagging_frames(&self, skip: bool) {
+        self.schedule.set_lag(if skip { Lag::Skip } else { Lag::Animate });
+    }
+
+    /// Frames since the player last pressed a key; the game plays demo
+    /// games by itself once this reaches `ATTRACT_AFTER` on the game over
+    /// screen
+    pub fn idle_frames(&self) -> u32 {
+        self.schedule.idle_frames()
+    }
+
+    pub fn is_stepping(&self) -> bool {
+        self.schedule.is_manual()
+    }
+
+    pub fn restart(&self) {
+        self.tx.send(Command::Restart);
+    }
+
+    pub fn state(&self) -> GameState {
+        self.observation.borrow().state
+    }
+
+    pub fn width(&self) -> SmallNat {
+        self.observation.borrow().width
+    }
+
+    pub fn height(&self) -> SmallNat {
+        self.observation.borrow().height
+    }
+
+    /// Ticks left on the clock of a timed game, see `GameConfig::time_budget`
+    pub fn time_left(&self) -> Option<u32> {
+        self.observation.borrow().clock
+    }
+
+    /// Row major board contents, one `Tile` per cell
+    pub fn observation(&self) -> Vec<u8> {
+        self.observation.borrow().tiles.clone()
+    }
+
+    pub fn on_event(&self, callback: Function) {
+        self.events
+            .borrow_mut()
+            .subscribe(Box::new(JsEventSink { callback }));
+    }
+
+    /// `callback(phase, message)` is called with an `ErrorPhase` when the
+    /// model or the renderer fails
+    pub fn on_error(&self, callback: Function) {
+        self.errors.set_callback(callback);
+    }
+
+    pub fn games_played(&self) -> u32 {
+        self.stats.borrow().games()
+    }
+
+    pub fn total_food(&self) -> u32 {
+        self.stats.borrow().total_food()
+    }
+
+    pub fn average_length(&self) -> f64 {
+        self.stats.borrow().average_length()
+    }
+
+    pub fn max_length(&self) -> u32 {
+        self.stats.borrow().max_length()
+    }
+
+    pub fn deaths(&self, reason: DeathReason) -> u32 {
+        self.stats.borrow().deaths(reason)
+    }
+
+    /// The player's Elo rating from their games against bot opponents, see
+    /// `GameConfig::set_opponent`
+    pub fn rating(&self) -> i32 {
+        self.ratings.borrow().player()
+    }
+
+    /// The bot opponent rated nearest to the player
+    pub fn suggested_opponent(&self) -> Strategy {
+        let hardest = BOT_DIFFICULTIES.len() as u8 - 1;
+        BOT_DIFFICULTIES[self.ratings.borrow().suggest(hardest) as usize]
+    }
+
+    /// Row major count of how often the head entered each tile this session
+    pub fn heatmap(&self) -> Vec<u32> {
+        self.stats.borrow().heatmap()
+    }
+
+    /// How the last game went once it is over, as `{duration, ticks,
+    /// length, food, cause, coverage_percent}` with `duration` in
+    /// milliseconds and `cause` a `DeathReason`, null while it goes on
+    pub fn game_summary(&self) -> JsValue {
+        match self.telemetry.borrow().summary() {
+            Some(summary) => summary_to_js(&summary),
+            None => JsValue::NULL,
+        }
+    }
+
+    /// Draw the heatmap over the board on the game over screen
+    pub fn show_heatmap(&self, shown: bool) {
+        self.stats.borrow_mut().set_overlay(shown);
+    }
+
+    /// Where tile `x`, `y` is shown, as `[left, top, width, height]` in css
+    /// pixels from the top left corner of the board canvas, for pages that
+    /// anchor their own elements to tiles. It changes with the size the
+    /// canvas is shown at, see `on_resize`.
+    pub fn tile_to_client(&self, x: SmallNat, y: SmallNat) -> Vec<f64> {
+        let observation = self.observation.borrow();
+        let board = (observation.width, observation.height);
+        let client = self.resizes.borrow().client_size();
+        tile_box(x, y, board, client).to_vec()
+    }
+
+    /// `callback(width, height)` is called with the css size of the board
+    /// canvas on the next frame and whenever it is shown at another size
+    /// after that, for elements placed with `tile_to_client` to be placed
+    /// again
+    pub fn on_resize(&self, callback: Function) {
+        self.resizes.borrow_mut().set_callback(callback);
+    }
+
+    /// The tier the game draws at, `High` unless
+    /// `GameConfig::adaptive_quality` lowered it, see
+    /// `EventKind::QualityChanged`
+    pub fn quality_tier(&self) -> QualityTier {
+        self.quality.get()
+    }
+
+    /// Draws the next ticks with `renderer`, without restarting the game
+    pub fn set_renderer(&self, renderer: Renderer) {
+        self.renderers.borrow_mut().select(renderer as usize);
+    }
+
+    pub fn renderer(&self) -> Renderer {
+        match self.renderers.borrow().selected() {
+            0 => Renderer::Animated,
+            _ => Renderer::Snapped,
+        }
+    }
+
+    /// Turns the scanlines and vignette over the board on or off
+    pub fn set_crt(&self, on: bool) {
+        let mut env = self.envs[0].borrow_mut();
+        env.set_crt(on);
+        env.present();
+    }
+
+    /// Draws the game in the look of `config` from now on without starting
+    /// over, e.g. a palette picked from a menu mid-game: the board is drawn
+    /// anew as the last tick left it, and `save_settings` and `screenshot`
+    /// take the new look. A game yet to take its first step keeps the
+    /// colors it was drawn in until the tiles are drawn again.
+    pub fn set_theme(&mut self, config: &GameConfig) {
+        let theme = config.game.theme.clone();
+        let world = self.shown_world();
+
+        for env in &self.envs {
+            let mut env = env.borrow_mut();
+            env.set_theme(theme.clone());
+            if let Some(ref world) = world {
+                redraw_view(&mut *env, &world.view());
+            }
+            env.present();
+        }
+        self.config.game.theme = theme;
+    }
+
+    /// Remembers the palette, look, motion and language of this game, and
+    /// whether the crt pass is on, for `init` to restore on later visits
+    pub fn save_settings(&self) {
+        let mut settings = self.config.settings();
+        settings.crt = Some(self.envs[0].borrow().crt());
+        settings.store(SETTINGS_KEY);
+    }
+
+    pub fn is_unlocked(&self, achievement: Achievement) -> bool {
+        self.achievements.borrow().is_unlocked(achievement)
+    }
+
+    /// Points of the current game, see `scoring::Combo`
+    pub fn score(&self) -> u32 {
+        self.scoring.borrow().score()
+    }
+
+    /// Food eaten in a row, each quickly after the previous one
+    pub fn combo(&self) -> u32 {
+        self.scoring.borrow().combo()
+    }
+
+    /// A PNG `Blob` of the board as it is, at twice its size and without
+    /// prompts or the game over screen. An image background only shows once
+    /// it has loaded.
+    pub fn screenshot(&self) -> Promise {
+        let canvas = match self.render_photo() {
+            Ok(canvas) => canvas,
+            Err(err) => return Promise::reject(&err.into()),
+        };
+
+        Promise::new(&mut |resolve, reject| {
+            if let Err(err) = canvas.to_blob(&resolve) {
+                let _ = reject.call1(&JsValue::NULL, &err);
+            }
+        })
+    }
+
+    /// The last ticks of the game, oldest first, as an array of `{tick,
+    /// length, tiles, updates}`: the board at the start of the tick, row
+    /// major like `observation()`, and what the tick changed, for debugging
+    pub fn history(&self) -> JsValue {
+        let array = Array::new();
+        for entry in self.timeline.borrow().entries() {
+            array.push(&history_entry_to_js(entry));
+        }
+        array.into()
+    }
+
+    /// The major events of the session, oldest first, as an array of
+    /// `{tick, kind, payload}` with `kind` an `EventKind` and `payload` as
+    /// `on_event` passes it: games starting, food eaten, power-ups
+    /// taken, deaths, wins, achievements and teams losing. Only the last
+    /// `GameConfig::timeline_len` are kept.
+    pub fn timeline(&self) -> JsValue {
+        let array = Array::new();
+        for &(tick, ref event) in self.events.borrow().history() {
+            array.push(&timeline_entry_to_js(tick, event));
+        }
+        array.into()
+    }
+
+    /// Takes the game back to the start of `tick` from `history()` and
+    /// redraws it, false if the tick is not in there. The jump ends the game
+    /// as a restart would, stats and events see a new game starting from
+    /// the tick; the replay records on, so it doesn't verify after a jump.
+    pub fn jump_to(&self, tick: u32) -> bool {
+        let jumped = self.timeline.borrow_mut().jump_to(tick);
+
+        // the jump is taken on the next step, which stepping holds back
+        if jumped && self.schedule.is_manual() {
+            self.schedule.step(1);
+        }
+        jumped
+    }
+
+    /// Every command of this session, encoded for `verify_replay`
+    pub fn replay(&self) -> Vec<u8> {
+        self.replay.borrow().to_bytes()
+    }
+
+    /// Hash of `replay()` to submit along with scores
+    pub fn replay_hash(&self) -> u32 {
+        self.replay.borrow().hash()
+    }
+
+    /// Engine log verbosity, shared by every game on the page
+    pub fn set_log_level(&self, level: LogLevel) {
+        log::set_level(level);
+    }
+
+    pub fn log_level(&self) -> LogLevel {
+        log::level()
+    }
+}
+
+#[cfg(feature = "clip")]
+#[wasm_bindgen]
+impl GameHandle {
+    /// A WebM `Blob` of this session played back from its replay into a
+    /// canvas of its own. The clip is recorded as it plays, so it takes as
+    /// long to make as the games took.
+    pub fn export_clip(&self) -> Promise {
+        let mut world = initial_world(
+            self.config.game.width,
+            self.config.game.height,
+            self.config.game.seed,
+            self.config.game.rules,
+        );
+        self.config.apply_rules(&mut world);
+
+        let commands = self.replay.borrow().commands().to_vec();
+        let playback = Playback::new(world, commands);
+
+        let theme = &self.config.game.theme;
+        match clip::record(playback, theme, self.errors.clone()) {
+            Ok(promise) => promise,
+            Err(err) => Promise::reject(&err.into()),
+        }
+    }
+}
+
+#[cfg(feature = "telemetry-json")]
+#[wasm_bindgen]
+impl GameHandle {
+    /// A JSON blob of how the current game, or the last one once it is
+    /// over, was played: `{ticks, inputs, turns, turns_per_minute, food,
+    /// death, heatmap: {width, height, visited, coverage, hottest}}`, see
+    /// `telemetry::Report`
+    pub fn export_telemetry(&self) -> String {
+        self.telemetry.borrow().to_json()
+    }
+}
+
+#[cfg(feature = "replays")]
+#[wasm_bindgen]
+impl GameHandle {
+    /// Resolves to the names of the replays saved with `save_replay`
+    pub fn list_replays(&self) -> Promise {
+        self.replays.list()
+    }
+
+    /// Saves `replay()` under `name` in IndexedDB, in place of any replay
+    /// saved under that name
+    pub fn save_replay(&self, name: &str) -> Promise {
+        self.replays.save(name, &self.replay())
+    }
+
+    /// Resolves to the bytes of the replay saved under `name`, for
+    /// `play_replay`, or `undefined`
+    pub fn load_replay(&self, name: &str) -> Promise {
+        self.replays.load(name)
+    }
+
+    pub fn delete_replay(&self, name: &str) -> Promise {
+        self.replays.delete(name)
+    }
+}
+
+#[cfg(feature = "leaderboard")]
+#[wasm_bindgen]
+impl GameHandle {
+    /// Posts a score for this game's seed to the configured leaderboard,
+    /// then calls `callback(error)` with `null` on success
+    pub fn submit_score(
+        &self,
+        name: String,
+        score: u32,
+        replay_hash: u32,
+        callback: Function,
+    ) {
+        let entry = ScoreEntry {
+            name,
+            score,
+            seed: self.config.game.seed,
+            replay_hash,
+        };
+
+        match self.leaderboard {
+            Some(ref board) => board.submit(&entry, move |r| {
+                leaderboard::call_back(&callback, r.map(|_| JsValue::NULL))
+            }),
+            None => leaderboard::call_back(
+                &callback,
+                Err(LeaderboardError::NoEndpoint),
+            ),
+        }
+    }
+
+    /// Fetches the best `limit` scores, then calls `callback(error, scores)`
+    /// with an array of `{name, score, seed, replay_hash}`
+    pub fn top_scores(&self, limit: u32, callback: Function) {
+        match self.leaderboard {
+            Some(ref board) => board.top_scores(limit, move |r| {
+                let scores = r.map(|s| leaderboard::scores_to_js(&s));
+                leaderboard::call_back(&callback, scores)
+            }),
+            None => leaderboard::call_back(
+                &callback,
+                Err(LeaderboardError::NoEndpoint),
+            ),
+        }
+    }
+}
+
+/// Builds a game from a config and attaches its canvas to the page
+pub struct GameBuilder {
+    config: GameConfig,
+    parent: Option<Element>,
+    mini_view: Option<Element>,
+}
+
+impl GameBuilder {
+    pub fn new(config: GameConfig) -> Self {
+        GameBuilder {
+            config,
+            parent: None,
+            mini_view: None,
+        }
+    }
+
+    /// Today's `DailyChallenge`, the same board and food for every player
+    pub fn daily_challenge() -> Self {
+        Self::new(DailyChallenge::today().config())
+    }
+
+    /// Element to put the canvas in, `document.body` by default
+    pub fn parent(&mut self, parent: Element) -> &mut Self {
+        self.parent = Some(parent);
+        self
+    }
+
+    /// Element to put a small copy of the board in, drawn along with the
+    /// main one at `MINI_TILE_SIZE` pixels a tile; none by default
+    pub fn mini_view(&mut self, parent: Element) -> &mut Self {
+        self.mini_view = Some(parent);
+        self
+    }
+
+    pub fn build(&self) -> Result<GameHandle, EnvError> {
+        let parent = match self.parent {
+            Some(ref parent) => parent.clone(),
+            None => document_body()?,
+        };
+
+        let mini_view = self.mini_view.as_ref();
+        GameHandle::attach(self.config.clone(), &parent, mini_view)
+    }
+}
+
+impl GameHandle {
+    fn attach(
+        config: GameConfig,
+        parent: &Element,
+        mini_view: Option<&Element>,
+    ) -> Result<Self, EnvError> {
+        enter_phase(ErrorPhase::Setup);
+        console::install();
+        config.validate()?;
+
+        let errors = ErrorReporter::new();
+        errors.install_panic_hook();
+
+        let facing = Direction::East;
+
+        let (width, height, seed) =
+            (config.game.width, config.game.height, config.game.seed);
+        let mut world = initial_world(width, height, seed, config.game.rules);
+        config.apply_rules(&mut world);
+        let autopilot = Autopilot::new(config.autopilot.policy(seed));
+        world.set_autopilot(Some(autopilot.clone()));
+        let ally = config.add_ally(&mut world);
+        let opponent = config.add_opponent(&mut world);
+        let mut replay = Replay::new(width, height, seed);
+        replay.lives = config.game.lives;
+        replay.rules = config.game.rules;
+        let replay = Rc::new(RefCell::new(replay));
+
+        let observation = Rc::new(RefCell::new(Observation::new()));
+        let events = Rc::new(RefCell::new(
+            EventBus::new()
+                .with_seed(config.game.seed)
+                .with_history(config.timeline_len as usize),
+        ));
+        let stats = Rc::new(RefCell::new(Stats::new()));
+        let telemetry = Rc::new(RefCell::new(
+            Telemetry::new(stats.clone(), js_sys::Date::now)
+                .with_events(events.clone()),
+        ));
+        let achievements = Rc::new(RefCell::new(
+            Achievements::persisted(ACHIEVEMENTS_KEY)
+                .with_events(events.clone()),
+        ));
+        let scoring = Rc::new(RefCell::new(
+            Scoring::new(Combo::default()).with_events(events.clone()),
+        ));
+        // the ally's points go to the team only, its combos aren't shown
+        let ally = ally.map(|ally| {
+            let scoring = Scoring::new(Combo::default()).with_snake(ally);
+            (ally, Rc::new(RefCell::new(scoring)))
+        });
+        let team = ally.as_ref().map(|&(ally, ref ally_scoring)| {
+            let mut team = Team::new(TeamId(0)).with_events(events.clone());
+            team.join(PLAYER, scoring.clone());
+            team.join(ally, ally_scoring.clone());
+            Rc::new(RefCell::new(team))
+        });
+        // the player's crash is the team's, and so an ally's, which is
+        // the player's too
+        let team_watch = team.as_ref().map(|team| Team::watch(team, PLAYER));
+        let ally_scoring = ally.map(|(_, scoring)| scoring);
+        let ratings = Rc::new(RefCell::new(Ratings::load(RATINGS_KEY)));
+        let rated = opponent.map(|(opponent, difficulty)| {
+            let bot = Scoring::new(Combo::default()).with_snake(opponent);
+            let bot = Rc::new(RefCell::new(bot));
+            let (player, pilot) = (scoring.clone(), autopilot.clone());
+            let rated = RatedGame::new(
+                ratings.clone(),
+                difficulty,
+                player,
+                bot.clone(),
+                pilot,
+            );
+            (bot, rated.persisted(RATINGS_KEY))
+        });
+        let (opponent_scoring, rated) = match rated {
+            Some((bot, rated)) => (Some(bot), Some(rated)),
+            None => (None, None),
+        };
+        let locale = config.locale.unwrap_or_else(browser_locale);
+        let motion = config.motion.unwrap_or_else(browser_motion);
+        let saver = PowerSaver::new(config.power_saving);
+        let mut monitor =
+            AdaptiveQuality::new(QUALITY_BUDGET_MS).with_events(events.clone());
+        let tier = monitor.tier();
+        let mut env = CanvasEnv::new(parent, errors.clone())?
+            .with_stats(stats.clone())
+            .with_telemetry(telemetry.clone())
+            .with_scoring(scoring.clone())
+            .with_locale(locale)
+            .with_motion(motion)
+            .with_theme(config.game.theme.clone())
+            .with_power_saver(saver.clone())
+            .with_quality(tier.clone());
+        if let Some(ref team) = team {
+            env = env.with_team(team.clone());
+        }
+        let resizes = Rc::new(RefCell::new(Resizes::new(env.canvas().clone())));
+
+        let env = Rc::new(RefCell::new(env));
+        let board = env.clone();
+        let mut envs = vec![env.clone()];
+        let mut env = Fanout::new(Box::new(env));
+        if let Some(parent) = mini_view {
+            let mut mini = CanvasEnv::mini(parent, errors.clone())?
+                .with_tile_size(MINI_TILE_SIZE)
+                .with_locale(locale)
+                .with_motion(motion)
+                .with_theme(config.game.theme.clone());
+            if let Some(ref team) = team {
+                mini = mini.with_team(team.clone());
+            }
+            let mini = Rc::new(RefCell::new(mini));
+            envs.push(mini.clone());
+            env = env.with(Box::new(mini));
+        }
+
+        if config.announce {
+            let announcer =
+                Announcer::new(parent, locale)?.with_scoring(scoring.clone());
+            events.borrow_mut().subscribe(Box::new(announcer));
+        }
+        if config.haptics {
+            events.borrow_mut().subscribe(Box::new(Haptics::new()));
+        }
+        let script = if config.tutorial { BASICS } else { &[] };
+        let schedule = Schedule::new();
+        let entities = Rc::new(RefCell::new(entities::<Fanout>()));
+
+        let timeline = Rc::new(RefCell::new(Timeline::new(HISTORY_LEN)));
+        let world = History::new(Checkpoints::new(world), timeline.clone());
+
+        let world = Counted::new(world, stats.clone());
+        let world = Stamped::new(world, events.clone());
+
+        let pacer = Rc::new(RefCell::new(Pacer::new(
+            FRAMES_PER_SECOND,
+            CATCH_UP_TICKS,
+        )));
+        let model = Tutorial::new(Record::new(world, replay.clone()), script)
+            .with_locale(locale)
+            .observe(PerChange(observation.clone()))
+            .observe(PerChange(events.clone()))
+            .observe(PerChange(stats.clone()))
+            .observe(PerChange(telemetry.clone()))
+            .observe(PerChange(achievements.clone()))
+            .observe(PerChange(scoring.clone()))
+            .observe(PerChange(ally_scoring))
+            .observe(PerChange(team_watch))
+            .observe(PerChange(opponent_scoring))
+            .observe(PerChange(rated))
+            .observe(PerChange(entities.clone()))
+            .zip_with(RenderSpeed::new(facing), halved_while(saver.clone()))
+            .alternating::<Command, _>(Dead::new());
+        let game = Paced::new(model, pacer.clone())
+            .make_game(env)
+            .with_schedule(schedule.clone())
+            .with_persistent(Box::new(entities));
+
+        let renderers = Rc::new(RefCell::new(board_renderers()));
+        let (tx, mut generator) =
+            game.new_game_with::<Command>(renderers.clone());
+
+        let sender = tx.clone();
+        let stepper = schedule.clone();
+        let observed = observation.clone();
+        let mut attract =
+            AttractMode::new(autopilot, ATTRACT_AFTER, ATTRACT_BETWEEN);
+        let keymap = KeyMap::default();
+        let mut key_repeat = KeyRepeat::new();
+        let watched = resizes.clone();
+        let frame_skip = config.frame_skip;
+        let mut skipped = false;
+        let adaptive = config.adaptive_quality;
+
+        let each_tick = Closure::wrap(Box::new(move |key: u8| {
+            let key = key_repeat.feed(Key::from(key));
+            stepper.input(key.code() != 0);
+
+            let over = observed.borrow().state == GameState::Over;
+            let attract = attract.frame(stepper.idle_frames(), over);
+            if let Some(attract) = attract {
+                board.borrow().show_attract(attract != Attract::Stop);
+                // a demo game, or the player's instead of the demo
+                sender.send(Command::Restart);
+            }
+
+            match keymap.binding(key) {
+                // the key stopping a demo game does nothing else
+                _ if attract == Some(Attract::Stop) => {}
+                _ if key.is_repeat() => sender.send(keymap.command(key)),
+                Some(Binding::StepOnce) => stepper.step(1),
+                Some(Binding::FastForward) => {
+                    stepper.fast_forward(FAST_FORWARD_TICKS)
+                }
+                // unpausing while stepping goes back to continuous ticks
+                Some(Binding::Pause) if stepper.is_manual() => stepper.run(),
+                _ => sender.send(keymap.command(key)),
+            }
+
+            // keys are read on every frame, taps only last one
+            if saver.is_on() {
+                skipped = !skipped;
+                if skipped {
+                    return;
+                }
+            }
+
+            let behind = {
+                let now = js_sys::Date::now();
+                let mut pacer = pacer.borrow_mut();
+                pacer.set_fps(if saver.is_on() {
+                    FRAMES_PER_SECOND / 2
+                } else {
+                    FRAMES_PER_SECOND
+                });
+                if stepper.is_manual() {
+                    pacer.hold(now);
+                    0
+                } else {
+                    pacer.frame(now)
+                }
+            };
+            if frame_skip && behind > 0 {
+                stepper.catch_up(behind);
+            }
+
+            let started = quality::now();
+            unsafe {
+                generator.resume();
+            }
+            if adaptive {
+                monitor.frame(quality::now() - started);
+            }
+            watched.borrow_mut().check();
+        }) as Box<FnMut(_)>);
+
+        let game_loop = GameLoop::new(&each_tick);
+
+        game_loop.start();
+
+        Ok(GameHandle {
+            game_loop,
+            _each_tick: each_tick,
+            tx,
+            observation,
+            events,
+            stats,
+            telemetry,
+            achievements,
+            scoring,
+            ratings,
+            envs,
+            quality: tier,
+            resizes,
+            errors,
+            replay,
+            renderers,
+            #[cfg(feature = "leaderboard")]
+            leaderboard: config.leaderboard.clone().map(Leaderboard::new),
+            #[cfg(feature = "replays")]
+            replays: ReplayLibrary::new(),
+            config,
+            schedule,
+            timeline,
+        })
+    }
+
+    // the world the board shows, from the snapshot of the last tick with
+    // what the tick changed applied to it; none while the ticks recorded
+    // are of the game before the one just started
+    fn shown_world(&self) -> Option<World<SmallRng, Wrapping>> {
+        let timeline = self.timeline.borrow();
+        let over = self.observation.borrow().state == GameState::Over;
+        let entry = match timeline.latest() {
+            Some(entry) if over || !timeline.ended() => entry,
+            _ => return None,
+        };
+
+        let mut world = initial_world(
+            self.config.game.width,
+            self.config.game.height,
+            self.config.game.seed,
+            self.config.game.rules,
+        );
+        world.restore(&entry.snapshot);
+        for update in entry.delta.changes() {
+            world.apply(&update);
+        }
+        Some(world)
+    }
+
+    // redraws the observed board into a canvas of its own, whole tiles only
+    // as the observation doesn't keep the direction of the snake
+    fn render_photo(&self) -> Result<HtmlCanvasElement, EnvError> {
+        let observation = self.observation.borrow();
+
+        let mut env = CanvasEnv::offscreen(self.errors.clone())?
+            .with_theme(self.config.game.theme.clone());
+        env.setup(
+            TILE_SIZE as SmallNat * PHOTO_SCALE,
+            observation.width,
+            observation.height,
+        );
+
+        // each tile as the board draws its block
+        let width = observation.width.max(1) as usize;
+        for (i, &tile) in observation.tiles.iter().enumerate() {
+            let at = Coordinate {
+                x: (i % width) as SmallNat,
+                y: (i / width) as SmallNat,
+            };
+            let block = match Tile::from_u8(tile) {
+                Some(tile) => tile.block(),
+                None => unreachable!("observation of a non-tile {}", tile),
+            };
+            let update = WorldUpdate::SetBlock { block, at };
+            WorldUpdateDraw::new(update, 1).render(&mut env);
+        }
+        // the layers onto the canvas
+        env.end_frame();
+
+        Ok(env.canvas().clone())
+    }
+}
+
+#[wasm_bindgen]
+pub fn main() -> Result<GameHandle, JsValue> {
+    Ok(GameBuilder::new(GameConfig::new()).build()?)
+}
+
+#[wasm_bindgen]
+pub fn daily_challenge() -> Result<GameHandle, JsValue> {
+    Ok(GameBuilder::daily_challenge().build()?)
+}
+
+/// The language of `navigator.language`, english for any other
+#[wasm_bindgen]
+pub fn browser_locale() -> Locale {
+    web_sys::window()
+        .and_then(|window| window.navigator().language())
+        .and_then(|tag| Locale::from_tag(&tag))
+        .unwrap_or_default()
+}
+
+// `VariableFrame::pack`, but for ticks drawn in half the frames while
+// `saver` is on, which draws every other frame, so that they last as long
+fn halved_while<T>(saver: PowerSaver) -> impl Fn((T, u8)) -> VariableFrame<T> {
+    move |(update, frames)| {
+        let frames = if saver.is_on() { (frames + 1) / 2 } else { frames };
+        VariableFrame::pack((update, frames))
+    }
+}
+
+/// `Reduced` if the player asked for less motion with
+/// `prefers-reduced-motion`
+#[wasm_bindgen]
+pub fn browser_motion() -> MotionPolicy {
+    let reduced = web_sys::window()
+        .and_then(|window| {
+            window
+                .match_media("(prefers-reduced-motion: reduce)")
+                .ok()
+                .and_then(|query| query)
+        })
+        .map_or(false, |query| query.matches());
+
+    if reduced {
+        MotionPolicy::Reduced
+    } else {
+        MotionPolicy::Full
+    }
+}
+
+/// `text` in `locale`, for pages to label their own controls the way the
+/// game does, e.g. the demo menu
+#[wasm_bindgen]
+pub fn translate(text: Text, locale: Locale) -> String {
+    locale.text(text).into()
+}
+
+#[wasm_bindgen]
+pub fn new_game(config: GameConfig) -> Result<GameHandle, JsValue> {
+    Ok(GameBuilder::new(config).build()?)
+}
+
+/// One of the other demos of the menu, running on a board of its
+/// `GameConfig`'s size and seed. Pause and restart are bound as in the snake
+/// game.
+#[wasm_bindgen]
+pub struct DemoHandle {
+    game_loop: GameLoop,
+    _each_tick: Closure<FnMut(u8)>,
+    tx: CmdSender<Command>,
+}
+
+#[wasm_bindgen]
+impl DemoHandle {
+    pub fn start(&self) -> bool {
+        self.game_loop.start()
+    }
+
+    pub fn stop(&self) -> bool {
+        self.game_loop.stop()
+    }
+
+    pub fn pause(&self) {
+        self.tx.send(Command::Pause);
+    }
+
+    pub fn restart(&self) {
+        self.tx.send(Command::Restart);
+    }
+}
+
+impl DemoHandle {
+    // runs `model` drawn by an `R` in a canvas of `config`'s theme
+    fn attach<M, R>(
+        model: M,
+        config: &GameConfig,
+        parent: &Element,
+    ) -> Result<Self, EnvError>
+    where
+        M: for<'m> Stateful<'m, Cmd = Command, Update = R::Patch> + 'static,
+        R: IncrRender<CanvasEnv> + 'static,
+    {
+        enter_phase(ErrorPhase::Setup);
+        config.validate()?;
+
+        let env = CanvasEnv::new(parent, ErrorReporter::new())?
+            .with_theme(config.game.theme.clone());
+
+        let (tx, mut generator) = model.make_game(env).new_game::<R, Command>();
+
+        let sender = tx.clone();
+        let keymap = KeyMap::default();
+        let mut key_repeat = KeyRepeat::new();
+        let each_tick = Closure::wrap(Box::new(move |key: u8| {
+            let key = key_repeat.feed(Key::from(key));
+            sender.send(keymap.command(key));
+
+            unsafe {
+                generator.resume();
+            }
+        }) as Box<FnMut(_)>);
+
+        let game_loop = GameLoop::new(&each_tick);
+
+        game_loop.start();
+
+        Ok(DemoHandle {
+            game_loop,
+            _each_tick: each_tick,
+            tx,
+        })
+    }
+}
+
+/// Conway's Game of Life, where restarting seeds a new board
+#[wasm_bindgen]
+pub fn life(config: GameConfig) -> Result<DemoHandle, JsValue> {
+    let rng = SmallRng::from_seed(rng_seed(config.game.seed));
+    let life = LifeModel::new(config.game.width, config.game.height, rng);
+
+    Ok(DemoHandle::attach::<_, LifeDraw>(life, &config, &document_body()?)?)
+}
+
+/// Falling blocks, won by clearing `BLOCKS_GOAL` rows. The arrow keys move
+/// the piece, up drops it and z and x turn it.
+#[wasm_bindgen]
+pub fn blocks(config: GameConfig) -> Result<DemoHandle, JsValue> {
+    let rng = SmallRng::from_seed(rng_seed(config.game.seed));
+    let blocks =
+        FallingBlocks::new(BLOCKS_WIDTH, config.game.height, BLOCKS_GOAL, rng);
+    let parent = document_body()?;
+
+    Ok(DemoHandle::attach::<_, BlocksDraw>(blocks, &config, &parent)?)
+}
+
+/// Plays the encoded `replay` back on a board of its own size and seed,
+/// with the rules of `config` it doesn't record, quitting once its
+/// commands run out
+#[wasm_bindgen]
+pub fn play_replay(
+    replay: &[u8],
+    mut config: GameConfig,
+) -> Result<DemoHandle, JsValue> {
+    let replay = Replay::from_bytes(replay).map_err(|err| {
+        JsValue::from(js_sys::Error::new(&format!("snake: {}", err)))
+    })?;
+
+    Grid::check_size(replay.width, replay.height).map_err(EnvError::from)?;
+    config.game.lives = replay.lives;
+    config.game.rules = replay.rules;
+    let mut world = initial_world(
+        replay.width,
+        replay.height,
+        replay.seed,
+        replay.rules,
+    );
+    config.apply_rules(&mut world);
+    let playback = Playback::new(world, replay.commands().to_vec());
+    let parent = document_body()?;
+
+    Ok(DemoHandle::attach::<_, TickDraw>(playback, &config, &parent)?)
+}
+
+/// Entry point for embedding pages and bundlers. `config` is a plain object
+/// with optional `width`, `height`, `seed`, `shrink_every`, `time_budget`,
+/// `time_bonus`, `hazard_every`, `laser_every`, `magnet_every`, `growth`,
+/// `pace` and `speed_up` numbers, an optional `spawn`, `background`,
+/// `palette`, `power_saving`, `autopilot` and `opponent` (a `Strategy`),
+/// `tutorial`, `grid`, `shapes`, `pixel_art`, `pixelated`, `crt`,
+/// `announce`, `haptics`, `frame_skip`,
+/// `adaptive_quality`, `wrap`, `allow_reverse`, `diagonal`, `ghost`, `team`
+/// and `reduced_motion` flags, an optional `leaderboard`
+/// endpoint url and `locale` language tag, and an optional `parent` given
+/// as a css selector or an element to put the canvas in, defaulting to
+/// `document.body`, and `mini_view` likewise for a small copy of the
+/// board, see `GameBuilder::mini_view`. The settings of
+/// `GameHandle::save_settings` take over from `config`'s. A board with a
+/// side that isn't a whole number of tiles from 1 to `MAX_BOARD_SIDE` is
+/// refused, see `ConfigError`.
+#[wasm_bindgen]
+pub fn init(config: JsValue) -> Result<GameHandle, JsValue> {
+    let mut game_config = GameConfig::new();
+
+    if let Some(width) = number_field(&config, "width")? {
+        game_config.game.width = board_side(width)?;
+    }
+    if let Some(height) = number_field(&config, "height")? {
+        game_config.game.height = board_side(height)?;
+    }
+    if let Some(seed) = number_field(&config, "seed")? {
+        game_config.game.seed = seed as u32;
+    }
+    if let Some(spawn) = number_field(&config, "spawn")? {
+        game_config.spawn = Spawn::from_u8(spawn as u8).ok_or_else(|| {
+            js_sys::Error::new("config.spawn must be a Spawn")
+        })?;
+    }
+    if let Some(every) = number_field(&config, "shrink_every")? {
+        game_config.game.shrink_every = every as u32;
+    }
+    if let Some(budget) = number_field(&config, "time_budget")? {
+        game_config.game.time_budget = budget as u32;
+    }
+    if let Some(bonus) = number_field(&config, "time_bonus")? {
+        game_config.game.time_bonus = bonus as u32;
+    }
+    if let Some(every) = number_field(&config, "hazard_every")? {
+        game_config.game.hazard_every = every as u32;
+    }
+    if let Some(every) = number_field(&config, "laser_every")? {
+        game_config.game.laser_every = every as u32;
+    }
+    if let Some(every) = number_field(&config, "magnet_every")? {
+        game_config.game.magnet_every = every as u32;
+    }
+    if let Some(growth) = number_field(&config, "growth")? {
+        game_config.set_growth(growth as u8);
+    }
+    if let Some(pace) = number_field(&config, "pace")? {
+        game_config.game.rules.pace = pace as u8;
+    }
+    if let Some(speed_up) = number_field(&config, "speed_up")? {
+        game_config.game.rules.speed_up = speed_up as u8;
+    }
+    if let Some(autopilot) = number_field(&config, "autopilot")? {
+        game_config.autopilot = Strategy::from_u8(autopilot as u8)
+            .ok_or_else(|| {
+                js_sys::Error::new("config.autopilot must be a Strategy")
+            })?;
+    }
+    if let Some(opponent) = number_field(&config, "opponent")? {
+        let opponent = Strategy::from_u8(opponent as u8).ok_or_else(|| {
+            js_sys::Error::new("config.opponent must be a Strategy")
+        })?;
+        game_config.set_opponent(opponent);
+    }
+    if let Some(background) = number_field(&config, "background")? {
+        let background = Background::from_u8(background as u8)
+            .ok_or_else(|| {
+                js_sys::Error::new("config.background must be a Background")
+            })?;
+        game_config.set_background(background);
+    }
+    if let Some(palette) = number_field(&config, "palette")? {
+        let palette = Palette::from_u8(palette as u8).ok_or_else(|| {
+            js_sys::Error::new("config.palette must be a Palette")
+        })?;
+        game_config.set_palette(palette);
+    }
+    if let Some(saving) = number_field(&config, "power_saving")? {
+        game_config.power_saving = PowerSaving::from_u8(saving as u8)
+            .ok_or_else(|| {
+                js_sys::Error::new("config.power_saving must be a PowerSaving")
+            })?;
+    }
+    if config.is_object() {
+        let tutorial = Reflect::get(&config, &JsValue::from_str("tutorial"))?;
+        game_config.tutorial = tutorial.as_bool().unwrap_or(false);
+
+        let announce = Reflect::get(&config, &JsValue::from_str("announce"))?;
+        game_config.announce = announce.as_bool().unwrap_or(false);
+
+        let haptics = Reflect::get(&config, &JsValue::from_str("haptics"))?;
+        game_config.haptics = haptics.as_bool().unwrap_or(true);
+
+        let skip = Reflect::get(&config, &JsValue::from_str("frame_skip"))?;
+        game_config.frame_skip = skip.as_bool().unwrap_or(true);
+
+        let team = Reflect::get(&config, &JsValue::from_str("team"))?;
+        game_config.team = team.as_bool().unwrap_or(false);
+
+        let adaptive = JsValue::from_str("adaptive_quality");
+        let adaptive = Reflect::get(&config, &adaptive)?;
+        game_config.adaptive_quality = adaptive.as_bool().unwrap_or(true);
+
+        let wrap = Reflect::get(&config, &JsValue::from_str("wrap"))?;
+        if let Some(wrap) = wrap.as_bool() {
+            game_config.set_wrap(wrap);
+        }
+
+        let reverse = JsValue::from_str("allow_reverse");
+        if let Some(allow) = Reflect::get(&config, &reverse)?.as_bool() {
+            game_config.set_allow_reverse(allow);
+        }
+
+        let diagonal = Reflect::get(&config, &JsValue::from_str("diagonal"))?;
+        if let Some(diagonal) = diagonal.as_bool() {
+            game_config.set_diagonal(diagonal);
+        }
+
+        let ghost = Reflect::get(&config, &JsValue::from_str("ghost"))?;
+        if let Some(ghost) = ghost.as_bool() {
+            game_config.set_ghost(ghost);
+        }
+
+        let grid = Reflect::get(&config, &JsValue::from_str("grid"))?;
+        if let Some(grid) = grid.as_bool() {
+            game_config.set_grid(grid);
+        }
+
+        let shapes = Reflect::get(&config, &JsValue::from_str("shapes"))?;
+        if let Some(shapes) = shapes.as_bool() {
+            game_config.set_shapes(shapes);
+        }
+
+        let pixel_art =
+            Reflect::get(&config, &JsValue::from_str("pixel_art"))?;
+        if let Some(pixel_art) = pixel_art.as_bool() {
+            game_config.set_pixel_art(pixel_art);
+        }
+
+        let pixelated =
+            Reflect::get(&config, &JsValue::from_str("pixelated"))?;
+        if let Some(pixelated) = pixelated.as_bool() {
+            game_config.set_pixelated(pixelated);
+        }
+
+        let crt = Reflect::get(&config, &JsValue::from_str("crt"))?;
+        if let Some(crt) = crt.as_bool() {
+            game_config.set_crt(crt);
+        }
+
+        let reduced =
+            Reflect::get(&config, &JsValue::from_str("reduced_motion"))?;
+        if let Some(reduced) = reduced.as_bool() {
+            game_config.set_motion(if reduced {
+                MotionPolicy::Reduced
+            } else {
+                MotionPolicy::Full
+            });
+        }
+
+        let endpoint =
+            Reflect::get(&config, &JsValue::from_str("leaderboard"))?;
+        if let Some(endpoint) = endpoint.as_string() {
+            game_config.set_leaderboard(endpoint);
+        }
+
+        let tag = Reflect::get(&config, &JsValue::from_str("locale"))?;
+        if let Some(tag) = tag.as_string() {
+            let locale = Locale::from_tag(&tag).ok_or_else(|| {
+                js_sys::Error::new("config.locale must be an en or fr tag")
+            })?;
+            game_config.set_locale(locale);
+        }
+    }
+
+    game_config.apply_settings(&Settings::load(SETTINGS_KEY));
+    let mut builder = GameBuilder::new(game_config);
+
+    if let Some(parent) = element_field(&config, "parent")? {
+        builder.parent(parent);
+    }
+    if let Some(parent) = element_field(&config, "mini_view")? {
+        builder.mini_view(parent);
+    }
+
+    Ok(builder.build()?)
+}
+
+// an element given as a css selector or as itself
+fn element_field(
+    config: &JsValue,
+    name: &str,
+) -> Result<Option<Element>, JsValue> {
+    if !config.is_object() {
+        return Ok(None);
+    }
+
+    let value = Reflect::get(config, &JsValue::from_str(name))?;
+
+    if let Some(selector) = value.as_string() {
+        Ok(Some(query_element(&selector)?))
+    } else if value.is_undefined() || value.is_null() {
+        Ok(None)
+    } else {
+        let element = value.dyn_into::<Element>().map_err(|_| {
+            js_sys::Error::new(&format!(
+                "{} must be a css selector or an element",
+                name
+            ))
+        })?;
+        Ok(Some(element))
+    }
+}
+
+fn number_field(config: &JsValue, name: &str) -> Result<Option<f64>, JsValue> {
+    if !config.is_object() {
+        return Ok(None);
+    }
+
+    let value = Reflect::get(config, &JsValue::from_str(name))?;
+
+    if value.is_undefined() {
+        Ok(None)
+    } else {
+        value.as_f64().map(Some).ok_or_else(|| {
+            js_sys::Error::new(&format!("config.{} must be a number", name))
+                .into()
+        })
+    }
+}
+
+// a side of the board given in js, which `as` would wrap round past the
+// largest `SmallNat` rather than reject
+fn board_side(side: f64) -> Result<SmallNat, EnvError> {
+    // `as` is undefined for floats out of range, so those never get there
+    if !side.is_finite() || side < 1.0 || side.fract() != 0.0 {
+        Err(ConfigError::InvalidSide.into())
+    } else if side > MAX_BOARD_SIDE as f64 {
+        let max = MAX_BOARD_SIDE;
+        Err(ConfigError::BoardTooLarge { max }.into())
+    } else {
+        Ok(side as SmallNat)
+    }
+}
+
+// mirror of the board kept in sync from world updates
+struct Observation {
+    width: SmallNat,
+    height: SmallNat,
+    tiles: Vec<u8>,
+    state: GameState,
+    clock: Option<u32>,
+}
+
+impl Observation {
+    fn new() -> Self {
+        Observation {
+            width: 0,
+            height: 0,
+            tiles: Vec::new(),
+            state: GameState::Over,
+            clock: None,
+        }
+    }
+
+    fn index(&self, at: Coordinate) -> Option<usize> {
+        if at.x < self.width && at.y < self.height {
+            Some(at.y as usize * self.width as usize + at.x as usize)
+        } else {
+            None
+        }
+    }
+
+    fn set(&mut self, at: Coordinate, tile: Tile) {
+        if let Some(index) = self.index(at) {
+            self.tiles[index] = tile as u8;
+        }
+    }
+}
+
+impl Observer<WorldUpdate, UpdateError> for Observation {
+    fn on_update(&mut self, update: &WorldUpdate) {
+        match *update {
+            WorldUpdate::LevelLoaded { width, height }
+            | WorldUpdate::Respawned { width, height } => {
+                self.width = width;
+                self.height = height;
+                self.tiles =
+                    vec![Tile::Empty as u8; width as usize * height as usize];
+                self.state = GameState::Playing;
+                self.clock = None;
+            }
+            WorldUpdate::Clock { left } => self.clock = Some(left),
+            WorldUpdate::SetBlock { block, at } => match block {
+                Block::Snake(..) => self.set(at, Tile::Snake),
+                Block::Food => self.set(at, Tile::Food),
+                Block::Obstacle => self.set(at, Tile::Obstacle),
+                Block::Checkpoint => self.set(at, Tile::Checkpoint),
+                Block::Looming => self.set(at, Tile::Looming),
+                Block::PowerUp(_) => self.set(at, Tile::PowerUp),
+                _ => self.set(at, Tile::Empty),
+            },
+            // the snake is still drawn on a ring that closed over it
+            WorldUpdate::ArenaShrunk {
+                inset,
+                width,
+                height,
+            } => {
+                for at in Arena::ring(width, height, inset) {
+                    let snake = self.index(at).map_or(false, |index| {
+                        self.tiles[index] == Tile::Snake as u8
+                    });
+                    if !snake {
+                        self.set(at, Tile::Obstacle);
+                    }
+                }
+            }
+            WorldUpdate::HeadMoved { at, .. }
+            | WorldUpdate::Bend { at, .. } => self.set(at, Tile::Snake),
+            WorldUpdate::TailRetracted { at, .. } => self.set(at, Tile::Empty),
+            WorldUpdate::FoodSpawned { at } => self.set(at, Tile::Food),
+            WorldUpdate::FoodMoved { from, to } => {
+                self.set(from, Tile::Empty);
+                self.set(to, Tile::Food);
+            }
+            WorldUpdate::PowerUpSpawned { at, .. } => {
+                self.set(at, Tile::PowerUp)
+            }
+            WorldUpdate::HazardWarning { at, .. } => {
+                self.set(at, Tile::Looming)
+            }
+            WorldUpdate::HazardRaised { at } => self.set(at, Tile::Obstacle),
+            WorldUpdate::Paused(true) => self.state = GameState::Paused,
+            WorldUpdate::Paused(false) => self.state = GameState::Playing,
+            _ => {}
+        }
+    }
+
+    fn on_error(&mut self, err: &UpdateError) {
+        match *err {
+            UpdateError::Restart | UpdateError::LifeLost => {}
+            _ => self.state = GameState::Over,
+        }
+    }
+}
+
+fn history_entry_to_js(entry: &Entry<Snapshot<SmallRng>>) -> JsValue {
+    let view = entry.snapshot.view();
+    let mut tiles = Vec::new();
+
+    for (_, block) in view.grid().iter() {
+        let tile = match *block {
+            Block::Snake(..) => Tile::Snake,
+            Block::Food => Tile::Food,
+            Block::Obstacle => Tile::Obstacle,
+            Block::Checkpoint => Tile::Checkpoint,
+            Block::Looming => Tile::Looming,
+            Block::PowerUp(_) => Tile::PowerUp,
+            _ => Tile::Empty,
+        };
+        tiles.push(tile as u8);
+    }
+
+    let updates = Array::new();
+    for update in entry.delta.changes() {
+        updates.push(&JsValue::from_str(&format!("{:?}", update)));
+    }
+
+    let object = Object::new();
+    let set = |name: &str, value: JsValue| {
+        let _ = Reflect::set(&object, &JsValue::from_str(name), &value);
+    };
+
+    set("tick", JsValue::from_f64(entry.tick as f64));
+    set("length", JsValue::from_f64(view.snake().length() as f64));
+    set("tiles", Uint8Array::from(&tiles[..]).into());
+    set("updates", updates.into());
+
+    object.into()
+}
+
+struct JsEventSink {
+    callback: Function,
+}
+
+impl EventSink for JsEventSink {
+    fn on_event(&mut self, event: &GameEvent) {
+        let (kind, payload) = event_to_js(event);
+        let _ = self.callback.call2(
+            &JsValue::NULL,
+            &JsValue::from_f64(kind as u8 as f64),
+            &payload,
+        );
+    }
+}
+
+// the kind of `event` and what `on_event` passes along with it, see
+// `EventKind`
+fn event_to_js(event: &GameEvent) -> (EventKind, JsValue) {
+    let tile = |at: Coordinate| vec![("x", at.x as f64), ("y", at.y as f64)];
+
+    let (kind, fields) = match *event {
+        GameEvent::Started => (EventKind::Started, None),
+        GameEvent::FoodEaten { at, length } => {
+            let mut fields = tile(at);
+            fields.push(("length", length as f64));
+            (EventKind::FoodEaten, Some(fields))
+        }
+        GameEvent::FoodSpawned { at } => {
+            (EventKind::FoodSpawned, Some(tile(at)))
+        }
+        GameEvent::PowerUpTaken { at, .. } => {
+            (EventKind::PowerUpTaken, Some(tile(at)))
+        }
+        GameEvent::InvalidInput(dir) => {
+            let fields = vec![("dir", dir as u8 as f64)];
+            (EventKind::InvalidInput, Some(fields))
+        }
+        GameEvent::Paused(true) => (EventKind::Paused, None),
+        GameEvent::Paused(false) => (EventKind::Resumed, None),
+        GameEvent::Died { reason, seed } => {
+            let object = fields_to_js(&[("seed", seed as f64)]);
+            let reason = match DeathReason::from_error(reason) {
+                Some(reason) => JsValue::from_f64(reason as u8 as f64),
+                None => JsValue::NULL,
+            };
+            let name = JsValue::from_str("reason");
+            let _ = Reflect::set(&object, &name, &reason);
+            return (EventKind::Died, object);
+        }
+        GameEvent::Won { seed } => {
+            (EventKind::Won, Some(vec![("seed", seed as f64)]))
+        }
+        GameEvent::AchievementUnlocked(achievement) => {
+            let fields = vec![("achievement", achievement as u8 as f64)];
+            (EventKind::AchievementUnlocked, Some(fields))
+        }
+        GameEvent::Combo { multiplier, points } => {
+            let fields = vec![
+                ("multiplier", multiplier as f64),
+                ("points", points as f64),
+            ];
+            (EventKind::Combo, Some(fields))
+        }
+        GameEvent::ComboLost => (EventKind::ComboLost, None),
+        GameEvent::TeamLost { team, score } => {
+            let fields = vec![("team", team.0 as f64), ("score", score as f64)];
+            (EventKind::TeamLost, Some(fields))
+        }
+        GameEvent::QualityChanged(tier) => {
+            let fields = vec![("tier", tier as u8 as f64)];
+            (EventKind::QualityChanged, Some(fields))
+        }
+        GameEvent::GameOver(ref summary) => {
+            return (EventKind::GameOver, summary_to_js(summary));
+        }
+    };
+
+    match fields {
+        Some(fields) => (kind, fields_to_js(&fields)),
+        None => (kind, JsValue::NULL),
+    }
+}
+
+// an object with the number `value` under each `name`
+fn fields_to_js(fields: &[(&str, f64)]) -> JsValue {
+    let object = Object::new();
+    for &(name, value) in fields {
+        let value = JsValue::from_f64(value);
+        let _ = Reflect::set(&object, &JsValue::from_str(name), &value);
+    }
+    object.into()
+}
+
+fn timeline_entry_to_js(tick: Tick, event: &GameEvent) -> JsValue {
+    let (kind, payload) = event_to_js(event);
+
+    let object = Object::new();
+    let set = |name: &str, value: &JsValue| {
+        let _ = Reflect::set(&object, &JsValue::from_str(name), value);
+    };
+
+    set("tick", &JsValue::from_f64(tick as f64));
+    set("kind", &JsValue::from_f64(kind as u8 as f64));
+    set("payload", &payload);
+
+    object.into()
+}
+
+fn summary_to_js(summary: &GameSummary) -> JsValue {
+    let object = Object::new();
+    let set = |name: &str, value: u32| {
+        let value = JsValue::from_f64(value as f64);
+        let _ = Reflect::set(&object, &JsValue::from_str(name), &value);
+    };
+
+    set("duration", summary.duration);
+    set("ticks", summary.ticks);
+    set("length", summary.length);
+    set("food", summary.food);
+    set("cause", summary.cause as u32);
+    set("coverage_percent", summary.coverage_percent);
+
+    object.into()
+}
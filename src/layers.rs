@@ -0,0 +1,125 @@
+use alloc::vec::Vec;
+
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{CanvasRenderingContext2d, Document, HtmlCanvasElement};
+
+use canvas::EnvError;
+use system::Layer;
+
+// the crt pass: a dark line on every other row of pixels, and the corners
+// darkened up to `VIGNETTE_COLOR`
+const SCANLINE_COLOR: &str = "rgba(0, 0, 0, 0.12)";
+const VIGNETTE_COLOR: &str = "rgba(0, 0, 0, 0.35)";
+
+/// A canvas off the page for each `Layer` of a `CanvasEnv`, drawn one over
+/// the other onto the canvas on the page at the end of every frame: what
+/// is cleared on one layer shows the ones under it, so an effect going
+/// away leaves the snake as it was and the game over screen coming and
+/// going doesn't repaint the board. The crt pass goes over all of them, as
+/// the last step of `composite`, so screenshots show it too.
+pub struct Layers {
+    // by `Layer`, the bottom one first
+    canvases: Vec<(HtmlCanvasElement, CanvasRenderingContext2d)>,
+    // painted by `paint_crt` once the layers have their size
+    crt: (HtmlCanvasElement, CanvasRenderingContext2d),
+}
+
+impl Layers {
+    pub fn new(document: &Document) -> Result<Self, EnvError> {
+        let mut canvases = Vec::with_capacity(Layer::ALL.len());
+        for _ in Layer::ALL.iter() {
+            canvases.push(create_canvas(document)?);
+        }
+        let crt = create_canvas(document)?;
+
+        Ok(Layers { canvases, crt })
+    }
+
+    pub fn gc(&self, layer: Layer) -> &CanvasRenderingContext2d {
+        &self.canvases[layer as usize].1
+    }
+
+    /// The contexts of every layer, e.g. to set them all up alike
+    pub fn gcs<'a>(
+        &'a self,
+    ) -> impl Iterator<Item = &'a CanvasRenderingContext2d> + 'a {
+        self.canvases.iter().map(|&(_, ref gc)| gc)
+    }
+
+    /// Makes every layer `width` x `height` pixels, which clears them and
+    /// the crt pass
+    pub fn resize(&self, width: u32, height: u32) {
+        for &(ref canvas, _) in self.canvases.iter().chain(Some(&self.crt)) {
+            canvas.set_width(width);
+            canvas.set_height(height);
+        }
+    }
+
+    /// Paints the crt pass at the size of the layers
+    pub fn paint_crt(&self) -> Result<(), JsValue> {
+        let (ref canvas, ref gc) = self.crt;
+        let width = canvas.width() as f64;
+        let height = canvas.height() as f64;
+
+        gc.clear_rect(0.0, 0.0, width, height);
+        gc.set_fill_style(&SCANLINE_COLOR.into());
+        for y in (0..canvas.height()).step_by(2) {
+            gc.fill_rect(0.0, y as f64, width, 1.0);
+        }
+
+        // out to (w + h) / 2 from the middle, past the corners without a
+        // square root
+        let (x, y) = (width / 2.0, height / 2.0);
+        let inner = if x < y { x } else { y };
+        let outer = x + y;
+        let gradient = gc.create_radial_gradient(x, y, inner, x, y, outer)?;
+        gradient.add_color_stop(0.0, "rgba(0, 0, 0, 0)")?;
+        gradient.add_color_stop(1.0, VIGNETTE_COLOR)?;
+        gc.set_fill_style(&gradient.into());
+        gc.fill_rect(0.0, 0.0, width, height);
+        Ok(())
+    }
+
+    pub fn clear(&self, layer: Layer) {
+        let canvas = &self.canvases[layer as usize].0;
+        let (width, height) = (canvas.width() as f64, canvas.height() as f64);
+
+        self.gc(layer).clear_rect(0.0, 0.0, width, height);
+    }
+
+    /// Draws every layer onto `gc` in place of what it showed, the bottom
+    /// one first, and the crt pass over them with `crt`
+    pub fn composite(
+        &self,
+        gc: &CanvasRenderingContext2d,
+        crt: bool,
+    ) -> Result<(), JsValue> {
+        let canvas = &self.canvases[Layer::Background as usize].0;
+        let (width, height) = (canvas.width() as f64, canvas.height() as f64);
+
+        gc.clear_rect(0.0, 0.0, width, height);
+        for &(ref canvas, _) in &self.canvases {
+            gc.draw_image_with_html_canvas_element(canvas, 0.0, 0.0)?;
+        }
+        if crt {
+            gc.draw_image_with_html_canvas_element(&self.crt.0, 0.0, 0.0)?;
+        }
+        Ok(())
+    }
+}
+
+fn create_canvas(
+    document: &Document,
+) -> Result<(HtmlCanvasElement, CanvasRenderingContext2d), EnvError> {
+    let canvas = document
+        .create_element("canvas")?
+        .dyn_into::<HtmlCanvasElement>()
+        .map_err(|_| EnvError::CreateCanvas)?;
+    let gc = canvas
+        .get_context("2d")?
+        .ok_or(EnvError::NoContext)?
+        .dyn_into::<CanvasRenderingContext2d>()
+        .map_err(|_| EnvError::NoContext)?;
+
+    Ok((canvas, gc))
+}
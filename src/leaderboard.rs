@@ -0,0 +1,449 @@
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+use std::cell::RefCell;
+use std::fmt;
+
+use js_sys::{Array, Function, Promise, Reflect};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{RequestInit, Response};
+
+use constants::{LEADERBOARD_ATTEMPTS, LEADERBOARD_RETRY_DELAY};
+
+/// A score as posted to and listed by the leaderboard endpoint
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ScoreEntry {
+    pub name: String,
+    pub score: u32,
+    pub seed: u32,
+    // identifies the inputs that produced the score, for verification
+    pub replay_hash: u32,
+}
+
+impl ScoreEntry {
+    pub fn to_json(&self) -> String {
+        format!(
+            r#"{{"name":{},"score":{},"seed":{},"replay_hash":{}}}"#,
+            json_string(&self.name),
+            self.score,
+            self.seed,
+            self.replay_hash,
+        )
+    }
+
+    fn from_js(value: &JsValue) -> Option<Self> {
+        let field = |name: &str| Reflect::get(value, &JsValue::from_str(name));
+
+        Some(ScoreEntry {
+            name: field("name").ok()?.as_string()?,
+            score: field("score").ok()?.as_f64()? as u32,
+            seed: field("seed").ok()?.as_f64().unwrap_or(0.0) as u32,
+            replay_hash: field("replay_hash").ok()?.as_f64().unwrap_or(0.0)
+                as u32,
+        })
+    }
+
+    fn to_js(&self) -> JsValue {
+        let object = js_sys::Object::new();
+        let set = |name: &str, value: JsValue| {
+            let _ = Reflect::set(&object, &JsValue::from_str(name), &value);
+        };
+
+        set("name", JsValue::from_str(&self.name));
+        set("score", JsValue::from_f64(self.score as f64));
+        set("seed", JsValue::from_f64(self.seed as f64));
+        set("replay_hash", JsValue::from_f64(self.replay_hash as f64));
+
+        object.into()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum LeaderboardError {
+    NoEndpoint,
+    // the endpoint answered with a non 2xx status
+    Status(u16),
+    MalformedResponse,
+    // fetch rejected, usually a network failure
+    Js(JsValue),
+}
+
+impl LeaderboardError {
+    /// Whether trying the same request again may succeed
+    pub fn is_retryable(&self) -> bool {
+        match *self {
+            LeaderboardError::Status(status) => status >= 500 || status == 429,
+            LeaderboardError::Js(_) => true,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for LeaderboardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LeaderboardError::NoEndpoint => {
+                write!(f, "no leaderboard endpoint configured")
+            }
+            LeaderboardError::Status(status) => {
+                write!(f, "leaderboard answered with status {}", status)
+            }
+            LeaderboardError::MalformedResponse => {
+                write!(f, "leaderboard response is not a list of scores")
+            }
+            LeaderboardError::Js(ref err) => {
+                write!(f, "leaderboard request failed: {:?}", err)
+            }
+        }
+    }
+}
+
+/// How often and how soon failed requests are sent again
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct RetryPolicy {
+    pub attempts: u32,
+    // milliseconds before the first retry, doubled on every retry after
+    pub delay: u32,
+}
+
+impl RetryPolicy {
+    pub fn new() -> Self {
+        RetryPolicy {
+            attempts: LEADERBOARD_ATTEMPTS,
+            delay: LEADERBOARD_RETRY_DELAY,
+        }
+    }
+
+    /// Milliseconds to wait before sending attempt number `attempt` (the
+    /// first one being 0), none once all attempts are used
+    pub fn delay(&self, attempt: u32) -> Option<u32> {
+        if attempt == 0 {
+            Some(0)
+        } else if attempt < self.attempts {
+            Some(self.delay.saturating_mul(1 << (attempt - 1).min(16)))
+        } else {
+            None
+        }
+    }
+}
+
+type Done = Box<FnMut(Result<JsValue, LeaderboardError>)>;
+
+// the status of a successful answer without a body
+const NO_CONTENT: u16 = 204;
+
+// a request being sent, possibly again
+struct Attempt {
+    method: &'static str,
+    url: String,
+    body: Option<String>,
+    retry: RetryPolicy,
+    attempt: u32,
+    done: Done,
+}
+
+/// Client of a leaderboard http endpoint: scores are posted as json to
+/// `endpoint` and the top scores are fetched from `endpoint?limit=n`
+#[derive(Debug, Clone)]
+pub struct Leaderboard {
+    endpoint: String,
+    retry: RetryPolicy,
+}
+
+impl Leaderboard {
+    pub fn new(endpoint: String) -> Self {
+        Leaderboard {
+            endpoint,
+            retry: RetryPolicy::new(),
+        }
+    }
+
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    pub fn submit<F>(&self, entry: &ScoreEntry, mut done: F)
+    where
+        F: 'static + FnMut(Result<(), LeaderboardError>),
+    {
+        self.send(
+            "POST",
+            self.endpoint.clone(),
+            Some(entry.to_json()),
+            Box::new(move |result| done(result.map(|_| ()))),
+        );
+    }
+
+    pub fn top_scores<F>(&self, limit: u32, mut done: F)
+    where
+        F: 'static + FnMut(Result<Vec<ScoreEntry>, LeaderboardError>),
+    {
+        let separator = if self.endpoint.contains('?') { '&' } else { '?' };
+        let url = format!("{}{}limit={}", self.endpoint, separator, limit);
+
+        self.send(
+            "GET",
+            url,
+            None,
+            Box::new(move |result| {
+                done(result.and_then(|value| parse_scores(&value)))
+            }),
+        );
+    }
+
+    fn send(
+        &self,
+        method: &'static str,
+        url: String,
+        body: Option<String>,
+        done: Done,
+    ) {
+        let attempt = Attempt {
+            method,
+            url,
+            body,
+            retry: self.retry,
+            attempt: 0,
+            done,
+        };
+
+        start(Rc::new(RefCell::new(attempt)));
+    }
+}
+
+fn start(attempt: Rc<RefCell<Attempt>>) {
+    let promise = {
+        let attempt = attempt.borrow();
+        let mut init = RequestInit::new();
+        init.method(attempt.method);
+        if let Some(ref body) = attempt.body {
+            let headers = js_sys::Object::new();
+            let _ = Reflect::set(
+                &headers,
+                &JsValue::from_str("Content-Type"),
+                &JsValue::from_str("application/json"),
+            );
+            init.headers(&headers);
+            init.body(Some(&JsValue::from_str(body)));
+        }
+
+        match web_sys::window() {
+            Some(window) => {
+                window.fetch_with_str_and_init(&attempt.url, &init)
+            }
+            None => Err(JsValue::from_str("no global window")),
+        }
+    };
+
+    let promise = match promise {
+        Ok(promise) => promise,
+        Err(err) => return finish(attempt, Err(LeaderboardError::Js(err))),
+    };
+
+    let on_response = {
+        let attempt = attempt.clone();
+        move |response: JsValue| {
+            let response: Response = match response.dyn_into() {
+                Ok(response) => response,
+                Err(_) => {
+                    let err = LeaderboardError::MalformedResponse;
+                    return finish(attempt.clone(), Err(err));
+                }
+            };
+
+            if !response.ok() {
+                let err = LeaderboardError::Status(response.status());
+                return finish(attempt.clone(), Err(err));
+            } else if response.status() == NO_CONTENT {
+                return finish(attempt.clone(), Ok(JsValue::UNDEFINED));
+            }
+
+            match response.json() {
+                Ok(json) => {
+                    let ok = attempt.clone();
+                    let err = attempt.clone();
+                    then(
+                        json,
+                        move |value| finish(ok.clone(), Ok(value)),
+                        move |_| {
+                            // submissions need no answer, which may well
+                            // be an empty body rather than json
+                            if err.borrow().method == "POST" {
+                                let none = Ok(JsValue::UNDEFINED);
+                                return finish(err.clone(), none);
+                            }
+                            let e = LeaderboardError::MalformedResponse;
+                            finish(err.clone(), Err(e))
+                        },
+                    );
+                }
+                // an empty body is fine for submissions
+                Err(_) => finish(attempt.clone(), Ok(JsValue::UNDEFINED)),
+            }
+        }
+    };
+    let on_reject = {
+        let attempt = attempt.clone();
+        move |err| finish(attempt.clone(), Err(LeaderboardError::Js(err)))
+    };
+
+    then(promise, on_response, on_reject);
+}
+
+// calls `done`, or schedules the next attempt of a retryable failure
+fn finish(
+    attempt: Rc<RefCell<Attempt>>,
+    result: Result<JsValue, LeaderboardError>,
+) {
+    let retry_in = match result {
+        Err(ref err) if err.is_retryable() => {
+            let mut attempt = attempt.borrow_mut();
+            attempt.attempt += 1;
+            attempt.retry.delay(attempt.attempt)
+        }
+        _ => None,
+    };
+
+    if let Some(delay) = retry_in {
+        warn!("retrying leaderboard request in {}ms", delay);
+
+        let retry = attempt.clone();
+        let callback = Closure::wrap(
+            Box::new(move || start(retry.clone())) as Box<FnMut()>
+        );
+        let scheduled = web_sys::window().map(|window| {
+            window.set_timeout_with_callback_and_timeout_and_arguments_0(
+                callback.as_ref().unchecked_ref(),
+                delay as i32,
+            )
+        });
+        callback.forget();
+
+        if let Some(Ok(_)) = scheduled {
+            return;
+        }
+    }
+
+    (attempt.borrow_mut().done)(result);
+}
+
+// the callbacks are only called once by the promise, leaking them is the
+// price of not tracking their lifetime
+fn then<F, R>(promise: Promise, on_fulfilled: F, on_rejected: R)
+where
+    F: 'static + FnMut(JsValue),
+    R: 'static + FnMut(JsValue),
+{
+    let on_fulfilled = Closure::wrap(Box::new(on_fulfilled) as Box<FnMut(_)>);
+    let on_rejected = Closure::wrap(Box::new(on_rejected) as Box<FnMut(_)>);
+
+    promise.then2(&on_fulfilled, &on_rejected);
+
+    on_fulfilled.forget();
+    on_rejected.forget();
+}
+
+fn parse_scores(value: &JsValue) -> Result<Vec<ScoreEntry>, LeaderboardError> {
+    if !Array::is_array(value) {
+        return Err(LeaderboardError::MalformedResponse);
+    }
+
+    let array: &Array = value.unchecked_ref();
+    let mut scores = Vec::new();
+
+    for i in 0..array.length() {
+        let entry = ScoreEntry::from_js(&array.get(i))
+            .ok_or(LeaderboardError::MalformedResponse)?;
+        scores.push(entry);
+    }
+
+    Ok(scores)
+}
+
+/// Scores as an array of `{name, score, seed, replay_hash}` objects
+pub fn scores_to_js(scores: &[ScoreEntry]) -> JsValue {
+    let array = Array::new();
+    for entry in scores {
+        array.push(&entry.to_js());
+    }
+    array.into()
+}
+
+/// Calls a node style `callback(error, value)` with a `LeaderboardError`
+/// message or the value
+pub fn call_back(
+    callback: &Function,
+    result: Result<JsValue, LeaderboardError>,
+) {
+    let _ = match result {
+        Ok(value) => callback.call2(&JsValue::NULL, &JsValue::NULL, &value),
+        Err(err) => callback.call1(
+            &JsValue::NULL,
+            &JsValue::from_str(&format!("{}", err)),
+        ),
+    };
+}
+
+fn json_string(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len() + 2);
+    escaped.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                escaped.push_str(&format!("\\u{:04x}", c as u32))
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score_json() {
+        let entry = ScoreEntry {
+            name: "a \"b\"\n".into(),
+            score: 12,
+            seed: 7,
+            replay_hash: 42,
+        };
+
+        assert_eq!(
+            entry.to_json(),
+            r#"{"name":"a \"b\"\n","score":12,"seed":7,"replay_hash":42}"#
+        );
+    }
+
+    #[test]
+    fn test_retry_backoff() {
+        let retry = RetryPolicy {
+            attempts: 3,
+            delay: 100,
+        };
+
+        assert_eq!(retry.delay(0), Some(0));
+        assert_eq!(retry.delay(1), Some(100));
+        assert_eq!(retry.delay(2), Some(200));
+        assert_eq!(retry.delay(3), None);
+
+        assert!(LeaderboardError::Status(503).is_retryable());
+        assert!(!LeaderboardError::Status(400).is_retryable());
+        assert!(!LeaderboardError::MalformedResponse.is_retryable());
+    }
+}
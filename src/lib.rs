@@ -7,116 +7,110 @@ extern crate core as std;
 #[macro_use]
 extern crate alloc;
 extern crate console_error_panic_hook;
+// for the panic hook, std is there on wasm32 whatever the features
+extern crate std as libstd;
 
 extern crate js_sys;
-extern crate void;
 extern crate wasm_bindgen;
 extern crate web_sys;
 extern crate wee_alloc;
 
 #[macro_use]
 extern crate itertools;
-extern crate arraydeque;
-extern crate morton;
 extern crate rand;
-
 #[macro_use]
-#[cfg(test)]
-extern crate indoc;
+extern crate snake_core;
+#[cfg(feature = "telemetry-json")]
+extern crate serde;
+#[cfg(feature = "telemetry-json")]
 #[macro_use]
-#[cfg(test)]
-extern crate matches;
+extern crate serde_derive;
+#[cfg(feature = "telemetry-json")]
+extern crate serde_json;
+
 #[macro_use]
 #[cfg(test)]
-extern crate quickcheck;
-
-use alloc::boxed::Box;
+extern crate indoc;
 
-use std::ops::Generator;
+pub use snake_core::SmallRng;
 
-pub use rand::rngs::SmallRng;
-use wasm_bindgen::prelude::*;
+// the simulation lives in snake-core, re-exported under the module paths
+// this crate always had
+pub use snake_core::{
+    acceleration, blocks, bot, checkpoint, config, data, dead, draw, formats,
+    history, i18n, keymap, life, log, replay, system, theme, tutorial, world,
+};
 
 #[macro_use]
 mod macros;
 
-pub mod data;
-pub mod draw;
-pub mod system;
-pub mod world;
-
-pub mod acceleration;
-pub mod dead;
-
+pub mod achievements;
+pub mod daily;
+pub mod errors;
+pub mod events;
+pub mod js_api;
+#[cfg(feature = "leaderboard")]
+pub mod leaderboard;
+#[cfg(feature = "replays")]
+pub mod replays;
+pub mod quality;
+pub mod ratings;
+pub mod scoring;
+pub mod settings;
+pub mod stats;
+pub mod telemetry;
+
+mod announcer;
 mod canvas;
+#[cfg(feature = "clip")]
+mod clip;
+mod console;
 mod constants;
-
-pub use acceleration::{RenderSpeed, VariableFrame};
-pub use canvas::partial_tile;
-pub use data::{Bounding, Direction, Key, Wrapping};
+mod haptics;
+mod layers;
+mod power;
+
+pub use acceleration::{Paced, RenderSpeed, VariableFrame};
+pub use achievements::{Achievement, Achievements};
+pub use blocks::{BlocksDraw, BlocksUpdate, FallingBlocks};
+pub use canvas::EnvError;
+pub use checkpoint::Checkpoints;
+pub use config::Config;
+pub use data::{Bounding, Command, Direction, Key, KeyRepeat, Wrapping};
+pub use daily::DailyChallenge;
 pub use dead::{CtrlEvent, Dead};
-pub use draw::WorldUpdateDraw;
+pub use errors::{ErrorPhase, ErrorReporter};
+pub use draw::{TickDraw, WorldUpdateDraw};
+pub use events::{EventBus, EventSink, GameEvent, Stamped};
+pub use js_api::{
+    DemoHandle, GameBuilder, GameConfig, GameHandle, Renderer, Spawn,
+};
+pub use history::{History, Rewind, Timeline};
+pub use i18n::{Locale, Text};
+pub use keymap::{Binding, KeyMap};
+#[cfg(feature = "leaderboard")]
+pub use leaderboard::{Leaderboard, LeaderboardError, RetryPolicy, ScoreEntry};
+pub use life::{LifeDraw, LifeModel, LifeUpdate};
+pub use log::LogLevel;
+pub use power::PowerSaving;
+pub use quality::{AdaptiveQuality, QualityTier};
+pub use ratings::{Outcome, RatedGame, Ratings};
+#[cfg(not(target_arch = "wasm32"))]
+pub use replay::verify_replay;
+pub use replay::{Playback, Record, Replay, Score, VerifyError};
+#[cfg(feature = "replays")]
+pub use replays::ReplayLibrary;
+pub use scoring::{Combo, ScoreRule, Scoring, Team, TeamWatch};
+pub use stats::{Counted, DeathReason, Game, Stats};
 pub use system::*;
-pub use world::{World, WorldBuilder, WorldUpdate};
-
-use canvas::CanvasEnv;
+pub use telemetry::{Report, Telemetry};
+pub use theme::{Background, Palette, Theme};
+pub use tutorial::Tutorial;
+pub use world::{
+    PerChange, Rules, Snake, SnakeBody, SnakeId, Snapshot, TeamId, TickDelta,
+    Versus, World, WorldBuilder, WorldUpdate, PLAYER,
+};
 
 #[global_allocator]
 #[cfg(not(any(feature = "std", test, debug)))]
 static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
-
-#[wasm_bindgen(module = "./game-loop")]
-extern "C" {
-    type GameLoop;
-
-    #[wasm_bindgen(constructor)]
-    fn new(run: &Closure<FnMut(u8)>) -> GameLoop;
-
-    #[wasm_bindgen(method)]
-    fn start(this: &GameLoop) -> bool;
-
-    #[wasm_bindgen(method)]
-    fn stop(this: &GameLoop) -> bool;
-}
-
-#[wasm_bindgen]
-pub fn main() {
-    #[cfg(feature = "std")]
-    ::std::panic::set_hook(Box::new(console_error_panic_hook::hook));
-
-    let facing = Direction::East;
-
-    let world: World<SmallRng, Wrapping> = WorldBuilder::new()
-        .width(64)
-        .height(32)
-        .set_snake(1, 1)
-        .extend(facing)
-        .extend(facing)
-        .extend(facing)
-        .extend(facing)
-        .build_with_seed([123; 16]);
-
-    let game = world
-        .zip_with(RenderSpeed::new(facing), VariableFrame::pack)
-        .alternating::<Key, _>(Dead::new())
-        .make_game(CanvasEnv::new());
-
-    let (tx, mut generator) =
-        game.new_game::<WorldUpdateDraw<VariableFrame<WorldUpdate>>, Key>();
-
-    let each_tick = Closure::wrap(Box::new(move |key: u8| {
-        let key = Key::from(key);
-
-        tx.send(key);
-
-        unsafe {
-            generator.resume();
-        }
-    }) as Box<FnMut(_)>);
-
-    let game_loop = GameLoop::new(&each_tick);
-
-    game_loop.start();
-
-    each_tick.forget();
-}
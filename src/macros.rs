@@ -1,22 +1,7 @@
 #[macro_export]
 macro_rules! console_log {
     ($($t:tt)*) => {{
-        #[cfg(feature="std")]
-        web_sys::console::log_1(&( &format_args!($($t)*).to_string().into()) )
-    }};
-}
-
-#[macro_export]
-macro_rules! yield_from {
-    ($e:expr) => {{
-        use std::ops::{Generator, GeneratorState};
-
-        let mut gen = $e;
-        loop {
-            match unsafe { gen.resume() } {
-                GeneratorState::Complete(_) => break,
-                GeneratorState::Yielded(x) => yield x,
-            }
-        }
+        #[cfg(all(feature = "std", target_arch = "wasm32"))]
+        ::web_sys::console::log_1(&( &format_args!($($t)*).to_string().into()) )
     }};
 }
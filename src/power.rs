@@ -0,0 +1,104 @@
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use std::cell::Cell;
+
+use js_sys::{Promise, Reflect};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use constants::LOW_BATTERY;
+use haptics::method;
+
+/// When the game saves power, drawing every other frame of the browser and
+/// no score popups
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum PowerSaving {
+    Off = 0,
+    On = 1,
+    // while `navigator.getBattery()` says the battery is low and not
+    // charging, never where it isn't supported
+    Auto = 2,
+}
+
+impl PowerSaving {
+    pub fn from_u8(n: u8) -> Option<Self> {
+        match n {
+            0 => Some(PowerSaving::Off),
+            1 => Some(PowerSaving::On),
+            2 => Some(PowerSaving::Auto),
+            _ => None,
+        }
+    }
+}
+
+/// Whether the game saves power right now, shared between the game loop
+/// and the env, see `PowerSaving`
+#[derive(Debug, Clone)]
+pub struct PowerSaver {
+    on: Rc<Cell<bool>>,
+}
+
+impl PowerSaver {
+    pub fn new(mode: PowerSaving) -> Self {
+        let saver = PowerSaver {
+            on: Rc::new(Cell::new(mode == PowerSaving::On)),
+        };
+        if mode == PowerSaving::Auto {
+            saver.follow_battery();
+        }
+        saver
+    }
+
+    pub fn is_on(&self) -> bool {
+        self.on.get()
+    }
+
+    // turns on and off with the level and the charging of the battery,
+    // once the browser told what they are
+    fn follow_battery(&self) {
+        let navigator = match web_sys::window() {
+            Some(window) => window.navigator(),
+            None => return,
+        };
+        let battery = method(&navigator, "getBattery")
+            .and_then(|get| get.call0(&navigator).ok())
+            .and_then(|battery| battery.dyn_into::<Promise>().ok());
+        let battery = match battery {
+            Some(battery) => battery,
+            None => return,
+        };
+
+        let on = self.on.clone();
+        let on_battery = Closure::wrap(Box::new(move |battery: JsValue| {
+            on.set(is_low(&battery));
+
+            let (on, manager) = (on.clone(), battery.clone());
+            let check = Closure::wrap(Box::new(move || {
+                on.set(is_low(&manager));
+            }) as Box<FnMut()>);
+            if let Some(listen) = method(&battery, "addEventListener") {
+                for &event in &["levelchange", "chargingchange"] {
+                    let event = JsValue::from_str(event);
+                    listen.call2(&battery, &event, check.as_ref()).ok();
+                }
+            }
+            check.forget();
+        }) as Box<FnMut(_)>);
+
+        battery.then(&on_battery);
+        on_battery.forget();
+    }
+}
+
+// whether the `BatteryManager` `battery` is low and not charging
+fn is_low(battery: &JsValue) -> bool {
+    let field = |name: &str| Reflect::get(battery, &JsValue::from_str(name));
+    let level = field("level").ok().and_then(|level| level.as_f64());
+    let charging = field("charging").ok().and_then(|c| c.as_bool());
+
+    match (level, charging) {
+        (Some(level), Some(false)) => level <= LOW_BATTERY,
+        _ => false,
+    }
+}
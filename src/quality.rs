@@ -0,0 +1,155 @@
+use alloc::collections::VecDeque;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use std::cell::{Cell, RefCell};
+
+use wasm_bindgen::prelude::*;
+
+use constants::{QUALITY_FRAMES, QUALITY_HEADROOM};
+use events::{EventBus, GameEvent};
+
+/// How much of the looks the game can afford on the device it runs on
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum QualityTier {
+    // no popups and no image smoothing either
+    Low = 0,
+    // no crt scanlines
+    Medium = 1,
+    High = 2,
+}
+
+impl QualityTier {
+    fn lower(self) -> Option<Self> {
+        match self {
+            QualityTier::High => Some(QualityTier::Medium),
+            QualityTier::Medium => Some(QualityTier::Low),
+            QualityTier::Low => None,
+        }
+    }
+
+    fn higher(self) -> Option<Self> {
+        match self {
+            QualityTier::Low => Some(QualityTier::Medium),
+            QualityTier::Medium => Some(QualityTier::High),
+            QualityTier::High => None,
+        }
+    }
+}
+
+/// Times the frames the game draws and lowers the `QualityTier` a tier
+/// once the slowest of them (the 95th percentile) take longer than the
+/// budget, raising it back once they take well under it again. Tiers
+/// change at most once per `QUALITY_FRAMES` frames. The env reads the
+/// tier from `tier` as it draws.
+pub struct AdaptiveQuality {
+    budget: f64,
+    tier: Rc<Cell<QualityTier>>,
+    // milliseconds each of the latest frames took, oldest first
+    frames: VecDeque<f64>,
+    events: Option<Rc<RefCell<EventBus>>>,
+}
+
+impl AdaptiveQuality {
+    /// Frames meant to take at most `budget` milliseconds, starting at the
+    /// highest tier
+    pub fn new(budget: f64) -> Self {
+        AdaptiveQuality {
+            budget,
+            tier: Rc::new(Cell::new(QualityTier::High)),
+            frames: VecDeque::with_capacity(QUALITY_FRAMES),
+            events: None,
+        }
+    }
+
+    /// Publishes `GameEvent::QualityChanged` on `events`
+    pub fn with_events(mut self, events: Rc<RefCell<EventBus>>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    pub fn tier(&self) -> Rc<Cell<QualityTier>> {
+        self.tier.clone()
+    }
+
+    /// The 95th percentile of the frame times counted since the tier last
+    /// changed, none until there are `QUALITY_FRAMES` of them
+    pub fn p95(&self) -> Option<f64> {
+        if self.frames.len() < QUALITY_FRAMES {
+            return None;
+        }
+
+        let mut sorted: Vec<f64> = self.frames.iter().cloned().collect();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        Some(sorted[sorted.len() * 95 / 100])
+    }
+
+    /// Counts a frame that took `millis`, the tier it changed to if it did
+    pub fn frame(&mut self, millis: f64) -> Option<QualityTier> {
+        if self.frames.len() == QUALITY_FRAMES {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(millis);
+
+        let p95 = self.p95()?;
+        let tier = self.tier.get();
+        let changed = if p95 > self.budget {
+            tier.lower()
+        } else if p95 < self.budget * QUALITY_HEADROOM {
+            tier.higher()
+        } else {
+            None
+        }?;
+
+        self.tier.set(changed);
+        self.frames.clear();
+        if let Some(ref events) = self.events {
+            events
+                .borrow_mut()
+                .publish(GameEvent::QualityChanged(changed));
+        }
+        Some(changed)
+    }
+}
+
+/// Milliseconds of some origin, to a fraction of one where the browser
+/// tells, for timing frames
+pub fn now() -> f64 {
+    web_sys::window()
+        .and_then(|window| window.performance())
+        .map_or_else(js_sys::Date::now, |performance| performance.now())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quality_follows_the_slow_frames() {
+        // the tiers `QUALITY_FRAMES` frames of `millis` each changed to
+        fn run(quality: &mut AdaptiveQuality, millis: f64) -> Vec<QualityTier> {
+            (0..QUALITY_FRAMES)
+                .filter_map(|_| quality.frame(millis))
+                .collect()
+        }
+
+        let mut quality = AdaptiveQuality::new(8.0);
+
+        // a few slow frames are fine
+        for i in 0..QUALITY_FRAMES {
+            let millis = if i % 50 == 0 { 30.0 } else { 6.0 };
+            assert_eq!(quality.frame(millis), None);
+        }
+        assert_eq!(quality.tier().get(), QualityTier::High);
+
+        assert_eq!(run(&mut quality, 12.0), [QualityTier::Medium]);
+        assert_eq!(run(&mut quality, 12.0), [QualityTier::Low]);
+        assert!(run(&mut quality, 12.0).is_empty());
+        assert_eq!(quality.p95(), Some(12.0));
+
+        // back up once there is room to spare, a tier at a time
+        assert_eq!(run(&mut quality, 2.0), [QualityTier::Medium]);
+        assert_eq!(run(&mut quality, 2.0), [QualityTier::High]);
+        assert_eq!(quality.tier().get(), QualityTier::High);
+    }
+}
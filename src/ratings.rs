@@ -0,0 +1,356 @@
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+use std::cell::RefCell;
+use std::cmp::Ordering;
+
+use web_sys::Storage;
+
+use bot::Autopilot;
+use constants::{RATING_DIFFICULTY_STEP, RATING_K, RATING_START};
+use formats::RATINGS as VERSION;
+use scoring::Scoring;
+use system::Observer;
+use world::{UpdateError, WorldUpdate};
+
+// the score the stronger of two players is expected to get, in thousandths,
+// every 50 points of difference in their ratings up to 800
+const EXPECTED: [i32; 17] = [
+    500, 571, 640, 703, 760, 808, 849, 882, 909, 930, 947, 960, 969, 977, 983,
+    987, 990,
+];
+
+/// How a game against a bot went for the player
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Outcome {
+    Win,
+    Draw,
+    Loss,
+}
+
+impl Outcome {
+    /// The outcome of a game the player scored `score` in and the bot
+    /// `bot_score`
+    pub fn of(score: u32, bot_score: u32) -> Self {
+        match score.cmp(&bot_score) {
+            Ordering::Greater => Outcome::Win,
+            Ordering::Equal => Outcome::Draw,
+            Ordering::Less => Outcome::Loss,
+        }
+    }
+
+    // in thousandths of a win
+    fn points(self) -> i32 {
+        match self {
+            Outcome::Win => 1000,
+            Outcome::Draw => 500,
+            Outcome::Loss => 0,
+        }
+    }
+}
+
+/// Elo ratings of the player and of each bot difficulty they played,
+/// remembered across visits, for picking bots that are a match for the
+/// player. A difficulty yet to be played is rated `RATING_DIFFICULTY_STEP`
+/// over the one below it.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Ratings {
+    player: i32,
+    // by difficulty
+    bots: Vec<(u8, i32)>,
+}
+
+impl Ratings {
+    pub fn new() -> Self {
+        Ratings {
+            player: RATING_START,
+            bots: Vec::new(),
+        }
+    }
+
+    /// The ratings stored in `localStorage[key]`, new ones when storage is
+    /// unavailable or holds a blob this version can't read
+    pub fn load(key: &str) -> Self {
+        storage()
+            .and_then(|storage| storage.get_item(key).ok())
+            .and_then(|blob| blob)
+            .and_then(|blob| Self::from_blob(&blob))
+            .unwrap_or_default()
+    }
+
+    pub fn store(&self, key: &str) {
+        let stored =
+            storage().map(|storage| storage.set_item(key, &self.to_blob()));
+        if let Some(Err(_)) = stored {
+            warn!("failed to persist ratings");
+        }
+    }
+
+    pub fn player(&self) -> i32 {
+        self.player
+    }
+
+    pub fn bot(&self, difficulty: u8) -> i32 {
+        self.bots
+            .iter()
+            .find(|&&(played, _)| played == difficulty)
+            .map_or_else(
+                || RATING_START + RATING_DIFFICULTY_STEP * difficulty as i32,
+                |&(_, rating)| rating,
+            )
+    }
+
+    /// Rates the player and the bot of `difficulty` by the `outcome` of a
+    /// game between them
+    pub fn record(&mut self, difficulty: u8, outcome: Outcome) {
+        let bot = self.bot(difficulty);
+        let expected = expected(self.player, bot);
+        let change = RATING_K * (outcome.points() - expected) / 1000;
+
+        self.player += change;
+        let played = self.bots.iter().position(|&(d, _)| d == difficulty);
+        match played {
+            Some(i) => self.bots[i].1 -= change,
+            None => self.bots.push((difficulty, bot - change)),
+        }
+    }
+
+    /// The difficulty up to `hardest` whose bot is rated nearest to the
+    /// player, the easier one of two as near
+    pub fn suggest(&self, hardest: u8) -> u8 {
+        let distance = |difficulty| (self.bot(difficulty) - self.player).abs();
+        (0..=hardest).min_by_key(|&d| distance(d)).unwrap_or(0)
+    }
+
+    /// `VERSION` followed by the player's rating and those of the bots, as
+    /// `bot<difficulty>=<rating>`, separated by spaces
+    pub fn to_blob(&self) -> String {
+        let mut blob = format!("{} player={}", VERSION, self.player);
+        for &(difficulty, rating) in &self.bots {
+            blob.push_str(&format!(" bot{}={}", difficulty, rating));
+        }
+        blob
+    }
+
+    /// Reads a blob of `to_blob` of this version or an older one, skipping
+    /// fields it doesn't know or can't parse
+    pub fn from_blob(blob: &str) -> Option<Self> {
+        let mut fields = blob.split_whitespace();
+        let version: u32 = fields.next()?.parse().ok()?;
+        if version == 0 || version > VERSION {
+            return None;
+        }
+
+        let mut ratings = Ratings::new();
+        for field in fields {
+            let mut pair = field.splitn(2, '=');
+            let (name, rating) = match (pair.next(), pair.next()) {
+                (Some(name), Some(value)) => match value.parse::<i32>() {
+                    Ok(rating) => (name, rating),
+                    Err(_) => continue,
+                },
+                _ => continue,
+            };
+
+            if name == "player" {
+                ratings.player = rating;
+            } else if name.starts_with("bot") {
+                if let Ok(difficulty) = name[3..].parse::<u8>() {
+                    ratings.bots.retain(|&(d, _)| d != difficulty);
+                    ratings.bots.push((difficulty, rating));
+                }
+            }
+        }
+
+        Some(ratings)
+    }
+}
+
+impl Default for Ratings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// the score a player rated `rating` is expected to get against one rated
+// `other`, in thousandths
+fn expected(rating: i32, other: i32) -> i32 {
+    let difference = rating - other;
+    let (steps, within) = (difference.abs() / 50, difference.abs() % 50);
+
+    let last = EXPECTED.len() as i32 - 1;
+    let stronger = if steps >= last {
+        EXPECTED[last as usize]
+    } else {
+        let step = steps as usize;
+        let (low, high) = (EXPECTED[step], EXPECTED[step + 1]);
+        low + (high - low) * within / 50
+    };
+
+    if difference < 0 {
+        1000 - stronger
+    } else {
+        stronger
+    }
+}
+
+/// Records the games of the player against the bot of a difficulty in
+/// `Ratings`, by the scores of both once a game is over; games the player
+/// restarted or quit aren't rated, nor demo games (see `AttractMode`)
+pub struct RatedGame {
+    ratings: Rc<RefCell<Ratings>>,
+    difficulty: u8,
+    player: Rc<RefCell<Scoring>>,
+    bot: Rc<RefCell<Scoring>>,
+    // engaged while a demo game plays
+    autopilot: Autopilot,
+    key: Option<&'static str>,
+}
+
+impl RatedGame {
+    pub fn new(
+        ratings: Rc<RefCell<Ratings>>,
+        difficulty: u8,
+        player: Rc<RefCell<Scoring>>,
+        bot: Rc<RefCell<Scoring>>,
+        autopilot: Autopilot,
+    ) -> Self {
+        RatedGame {
+            ratings,
+            difficulty,
+            player,
+            bot,
+            autopilot,
+            key: None,
+        }
+    }
+
+    /// Stores the ratings in `localStorage[key]` after each rated game
+    pub fn persisted(mut self, key: &'static str) -> Self {
+        self.key = Some(key);
+        self
+    }
+}
+
+impl Observer<WorldUpdate, UpdateError> for RatedGame {
+    fn on_update(&mut self, _: &WorldUpdate) {}
+
+    fn on_error(&mut self, err: &UpdateError) {
+        match *err {
+            UpdateError::Restart
+            | UpdateError::Quit
+            | UpdateError::LifeLost => return,
+            _ if self.autopilot.is_engaged() => return,
+            _ => {}
+        }
+
+        let (score, bot_score) =
+            (self.player.borrow().score(), self.bot.borrow().score());
+        let outcome = Outcome::of(score, bot_score);
+        info!("{:?} against bot {}", outcome, self.difficulty);
+        let mut ratings = self.ratings.borrow_mut();
+        ratings.record(self.difficulty, outcome);
+        if let Some(key) = self.key {
+            ratings.store(key);
+        }
+    }
+}
+
+fn storage() -> Option<Storage> {
+    web_sys::window()
+        .and_then(|window| window.local_storage().ok())
+        .and_then(|storage| storage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bot::Strategy;
+    use scoring::Combo;
+    use world::SnakeId;
+
+    #[test]
+    fn test_expected_scores() {
+        assert_eq!(expected(1200, 1200), 500);
+        assert_eq!(expected(1400, 1200), 760);
+        assert_eq!(expected(1200, 1400), 240);
+        assert_eq!(expected(1225, 1200), 535);
+        assert_eq!(expected(3000, 1200), 990);
+    }
+
+    #[test]
+    fn test_wins_move_the_suggestion_up() {
+        let mut ratings = Ratings::new();
+        assert_eq!(ratings.suggest(4), 0);
+
+        for _ in 0..20 {
+            let next = ratings.suggest(4);
+            ratings.record(next, Outcome::Win);
+        }
+        assert!(ratings.player() > RATING_START + RATING_DIFFICULTY_STEP);
+        assert!(ratings.suggest(4) > 0);
+
+        // what the player gains, the bot loses
+        let total: i32 = ratings.bots.iter().map(|&(_, rating)| rating).sum();
+        let unrated: i32 = ratings
+            .bots
+            .iter()
+            .map(|&(d, _)| RATING_START + RATING_DIFFICULTY_STEP * d as i32)
+            .sum();
+        assert_eq!(ratings.player() - RATING_START, unrated - total);
+    }
+
+    #[test]
+    fn test_ratings_round_trip() {
+        let mut ratings = Ratings::new();
+        ratings.record(2, Outcome::Draw);
+        ratings.record(0, Outcome::Loss);
+
+        let blob = ratings.to_blob();
+        assert_eq!(blob, "1 player=1197 bot2=1587 bot0=1216");
+        assert_eq!(Ratings::from_blob(&blob), Some(ratings));
+        assert_eq!(Ratings::from_blob("2 player=1500"), None);
+        let ratings = Ratings::from_blob("1 bot=12 botx=3").unwrap();
+        assert!(ratings.bots.is_empty());
+    }
+
+    #[test]
+    fn test_rated_games_are_the_ones_played_out() {
+        let ratings = Rc::new(RefCell::new(Ratings::new()));
+        let bot = Scoring::new(Combo::default()).with_snake(SnakeId(1));
+        let (player, bot) = (
+            Rc::new(RefCell::new(Scoring::new(Combo::default()))),
+            Rc::new(RefCell::new(bot)),
+        );
+        let autopilot = Autopilot::new(Strategy::Greedy.policy(0));
+        let mut rated = RatedGame::new(
+            ratings.clone(),
+            1,
+            player.clone(),
+            bot.clone(),
+            autopilot.clone(),
+        );
+
+        // the bot ate the only food
+        let eaten = WorldUpdate::FoodEaten {
+            snake: SnakeId(1),
+            at: Default::default(),
+        };
+        player.borrow_mut().on_update(&eaten);
+        bot.borrow_mut().on_update(&eaten);
+        assert_eq!(player.borrow().score(), 0);
+        rated.on_error(&UpdateError::LifeLost);
+        rated.on_error(&UpdateError::Restart);
+        assert_eq!(*ratings.borrow(), Ratings::new());
+
+        autopilot.engage(true);
+        rated.on_error(&UpdateError::CollideBody);
+        assert_eq!(*ratings.borrow(), Ratings::new());
+
+        autopilot.engage(false);
+        rated.on_error(&UpdateError::CollideBody);
+        let mut lost = Ratings::new();
+        lost.record(1, Outcome::Loss);
+        assert_eq!(*ratings.borrow(), lost);
+    }
+}
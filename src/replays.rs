@@ -0,0 +1,170 @@
+use alloc::boxed::Box;
+
+use js_sys::{Function, Promise, Uint8Array};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{
+    IdbDatabase, IdbFactory, IdbObjectStore, IdbRequest, IdbTransactionMode,
+};
+
+use constants::{REPLAYS_DB, REPLAYS_DB_VERSION, REPLAYS_STORE};
+
+/// Replays of `GameHandle::replay` kept in IndexedDB under a name. Each
+/// call opens the database and returns a promise settled once its request
+/// is done; the promises reject in browsers without IndexedDB.
+#[wasm_bindgen]
+pub struct ReplayLibrary {
+    factory: Option<IdbFactory>,
+}
+
+#[wasm_bindgen]
+impl ReplayLibrary {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> ReplayLibrary {
+        ReplayLibrary {
+            factory: web_sys::window()
+                .and_then(|window| window.indexed_db().ok())
+                .and_then(|factory| factory),
+        }
+    }
+
+    /// Resolves to an array of the names stored, in order
+    pub fn list(&self) -> Promise {
+        self.with_store(IdbTransactionMode::Readonly, |store| {
+            store.get_all_keys()
+        })
+    }
+
+    /// Stores `bytes` under `name`, in place of any replay of that name
+    pub fn save(&self, name: &str, bytes: &[u8]) -> Promise {
+        let key = JsValue::from_str(name);
+        let value: JsValue = Uint8Array::from(bytes).into();
+
+        self.with_store(IdbTransactionMode::Readwrite, move |store| {
+            store.put_with_key(&value, &key)
+        })
+    }
+
+    /// Resolves to the bytes stored under `name`, `undefined` if none are
+    pub fn load(&self, name: &str) -> Promise {
+        let key = JsValue::from_str(name);
+
+        self.with_store(IdbTransactionMode::Readonly, move |store| {
+            store.get(&key)
+        })
+    }
+
+    pub fn delete(&self, name: &str) -> Promise {
+        let key = JsValue::from_str(name);
+
+        self.with_store(IdbTransactionMode::Readwrite, move |store| {
+            store.delete(&key)
+        })
+    }
+}
+
+impl ReplayLibrary {
+    // runs `op` on the store once the database is open, resolving to the
+    // result of its request
+    fn with_store<F>(&self, mode: IdbTransactionMode, op: F) -> Promise
+    where
+        F: 'static + FnOnce(&IdbObjectStore) -> Result<IdbRequest, JsValue>,
+    {
+        let factory = match self.factory {
+            Some(ref factory) => factory,
+            None => {
+                let err = js_sys::Error::new("snake: IndexedDB is unavailable");
+                return Promise::reject(&err.into());
+            }
+        };
+        let open = match factory.open_with_u32(REPLAYS_DB, REPLAYS_DB_VERSION)
+        {
+            Ok(open) => open,
+            Err(err) => return Promise::reject(&err),
+        };
+
+        let mut op = Some(op);
+        Promise::new(&mut |resolve, reject| {
+            // the first visit, or one after `REPLAYS_DB_VERSION` went up
+            let on_upgrade = {
+                let open = open.clone();
+                Closure::wrap(Box::new(move || {
+                    let created = open.result().and_then(|db| {
+                        let db: IdbDatabase = db.unchecked_into();
+                        db.create_object_store(REPLAYS_STORE)
+                    });
+                    if created.is_err() {
+                        warn!("failed to create the replay store");
+                    }
+                }) as Box<FnMut()>)
+            };
+
+            let on_open = {
+                let open = open.clone();
+                let reject = reject.clone();
+                let mut op = op.take();
+                Closure::wrap(Box::new(move || {
+                    let request = open.result().and_then(|db| {
+                        let db: IdbDatabase = db.unchecked_into();
+                        let name = REPLAYS_STORE;
+                        let tx = db.transaction_with_str_and_mode(name, mode)?;
+                        let store = tx.object_store(name)?;
+                        match op.take() {
+                            Some(op) => op(&store),
+                            None => Err(JsValue::UNDEFINED),
+                        }
+                    });
+
+                    match request {
+                        Ok(request) => settle(&request, &resolve, &reject),
+                        Err(err) => {
+                            let _ = reject.call1(&JsValue::NULL, &err);
+                        }
+                    }
+                }) as Box<FnMut()>)
+            };
+
+            let upgrade = on_upgrade.as_ref().unchecked_ref();
+            open.set_onupgradeneeded(Some(upgrade));
+            open.set_onsuccess(Some(on_open.as_ref().unchecked_ref()));
+            open.set_onerror(Some(&failed(&reject)));
+            on_upgrade.forget();
+            on_open.forget();
+        })
+    }
+}
+
+impl Default for ReplayLibrary {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// resolves with the result of `request`, or rejects if it fails
+fn settle(request: &IdbRequest, resolve: &Function, reject: &Function) {
+    let on_success = {
+        let request = request.clone();
+        let resolve = resolve.clone();
+        Closure::wrap(Box::new(move || {
+            let result = request.result().unwrap_or(JsValue::UNDEFINED);
+            let _ = resolve.call1(&JsValue::NULL, &result);
+        }) as Box<FnMut()>)
+    };
+
+    request.set_onsuccess(Some(on_success.as_ref().unchecked_ref()));
+    request.set_onerror(Some(&failed(reject)));
+    on_success.forget();
+}
+
+// an error handler rejecting with the same error for any request
+fn failed(reject: &Function) -> Function {
+    let reject = reject.clone();
+    let on_error = Closure::wrap(Box::new(move || {
+        let err = js_sys::Error::new("snake: replay library request failed");
+        let _ = reject.call1(&JsValue::NULL, &err);
+    }) as Box<FnMut()>);
+
+    let handler = on_error.as_ref().unchecked_ref::<Function>().clone();
+    on_error.forget();
+    handler
+}
@@ -0,0 +1,475 @@
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use std::cell::RefCell;
+use std::cmp::min;
+use std::fmt;
+
+use constants::{COMBO_MAX, COMBO_WINDOW, FOOD_POINTS};
+use data::Block;
+use events::{EventBus, GameEvent};
+use system::Observer;
+use world::{SnakeId, TeamId, UpdateError, WorldUpdate, PLAYER};
+
+/// How food eaten turns into points, for modes to score their own way
+pub trait ScoreRule {
+    /// Whether food eaten `ticks` after the previous one keeps the combo
+    /// going
+    fn continues(&self, ticks: u32) -> bool;
+
+    /// Points for a food eaten as the `combo`th in a row, counting from 1,
+    /// by a snake `length` blocks long
+    fn points(&self, combo: u32, length: u32) -> u32;
+}
+
+/// Food is worth `points`, times the combo up to `max`, as long as each
+/// food comes within `window` ticks of the previous one
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Combo {
+    pub window: u32,
+    pub points: u32,
+    pub max: u32,
+}
+
+impl Default for Combo {
+    fn default() -> Self {
+        Combo {
+            window: COMBO_WINDOW,
+            points: FOOD_POINTS,
+            max: COMBO_MAX,
+        }
+    }
+}
+
+impl ScoreRule for Combo {
+    fn continues(&self, ticks: u32) -> bool {
+        ticks <= self.window
+    }
+
+    fn points(&self, combo: u32, _length: u32) -> u32 {
+        self.points * min(combo, self.max)
+    }
+}
+
+/// What a food was worth: `points` in all, `base` of them before any
+/// combo, shown as `+10` or `+10 ×3`
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Gain {
+    pub points: u32,
+    pub base: u32,
+}
+
+impl fmt::Display for Gain {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.base == 0 || self.points <= self.base {
+            return write!(f, "+{}", self.points);
+        }
+        write!(f, "+{} \u{d7}{}", self.base, self.points / self.base)
+    }
+}
+
+/// Observes a `World` and keeps the score of the current game by `rule`,
+/// of the player's snake or another one, optionally announcing combos on
+/// an `EventBus`
+pub struct Scoring {
+    rule: Box<ScoreRule>,
+    snake: SnakeId,
+    score: u32,
+    combo: u32,
+    // ticks since the last food, `None` before the first one
+    since_food: Option<u32>,
+    length: u32,
+    last: Option<Gain>,
+
+    events: Option<Rc<RefCell<EventBus>>>,
+}
+
+impl Scoring {
+    pub fn new<S: ScoreRule + 'static>(rule: S) -> Self {
+        Scoring {
+            rule: Box::new(rule),
+            snake: PLAYER,
+            score: 0,
+            combo: 0,
+            since_food: None,
+            length: 0,
+            last: None,
+            events: None,
+        }
+    }
+
+    /// Publishes `GameEvent::Combo` and `GameEvent::ComboLost` on `events`
+    pub fn with_events(mut self, events: Rc<RefCell<EventBus>>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Counts the points of `snake` rather than the player's, e.g. of a
+    /// bot on the player's `Team`
+    pub fn with_snake(mut self, snake: SnakeId) -> Self {
+        self.snake = snake;
+        self
+    }
+
+    pub fn snake(&self) -> SnakeId {
+        self.snake
+    }
+
+    pub fn score(&self) -> u32 {
+        self.score
+    }
+
+    /// Food eaten in a row so far, 0 once the combo is lost
+    pub fn combo(&self) -> u32 {
+        self.combo
+    }
+
+    /// What the last food of the game was worth, `None` before the first
+    pub fn last_gain(&self) -> Option<Gain> {
+        self.last
+    }
+
+    fn publish(&self, event: GameEvent) {
+        if let Some(ref events) = self.events {
+            events.borrow_mut().publish(event);
+        }
+    }
+
+    fn eat(&mut self) {
+        self.combo = match self.since_food {
+            Some(ticks) if self.rule.continues(ticks) => self.combo + 1,
+            _ => 1,
+        };
+        self.since_food = Some(0);
+
+        let points = self.rule.points(self.combo, self.length);
+        self.score += points;
+        self.last = Some(Gain {
+            points,
+            base: self.rule.points(1, self.length),
+        });
+
+        if self.combo > 1 {
+            let multiplier = self.combo;
+            self.publish(GameEvent::Combo { multiplier, points });
+        }
+    }
+
+    fn tick(&mut self) {
+        let ticks = match self.since_food {
+            Some(ticks) => ticks + 1,
+            None => return,
+        };
+        self.since_food = Some(ticks);
+
+        if self.combo > 0 && !self.rule.continues(ticks) {
+            if self.combo > 1 {
+                self.publish(GameEvent::ComboLost);
+            }
+            self.combo = 0;
+        }
+    }
+}
+
+impl Observer<WorldUpdate, UpdateError> for Scoring {
+    fn on_init(&mut self, update: &WorldUpdate) {
+        match *update {
+            WorldUpdate::LevelLoaded { .. } => {
+                self.score = 0;
+                self.combo = 0;
+                self.since_food = None;
+                self.length = 0;
+                self.last = None;
+            }
+            // the score carries on with the next life, the combo doesn't
+            WorldUpdate::Respawned { .. } => {
+                self.combo = 0;
+                self.since_food = None;
+                self.length = 0;
+            }
+            WorldUpdate::SetBlock {
+                block: Block::Snake(_, snake),
+                ..
+            } if snake == self.snake => {
+                self.length += 1;
+            }
+            _ => {}
+        }
+    }
+
+    fn on_update(&mut self, update: &WorldUpdate) {
+        match *update {
+            WorldUpdate::HeadMoved { snake, .. }
+            | WorldUpdate::Bend { snake, .. }
+                if snake == self.snake =>
+            {
+                self.length += 1;
+                self.tick();
+            }
+            WorldUpdate::TailRetracted { snake, .. } if snake == self.snake => {
+                self.length = self.length.saturating_sub(1);
+            }
+            WorldUpdate::FoodEaten { snake, .. } if snake == self.snake => {
+                self.eat()
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Snakes that play together: they pool the points their `Scoring`s count
+/// and lose together as soon as one of them dies
+pub struct Team {
+    id: TeamId,
+    members: Vec<(SnakeId, Rc<RefCell<Scoring>>)>,
+    lost: bool,
+
+    events: Option<Rc<RefCell<EventBus>>>,
+}
+
+impl Team {
+    pub fn new(id: TeamId) -> Self {
+        Team {
+            id,
+            members: Vec::new(),
+            lost: false,
+            events: None,
+        }
+    }
+
+    /// Publishes `GameEvent::TeamLost` on `events`
+    pub fn with_events(mut self, events: Rc<RefCell<EventBus>>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Adds `snake` to the team, its points counted by `scoring`
+    pub fn join(&mut self, snake: SnakeId, scoring: Rc<RefCell<Scoring>>) {
+        self.members.retain(|&(member, _)| member != snake);
+        self.members.push((snake, scoring));
+    }
+
+    pub fn id(&self) -> TeamId {
+        self.id
+    }
+
+    /// The snakes of the team, in the order they joined
+    pub fn members(&self) -> Vec<SnakeId> {
+        self.members.iter().map(|&(snake, _)| snake).collect()
+    }
+
+    /// Whether `snake` plays on the team
+    pub fn has(&self, snake: SnakeId) -> bool {
+        self.members.iter().any(|&(member, _)| member == snake)
+    }
+
+    /// Points of every snake of the team together
+    pub fn score(&self) -> u32 {
+        self.members
+            .iter()
+            .map(|&(_, ref scoring)| scoring.borrow().score())
+            .sum()
+    }
+
+    /// Whether a snake of the team died in the current game
+    pub fn is_lost(&self) -> bool {
+        self.lost
+    }
+
+    /// An observer of the world `snake` plays in, which loses the game for
+    /// all of `team` when `snake` dies: the player's with the world, any
+    /// other one with its `WorldUpdate::SnakeDied`
+    pub fn watch(team: &Rc<RefCell<Team>>, snake: SnakeId) -> TeamWatch {
+        TeamWatch {
+            team: team.clone(),
+            snake,
+        }
+    }
+
+    fn lose(&mut self) {
+        if self.lost {
+            return;
+        }
+        self.lost = true;
+
+        let (team, score) = (self.id, self.score());
+        if let Some(ref events) = self.events {
+            events.borrow_mut().publish(GameEvent::TeamLost { team, score });
+        }
+    }
+}
+
+/// Observes the world of one snake of a `Team`, see `Team::watch`
+pub struct TeamWatch {
+    team: Rc<RefCell<Team>>,
+    snake: SnakeId,
+}
+
+impl TeamWatch {
+    pub fn snake(&self) -> SnakeId {
+        self.snake
+    }
+}
+
+impl Observer<WorldUpdate, UpdateError> for TeamWatch {
+    fn on_init(&mut self, update: &WorldUpdate) {
+        // a new game for one of the team is one for all of it
+        if let WorldUpdate::LevelLoaded { .. } = *update {
+            self.team.borrow_mut().lost = false;
+        }
+    }
+
+    fn on_update(&mut self, update: &WorldUpdate) {
+        match *update {
+            WorldUpdate::SnakeDied { snake, .. } if snake == self.snake => {
+                self.team.borrow_mut().lose()
+            }
+            _ => {}
+        }
+    }
+
+    fn on_error(&mut self, err: &UpdateError) {
+        match *err {
+            _ if self.snake != PLAYER => {}
+            UpdateError::Restart
+            | UpdateError::Quit
+            | UpdateError::LifeLost => {}
+            _ => self.team.borrow_mut().lose(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data::Direction;
+
+    #[test]
+    fn test_quick_food_builds_a_combo() {
+        let events = Rc::new(RefCell::new(EventBus::new()));
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        {
+            let seen = seen.clone();
+            events.borrow_mut().subscribe(Box::new(move |e: &GameEvent| {
+                seen.borrow_mut().push(*e);
+            }));
+        }
+
+        let rule = Combo {
+            window: 2,
+            points: 10,
+            max: 3,
+        };
+        let mut scoring = Scoring::new(rule).with_events(events);
+        let moved = WorldUpdate::TailRetracted {
+            snake: PLAYER,
+            at: Default::default(),
+            dir: Direction::East,
+        };
+        let head = WorldUpdate::HeadMoved {
+            snake: PLAYER,
+            at: Default::default(),
+            neck: Default::default(),
+            dir: Direction::East,
+        };
+        let eaten = WorldUpdate::FoodEaten {
+            snake: PLAYER,
+            at: Default::default(),
+        };
+
+        scoring.on_update(&eaten);
+        scoring.on_update(&head);
+        scoring.on_update(&eaten);
+        scoring.on_update(&head);
+        scoring.on_update(&eaten);
+        scoring.on_update(&eaten);
+        assert_eq!(scoring.combo(), 4);
+        // the multiplier stops at 3
+        assert_eq!(scoring.score(), 10 + 20 + 30 + 30);
+        assert_eq!(format!("{}", scoring.last_gain().unwrap()), "+10 \u{d7}3");
+
+        for _ in 0..3 {
+            scoring.on_update(&head);
+            scoring.on_update(&moved);
+        }
+        assert_eq!(scoring.combo(), 0);
+        assert_eq!(
+            *seen.borrow(),
+            vec![
+                GameEvent::Combo {
+                    multiplier: 2,
+                    points: 20,
+                },
+                GameEvent::Combo {
+                    multiplier: 3,
+                    points: 30,
+                },
+                GameEvent::Combo {
+                    multiplier: 4,
+                    points: 30,
+                },
+                GameEvent::ComboLost,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_team_pools_score_and_loses_together() {
+        let events = Rc::new(RefCell::new(EventBus::new()));
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        {
+            let seen = seen.clone();
+            events.borrow_mut().subscribe(Box::new(move |e: &GameEvent| {
+                seen.borrow_mut().push(*e);
+            }));
+        }
+
+        let bot = Scoring::new(Combo::default()).with_snake(SnakeId(1));
+        let (player, bot) = (
+            Rc::new(RefCell::new(Scoring::new(Combo::default()))),
+            Rc::new(RefCell::new(bot)),
+        );
+        let team = Team::new(TeamId(1)).with_events(events);
+        let team = Rc::new(RefCell::new(team));
+        team.borrow_mut().join(PLAYER, player.clone());
+        team.borrow_mut().join(SnakeId(1), bot.clone());
+
+        // each counts the food of its own snake
+        let eaten = |snake| WorldUpdate::FoodEaten {
+            snake,
+            at: Default::default(),
+        };
+        for &snake in &[PLAYER, SnakeId(1), SnakeId(2)] {
+            player.borrow_mut().on_update(&eaten(snake));
+            bot.borrow_mut().on_update(&eaten(snake));
+        }
+        assert_eq!(player.borrow().score(), FOOD_POINTS);
+        assert_eq!(team.borrow().score(), 2 * FOOD_POINTS);
+        assert_eq!(team.borrow().members(), vec![PLAYER, SnakeId(1)]);
+        assert!(team.borrow().has(SnakeId(1)));
+        assert!(!team.borrow().has(SnakeId(2)));
+
+        let (mut left, mut right) =
+            (Team::watch(&team, PLAYER), Team::watch(&team, SnakeId(1)));
+        left.on_error(&UpdateError::LifeLost);
+        // the world's errors are the player's
+        right.on_error(&UpdateError::CollideBody);
+        let died = |snake| WorldUpdate::SnakeDied {
+            snake,
+            reason: UpdateError::CollideBody,
+        };
+        right.on_update(&died(SnakeId(2)));
+        assert!(!team.borrow().is_lost());
+
+        right.on_update(&died(SnakeId(1)));
+        left.on_error(&UpdateError::CollideBody);
+        assert!(team.borrow().is_lost());
+        assert_eq!(
+            *seen.borrow(),
+            vec![GameEvent::TeamLost {
+                team: TeamId(1),
+                score: 2 * FOOD_POINTS,
+            }]
+        );
+    }
+}
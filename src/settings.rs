@@ -0,0 +1,152 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use web_sys::Storage;
+
+use formats::SETTINGS as VERSION;
+use i18n::Locale;
+use system::MotionPolicy;
+use theme::Palette;
+
+/// Settings a player picked, remembered across visits. Each is `None`
+/// until the player picks it, so that the page's default applies.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Settings {
+    pub palette: Option<Palette>,
+    pub pixel_art: Option<bool>,
+    pub shapes: Option<bool>,
+    pub crt: Option<bool>,
+    pub motion: Option<MotionPolicy>,
+    pub locale: Option<Locale>,
+}
+
+impl Settings {
+    /// The settings stored in `localStorage[key]`, none when storage is
+    /// unavailable or holds a blob this version can't read
+    pub fn load(key: &str) -> Self {
+        storage()
+            .and_then(|storage| storage.get_item(key).ok())
+            .and_then(|blob| blob)
+            .and_then(|blob| Self::from_blob(&blob))
+            .unwrap_or_default()
+    }
+
+    pub fn store(&self, key: &str) {
+        let stored =
+            storage().map(|storage| storage.set_item(key, &self.to_blob()));
+        if let Some(Err(_)) = stored {
+            warn!("failed to persist settings");
+        }
+    }
+
+    /// `VERSION` followed by `name=value` pairs of the settings picked,
+    /// separated by spaces
+    pub fn to_blob(&self) -> String {
+        let mut fields = Vec::new();
+        if let Some(palette) = self.palette {
+            fields.push(format!("palette={}", palette as u8));
+        }
+        if let Some(pixel_art) = self.pixel_art {
+            fields.push(format!("pixel_art={}", pixel_art));
+        }
+        if let Some(shapes) = self.shapes {
+            fields.push(format!("shapes={}", shapes));
+        }
+        if let Some(crt) = self.crt {
+            fields.push(format!("crt={}", crt));
+        }
+        if let Some(motion) = self.motion {
+            fields.push(format!("reduced_motion={}", motion.is_reduced()));
+        }
+        if let Some(locale) = self.locale {
+            fields.push(format!("locale={}", locale as u8));
+        }
+
+        let mut blob = format!("{}", VERSION);
+        for field in fields {
+            blob.push(' ');
+            blob.push_str(&field);
+        }
+        blob
+    }
+
+    /// Reads a blob of `to_blob` of this version or an older one, skipping
+    /// fields it doesn't know or can't parse
+    pub fn from_blob(blob: &str) -> Option<Self> {
+        let mut fields = blob.split_whitespace();
+        let version: u32 = fields.next()?.parse().ok()?;
+        if version == 0 || version > VERSION {
+            return None;
+        }
+
+        let mut settings = Settings::default();
+        for field in fields {
+            let mut pair = field.splitn(2, '=');
+            let (name, value) = match (pair.next(), pair.next()) {
+                (Some(name), Some(value)) => (name, value),
+                _ => continue,
+            };
+
+            let flag = value.parse::<bool>().ok();
+            let number = value.parse::<u8>().ok();
+            match name {
+                "palette" => {
+                    settings.palette = number.and_then(Palette::from_u8)
+                }
+                "pixel_art" => settings.pixel_art = flag,
+                "shapes" => settings.shapes = flag,
+                "crt" => settings.crt = flag,
+                "reduced_motion" => {
+                    settings.motion = flag.map(|reduced| {
+                        if reduced {
+                            MotionPolicy::Reduced
+                        } else {
+                            MotionPolicy::Full
+                        }
+                    })
+                }
+                "locale" => {
+                    settings.locale = number.and_then(Locale::from_u8)
+                }
+                _ => {}
+            }
+        }
+
+        Some(settings)
+    }
+}
+
+fn storage() -> Option<Storage> {
+    web_sys::window()
+        .and_then(|window| window.local_storage().ok())
+        .and_then(|storage| storage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_round_trip() {
+        let settings = Settings {
+            palette: Some(Palette::ColorBlind),
+            crt: Some(true),
+            motion: Some(MotionPolicy::Reduced),
+            ..Settings::default()
+        };
+
+        let blob = settings.to_blob();
+        assert_eq!(blob, "1 palette=2 crt=true reduced_motion=true");
+        assert_eq!(Settings::from_blob(&blob), Some(settings));
+    }
+
+    #[test]
+    fn test_unknown_fields_and_versions() {
+        let settings = Settings::from_blob("1 sound=off shapes=true").unwrap();
+        assert_eq!(settings.shapes, Some(true));
+        assert_eq!(settings.palette, None);
+
+        assert_eq!(Settings::from_blob("2 shapes=true"), None);
+        assert_eq!(Settings::from_blob("shapes=true"), None);
+    }
+}
@@ -0,0 +1,409 @@
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use std::cell::RefCell;
+
+use wasm_bindgen::prelude::*;
+
+use data::{Block, Command, Coordinate, Grid, SmallNat};
+use system::{Observer, Stateful, Tick};
+use world::{UpdateError, WorldUpdate, PLAYER};
+
+/// Ways a game can end, as counted by `Stats::deaths`
+#[wasm_bindgen]
+#[cfg_attr(feature = "telemetry-json", derive(Serialize))]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum DeathReason {
+    OutOfBound = 0,
+    CollideBody = 1,
+    CollideObstacle = 2,
+    TimeUp = 3,
+}
+
+const DEATH_REASONS: usize = 4;
+
+impl DeathReason {
+    pub fn from_error(err: UpdateError) -> Option<Self> {
+        match err {
+            UpdateError::OutOfBound => Some(DeathReason::OutOfBound),
+            UpdateError::CollideBody => Some(DeathReason::CollideBody),
+            UpdateError::CollideObstacle => {
+                Some(DeathReason::CollideObstacle)
+            }
+            UpdateError::TimeUp => Some(DeathReason::TimeUp),
+            _ => None,
+        }
+    }
+}
+
+/// Per session statistics, accumulated by observing a `World` across games,
+/// and those of the current game, see `Stats::game`
+pub struct Stats {
+    games: u32,
+    finished: u32,
+    total_food: u32,
+    total_length: u32,
+    max_length: u32,
+    deaths: [u32; DEATH_REASONS],
+
+    // number of times the head entered each tile
+    heatmap: Grid<u32>,
+    max_visits: u32,
+    overlay: bool,
+
+    game: Game,
+}
+
+/// How a single game was played, from its first tick to what ended it; it
+/// starts over with every game, lives lost don't end one. `Counted` counts
+/// its ticks and inputs, `Stats` the rest.
+#[derive(Clone)]
+pub struct Game {
+    ticks: u32,
+    inputs: u32,
+    turns: u32,
+    food: u32,
+    length: u32,
+    death: Option<DeathReason>,
+    over: bool,
+    // number of times the head entered each tile this game
+    heatmap: Grid<u32>,
+}
+
+impl Game {
+    fn new(width: SmallNat, height: SmallNat) -> Self {
+        Game {
+            ticks: 0,
+            inputs: 0,
+            turns: 0,
+            food: 0,
+            length: 0,
+            death: None,
+            over: false,
+            heatmap: Grid::filled(width, height, 0),
+        }
+    }
+
+    /// Ticks the game lasted, so far if it goes on
+    pub fn ticks(&self) -> u32 {
+        self.ticks
+    }
+
+    /// Commands the game was given, whether or not they turned the snake
+    pub fn inputs(&self) -> u32 {
+        self.inputs
+    }
+
+    pub fn turns(&self) -> u32 {
+        self.turns
+    }
+
+    pub fn food(&self) -> u32 {
+        self.food
+    }
+
+    pub fn length(&self) -> u32 {
+        self.length
+    }
+
+    /// What ended the game, none while it goes on or if it was restarted
+    pub fn death(&self) -> Option<DeathReason> {
+        self.death
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.over
+    }
+
+    /// Tiles of the board
+    pub fn tiles(&self) -> u32 {
+        self.heatmap.width() as u32 * self.heatmap.height() as u32
+    }
+
+    pub fn heatmap_size(&self) -> (SmallNat, SmallNat) {
+        (self.heatmap.width(), self.heatmap.height())
+    }
+
+    /// The tiles the head entered and the one it entered most, with how
+    /// many times
+    pub fn hottest(&self) -> (u32, Option<(Coordinate, u32)>) {
+        let visited = self.heatmap.iter().filter(|&(_, &n)| n > 0).count();
+        let hottest = self
+            .heatmap
+            .iter()
+            .filter(|&(_, &n)| n > 0)
+            .max_by_key(|&(_, &n)| n)
+            .map(|(at, &n)| (at, n));
+        (visited as u32, hottest)
+    }
+}
+
+impl Stats {
+    pub fn new() -> Self {
+        Stats {
+            games: 0,
+            finished: 0,
+            total_food: 0,
+            total_length: 0,
+            max_length: 0,
+            deaths: [0; DEATH_REASONS],
+            heatmap: Grid::filled(1, 1, 0),
+            max_visits: 0,
+            overlay: false,
+            game: Game::new(1, 1),
+        }
+    }
+
+    /// The current game, or the last one once it is over
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    pub fn games(&self) -> u32 {
+        self.games
+    }
+
+    pub fn total_food(&self) -> u32 {
+        self.total_food
+    }
+
+    /// Average final length of the games that have ended
+    pub fn average_length(&self) -> f64 {
+        if self.finished == 0 {
+            0.0
+        } else {
+            self.total_length as f64 / self.finished as f64
+        }
+    }
+
+    pub fn max_length(&self) -> u32 {
+        self.max_length
+    }
+
+    pub fn deaths(&self, reason: DeathReason) -> u32 {
+        self.deaths[reason as usize]
+    }
+
+    pub fn visits(&self, at: Coordinate) -> u32 {
+        self.heatmap.get(at).cloned().unwrap_or(0)
+    }
+
+    /// Visits of a tile relative to the most visited one
+    pub fn heat(&self, at: Coordinate) -> f64 {
+        if self.max_visits == 0 {
+            0.0
+        } else {
+            self.visits(at) as f64 / self.max_visits as f64
+        }
+    }
+
+    /// Row major visit counts
+    pub fn heatmap(&self) -> Vec<u32> {
+        self.heatmap.iter().map(|(_, &visits)| visits).collect()
+    }
+
+    pub fn heatmap_size(&self) -> (u32, u32) {
+        (self.heatmap.width() as u32, self.heatmap.height() as u32)
+    }
+
+    /// Whether renderers should overlay the heatmap on the game over screen
+    pub fn overlay(&self) -> bool {
+        self.overlay
+    }
+
+    pub fn set_overlay(&mut self, overlay: bool) {
+        self.overlay = overlay;
+    }
+
+    fn visit(&mut self, at: Coordinate) {
+        if let Some(visits) = self.heatmap.get_mut(at) {
+            *visits += 1;
+            if *visits > self.max_visits {
+                self.max_visits = *visits;
+            }
+        }
+        if let Some(visits) = self.game.heatmap.get_mut(at) {
+            *visits += 1;
+        }
+    }
+}
+
+impl Observer<WorldUpdate, UpdateError> for Stats {
+    fn on_init(&mut self, update: &WorldUpdate) {
+        match *update {
+            WorldUpdate::LevelLoaded { width, height } => {
+                self.games += 1;
+                self.game = Game::new(width, height);
+
+                // visits carry over between games on the same board
+                if (width, height)
+                    != (self.heatmap.width(), self.heatmap.height())
+                {
+                    self.heatmap = Grid::filled(width, height, 0);
+                    self.max_visits = 0;
+                }
+            }
+            // a respawn carries on with the same game
+            WorldUpdate::Respawned { .. } => self.game.length = 0,
+            WorldUpdate::SetBlock {
+                block: Block::Snake(_, PLAYER),
+                ..
+            } => {
+                self.game.length += 1;
+            }
+            _ => {}
+        }
+    }
+
+    fn on_update(&mut self, update: &WorldUpdate) {
+        match *update {
+            WorldUpdate::SetBlock {
+                block: Block::Snake(_, PLAYER),
+                at,
+            }
+            | WorldUpdate::HeadMoved { snake: PLAYER, at, .. } => {
+                self.game.length += 1;
+                self.visit(at);
+            }
+            WorldUpdate::Bend { snake: PLAYER, at, .. } => {
+                self.game.length += 1;
+                self.game.turns += 1;
+                self.visit(at);
+            }
+            WorldUpdate::FoodEaten { snake: PLAYER, .. } => {
+                self.total_food += 1;
+                self.game.food += 1;
+            }
+            WorldUpdate::TailRetracted { snake: PLAYER, .. } => {
+                self.game.length = self.game.length.saturating_sub(1);
+            }
+            _ => {}
+        }
+    }
+
+    fn on_error(&mut self, err: &UpdateError) {
+        // the game goes on
+        if *err == UpdateError::LifeLost || self.game.over {
+            return;
+        }
+        self.game.over = true;
+        // or ends without being played out
+        if *err == UpdateError::Restart || *err == UpdateError::Quit {
+            return;
+        }
+
+        self.game.death = DeathReason::from_error(*err);
+        if let Some(reason) = self.game.death {
+            self.deaths[reason as usize] += 1;
+        }
+
+        let length = self.game.length;
+        self.finished += 1;
+        self.total_length += length;
+        if length > self.max_length {
+            self.max_length = length;
+        }
+    }
+}
+
+/// Counts the ticks `model` is stepped and the commands it is given into
+/// the current game of a shared `Stats`, until the game it observes is over
+pub struct Counted<M> {
+    model: M,
+    stats: Rc<RefCell<Stats>>,
+}
+
+impl<M> Counted<M> {
+    pub fn new(model: M, stats: Rc<RefCell<Stats>>) -> Self {
+        Counted { model, stats }
+    }
+}
+
+impl<'m, M> Stateful<'m> for Counted<M>
+where
+    M: Stateful<'m, Cmd = Command>,
+{
+    type Cmd = Command;
+    type Init = M::Init;
+    type Update = M::Update;
+    type Error = M::Error;
+
+    fn initialize(&'m mut self) -> Self::Init {
+        self.model.initialize()
+    }
+
+    #[inline]
+    fn step(
+        &mut self,
+        cmd: Option<Self::Cmd>,
+        tick: Tick,
+    ) -> Result<Option<Self::Update>, Self::Error> {
+        {
+            let game = &mut self.stats.borrow_mut().game;
+            if !game.over {
+                game.ticks += 1;
+                game.inputs += cmd.is_some() as u32;
+            }
+        }
+        self.model.step(cmd, tick)
+    }
+
+    fn tear_down(&mut self) {
+        self.model.tear_down();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::SmallRng;
+
+    use super::*;
+    use data::Bounding;
+    use world::{PerChange, World};
+
+    #[test]
+    fn test_stats_count_food_and_deaths() {
+        let world: World<SmallRng, Bounding> = World::from_ascii(indoc!(
+            "
+            ..*..
+            ..^..
+            ..^.."
+        ));
+
+        let stats = Rc::new(RefCell::new(Stats::new()));
+        let mut observed = world.observe(PerChange(stats.clone()));
+
+        for _ in observed.initialize() {}
+        while observed.step(None, 0).is_ok() {}
+
+        let stats = stats.borrow();
+
+        assert_eq!(stats.games(), 1);
+        assert_eq!(stats.total_food(), 1);
+        assert_eq!(stats.deaths(DeathReason::OutOfBound), 1);
+        assert_eq!(stats.max_length(), 3);
+        assert_eq!(stats.average_length(), 3.0);
+        assert_eq!(stats.visits(Coordinate { x: 2, y: 0 }), 1);
+        assert_eq!(stats.heat(Coordinate { x: 2, y: 0 }), 1.0);
+        assert_eq!(stats.heat(Coordinate { x: 0, y: 0 }), 0.0);
+    }
+
+    #[test]
+    fn test_restarted_games_are_not_counted() {
+        let world: World<SmallRng, Bounding> = World::from_ascii(indoc!(
+            "
+            .....
+            ..^..
+            ..^.."
+        ));
+
+        let stats = Rc::new(RefCell::new(Stats::new()));
+        let mut observed = world.observe(PerChange(stats.clone()));
+
+        for _ in observed.initialize() {}
+        let restart = Some(Command::Restart);
+        assert!(observed.step(restart, 0).is_err());
+
+        assert_eq!(stats.borrow().games(), 0);
+        assert_eq!(stats.borrow().average_length(), 0.0);
+    }
+}
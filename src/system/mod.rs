@@ -1,7 +0,0 @@
-mod input_buffer;
-mod render;
-mod state;
-
-pub use self::input_buffer::InputDblBuffer;
-pub use self::render::*;
-pub use self::state::*;
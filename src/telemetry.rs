@@ -0,0 +1,331 @@
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+use std::cell::RefCell;
+
+#[cfg(feature = "telemetry-json")]
+use serde_json;
+
+use data::SmallNat;
+use events::{EventBus, GameEvent};
+use i18n::{Locale, Text};
+use stats::{DeathReason, Stats};
+use system::Observer;
+use world::{UpdateError, WorldUpdate};
+
+/// The current game of a shared `Stats` timed, for
+/// `GameHandle::export_telemetry` and the game over screen. It only keeps
+/// the clock; it has to observe the world after the `Stats` it reads, so
+/// that the game is counted in full by the time it ends.
+pub struct Telemetry {
+    stats: Rc<RefCell<Stats>>,
+    // milliseconds, of whatever origin
+    clock: fn() -> f64,
+    started: f64,
+    ended: Option<f64>,
+
+    events: Option<Rc<RefCell<EventBus>>>,
+}
+
+/// How a game that ended went, as `GameEvent::GameOver` publishes it and
+/// the game over screen and screen readers put it
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct GameSummary {
+    // milliseconds from the first tick to the crash
+    pub duration: u32,
+    pub ticks: u32,
+    pub length: u32,
+    pub food: u32,
+    pub cause: DeathReason,
+    // of the tiles of the board the head entered, rounded down
+    pub coverage_percent: u32,
+}
+
+impl GameSummary {
+    /// The summary as lines of text in `locale`, the first a title
+    pub fn lines(&self, locale: Locale) -> Vec<String> {
+        let text = |text| locale.text(text);
+        let seconds = self.duration / 1000;
+        let (minutes, seconds) = (seconds / 60, seconds % 60);
+        let cause = match self.cause {
+            DeathReason::OutOfBound => Text::OutOfBound,
+            DeathReason::CollideBody => Text::CollideBody,
+            DeathReason::CollideObstacle => Text::CollideObstacle,
+            DeathReason::TimeUp => Text::TimeUp,
+        };
+
+        vec![
+            text(Text::GameOver).into(),
+            text(cause).into(),
+            format!("{} {}:{:02}", text(Text::Time), minutes, seconds),
+            format!("{} {}", text(Text::Length), self.length),
+            format!("{} {}", text(Text::FoodEaten), self.food),
+            format!("{} {}%", text(Text::Coverage), self.coverage_percent),
+        ]
+    }
+}
+
+/// How a game went, as `Telemetry::to_json` puts it; `death` is none until
+/// it ended in a crash
+#[cfg_attr(feature = "telemetry-json", derive(Serialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub ticks: u32,
+    pub inputs: u32,
+    pub turns: u32,
+    pub turns_per_minute: f64,
+    pub food: u32,
+    pub death: Option<DeathReason>,
+    pub heatmap: HeatmapReport,
+}
+
+/// The tiles the head entered in a `Report`, `hottest` none until it moved
+#[cfg_attr(feature = "telemetry-json", derive(Serialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeatmapReport {
+    pub width: SmallNat,
+    pub height: SmallNat,
+    pub visited: u32,
+    // of the tiles of the board
+    pub coverage: f64,
+    pub hottest: Option<Hottest>,
+}
+
+#[cfg_attr(feature = "telemetry-json", derive(Serialize))]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Hottest {
+    pub x: SmallNat,
+    pub y: SmallNat,
+    pub visits: u32,
+}
+
+impl Telemetry {
+    pub fn new(stats: Rc<RefCell<Stats>>, clock: fn() -> f64) -> Self {
+        Telemetry {
+            stats,
+            clock,
+            started: clock(),
+            ended: None,
+            events: None,
+        }
+    }
+
+    /// Publishes `GameEvent::GameOver` on `events` when a game ends
+    pub fn with_events(mut self, events: Rc<RefCell<EventBus>>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    pub fn turns_per_minute(&self) -> f64 {
+        let ended = self.ended.unwrap_or_else(self.clock);
+        let minutes = (ended - self.started) / 60_000.0;
+        if minutes > 0.0 {
+            self.stats.borrow().game().turns() as f64 / minutes
+        } else {
+            0.0
+        }
+    }
+
+    /// How the game went, none until it ended in a crash
+    pub fn summary(&self) -> Option<GameSummary> {
+        let stats = self.stats.borrow();
+        let game = stats.game();
+        let (ended, cause) = match (self.ended, game.death()) {
+            (Some(ended), Some(cause)) => (ended, cause),
+            _ => return None,
+        };
+
+        let (visited, _) = game.hottest();
+        Some(GameSummary {
+            duration: (ended - self.started).max(0.0) as u32,
+            ticks: game.ticks(),
+            length: game.length(),
+            food: game.food(),
+            cause,
+            coverage_percent: 100 * visited / game.tiles().max(1),
+        })
+    }
+
+    /// How the game went so far
+    pub fn report(&self) -> Report {
+        let turns_per_minute = self.turns_per_minute();
+        let stats = self.stats.borrow();
+        let game = stats.game();
+        let (width, height) = game.heatmap_size();
+        let (visited, hottest) = game.hottest();
+
+        Report {
+            ticks: game.ticks(),
+            inputs: game.inputs(),
+            turns: game.turns(),
+            turns_per_minute,
+            food: game.food(),
+            death: game.death(),
+            heatmap: HeatmapReport {
+                width,
+                height,
+                visited,
+                coverage: visited as f64 / game.tiles().max(1) as f64,
+                hottest: hottest.map(|(at, visits)| Hottest {
+                    x: at.x,
+                    y: at.y,
+                    visits,
+                }),
+            },
+        }
+    }
+
+    /// `report` as json, with `death` the name of a `DeathReason`
+    #[cfg(feature = "telemetry-json")]
+    pub fn to_json(&self) -> String {
+        // nothing in a `Report` fails to serialize
+        serde_json::to_string(&self.report()).unwrap_or_default()
+    }
+}
+
+impl Observer<WorldUpdate, UpdateError> for Telemetry {
+    fn on_init(&mut self, update: &WorldUpdate) {
+        // a respawn carries on with the same game
+        if let WorldUpdate::LevelLoaded { .. } = *update {
+            self.started = (self.clock)();
+            self.ended = None;
+        }
+    }
+
+    fn on_update(&mut self, _update: &WorldUpdate) {}
+
+    fn on_error(&mut self, err: &UpdateError) {
+        if *err == UpdateError::LifeLost || self.ended.is_some() {
+            return;
+        }
+
+        self.ended = Some((self.clock)());
+
+        let summary = self.summary();
+        if let (Some(summary), Some(events)) = (summary, self.events.as_ref()) {
+            events.borrow_mut().publish(GameEvent::GameOver(summary));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::SmallRng;
+
+    use super::*;
+    use data::{Bounding, Command, Direction};
+    use stats::Counted;
+    use system::Stateful;
+    use world::{PerChange, World};
+
+    fn clock() -> f64 {
+        0.0
+    }
+
+    #[test]
+    fn test_telemetry_of_a_game() {
+        let world: World<SmallRng, Bounding> = World::from_ascii(indoc!(
+            "
+            .....
+            ..^..
+            ..^.."
+        ));
+
+        let stats = Rc::new(RefCell::new(Stats::new()));
+        let telemetry = Telemetry::new(stats.clone(), clock);
+        let telemetry = Rc::new(RefCell::new(telemetry));
+        let mut observed = Counted::new(world, stats.clone())
+            .observe(PerChange(stats.clone()))
+            .observe(PerChange(telemetry.clone()));
+
+        for _ in observed.initialize() {}
+        let mut cmd = Some(Command::Turn(Direction::East));
+        while observed.step(cmd.take(), 0).is_ok() {}
+
+        let report = telemetry.borrow().report();
+        assert_eq!(report.inputs, 1);
+        assert_eq!(report.turns, 1);
+        assert_eq!(report.death, Some(DeathReason::OutOfBound));
+        assert!(report.ticks >= 2);
+        // a stopped clock, no time went by
+        assert_eq!(report.turns_per_minute, 0.0);
+
+        // the body it started with, then (3, 1) and (4, 1)
+        assert_eq!((report.heatmap.width, report.heatmap.height), (5, 3));
+        assert_eq!(report.heatmap.visited, 4);
+        let hottest = report.heatmap.hottest.map(|hottest| hottest.visits);
+        assert_eq!(hottest, Some(1));
+    }
+
+    #[cfg(feature = "telemetry-json")]
+    #[test]
+    fn test_telemetry_as_json() {
+        let world: World<SmallRng, Bounding> = World::from_ascii(indoc!(
+            "
+            .....
+            ..^..
+            ..^.."
+        ));
+
+        let stats = Rc::new(RefCell::new(Stats::new()));
+        let telemetry = Telemetry::new(stats.clone(), clock);
+        let telemetry = Rc::new(RefCell::new(telemetry));
+        let mut observed = Counted::new(world, stats.clone())
+            .observe(PerChange(stats.clone()))
+            .observe(PerChange(telemetry.clone()));
+
+        for _ in observed.initialize() {}
+        let mut cmd = Some(Command::Turn(Direction::East));
+        while observed.step(cmd.take(), 0).is_ok() {}
+
+        let json = telemetry.borrow().to_json();
+        assert!(json.starts_with(r#"{"ticks":"#));
+        assert!(json.contains(r#""inputs":1,"turns":1,"#));
+        assert!(json.contains(r#""death":"OutOfBound""#));
+        assert!(json.contains(r#""width":5,"height":3,"visited":4,"#));
+    }
+
+    #[test]
+    fn test_game_over_is_summed_up() {
+        let world: World<SmallRng, Bounding> = World::from_ascii(indoc!(
+            "
+            .....
+            ..^..
+            ..^.."
+        ));
+
+        let events = Rc::new(RefCell::new(EventBus::new()));
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        {
+            let seen = seen.clone();
+            events.borrow_mut().subscribe(Box::new(move |e: &GameEvent| {
+                seen.borrow_mut().push(*e);
+            }));
+        }
+        let stats = Rc::new(RefCell::new(Stats::new()));
+        let telemetry = Telemetry::new(stats.clone(), clock)
+            .with_events(events.clone());
+        let telemetry = Rc::new(RefCell::new(telemetry));
+        let mut observed = Counted::new(world, stats.clone())
+            .observe(PerChange(stats.clone()))
+            .observe(PerChange(telemetry.clone()));
+
+        for _ in observed.initialize() {}
+        assert_eq!(telemetry.borrow().summary(), None);
+        let mut cmd = Some(Command::Turn(Direction::East));
+        while observed.step(cmd.take(), 0).is_ok() {}
+
+        let summary = telemetry.borrow().summary().unwrap();
+        assert_eq!(summary.cause, DeathReason::OutOfBound);
+        assert_eq!(summary.length, 2);
+        assert_eq!(summary.food, 0);
+        // 4 of the 15 tiles
+        assert_eq!(summary.coverage_percent, 26);
+        assert_eq!(*seen.borrow(), vec![GameEvent::GameOver(summary)]);
+
+        let lines = summary.lines(Locale::En);
+        assert_eq!(lines[0], "Game Over");
+        assert_eq!(lines[1], "ran off the board");
+        assert_eq!(lines[2], "time 0:00");
+    }
+}